@@ -0,0 +1,34 @@
+use bf_core::bf::Bot;
+use compiler::parser;
+use std::fs;
+use std::path::Path;
+
+/// Reads and parses every bot in a directory, that is every file with the `.bf` extension. The bots
+/// are named after their file, without the extension, and sorted by name.
+pub fn load_dir(dir: &Path) -> Result<Vec<(String, Bot)>, String> {
+    let entries = fs::read_dir(dir)
+        .map_err(|error| format!("Could not read {}: {}", dir.display(), error))?;
+    let mut paths = vec![];
+    for entry in entries {
+        let path = entry.map_err(|error| format!("Could not read {}: {}", dir.display(), error))?
+            .path();
+        if path.extension().is_some_and(|extension| extension == "bf") {
+            paths.push(path);
+        }
+    }
+    paths.sort();
+    paths.iter().map(|path| Ok((bot_name(path), load_bot(path)?))).collect()
+}
+
+/// Returns the name of the bot in the given file: the file name without its extension.
+pub fn bot_name(path: &Path) -> String {
+    path.file_stem().map_or_else(|| path.display().to_string(),
+                                 |stem| stem.to_string_lossy().into_owned())
+}
+
+/// Reads and parses the bot in the given BrainFuck source file.
+pub fn load_bot(path: &Path) -> Result<Bot, String> {
+    let source = fs::read_to_string(path)
+        .map_err(|error| format!("Could not read {}: {}", path.display(), error))?;
+    parser::parse(&source).map_err(|error| format!("Could not parse {}: {}", path.display(), error))
+}
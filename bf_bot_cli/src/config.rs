@@ -0,0 +1,57 @@
+//! Reads TOML files, such as configuration files, into the types that describe their contents.
+use serde::de::DeserializeOwned;
+use std::fs;
+use std::path::Path;
+
+/// Reads the TOML file at the given path into a value of the given type.
+pub fn load<T: DeserializeOwned>(path: &Path) -> Result<T, String> {
+    let text = fs::read_to_string(path)
+        .map_err(|error| format!("Could not read {}: {}", path.display(), error))?;
+    parse(&text).map_err(|error| format!("Invalid config {}: {}", path.display(), error))
+}
+
+/// Parses TOML text into a value of the given type.
+pub fn parse<T: DeserializeOwned>(text: &str) -> Result<T, String> {
+    toml::from_str(text).map_err(|error| error.to_string())
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeMap;
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Example {
+        name: String,
+        size: usize,
+        rate: f64,
+        #[serde(default)]
+        verbose: bool,
+        #[serde(default)]
+        operators: BTreeMap<String, String>,
+    }
+
+    #[test]
+    fn parse_readsAllValueTypes() {
+        let example = parse::<Example>("name = \"my \\\"run\\\" #1\" # a comment\nsize = 1_000\n\
+                                        rate = 1\nverbose = true")
+            .unwrap();
+        assert_eq!(example.name, "my \"run\" #1");
+        assert_eq!((example.size, example.rate, example.verbose), (1000, 1.0, true));
+    }
+
+    #[test]
+    fn parse_readsSections() {
+        let example = parse::<Example>("name = \"\"\nsize = 0\nrate = 0.5\n\
+                                        [operators]\nmutation = \"point\"")
+            .unwrap();
+        assert_eq!(example.operators["mutation"], "point");
+    }
+
+    #[test]
+    fn parse_invalid_fails() {
+        assert!(parse::<Example>("name = \"a\"\nsize = -1\nrate = 0.5").is_err());
+        assert!(parse::<Example>("name = \"a\"\nname = \"b\"\nsize = 1\nrate = 0.5").is_err());
+    }
+}
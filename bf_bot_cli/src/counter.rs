@@ -0,0 +1,110 @@
+use bf_bot_evolve::evolution::{seeded_rng, EvolutionParams, EvolutionRun};
+use bf_bot_evolve::fitness::{CounterFitness, FitnessFunction};
+use bf_bot_evolve::genome::{self, Genome};
+use bf_bot_evolve::operators::{SinglePointCrossover, WeightedMutation};
+use bf_bot_evolve::population::{Individual, Population};
+use bf_core::match_runner::Match;
+use bot_files;
+use clap::{value_parser, Arg, ArgMatches, Command};
+use interrupt;
+use std::fs;
+use std::path::PathBuf;
+
+pub fn command() -> Command {
+    Command::new("counter")
+        .about("Breeds a bot that beats one specific target, without doing worse against a hill \
+                than the target itself. The evolution starts from the target and the bots of the \
+                hill. Press Ctrl-C to stop after the current generation and keep its champion.")
+        .arg(Arg::new("target").required(true).value_parser(value_parser!(PathBuf)))
+        .arg(Arg::new("hill")
+                 .long("hill")
+                 .required(true)
+                 .value_parser(value_parser!(PathBuf))
+                 .help("The directory with the .bf bots that the counter must keep up with."))
+        .arg(Arg::new("output")
+                 .long("output")
+                 .value_parser(value_parser!(PathBuf))
+                 .help("Write the counter to this file instead of printing it."))
+        .arg(Arg::new("generations")
+                 .long("generations")
+                 .default_value("50")
+                 .value_parser(value_parser!(u32)))
+        .arg(Arg::new("population-size")
+                 .long("population-size")
+                 .default_value("50")
+                 .value_parser(value_parser!(usize)))
+        .arg(Arg::new("target-weight")
+                 .long("target-weight")
+                 .default_value("0.8")
+                 .value_parser(value_parser!(f64))
+                 .help("How much beating the target counts, between 0 and 1. The hill gets the \
+                        rest."))
+        .arg(Arg::new("seed").long("seed").default_value("0").value_parser(value_parser!(u64)))
+}
+
+pub fn run(matches: &ArgMatches) -> Result<(), String> {
+    let target_path = matches.get_one::<PathBuf>("target").unwrap();
+    let hill_dir = matches.get_one::<PathBuf>("hill").unwrap();
+    let source = fs::read_to_string(target_path)
+        .map_err(|error| format!("Could not read {}: {}", target_path.display(), error))?;
+    let target = Genome::from_source(&source)
+        .map_err(|error| format!("Could not parse {}: {}", target_path.display(), error))?;
+    let hill = bot_files::load_dir(hill_dir)?;
+    if hill.is_empty() {
+        return Err(format!("The hill {} contains no .bf files", hill_dir.display()));
+    }
+
+    let params = EvolutionParams {
+        population_size: *matches.get_one("population-size").unwrap(),
+        elitism: 2,
+        seed: *matches.get_one("seed").unwrap(),
+        ..EvolutionParams::default()
+    };
+    // Warm start: half of the population descends from the target and the hill, so that the
+    // evolution begins with strategies that are known to work.
+    let mut seeds = Population::from_sources(hill_dir)
+        .map_err(|error| format!("Could not read the hill in {}: {}", hill_dir.display(), error))?;
+    let target_bot = target.compile();
+    seeds.individuals.insert(0, Individual::new(target));
+    let population =
+        seeds.mixed_with_random(params.population_size, 0.5, 20, &mut seeded_rng(params.seed));
+    let fitness = CounterFitness::new(target_bot.clone(),
+                                      hill.into_iter().map(|(_, bot)| bot).collect())
+        .with_target_weight(*matches.get_one("target-weight").unwrap());
+    let target_on_hill = fitness.get_hill().evaluate(&target_bot);
+    let fitness = fitness.with_hill_floor(target_on_hill);
+    let hill_fitness = fitness.get_hill().clone();
+    let mut run = EvolutionRun::new(params,
+                                    population,
+                                    Box::new(WeightedMutation::default()),
+                                    Box::new(SinglePointCrossover),
+                                    Box::new(fitness))
+        .map_err(|error| error.to_string())?
+        .with_cancellation(interrupt::cancel_on_ctrl_c()?);
+    let generations = *matches.get_one::<u32>("generations").unwrap();
+    while run.get_generation() < generations && !run.is_cancelled() {
+        run.run_generation();
+        eprintln!("Generation {}: best {:.4}",
+                  run.get_generation(),
+                  run.best().and_then(|best| best.fitness).unwrap_or(f64::NAN));
+    }
+
+    let counter = genome::simplify(&run.best().ok_or("The population is empty")?.genome);
+    let counter_bot = counter.compile();
+    let result = Match::new(&counter_bot, &target_bot).run().map_err(|error| error.to_string())?;
+    eprintln!("The counter scores {} against the target, and {:.4} against the hill (the target \
+               scores {:.4}).",
+              result.score(),
+              hill_fitness.evaluate(&counter_bot),
+              target_on_hill);
+    match matches.get_one::<PathBuf>("output") {
+        Some(output) => {
+            fs::write(output, format!("{}\n", counter.to_source()))
+                .map_err(|error| format!("Could not write to {}: {}", output.display(), error))
+        }
+        None => {
+            println!("{}", counter.to_source());
+            Ok(())
+        }
+    }
+}
@@ -0,0 +1,191 @@
+use bf_core::debugger::{Breakpoint, Debugger, StopReason};
+use bf_core::engine::Orientation;
+use bf_core::simul_round::RoundParams;
+use bot_files;
+use clap::{value_parser, Arg, ArgAction, ArgMatches, Command};
+use std::io::{self, BufRead, Write};
+use std::path::PathBuf;
+
+const COMMANDS_HELP: &str = "\
+Commands:
+  step [N]              Execute N steps, or one.
+  continue              Run until a breakpoint is hit or the round ends.
+  flag                  Run until the value of either flag changes.
+  reach left|right X    Run until the bot arrives at cell X of the tape.
+  break left|right I    Stop whenever the bot is about to execute instruction I.
+  delete left|right I   Remove a breakpoint.
+  dump                  Show the tape, both bots and the breakpoints.
+  help                  Show this list.
+  quit                  Stop debugging.";
+
+pub fn command() -> Command {
+    Command::new("debug")
+        .about("Plays a single round between two bots under the control of interactive commands.")
+        .arg(Arg::new("left").required(true).value_parser(value_parser!(PathBuf)))
+        .arg(Arg::new("right").required(true).value_parser(value_parser!(PathBuf)))
+        .arg(Arg::new("tape-length")
+                 .long("tape-length")
+                 .default_value("10")
+                 .value_parser(value_parser!(u32).range(10..=30)))
+        .arg(Arg::new("invert-polarity")
+                 .long("invert-polarity")
+                 .action(ArgAction::SetTrue)
+                 .help("Let the right bot play with reversed polarity."))
+        .arg(Arg::new("max-steps")
+                 .long("max-steps")
+                 .default_value("100000")
+                 .value_parser(value_parser!(u32)))
+        .after_help(COMMANDS_HELP)
+}
+
+/// A command typed by the user.
+#[derive(Debug, PartialEq)]
+enum DebugCommand {
+    Step(u32),
+    Continue,
+    Flag,
+    Reach(Orientation, i32),
+    Break(Breakpoint),
+    Delete(Breakpoint),
+    Dump,
+    Help,
+    Quit,
+}
+
+pub fn run(matches: &ArgMatches) -> Result<(), String> {
+    let left = bot_files::load_bot(matches.get_one::<PathBuf>("left").unwrap())?;
+    let right = bot_files::load_bot(matches.get_one::<PathBuf>("right").unwrap())?;
+    let round_params = RoundParams::new(*matches.get_one("tape-length").unwrap(),
+                                        matches.get_flag("invert-polarity"),
+                                        *matches.get_one("max-steps").unwrap());
+    let mut debugger =
+        Debugger::new(&left, &right, &round_params).map_err(|error| error.to_string())?;
+    print!("{}", debugger.dump());
+    let stdin = io::stdin();
+    let mut lines = stdin.lock().lines();
+    loop {
+        print!("(debug) ");
+        io::stdout().flush().map_err(|error| error.to_string())?;
+        let line = match lines.next() {
+            Some(line) => line.map_err(|error| error.to_string())?,
+            None => return Ok(()),
+        };
+        if line.trim().is_empty() {
+            continue;
+        }
+        let stop_reason = match parse_command(&line) {
+            Ok(DebugCommand::Step(nr_steps)) => debugger.step(nr_steps),
+            Ok(DebugCommand::Continue) => debugger.run(),
+            Ok(DebugCommand::Flag) => debugger.run_until_flag_changed(),
+            Ok(DebugCommand::Reach(bot, position)) => debugger.run_until_position(bot, position),
+            Ok(DebugCommand::Break(breakpoint)) => {
+                debugger.add_breakpoint(breakpoint);
+                continue;
+            }
+            Ok(DebugCommand::Delete(breakpoint)) => {
+                if !debugger.remove_breakpoint(&breakpoint) {
+                    println!("There is no such breakpoint.");
+                }
+                continue;
+            }
+            Ok(DebugCommand::Dump) => {
+                print!("{}", debugger.dump());
+                continue;
+            }
+            Ok(DebugCommand::Help) => {
+                println!("{}", COMMANDS_HELP);
+                continue;
+            }
+            Ok(DebugCommand::Quit) => return Ok(()),
+            Err(message) => {
+                println!("{}", message);
+                continue;
+            }
+        };
+        println!("{}", describe_stop(&stop_reason));
+        print!("{}", debugger.dump());
+    }
+}
+
+fn parse_command(line: &str) -> Result<DebugCommand, String> {
+    let words = line.split_whitespace().collect::<Vec<_>>();
+    let number = |index: usize| {
+        words.get(index)
+            .ok_or_else(|| format!("'{}' needs a number.", words[0]))?
+            .parse::<i64>()
+            .map_err(|_| format!("'{}' is not a number.", words[index]))
+    };
+    let bot = |index: usize| match words.get(index) {
+        Some(&"left") => Ok(Orientation::Normal),
+        Some(&"right") => Ok(Orientation::Reversed),
+        _ => Err(format!("'{}' needs to know which bot: left or right.", words[0])),
+    };
+    let breakpoint = || -> Result<Breakpoint, String> {
+        Ok(Breakpoint::new(bot(1)?, non_negative(number(2)?)? as usize))
+    };
+    match words[0] {
+        "step" | "s" if words.len() == 1 => Ok(DebugCommand::Step(1)),
+        "step" | "s" => Ok(DebugCommand::Step(non_negative(number(1)?)? as u32)),
+        "continue" | "c" => Ok(DebugCommand::Continue),
+        "flag" | "f" => Ok(DebugCommand::Flag),
+        "reach" | "r" => Ok(DebugCommand::Reach(bot(1)?, number(2)? as i32)),
+        "break" | "b" => Ok(DebugCommand::Break(breakpoint()?)),
+        "delete" => Ok(DebugCommand::Delete(breakpoint()?)),
+        "dump" | "d" => Ok(DebugCommand::Dump),
+        "help" | "h" => Ok(DebugCommand::Help),
+        "quit" | "q" => Ok(DebugCommand::Quit),
+        command => Err(format!("Unknown command '{}'. Type 'help' for a list.", command)),
+    }
+}
+
+fn non_negative(number: i64) -> Result<i64, String> {
+    if number < 0 {
+        Err(format!("{} is negative.", number))
+    } else {
+        Ok(number)
+    }
+}
+
+fn describe_stop(stop_reason: &StopReason) -> String {
+    let bot_name = |bot: Orientation| match bot {
+        Orientation::Normal => "left",
+        Orientation::Reversed => "right",
+    };
+    match *stop_reason {
+        StopReason::StepsExecuted => "Stepped.".to_string(),
+        StopReason::Breakpoint(breakpoint) => {
+            format!("The {} bot hit the breakpoint at instruction {}.",
+                    bot_name(breakpoint.bot),
+                    breakpoint.code_index)
+        }
+        StopReason::FlagChanged { index } => format!("The flag at cell {} changed.", index),
+        StopReason::PositionReached { bot, position } => {
+            format!("The {} bot arrived at cell {}.", bot_name(bot), position)
+        }
+        StopReason::Finished(ref result) => format!("The round has ended: {:?}.", result.status()),
+    }
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parseCommand_validCommands_parsed() {
+        assert_eq!(parse_command("step"), Ok(DebugCommand::Step(1)));
+        assert_eq!(parse_command("s 20"), Ok(DebugCommand::Step(20)));
+        assert_eq!(parse_command("reach right 3"),
+                   Ok(DebugCommand::Reach(Orientation::Reversed, 3)));
+        assert_eq!(parse_command(" break left 7 "),
+                   Ok(DebugCommand::Break(Breakpoint::new(Orientation::Normal, 7))));
+    }
+
+    #[test]
+    fn parseCommand_invalidArguments_explained() {
+        assert_eq!(parse_command("step -3"), Err("-3 is negative.".to_string()));
+        assert_eq!(parse_command("break 7"),
+                   Err("'break' needs to know which bot: left or right.".to_string()));
+        assert!(parse_command("jump").is_err());
+    }
+}
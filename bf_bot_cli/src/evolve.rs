@@ -0,0 +1,353 @@
+use bot_files;
+use clap::{value_parser, Arg, ArgMatches, Command};
+use config;
+use bf_core::analysis;
+use bf_core::bf::{Bot, NamedBot};
+use bf_bot_evolve::evolution::{seeded_rng, EvolutionParams, EvolutionRun, GenerationStats};
+use bf_bot_evolve::fitness::{FitnessFunction, HillFitness, LengthPenalty, MatchCache,
+                             DEFAULT_MATCH_CACHE_CAPACITY};
+use bf_bot_evolve::genome::{self, TemplateConfig};
+use bf_bot_evolve::operators::{CrossoverOperator, DeleteBiasedMutation, MutationOperator,
+                               PointMutation, SinglePointCrossover, SubtreeCrossover,
+                               TwoPointCrossover, WeightedMutation};
+use bf_bot_evolve::population::{Individual, Population};
+use interrupt;
+use recording::Recorder;
+use std::fs::{self, File};
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+const CONFIG_HELP: &str = "\
+The config file is a TOML file with the following keys, all of which are optional except hill:
+
+  hill             directory with the .bf bots to evolve against
+  output           directory for stats.csv, the simplified champion.bf and its ancestry in
+                   lineage.dot (default: \"output\")
+  database         SQLite file to record the run in, if bfevolve was built with the sqlite
+                   feature (default: none)
+  generations      number of generations to run (default: 100)
+  seed             seed of the random number generator (default: 0)
+  population_size  number of bots in every generation (default: 100)
+  genome_length    number of instructions of the initial random bots (default: 20)
+  initial_bots     \"random\" for random instructions or \"templates\" for bots that lay decoys
+                   and then scan or clear the tape (default: \"random\")
+  seeds            directory with .bf warriors to start the evolution from (default: none)
+  random_ratio     fraction of the initial population that is random when seeds are given
+                   (default: 0.5)
+  crossover_rate   probability that an offspring has two parents (default: 0.7)
+  mutation_rate    probability that an offspring is mutated (default: 0.3)
+  mutation_adaptation
+                   \"one-fifth <factor>\" to adjust mutation_rate by the success of mutations,
+                   or \"self-adaptive <learning rate>\" to evolve a rate per bot (default: fixed)
+  selection        \"tournament <size>\", \"roulette\" or \"rank\" (default: \"tournament 3\")
+  elitism          number of fittest bots copied unchanged (default: 0)
+  mutation         \"weighted\" or \"point\" (default: \"weighted\")
+  crossover        \"single-point\", \"two-point\" or \"subtree\" (default: \"single-point\")
+  match_cache      number of matches to remember, so that pairings that recur aren't played
+                   again; 0 disables the cache (default: 100000)
+
+To keep the bots from bloating:
+
+  max_genome_length  discard offspring with more instructions than this (default: no maximum)
+  length_penalty     fitness subtracted per instruction of the compiled bot (default: 0)
+  target_length      above this number of instructions, mutations favour deletions
+                     (default: no target)
+
+Relative paths are relative to the directory of the config file.";
+
+pub fn command() -> Command {
+    Command::new("evolve")
+        .about("Evolves bots against a hill of opponents, as described in a config file. Press \
+                Ctrl-C to stop after the current generation and keep its champion.")
+        .arg(Arg::new("config")
+                 .long("config")
+                 .required(true)
+                 .value_parser(value_parser!(PathBuf)))
+        .after_help(CONFIG_HELP)
+}
+
+/// The keys of the config file, as described in `CONFIG_HELP`. Missing keys are `None`.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct ConfigFile {
+    hill: Option<PathBuf>,
+    output: Option<PathBuf>,
+    database: Option<PathBuf>,
+    generations: Option<u32>,
+    seed: Option<u64>,
+    population_size: Option<usize>,
+    genome_length: Option<usize>,
+    initial_bots: Option<String>,
+    seeds: Option<PathBuf>,
+    random_ratio: Option<f64>,
+    crossover_rate: Option<f64>,
+    mutation_rate: Option<f64>,
+    mutation_adaptation: Option<String>,
+    selection: Option<String>,
+    elitism: Option<usize>,
+    mutation: Option<String>,
+    crossover: Option<String>,
+    match_cache: Option<usize>,
+    max_genome_length: Option<usize>,
+    length_penalty: Option<f64>,
+    target_length: Option<usize>,
+}
+
+/// Everything that the config file specifies.
+struct EvolveConfig {
+    params: EvolutionParams,
+    generations: u32,
+    genome_length: usize,
+    templates: bool,
+    hill: PathBuf,
+    seeds: Option<PathBuf>,
+    random_ratio: f64,
+    output: PathBuf,
+    database: Option<PathBuf>,
+    mutation: Box<dyn MutationOperator>,
+    crossover: Box<dyn CrossoverOperator>,
+    length_penalty: f64,
+    match_cache: usize,
+}
+
+impl EvolveConfig {
+    fn from_config(config: ConfigFile, base_dir: &Path) -> Result<EvolveConfig, String> {
+        let defaults = EvolutionParams::default();
+        let params = EvolutionParams {
+            population_size: config.population_size.unwrap_or(defaults.population_size),
+            crossover_rate: config.crossover_rate.unwrap_or(defaults.crossover_rate),
+            mutation_rate: config.mutation_rate.unwrap_or(defaults.mutation_rate),
+            mutation_adaptation: config.mutation_adaptation
+                .map(|adaptation| adaptation.parse())
+                .transpose()?,
+            selection: match config.selection {
+                Some(selection) => selection.parse()?,
+                None => defaults.selection,
+            },
+            elitism: config.elitism.unwrap_or(defaults.elitism),
+            max_genome_length: config.max_genome_length,
+            seed: config.seed.unwrap_or(defaults.seed),
+        };
+        let hill = config.hill
+            .ok_or_else(|| "The config does not specify a hill directory".to_string())?;
+        let mut mutation = mutation_operator(config.mutation.as_deref().unwrap_or("weighted"))?;
+        if let Some(target_length) = config.target_length {
+            mutation = Box::new(DeleteBiasedMutation::new(mutation, target_length));
+        }
+        let crossover = config.crossover.as_deref().unwrap_or("single-point");
+        Ok(EvolveConfig {
+            params,
+            generations: config.generations.unwrap_or(100),
+            genome_length: config.genome_length.unwrap_or(20),
+            templates: match config.initial_bots.as_deref().unwrap_or("random") {
+                "random" => false,
+                "templates" => true,
+                name => return Err(format!("Unknown kind of initial bots: {}", name)),
+            },
+            hill: base_dir.join(hill),
+            seeds: config.seeds.map(|seeds| base_dir.join(seeds)),
+            random_ratio: config.random_ratio.unwrap_or(0.5),
+            output: base_dir.join(config.output.unwrap_or_else(|| PathBuf::from("output"))),
+            database: config.database.map(|database| base_dir.join(database)),
+            mutation,
+            crossover: crossover_operator(crossover)?,
+            length_penalty: config.length_penalty.unwrap_or(0.0),
+            match_cache: config.match_cache.unwrap_or(DEFAULT_MATCH_CACHE_CAPACITY),
+        })
+    }
+}
+
+fn mutation_operator(name: &str) -> Result<Box<dyn MutationOperator>, String> {
+    match name {
+        "weighted" => Ok(Box::new(WeightedMutation::default())),
+        "point" => Ok(Box::new(PointMutation)),
+        _ => Err(format!("Unknown mutation operator: {}", name)),
+    }
+}
+
+fn crossover_operator(name: &str) -> Result<Box<dyn CrossoverOperator>, String> {
+    match name {
+        "single-point" => Ok(Box::new(SinglePointCrossover)),
+        "two-point" => Ok(Box::new(TwoPointCrossover)),
+        "subtree" => Ok(Box::new(SubtreeCrossover)),
+        _ => Err(format!("Unknown crossover operator: {}", name)),
+    }
+}
+
+pub fn run(matches: &ArgMatches) -> Result<(), String> {
+    evolve(matches.get_one::<PathBuf>("config").unwrap(), |_, _, _| {})
+}
+
+/// Runs the evolution described in the given config file. After every generation, the observer is
+/// given its statistics, its fittest bot and the bots of the hill.
+pub fn evolve<F>(config_path: &Path, mut observer: F) -> Result<(), String>
+    where F: FnMut(&GenerationStats, Option<&Individual>, &[(String, Bot)])
+{
+    let base_dir = config_path.parent().unwrap_or_else(|| Path::new(""));
+    let config = EvolveConfig::from_config(config::load(config_path)?, base_dir)?;
+    let EvolveConfig {
+        params, generations, genome_length, mutation, crossover, length_penalty, ..
+    } = config;
+    let output = config.output;
+    let hill = bot_files::load_dir(&config.hill)?;
+    if hill.is_empty() {
+        return Err(format!("The hill {} contains no .bf files", config.hill.display()));
+    }
+
+    let mut rng = seeded_rng(params.seed);
+    let population = match config.seeds {
+        Some(ref seeds) => {
+            Population::from_sources(seeds)
+                .map_err(|error| format!("Could not read the seeds in {}: {}",
+                                         seeds.display(),
+                                         error))?
+                .mixed_with_random(params.population_size,
+                                   config.random_ratio,
+                                   genome_length,
+                                   &mut rng)
+        }
+        None if config.templates => {
+            Population::from_templates(params.population_size,
+                                       &TemplateConfig::default(),
+                                       &mut rng)
+        }
+        None => Population::random(params.population_size, genome_length, &mut rng),
+    };
+    let cache = Arc::new(MatchCache::new(config.match_cache));
+    let mut fitness: Box<dyn FitnessFunction> =
+        Box::new(HillFitness::new(hill.iter().map(|(_, bot)| bot.clone()).collect())
+            .with_match_cache(cache.clone()));
+    if length_penalty != 0.0 {
+        fitness = Box::new(LengthPenalty::new(fitness, length_penalty));
+    }
+    let mut run = EvolutionRun::new(params, population, mutation, crossover, fitness)
+        .map_err(|error| error.to_string())?
+        .with_cancellation(interrupt::cancel_on_ctrl_c()?);
+    let recorder = match config.database {
+        Some(ref database) => {
+            let description = format!("evolve --config {}", config_path.display());
+            Some(Recorder::start(database, &run.metadata(), &description)?)
+        }
+        None => None,
+    };
+
+    let output_error = |error| format!("Could not write to {}: {}", output.display(), error);
+    fs::create_dir_all(&output).map_err(output_error)?;
+    let mut stats_file = BufWriter::new(File::create(output.join("stats.csv"))
+        .map_err(output_error)?);
+    writeln!(stats_file, "{}", STATS_HEADER).map_err(output_error)?;
+    loop {
+        let stats = run.stats();
+        writeln!(stats_file, "{}", stats_line(&stats)).map_err(output_error)?;
+        if let Some(ref recorder) = recorder {
+            recorder.record_generation(&stats, run.best())?;
+        }
+        observer(&stats, run.best(), &hill);
+        println!("Generation {}: best {:.4}, mean {:.4}, diversity {:.2}",
+                 stats.generation,
+                 stats.best_fitness,
+                 stats.mean_fitness,
+                 stats.diversity);
+        if run.get_generation() >= generations {
+            break;
+        }
+        if run.is_cancelled() {
+            println!("Stopped at generation {}.", run.get_generation());
+            break;
+        }
+        run.run_generation();
+    }
+    stats_file.flush().map_err(output_error)?;
+    if cache.nr_hits() > 0 {
+        println!("{} of {} matches were taken from the match cache.",
+                 cache.nr_hits(),
+                 cache.nr_hits() + cache.nr_misses());
+    }
+    for (operator, impact) in run.get_mutation_impact() {
+        println!("{} mutation: {} offspring, {:.1}% fitter and {:.1}% less fit than their parents",
+                 operator,
+                 impact.nr_offspring,
+                 100.0 * impact.improvement_ratio(),
+                 100.0 * impact.nr_worsened as f64 / impact.nr_offspring as f64);
+    }
+
+    let champion = genome::simplify(&run.best().ok_or("The population is empty")?.genome);
+    fs::write(output.join("champion.bf"), format!("{}\n", champion.to_source()))
+        .map_err(output_error)?;
+    run.export_lineage_dot(output.join("lineage.dot")).map_err(output_error)?;
+    println!("The champion is a {}.", analysis::classify(&champion.compile()));
+    if let Some(ref recorder) = recorder {
+        let hill =
+            hill.into_iter().map(|(name, bot)| NamedBot::new(&name, bot)).collect::<Vec<_>>();
+        recorder.record_matches(&champion.compile(), &hill)?;
+    }
+    println!("Wrote the champion, its lineage and statistics to {}", output.display());
+    Ok(())
+}
+
+const STATS_HEADER: &str = "generation,best_fitness,mean_fitness,median_fitness,\
+                            fitness_std_dev,min_length,mean_length,max_length,diversity";
+
+fn stats_line(stats: &GenerationStats) -> String {
+    format!("{},{},{},{},{},{},{},{},{}",
+            stats.generation,
+            stats.best_fitness,
+            stats.mean_fitness,
+            stats.median_fitness,
+            stats.fitness_std_dev,
+            stats.min_length,
+            stats.mean_length,
+            stats.max_length,
+            stats.diversity)
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod tests {
+    use super::*;
+    use bf_bot_evolve::evolution::MutationAdaptation;
+    use bf_bot_evolve::operators::SelectionMethod;
+
+    #[test]
+    fn fromConfig_readsParamsAndResolvesPaths() {
+        let config = config::parse("hill = \"hill\"\nseed = 5\nselection = \"rank\"\n\
+                                    crossover = \"subtree\"")
+            .unwrap();
+        let config = EvolveConfig::from_config(config, Path::new("runs")).unwrap();
+        assert_eq!(config.params.seed, 5);
+        assert_eq!(config.params.selection, SelectionMethod::Rank);
+        assert_eq!(config.hill, Path::new("runs/hill"));
+        assert_eq!(config.output, Path::new("runs/output"));
+    }
+
+    #[test]
+    fn fromConfig_bloatControl_read() {
+        let config = config::parse("hill = \"hill\"\nmax_genome_length = 50\n\
+                                    length_penalty = 0.01")
+            .unwrap();
+        let config = EvolveConfig::from_config(config, Path::new("")).unwrap();
+        assert_eq!(config.params.max_genome_length, Some(50));
+        assert_eq!(config.length_penalty, 0.01);
+    }
+
+    #[test]
+    fn fromConfig_mutationAdaptation_read() {
+        let config = config::parse("hill = \"hill\"\nmutation_adaptation = \"one-fifth 1.2\"")
+            .unwrap();
+        let config = EvolveConfig::from_config(config, Path::new("")).unwrap();
+        assert_eq!(config.params.mutation_adaptation,
+                   Some(MutationAdaptation::OneFifthRule { factor: 1.2 }));
+    }
+
+    #[test]
+    fn fromConfig_withoutHill_fails() {
+        assert!(EvolveConfig::from_config(ConfigFile::default(), Path::new("")).is_err());
+    }
+
+    #[test]
+    fn fromConfig_unknownOperator_fails() {
+        let config = config::parse("hill = \"hill\"\nmutation = \"magic\"").unwrap();
+        assert!(EvolveConfig::from_config(config, Path::new("")).is_err());
+    }
+}
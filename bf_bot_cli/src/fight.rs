@@ -0,0 +1,68 @@
+use bf_core::bf::NamedBot;
+use bf_core::match_runner::Match;
+use bot_files;
+use clap::{value_parser, Arg, ArgMatches, Command};
+use std::path::PathBuf;
+use tournament;
+
+pub fn command() -> Command {
+    Command::new("fight")
+        .about("Plays a complete match between two bots and shows the outcome of every round.")
+        .arg(Arg::new("left").required(true).value_parser(value_parser!(PathBuf)))
+        .arg(Arg::new("right").required(true).value_parser(value_parser!(PathBuf)))
+        .arg(Arg::new("format")
+                 .long("format")
+                 .default_value("text")
+                 .value_parser(["text", "json", "egojoust"])
+                 .help("Print the outcome as text, as a JSON report for other tools, or exactly \
+                        like egojoust does."))
+        .arg(Arg::new("time-budget")
+                 .long("time-budget")
+                 .value_parser(value_parser!(f64))
+                 .help("The number of seconds the match may take. Rounds that are still being \
+                        played when the time is up are draws."))
+}
+
+pub fn run(matches: &ArgMatches) -> Result<(), String> {
+    let left_path = matches.get_one::<PathBuf>("left").unwrap();
+    let right_path = matches.get_one::<PathBuf>("right").unwrap();
+    let left = NamedBot::new(&bot_files::bot_name(left_path), bot_files::load_bot(left_path)?);
+    let right = NamedBot::new(&bot_files::bot_name(right_path),
+                              bot_files::load_bot(right_path)?);
+    let mut fight = Match::between(&left, &right);
+    if let Some(&seconds) = matches.get_one::<f64>("time-budget") {
+        fight = fight.with_time_budget(tournament::time_budget(seconds)?);
+    }
+    let result = fight.run().map_err(|error| error.to_string())?;
+    match matches.get_one::<String>("format").unwrap().as_str() {
+        "json" => println!("{}", result.to_json_report()),
+        "egojoust" => print!("{}", result.egojoust_output()),
+        _ => {
+            print!("{}", result);
+            if result.ran_out_of_time() {
+                println!("The match ran out of time. Its unfinished rounds count as draws.");
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod tests {
+    use super::*;
+    use bf_core::bf::{Bot, Instruction};
+
+    #[test]
+    fn display_suicidalRight_leftWinsEveryRound() {
+        let left = NamedBot::new("left", Bot::new(vec![]));
+        let right = NamedBot::new("right", Bot::new(vec![Instruction::MoveBack]));
+        let description = Match::between(&left, &right).run().unwrap().to_string();
+        let lines = description.lines().collect::<Vec<_>>();
+        assert_eq!(lines[0], "left vs right, tape lengths 10 to 30");
+        assert_eq!(lines[1], format!("normal polarity:   {}", "<".repeat(21)));
+        assert_eq!(lines[2], format!("inverted polarity: {}", "<".repeat(21)));
+        assert_eq!(lines[3], "left wins: 42, right wins: 0, draws: 0");
+        assert_eq!(lines[4], "Score: 42, left wins the match");
+    }
+}
@@ -0,0 +1,218 @@
+use bf_core::tournament::{Hill, HillEntry, Submission};
+use bot_files;
+use clap::{value_parser, Arg, ArgMatches, Command};
+use hill_state::{HillState, PairResult, RankedBot, SubmissionRecord};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// The file in the hill directory that lists the current standings.
+const STANDINGS_FILE: &str = "standings.txt";
+/// The subdirectory of the hill directory that evicted bots are moved to.
+const EVICTED_DIR: &str = "evicted";
+
+pub fn command() -> Command {
+    Command::new("hill")
+        .about("Maintains a local hill: a directory with the .bf files of the bots on it, and a \
+                hill.toml file with their ranking, the results of their matches and the history \
+                of submissions.")
+        .subcommand_required(true)
+        .subcommand(Command::new("submit")
+                        .about("Plays a bot against every bot on the hill and ranks it. Only \
+                                matches that were not played before are played. If the hill is \
+                                full, the lowest ranked bot is moved to the evicted \
+                                subdirectory. A bot that replaces one of the same name but ranks \
+                                too low takes the old version off the hill with it.")
+                        .arg(Arg::new("bot").required(true).value_parser(value_parser!(PathBuf)))
+                        .arg(Arg::new("hill")
+                                 .long("hill")
+                                 .required(true)
+                                 .value_parser(value_parser!(PathBuf)))
+                        .arg(Arg::new("capacity")
+                                 .long("capacity")
+                                 .default_value("10")
+                                 .value_parser(value_parser!(usize))
+                                 .help("The maximum number of bots on the hill.")))
+}
+
+pub fn run(matches: &ArgMatches) -> Result<(), String> {
+    match matches.subcommand() {
+        Some(("submit", sub_matches)) => submit(sub_matches),
+        _ => unreachable!("Clap rejects unknown subcommands."),
+    }
+}
+
+fn submit(matches: &ArgMatches) -> Result<(), String> {
+    let bot_path = matches.get_one::<PathBuf>("bot").unwrap();
+    let hill_dir = matches.get_one::<PathBuf>("hill").unwrap();
+    let capacity = *matches.get_one::<usize>("capacity").unwrap();
+    let name = bot_files::bot_name(bot_path);
+    let bot = bot_files::load_bot(bot_path)?;
+
+    fs::create_dir_all(hill_dir).map_err(|error| write_error(hill_dir, error))?;
+    let state = HillState::load(hill_dir)?.unwrap_or_default();
+    let mut hill = restore(hill_dir, &state, capacity)?;
+
+    let fingerprint = bot.fingerprint();
+    // The hill replaces a bot of the same name, even if the new version is rejected.
+    let previous = hill.get_entries().iter().find(|entry| entry.name == name).cloned();
+    let submission = hill.submit(&name, bot);
+    let mut record = SubmissionRecord {
+        name: name.clone(),
+        fingerprint,
+        time: SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |time| time.as_secs()),
+        rank: None,
+        evicted: None,
+    };
+    match submission {
+        Submission::Accepted { rank, evicted } => {
+            let destination = hill_dir.join(format!("{}.bf", name));
+            if fs::canonicalize(bot_path).ok() != fs::canonicalize(&destination).ok() {
+                fs::copy(bot_path, &destination).map_err(|error| write_error(&destination, error))?;
+            }
+            println!("{} entered the hill at rank {}.", name, rank + 1);
+            record.rank = Some(rank + 1);
+            if let Some(evicted) = evicted {
+                evict(hill_dir, &evicted)?;
+                record.evicted = Some(evicted.name);
+            }
+        }
+        Submission::Rejected => {
+            println!("{} ranked too low to enter the hill.", name);
+            if let Some(previous) = previous {
+                evict(hill_dir, &previous)?;
+                record.evicted = Some(previous.name);
+            }
+        }
+    }
+
+    let mut history = state.history;
+    history.push(record);
+    save(hill_dir, &hill, history)?;
+    let standings = describe_standings(hill.get_entries());
+    let standings_path = hill_dir.join(STANDINGS_FILE);
+    fs::write(&standings_path, &standings).map_err(|error| write_error(&standings_path, error))?;
+    print!("{}", standings);
+    Ok(())
+}
+
+/// Rebuilds the hill from its files and its saved state. Only the matches whose result isn't in
+/// the state are played: those of bots that are new, or whose file changed since the state was
+/// saved. A hill that is over capacity, for example because its capacity was lowered, is trimmed
+/// as it is rebuilt.
+fn restore(hill_dir: &Path, state: &HillState, capacity: usize) -> Result<Hill, String> {
+    let mut bots = bot_files::load_dir(hill_dir)?;
+    let unchanged = |name: &str| {
+        let saved = state.bots.iter().find(|saved| saved.name == name);
+        let current = bots.iter().find(|(current, _)| current == name);
+        match (saved, current) {
+            (Some(saved), Some((_, bot))) => saved.fingerprint == bot.fingerprint(),
+            _ => false,
+        }
+    };
+    let mut hill = Hill::new(capacity.max(1));
+    for result in &state.results {
+        if unchanged(&result.bot) && unchanged(&result.opponent) {
+            hill.add_result(&result.bot, &result.opponent, result.points);
+        }
+    }
+    // Submitting the bots in the order of their saved ranks keeps the order of bots with equal
+    // points. Bots the state doesn't know follow, by name.
+    let saved_rank = |name: &str| state.bots.iter().position(|saved| saved.name == name);
+    bots.sort_by_key(|(name, _)| saved_rank(name).unwrap_or(usize::MAX));
+    for (incumbent_name, incumbent) in bots {
+        if let Submission::Accepted { evicted: Some(evicted), .. } =
+            hill.submit(&incumbent_name, incumbent) {
+            evict(hill_dir, &evicted)?;
+        }
+    }
+    Ok(hill)
+}
+
+/// Saves the ranking and the results of the hill, together with its history of submissions.
+fn save(hill_dir: &Path, hill: &Hill, history: Vec<SubmissionRecord>) -> Result<(), String> {
+    let bots = hill.get_entries()
+        .iter()
+        .map(|entry| {
+            RankedBot {
+                name: entry.name.clone(),
+                fingerprint: entry.bot.fingerprint(),
+                points: entry.points,
+            }
+        })
+        .collect();
+    let mut results = hill.get_results()
+        .map(|(bot, opponent, points)| {
+            PairResult {
+                bot: bot.to_string(),
+                opponent: opponent.to_string(),
+                points,
+            }
+        })
+        .collect::<Vec<_>>();
+    // The results are kept in no particular order, so they are sorted to keep the file stable.
+    results.sort_by(|a, b| (&a.bot, &a.opponent).cmp(&(&b.bot, &b.opponent)));
+    HillState {
+        capacity: hill.get_capacity(),
+        bots,
+        results,
+        history,
+    }
+    .save(hill_dir)
+}
+
+/// Moves the file of an evicted bot out of the hill directory.
+fn evict(hill_dir: &Path, evicted: &HillEntry) -> Result<(), String> {
+    let evicted_dir = hill_dir.join(EVICTED_DIR);
+    fs::create_dir_all(&evicted_dir).map_err(|error| write_error(&evicted_dir, error))?;
+    let file_name = format!("{}.bf", evicted.name);
+    let destination = evicted_dir.join(&file_name);
+    fs::rename(hill_dir.join(&file_name), &destination)
+        .map_err(|error| write_error(&destination, error))?;
+    println!("{} was evicted from the hill.", evicted.name);
+    Ok(())
+}
+
+fn write_error(path: &Path, error: ::std::io::Error) -> String {
+    format!("Could not write {}: {}", path.display(), error)
+}
+
+fn describe_standings(entries: &[HillEntry]) -> String {
+    let name_width = entries.iter().map(|entry| entry.name.len()).max().unwrap_or(0).max(4);
+    let mut standings = format!("{:>4}  {:<name_width$}  {:>8}\n",
+                                "Rank",
+                                "Name",
+                                "Points",
+                                name_width = name_width);
+    for (rank, entry) in entries.iter().enumerate() {
+        // Adding zero turns a negative zero, the sum of no points at all, into a positive one.
+        standings.push_str(&format!("{:>4}  {:<name_width$}  {:>8.2}\n",
+                                    rank + 1,
+                                    entry.name,
+                                    entry.points + 0.0,
+                                    name_width = name_width));
+    }
+    standings
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod tests {
+    use super::*;
+    use bf_core::bf::Bot;
+
+    #[test]
+    fn describeStandings_oneLinePerBot() {
+        let entry = |name: &str, points| {
+            HillEntry {
+                name: name.to_string(),
+                author: None,
+                bot: Bot::new(vec![]),
+                points,
+            }
+        };
+        let standings = describe_standings(&[entry("rusher", 12.0), entry("idle", -12.0)]);
+        assert_eq!(standings,
+                   "Rank  Name      Points\n   1  rusher     12.00\n   2  idle      -12.00\n");
+    }
+}
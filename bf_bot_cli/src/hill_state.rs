@@ -0,0 +1,335 @@
+//! The state of a local hill, kept in `hill.toml` next to the `.bf` files of its bots: the ranking,
+//! the results of every match between the bots on the hill, and the history of submissions. With
+//! it, a submission only plays the matches of the new bot, instead of replaying the whole hill.
+//!
+//! Every bot, result and submission is a numbered section:
+//!
+//! ```toml
+//! version = 1
+//! capacity = 10
+//!
+//! [bot.1]
+//! name = "rusher"
+//! fingerprint = "af63bd4c8601b7df"
+//! points = 2.0
+//!
+//! [result.1]
+//! bot = "rusher"
+//! opponent = "idle"
+//! points = 2.0
+//!
+//! [submission.1]
+//! name = "rusher"
+//! fingerprint = "af63bd4c8601b7df"
+//! time = 1700000000
+//! rank = 1
+//! evicted = "clearer"
+//! ```
+use config;
+use serde::{Deserializer, Serializer};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::path::Path;
+use std::str::FromStr;
+
+/// The name of the state file in the hill directory.
+pub const HILL_STATE_FILE: &str = "hill.toml";
+/// The version of the format of the state file, increased whenever a key is removed or changes its
+/// meaning. Files of a newer version are refused rather than misread.
+pub const HILL_STATE_VERSION: i64 = 1;
+
+/// A bot on the hill, as it was ranked when the state was saved.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RankedBot {
+    pub name: String,
+    /// The fingerprint of the bot, which tells whether its file was changed since.
+    pub fingerprint: u64,
+    pub points: f64,
+}
+
+/// The points that one bot earned against another.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PairResult {
+    pub bot: String,
+    pub opponent: String,
+    pub points: f64,
+}
+
+/// A bot that was submitted to the hill.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SubmissionRecord {
+    pub name: String,
+    pub fingerprint: u64,
+    /// The moment of the submission, in seconds since the Unix epoch.
+    pub time: u64,
+    /// The rank at which the bot entered the hill, starting at one, or `None` if it was rejected.
+    pub rank: Option<usize>,
+    /// The bot that was evicted to make room, if any.
+    pub evicted: Option<String>,
+}
+
+/// Everything about a hill that is not in the files of its bots.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct HillState {
+    pub capacity: usize,
+    /// Ordered by rank.
+    pub bots: Vec<RankedBot>,
+    pub results: Vec<PairResult>,
+    /// Ordered from the first submission to the last.
+    pub history: Vec<SubmissionRecord>,
+}
+
+impl HillState {
+    /// Reads the state of the hill in the given directory, or returns `None` if it has no state
+    /// file yet.
+    pub fn load(hill_dir: &Path) -> Result<Option<HillState>, String> {
+        let path = hill_dir.join(HILL_STATE_FILE);
+        if !path.exists() {
+            return Ok(None);
+        }
+        let text = fs::read_to_string(&path)
+            .map_err(|error| format!("Could not read {}: {}", path.display(), error))?;
+        text.parse()
+            .map(Some)
+            .map_err(|error| format!("Invalid hill state {}: {}", path.display(), error))
+    }
+
+    /// Writes the state to the state file in the given directory.
+    pub fn save(&self, hill_dir: &Path) -> Result<(), String> {
+        let path = hill_dir.join(HILL_STATE_FILE);
+        fs::write(&path, self.to_string())
+            .map_err(|error| format!("Could not write {}: {}", path.display(), error))
+    }
+}
+
+/// The contents of the state file. Every section is keyed by its number, starting at one.
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+struct StateFile {
+    version: i64,
+    capacity: usize,
+    bot: BTreeMap<String, BotEntry>,
+    result: BTreeMap<String, ResultEntry>,
+    submission: BTreeMap<String, SubmissionEntry>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct BotEntry {
+    name: String,
+    #[serde(with = "hex")]
+    fingerprint: u64,
+    #[serde(default)]
+    points: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct ResultEntry {
+    bot: String,
+    opponent: String,
+    #[serde(default)]
+    points: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct SubmissionEntry {
+    name: String,
+    #[serde(with = "hex")]
+    fingerprint: u64,
+    #[serde(default)]
+    time: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    rank: Option<usize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    evicted: Option<String>,
+}
+
+/// Fingerprints are stored as hexadecimal strings because TOML integers are signed.
+mod hex {
+    use super::*;
+    use serde::de::Error;
+    use serde::Deserialize;
+
+    pub fn serialize<S: Serializer>(fingerprint: &u64, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&format!("{:016x}", fingerprint))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<u64, D::Error> {
+        let text = String::deserialize(deserializer)?;
+        u64::from_str_radix(&text, 16)
+            .map_err(|_| D::Error::custom(format!("{} is not a hexadecimal number", text)))
+    }
+}
+
+/// Numbers the given entries from one, as the sections of the state file.
+fn numbered<T, E, F: Fn(&T) -> E>(items: &[T], entry: F) -> BTreeMap<String, E> {
+    items.iter().enumerate().map(|(index, item)| ((index + 1).to_string(), entry(item))).collect()
+}
+
+/// Returns the entries of the sections in the order of their numbers.
+fn in_order<E>(sections: BTreeMap<String, E>) -> Result<Vec<E>, String> {
+    let mut numbered = sections.into_iter()
+        .map(|(number, entry)| match number.parse::<usize>() {
+            Ok(index) => Ok((index, entry)),
+            Err(_) => Err(format!("{} is not a section number", number)),
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    numbered.sort_by_key(|&(index, _)| index);
+    Ok(numbered.into_iter().map(|(_, entry)| entry).collect())
+}
+
+impl fmt::Display for HillState {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let file = StateFile {
+            version: HILL_STATE_VERSION,
+            capacity: self.capacity,
+            bot: numbered(&self.bots, |bot| {
+                BotEntry {
+                    name: bot.name.clone(),
+                    fingerprint: bot.fingerprint,
+                    points: bot.points,
+                }
+            }),
+            result: numbered(&self.results, |result| {
+                ResultEntry {
+                    bot: result.bot.clone(),
+                    opponent: result.opponent.clone(),
+                    points: result.points,
+                }
+            }),
+            submission: numbered(&self.history, |submission| {
+                SubmissionEntry {
+                    name: submission.name.clone(),
+                    fingerprint: submission.fingerprint,
+                    time: submission.time,
+                    rank: submission.rank,
+                    evicted: submission.evicted.clone(),
+                }
+            }),
+        };
+        writeln!(f, "# The state of this hill. The bots themselves are the .bf files next to it.")?;
+        write!(f, "{}", toml::to_string(&file).map_err(|_| fmt::Error)?)
+    }
+}
+
+impl FromStr for HillState {
+    type Err = String;
+
+    fn from_str(text: &str) -> Result<HillState, String> {
+        let file: StateFile = config::parse(text)?;
+        if file.version > HILL_STATE_VERSION {
+            return Err(format!("version {} is not supported, only version {}",
+                               file.version,
+                               HILL_STATE_VERSION));
+        }
+        Ok(HillState {
+            capacity: file.capacity,
+            bots: in_order(file.bot)?
+                .into_iter()
+                .map(|bot| {
+                    RankedBot {
+                        name: bot.name,
+                        fingerprint: bot.fingerprint,
+                        points: bot.points,
+                    }
+                })
+                .collect(),
+            results: in_order(file.result)?
+                .into_iter()
+                .map(|result| {
+                    PairResult {
+                        bot: result.bot,
+                        opponent: result.opponent,
+                        points: result.points,
+                    }
+                })
+                .collect(),
+            history: in_order(file.submission)?
+                .into_iter()
+                .map(|submission| {
+                    SubmissionRecord {
+                        name: submission.name,
+                        fingerprint: submission.fingerprint,
+                        time: submission.time,
+                        rank: submission.rank,
+                        evicted: submission.evicted,
+                    }
+                })
+                .collect(),
+        })
+    }
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod tests {
+    use super::*;
+
+    fn make_state() -> HillState {
+        HillState {
+            capacity: 3,
+            bots: vec![RankedBot {
+                           name: "rusher".to_string(),
+                           fingerprint: u64::MAX,
+                           points: 2.0,
+                       },
+                       RankedBot {
+                           name: "idle".to_string(),
+                           fingerprint: 0,
+                           points: -2.0,
+                       }],
+            results: vec![PairResult {
+                              bot: "rusher".to_string(),
+                              opponent: "idle".to_string(),
+                              points: 2.0,
+                          }],
+            history: vec![SubmissionRecord {
+                              name: "rusher".to_string(),
+                              fingerprint: u64::MAX,
+                              time: 1_700_000_000,
+                              rank: Some(1),
+                              evicted: Some("clearer".to_string()),
+                          },
+                          SubmissionRecord {
+                              name: "suicidal".to_string(),
+                              fingerprint: 1,
+                              time: 1_700_000_001,
+                              rank: None,
+                              evicted: None,
+                          }],
+        }
+    }
+
+    #[test]
+    fn parse_writtenState_roundTrips() {
+        let state = make_state();
+        assert_eq!(state.to_string().parse::<HillState>(), Ok(state));
+    }
+
+    #[test]
+    fn parse_newerVersion_refused() {
+        assert_eq!("version = 2".parse::<HillState>(),
+                   Err("version 2 is not supported, only version 1".to_string()));
+    }
+
+    #[test]
+    fn parse_manySections_keepsTheirOrder() {
+        let mut state = make_state();
+        state.bots = (0..12)
+            .map(|index| {
+                RankedBot {
+                    name: format!("bot \"{}\"", index),
+                    fingerprint: index,
+                    points: -(index as f64),
+                }
+            })
+            .collect();
+        assert_eq!(state.to_string().parse::<HillState>(), Ok(state));
+    }
+
+    #[test]
+    fn parse_missingName_fails() {
+        let text = "version = 1\n[submission.1]\nfingerprint = \"00\"\ntime = 0";
+        assert!(text.parse::<HillState>().is_err());
+    }
+}
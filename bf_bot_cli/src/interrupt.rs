@@ -0,0 +1,12 @@
+use bf_core::cancellation::CancellationToken;
+use ctrlc;
+
+/// Returns a token that is cancelled when the user presses Ctrl-C, so that a long computation can
+/// stop cleanly and report what it has achieved so far. Pressing Ctrl-C again then has no effect.
+pub fn cancel_on_ctrl_c() -> Result<CancellationToken, String> {
+    let cancellation = CancellationToken::new();
+    let handle = cancellation.clone();
+    ctrlc::set_handler(move || handle.cancel())
+        .map_err(|error| format!("Could not handle Ctrl-C: {}", error))?;
+    Ok(cancellation)
+}
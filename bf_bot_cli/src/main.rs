@@ -0,0 +1,64 @@
+//! The `bfevolve` command line tool.
+
+extern crate bf_bot_compiler as compiler;
+extern crate bf_bot_core as bf_core;
+extern crate bf_bot_evolve;
+extern crate clap;
+extern crate ctrlc;
+#[macro_use]
+extern crate serde;
+extern crate toml;
+
+use clap::Command;
+use std::process;
+
+mod bot_files;
+mod config;
+mod counter;
+mod debug;
+mod evolve;
+mod fight;
+mod hill;
+mod hill_state;
+mod interrupt;
+mod minimize;
+mod recording;
+#[cfg(feature = "serve")]
+mod serve;
+mod tournament;
+mod watch;
+
+fn main() {
+    let command = Command::new("bfevolve")
+        .about("Plays, inspects and evolves BrainFuck Joust bots.")
+        .subcommand_required(true)
+        .arg_required_else_help(true)
+        .subcommand(counter::command())
+        .subcommand(debug::command())
+        .subcommand(evolve::command())
+        .subcommand(fight::command())
+        .subcommand(hill::command())
+        .subcommand(minimize::command())
+        .subcommand(tournament::command())
+        .subcommand(watch::command());
+    #[cfg(feature = "serve")]
+    let command = command.subcommand(serve::command());
+    let matches = command.get_matches();
+    let outcome = match matches.subcommand() {
+        Some(("counter", sub_matches)) => counter::run(sub_matches),
+        Some(("debug", sub_matches)) => debug::run(sub_matches),
+        Some(("evolve", sub_matches)) => evolve::run(sub_matches),
+        Some(("fight", sub_matches)) => fight::run(sub_matches),
+        Some(("hill", sub_matches)) => hill::run(sub_matches),
+        Some(("minimize", sub_matches)) => minimize::run(sub_matches),
+        #[cfg(feature = "serve")]
+        Some(("serve", sub_matches)) => serve::run(sub_matches),
+        Some(("tournament", sub_matches)) => tournament::run(sub_matches),
+        Some(("watch", sub_matches)) => watch::run(sub_matches),
+        _ => unreachable!("Clap rejects unknown subcommands."),
+    };
+    if let Err(message) = outcome {
+        eprintln!("error: {}", message);
+        process::exit(1);
+    }
+}
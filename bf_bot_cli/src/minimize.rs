@@ -0,0 +1,53 @@
+use bf_bot_evolve::fitness::{FitnessFunction, HillFitness};
+use bf_bot_evolve::genome::{self, Genome};
+use bot_files;
+use clap::{value_parser, Arg, ArgMatches, Command};
+use std::fs;
+use std::path::PathBuf;
+
+pub fn command() -> Command {
+    Command::new("minimize")
+        .about("Shrinks a bot by deleting every part of it that it can do without, without \
+                lowering its score against a hill.")
+        .arg(Arg::new("bot").required(true).value_parser(value_parser!(PathBuf)))
+        .arg(Arg::new("hill")
+                 .long("hill")
+                 .required(true)
+                 .value_parser(value_parser!(PathBuf))
+                 .help("The directory with the .bf bots to score against."))
+        .arg(Arg::new("output")
+                 .long("output")
+                 .value_parser(value_parser!(PathBuf))
+                 .help("Write the shrunk bot to this file instead of printing it."))
+}
+
+pub fn run(matches: &ArgMatches) -> Result<(), String> {
+    let bot_path = matches.get_one::<PathBuf>("bot").unwrap();
+    let hill_dir = matches.get_one::<PathBuf>("hill").unwrap();
+    let source = fs::read_to_string(bot_path)
+        .map_err(|error| format!("Could not read {}: {}", bot_path.display(), error))?;
+    let genome = Genome::from_source(&source)
+        .map_err(|error| format!("Could not parse {}: {}", bot_path.display(), error))?;
+    let hill = bot_files::load_dir(hill_dir)?;
+    if hill.is_empty() {
+        return Err(format!("The hill {} contains no .bf files", hill_dir.display()));
+    }
+
+    let fitness = HillFitness::new(hill.into_iter().map(|(_, bot)| bot).collect());
+    let minimized = genome::minimize(&genome, &fitness);
+    eprintln!("Shrunk from {} to {} instructions, scoring {:.4} against the hill (was {:.4}).",
+              genome.compile().get_program().len(),
+              minimized.compile().get_program().len(),
+              fitness.evaluate(&minimized.compile()),
+              fitness.evaluate(&genome.compile()));
+    match matches.get_one::<PathBuf>("output") {
+        Some(output) => {
+            fs::write(output, format!("{}\n", minimized.to_source()))
+                .map_err(|error| format!("Could not write to {}: {}", output.display(), error))
+        }
+        None => {
+            println!("{}", minimized.to_source());
+            Ok(())
+        }
+    }
+}
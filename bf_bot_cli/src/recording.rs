@@ -0,0 +1,88 @@
+#[cfg(feature = "sqlite")]
+use bf_bot_evolve::storage::{ResultsDatabase, RunId};
+use bf_bot_evolve::evolution::{GenerationStats, RunMetadata};
+use bf_bot_evolve::population::Individual;
+use bf_core::bf::{Bot, NamedBot};
+#[cfg(feature = "sqlite")]
+use bf_core::match_runner::Match;
+use std::path::Path;
+
+/// Records an evolution run in a results database, if `bfevolve` was built with the `sqlite`
+/// feature.
+#[cfg(feature = "sqlite")]
+pub struct Recorder {
+    database: ResultsDatabase,
+    run: RunId,
+}
+
+#[cfg(feature = "sqlite")]
+impl Recorder {
+    /// Opens the database in the given file and adds a new run to it.
+    pub fn start(path: &Path,
+                 metadata: &RunMetadata,
+                 description: &str)
+                 -> Result<Recorder, String> {
+        let database = ResultsDatabase::open(path).map_err(|error| database_error(path, error))?;
+        let run = database.record_run(metadata, description)
+            .map_err(|error| database_error(path, error))?;
+        Ok(Recorder { database, run })
+    }
+
+    /// Records the statistics and the champion of a generation.
+    pub fn record_generation(&self,
+                             stats: &GenerationStats,
+                             champion: Option<&Individual>)
+                             -> Result<(), String> {
+        self.database.record_generation(self.run, stats).map_err(|error| error.to_string())?;
+        match champion {
+            Some(champion) => {
+                self.database
+                    .record_champion(self.run, stats.generation, champion)
+                    .map_err(|error| error.to_string())
+            }
+            None => Ok(()),
+        }
+    }
+
+    /// Plays the final champion against every bot on the hill and records the matches.
+    pub fn record_matches(&self, champion: &Bot, hill: &[NamedBot]) -> Result<(), String> {
+        let champion = NamedBot::new("champion", champion.clone());
+        for opponent in hill {
+            let result =
+                Match::between(&champion, opponent).run().map_err(|error| error.to_string())?;
+            self.database
+                .record_match(Some(self.run), &champion.bot, &opponent.bot, &result)
+                .map_err(|error| error.to_string())?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "sqlite")]
+fn database_error<E: ToString>(path: &Path, error: E) -> String {
+    format!("Could not use the database {}: {}", path.display(), error.to_string())
+}
+
+/// Stands in for the recorder when `bfevolve` was built without the `sqlite` feature.
+#[cfg(not(feature = "sqlite"))]
+pub struct Recorder;
+
+#[cfg(not(feature = "sqlite"))]
+impl Recorder {
+    pub fn start(_: &Path, _: &RunMetadata, _: &str) -> Result<Recorder, String> {
+        Err("This bfevolve was built without the sqlite feature, so it can't write to a \
+             database."
+            .to_string())
+    }
+
+    pub fn record_generation(&self,
+                             _: &GenerationStats,
+                             _: Option<&Individual>)
+                             -> Result<(), String> {
+        Ok(())
+    }
+
+    pub fn record_matches(&self, _: &Bot, _: &[NamedBot]) -> Result<(), String> {
+        Ok(())
+    }
+}
@@ -0,0 +1,286 @@
+//! Runs an evolution while serving its progress over HTTP, so that a long run on a remote machine
+//! can be followed from a browser. Only compiled with the `serve` feature.
+use bf_bot_evolve::evolution::GenerationStats;
+use bf_core::bf::{Bot, NamedBot};
+use bf_core::match_runner::{quoted, Match};
+use clap::{value_parser, Arg, ArgMatches, Command};
+use evolve;
+use std::io::{BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream};
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+/// The page that shows the statistics, the champion and the hill, and keeps them up to date.
+const INDEX_PAGE: &str = include_str!("serve_page.html");
+
+/// How long the server waits for a client to send its request or accept the response, so that a
+/// client that stalls cannot keep the others waiting forever.
+const CLIENT_TIMEOUT: Duration = Duration::from_secs(10);
+
+pub fn command() -> Command {
+    Command::new("serve")
+        .about("Runs an evolution like the evolve command does, and serves its progress over \
+                HTTP: a page with the statistics of every generation, the champion and the hill, \
+                and downloads of the matches between the champion and every bot on the hill. \
+                The server stops when the evolution does.")
+        .arg(Arg::new("config")
+                 .long("config")
+                 .required(true)
+                 .value_parser(value_parser!(PathBuf)))
+        .arg(Arg::new("address")
+                 .long("address")
+                 .default_value("127.0.0.1:8080")
+                 .help("The address to listen on. Use 0.0.0.0:<port> to allow other machines \
+                        to connect."))
+}
+
+/// What the server knows about the evolution: it is updated after every generation.
+#[derive(Debug, Default)]
+struct Monitor {
+    stats: Vec<GenerationStats>,
+    champion: Option<Bot>,
+    hill: Vec<(String, Bot)>,
+}
+
+/// An HTTP response, without the headers that every response has.
+#[derive(Debug, PartialEq)]
+struct Response {
+    status: &'static str,
+    content_type: &'static str,
+    /// The name under which the browser saves the body, if it should be downloaded.
+    download_as: Option<String>,
+    body: String,
+}
+
+impl Response {
+    fn ok(content_type: &'static str, body: String) -> Response {
+        Response {
+            status: "200 OK",
+            content_type,
+            download_as: None,
+            body,
+        }
+    }
+
+    fn not_found() -> Response {
+        Response::ok("text/plain", "Not found\n".to_string()).with_status("404 Not Found")
+    }
+
+    fn with_status(self, status: &'static str) -> Response {
+        Response { status, ..self }
+    }
+}
+
+pub fn run(matches: &ArgMatches) -> Result<(), String> {
+    let address = matches.get_one::<String>("address").unwrap();
+    let listener = TcpListener::bind(address.as_str())
+        .map_err(|error| format!("Could not listen on {}: {}", address, error))?;
+    println!("Serving the progress of the evolution at http://{}/", address);
+    let monitor = Arc::new(Mutex::new(Monitor::default()));
+    let server_monitor = monitor.clone();
+    // The thread is not joined: it serves until the process ends.
+    thread::spawn(move || {
+        for stream in listener.incoming().flatten() {
+            // A client that hangs up halfway is no reason to stop serving the others.
+            let _ = respond(stream, &server_monitor);
+        }
+    });
+    evolve::evolve(matches.get_one::<PathBuf>("config").unwrap(),
+                   |stats, champion, hill| {
+        let mut monitor = lock(&monitor);
+        monitor.stats.push(stats.clone());
+        monitor.champion = champion.map(|champion| champion.genome.compile());
+        if monitor.hill.is_empty() {
+            monitor.hill = hill.to_vec();
+        }
+    })
+}
+
+fn lock(monitor: &Mutex<Monitor>) -> ::std::sync::MutexGuard<'_, Monitor> {
+    // Every update of the monitor is complete before the next one starts, even after a panic.
+    monitor.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+/// Reads a request and answers it. Only the path of the request matters; the method and headers
+/// are ignored.
+fn respond(stream: TcpStream, monitor: &Mutex<Monitor>) -> ::std::io::Result<()> {
+    stream.set_read_timeout(Some(CLIENT_TIMEOUT))?;
+    stream.set_write_timeout(Some(CLIENT_TIMEOUT))?;
+    let mut reader = BufReader::new(stream);
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line)?;
+    let mut header = String::new();
+    while reader.read_line(&mut header)? > 0 && header.trim() != "" {
+        header.clear();
+    }
+    let path = request_line.split_whitespace().nth(1).unwrap_or("/");
+    let response = route(path, monitor);
+    let mut stream = reader.into_inner();
+    write!(stream,
+           "HTTP/1.1 {}\r\nContent-Type: {}; charset=utf-8\r\nContent-Length: {}\r\n",
+           response.status,
+           response.content_type,
+           response.body.len())?;
+    if let Some(file_name) = response.download_as {
+        write!(stream, "Content-Disposition: attachment; filename=\"{}\"\r\n", file_name)?;
+    }
+    write!(stream, "Connection: close\r\n\r\n{}", response.body)?;
+    stream.flush()
+}
+
+/// Decides how to answer a request for the given path. The monitor is only locked while reading
+/// it, so that the evolution is not held up by a request.
+fn route(path: &str, monitor: &Mutex<Monitor>) -> Response {
+    match path {
+        "/" => Response::ok("text/html", INDEX_PAGE.to_string()),
+        "/stats" => Response::ok("application/json", stats_json(&lock(monitor).stats)),
+        "/hill" => Response::ok("application/json", hill_json(&lock(monitor))),
+        "/champion.bf" => {
+            match lock(monitor).champion {
+                Some(ref champion) => {
+                    Response {
+                        download_as: Some("champion.bf".to_string()),
+                        ..Response::ok("text/plain", format!("{}\n", champion.to_source()))
+                    }
+                }
+                None => Response::not_found(),
+            }
+        }
+        _ => match_report(path, monitor).unwrap_or_else(Response::not_found),
+    }
+}
+
+/// Plays the champion against the hill bot in a path like `/matches/<name>.json`, and returns the
+/// JSON report of the match. The bots are copied out of the monitor first, so that the match is
+/// played without holding the lock.
+fn match_report(path: &str, monitor: &Mutex<Monitor>) -> Option<Response> {
+    let name = percent_decode(path.strip_prefix("/matches/")?.strip_suffix(".json")?)?;
+    let name = name.as_str();
+    let (champion, opponent) = {
+        let monitor = lock(monitor);
+        let champion = monitor.champion.clone()?;
+        let (_, opponent) = monitor.hill.iter().find(|(bot_name, _)| bot_name == name)?;
+        (champion, opponent.clone())
+    };
+    let champion = NamedBot::new("champion", champion);
+    let opponent = NamedBot::new(name, opponent);
+    let report = Match::between(&champion, &opponent).run().ok()?.to_json_report();
+    Some(Response {
+        download_as: Some(format!("champion-vs-{}.json", name)),
+        ..Response::ok("application/json", report)
+    })
+}
+
+fn stats_json(stats: &[GenerationStats]) -> String {
+    let rows = stats.iter()
+        .map(|stats| {
+            format!("{{\"generation\":{},\"best_fitness\":{},\"mean_fitness\":{},\
+                     \"median_fitness\":{},\"fitness_std_dev\":{},\"min_length\":{},\
+                     \"mean_length\":{},\"max_length\":{},\"diversity\":{}}}",
+                    stats.generation,
+                    json_number(stats.best_fitness),
+                    json_number(stats.mean_fitness),
+                    json_number(stats.median_fitness),
+                    json_number(stats.fitness_std_dev),
+                    stats.min_length,
+                    json_number(stats.mean_length),
+                    stats.max_length,
+                    json_number(stats.diversity))
+        })
+        .collect::<Vec<_>>();
+    format!("[{}]", rows.join(","))
+}
+
+fn hill_json(monitor: &Monitor) -> String {
+    let champion = match monitor.champion {
+        Some(ref champion) => quoted(&champion.to_source()),
+        None => "null".to_string(),
+    };
+    let bots = monitor.hill
+        .iter()
+        .map(|(name, bot)| {
+            format!("{{\"name\":{},\"source\":{}}}", quoted(name), quoted(&bot.to_source()))
+        })
+        .collect::<Vec<_>>();
+    format!("{{\"champion\":{},\"bots\":[{}]}}", champion, bots.join(","))
+}
+
+/// Decodes the `%xx` escapes in a part of a URL, or returns `None` if they are not valid UTF-8.
+fn percent_decode(text: &str) -> Option<String> {
+    let mut bytes = vec![];
+    let mut rest = text.as_bytes();
+    while let Some((&byte, tail)) = rest.split_first() {
+        let escaped = tail.get(..2)
+            .and_then(|hex| ::std::str::from_utf8(hex).ok())
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match escaped {
+            Some(decoded) if byte == b'%' => {
+                bytes.push(decoded);
+                rest = &tail[2..];
+            }
+            _ => {
+                bytes.push(byte);
+                rest = tail;
+            }
+        }
+    }
+    String::from_utf8(bytes).ok()
+}
+
+/// JSON has no infinity or NaN, which a fitness can be.
+fn json_number(number: f64) -> String {
+    if number.is_finite() {
+        number.to_string()
+    } else {
+        "null".to_string()
+    }
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod tests {
+    use super::*;
+    use bf_bot_evolve::population::Population;
+    use bf_core::bf::Instruction;
+
+    fn make_monitor() -> Mutex<Monitor> {
+        Mutex::new(Monitor {
+            stats: vec![],
+            champion: Some(Bot::new(vec![Instruction::MoveForward])),
+            hill: vec![("idle \"bot\"".to_string(), Bot::new(vec![]))],
+        })
+    }
+
+    #[test]
+    fn route_hill_listsChampionAndBots() {
+        let response = route("/hill", &make_monitor());
+        assert_eq!(response.body,
+                   "{\"champion\":\">\",\"bots\":[{\"name\":\"idle \\\"bot\\\"\",\
+                    \"source\":\"\"}]}");
+    }
+
+    #[test]
+    fn route_matchOfHillBot_downloadsReport() {
+        let response = route("/matches/idle%20%22bot%22.json", &make_monitor());
+        assert_eq!(response.status, "200 OK");
+        assert!(response.body.contains("\"bot_b_name\":\"idle \\\"bot\\\"\""));
+        assert!(response.download_as.is_some());
+    }
+
+    #[test]
+    fn route_unknownPathOrNoChampion_notFound() {
+        assert_eq!(route("/matches/other.json", &make_monitor()).status, "404 Not Found");
+        assert_eq!(route("/champion.bf", &Mutex::default()).status, "404 Not Found");
+    }
+
+    #[test]
+    fn statsJson_infiniteFitness_null() {
+        let stats = GenerationStats {
+            best_fitness: f64::INFINITY,
+            ..GenerationStats::of(0, &Population::new(vec![]))
+        };
+        assert!(stats_json(&[stats]).contains("\"best_fitness\":null"));
+    }
+}
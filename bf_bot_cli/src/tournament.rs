@@ -0,0 +1,92 @@
+use bf_core::bf::NamedBot;
+use bf_core::tournament::{PairingOutcome, RoundRobin};
+use bot_files;
+use clap::{value_parser, Arg, ArgMatches, Command};
+use interrupt;
+use std::io::{self, Write};
+use std::path::PathBuf;
+use std::time::Duration;
+
+/// The number of characters of the progress bar, between its brackets.
+const PROGRESS_BAR_WIDTH: usize = 30;
+
+pub fn command() -> Command {
+    Command::new("tournament")
+        .about("Plays a round-robin tournament between the bots in a directory and shows the \
+                standings. Press Ctrl-C to stop early and see the standings so far.")
+        .arg(Arg::new("dir").required(true).value_parser(value_parser!(PathBuf)))
+        .arg(Arg::new("time-budget")
+                 .long("time-budget")
+                 .value_parser(value_parser!(f64))
+                 .help("The number of seconds a match may take. Rounds that are still being \
+                        played when the time is up are draws."))
+}
+
+pub fn run(matches: &ArgMatches) -> Result<(), String> {
+    let dir = matches.get_one::<PathBuf>("dir").unwrap();
+    let bots = bot_files::load_dir(dir)?
+        .into_iter()
+        .map(|(name, bot)| NamedBot::new(&name, bot))
+        .collect::<Vec<_>>();
+    let mut tournament =
+        RoundRobin::new(bots).with_cancellation(interrupt::cancel_on_ctrl_c()?);
+    if let Some(&seconds) = matches.get_one::<f64>("time-budget") {
+        tournament = tournament.with_time_budget(time_budget(seconds)?);
+    }
+    let mut nr_out_of_time = 0;
+    let standings = tournament.run_with_progress(|outcome| {
+        // The progress is only decoration, so failing to show it is no reason to stop.
+        if outcome.result.ran_out_of_time() {
+            nr_out_of_time += 1;
+        }
+        let mut stderr = io::stderr();
+        let _ = write!(stderr, "\r{}", progress_line(&tournament, outcome));
+        let _ = stderr.flush();
+    });
+    if tournament.nr_pairings() > 0 {
+        eprintln!();
+    }
+    if tournament.is_cancelled() {
+        println!("Stopped early. These standings only count the matches that were completed.");
+    }
+    if nr_out_of_time > 0 {
+        println!("{} matches ran out of time. Their unfinished rounds count as draws.",
+                 nr_out_of_time);
+    }
+    print!("{}", standings);
+    Ok(())
+}
+
+/// Converts a number of seconds from the command line into a duration.
+pub fn time_budget(seconds: f64) -> Result<Duration, String> {
+    Duration::try_from_secs_f64(seconds)
+        .map_err(|_| format!("{} is not a valid number of seconds.", seconds))
+}
+
+/// Returns a progress bar, followed by the pairing that was just played.
+fn progress_line(tournament: &RoundRobin, outcome: &PairingOutcome) -> String {
+    let nr_filled = outcome.nr_played * PROGRESS_BAR_WIDTH / outcome.nr_pairings;
+    let bots = tournament.get_bots();
+    // Pads the line, so that it covers a longer line that it overwrites.
+    format!("[{:<width$}] {:>3.0}% {:<40}",
+            "#".repeat(nr_filled),
+            outcome.progress(),
+            format!("{} vs {}", bots[outcome.bot_a].name, bots[outcome.bot_b].name),
+            width = PROGRESS_BAR_WIDTH)
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod tests {
+    use super::*;
+    use bf_core::bf::Bot;
+
+    #[test]
+    fn progressLine_halfway_halfFilled() {
+        let tournament = RoundRobin::new(vec![NamedBot::new("a", Bot::new(vec![])),
+                                              NamedBot::new("b", Bot::new(vec![]))]);
+        let outcome = PairingOutcome { nr_pairings: 2, ..tournament.pairings().next().unwrap() };
+        assert_eq!(progress_line(&tournament, &outcome).trim_end(),
+                   format!("[{:<30}]  50% a vs b", "#".repeat(15)));
+    }
+}
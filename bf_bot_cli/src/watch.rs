@@ -0,0 +1,136 @@
+use bf_core::engine::{Arena, BotSnapshot};
+use bf_core::simul_round::{RoundParams, RoundResult, RoundStatus, StepOutcome};
+use bot_files;
+use clap::{value_parser, Arg, ArgAction, ArgMatches, Command};
+use std::path::PathBuf;
+use std::thread;
+use std::time::Duration;
+
+/// The number of characters used to display a single cell of the tape.
+const CELL_WIDTH: usize = 6;
+/// Clears the terminal and moves the cursor to the top left corner.
+const CLEAR_SCREEN: &str = "\x1b[2J\x1b[H";
+
+pub fn command() -> Command {
+    Command::new("watch")
+        .about("Plays a single round between two bots and shows the arena after every step.")
+        .arg(Arg::new("left").required(true).value_parser(value_parser!(PathBuf)))
+        .arg(Arg::new("right").required(true).value_parser(value_parser!(PathBuf)))
+        .arg(Arg::new("tape-length")
+                 .long("tape-length")
+                 .default_value("10")
+                 .value_parser(value_parser!(u32).range(10..=30)))
+        .arg(Arg::new("invert-polarity")
+                 .long("invert-polarity")
+                 .action(ArgAction::SetTrue)
+                 .help("Let the right bot play with reversed polarity."))
+        .arg(Arg::new("max-steps")
+                 .long("max-steps")
+                 .default_value("100000")
+                 .value_parser(value_parser!(u32)))
+        .arg(Arg::new("delay")
+                 .long("delay")
+                 .default_value("100")
+                 .value_parser(value_parser!(u64))
+                 .help("The number of milliseconds to wait between steps."))
+}
+
+pub fn run(matches: &ArgMatches) -> Result<(), String> {
+    let left = bot_files::load_bot(matches.get_one::<PathBuf>("left").unwrap())?;
+    let right = bot_files::load_bot(matches.get_one::<PathBuf>("right").unwrap())?;
+    let round_params = RoundParams::new(*matches.get_one("tape-length").unwrap(),
+                                        matches.get_flag("invert-polarity"),
+                                        *matches.get_one("max-steps").unwrap());
+    let delay = Duration::from_millis(*matches.get_one("delay").unwrap());
+
+    let mut arena = Arena::new(&left, &right, &round_params).map_err(|error| error.to_string())?;
+    print!("{}{}", CLEAR_SCREEN, render_frame(&arena));
+    loop {
+        let outcome = arena.step();
+        print!("{}{}", CLEAR_SCREEN, render_frame(&arena));
+        if let StepOutcome::Finished(result) = outcome {
+            println!("\n{}", describe_result(&result));
+            return Ok(());
+        }
+        thread::sleep(delay);
+    }
+}
+
+/// Renders the tape, the positions of both bots and their next instructions.
+fn render_frame(arena: &Arena) -> String {
+    let snapshot = arena.snapshot();
+    let (tape, start_bot, end_bot) = (snapshot.tape, snapshot.start_bot, snapshot.end_bot);
+    let mut frame = format!("Step {}\n", snapshot.step_nr);
+    frame.push_str(&describe_bot("Left (A)", &start_bot, tape.len()));
+    frame.push_str(&describe_bot("Right (B)", &end_bot, tape.len()));
+    frame.push('\n');
+    for index in 0..tape.len() {
+        frame.push_str(&format!("{:^width$}", index, width = CELL_WIDTH));
+    }
+    frame.push('\n');
+    for (index, value) in tape.iter().enumerate() {
+        let cell = if index == 0 || index == tape.len() - 1 {
+            format!("[{}]", value)
+        } else {
+            value.to_string()
+        };
+        frame.push_str(&format!("{:^width$}", cell, width = CELL_WIDTH));
+    }
+    frame.push('\n');
+    for index in 0..tape.len() as i32 {
+        let marker = match (start_bot.position == index, end_bot.position == index) {
+            (true, true) => "AB",
+            (true, false) => "A",
+            (false, true) => "B",
+            (false, false) => "",
+        };
+        frame.push_str(&format!("{:^width$}", marker, width = CELL_WIDTH));
+    }
+    frame.push('\n');
+    frame
+}
+
+fn describe_bot(name: &str, bot: &BotSnapshot, tape_length: usize) -> String {
+    let position = if bot.position < 0 || bot.position >= tape_length as i32 {
+        String::from("off the tape")
+    } else {
+        format!("cell {}", bot.position)
+    };
+    format!("{:<10} {}, next instruction {} at loop depth {}\n",
+            name,
+            position,
+            bot.code_pointer,
+            bot.loop_depth)
+}
+
+fn describe_result(result: &RoundResult) -> String {
+    let winner = match result.status() {
+        RoundStatus::StartBotWon => "The left bot wins",
+        RoundStatus::EndBotWon => "The right bot wins",
+        _ => "The round is a draw",
+    };
+    format!("{} after {} steps ({:?}, {:?}).",
+            winner,
+            result.nr_steps,
+            result.bot_a_loss,
+            result.bot_b_loss)
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod tests {
+    use super::*;
+    use bf_core::bf::Bot;
+
+    #[test]
+    fn renderFrame_marksFlagsAndBotPositions() {
+        let bot = Bot::new(vec![]);
+        let arena = Arena::new(&bot, &bot, &RoundParams::new(10, false, 100)).unwrap();
+        let frame = render_frame(&arena);
+        let lines = frame.lines().collect::<Vec<_>>();
+        assert_eq!(lines[0], "Step 0");
+        assert_eq!(lines[5].split_whitespace().collect::<Vec<_>>(),
+                   vec!["[-128]", "0", "0", "0", "0", "0", "0", "0", "0", "[-128]"]);
+        assert_eq!(lines[6].split_whitespace().collect::<Vec<_>>(), vec!["A", "B"]);
+    }
+}
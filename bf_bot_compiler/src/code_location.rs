@@ -0,0 +1,46 @@
+use std::fmt;
+
+/// A position in a piece of `BrainFuck` source code.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CodeLocation {
+    /// Starts at line 1.
+    pub line: u32,
+    /// Starts at column 1.
+    pub column: u32,
+}
+
+impl CodeLocation {
+    pub fn new(line: u32, column: u32) -> CodeLocation {
+        CodeLocation { line, column }
+    }
+
+    /// Returns the location of the character that follows the given character, which is itself
+    /// located at this location.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bf_bot_compiler::code_location::CodeLocation;
+    /// assert_eq!(CodeLocation::new(1, 1).advance('+'), CodeLocation::new(1, 2));
+    /// assert_eq!(CodeLocation::new(1, 5).advance('\n'), CodeLocation::new(2, 1));
+    /// ```
+    pub fn advance(&self, character: char) -> CodeLocation {
+        if character == '\n' {
+            CodeLocation::new(self.line + 1, 1)
+        } else {
+            CodeLocation::new(self.line, self.column + 1)
+        }
+    }
+}
+
+impl Default for CodeLocation {
+    fn default() -> CodeLocation {
+        CodeLocation::new(1, 1)
+    }
+}
+
+impl fmt::Display for CodeLocation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "line {}, column {}", self.line, self.column)
+    }
+}
@@ -6,5 +6,6 @@
 //! Prettyfier that tries to format the code in an intellinent way using spacing and newlines.
 extern crate bf_bot_core;
 
+pub mod code_location;
 pub mod parser;
 pub mod linter;
@@ -1,5 +1,9 @@
 #![allow(dead_code, unused_variables)]
 
+use std::fmt;
+
+use code_location::CodeLocation;
+
 #[derive(Debug)]
 #[derive(PartialEq)]
 pub enum Severity {
@@ -7,15 +11,6 @@ pub enum Severity {
     ERROR,
 }
 
-#[derive(Debug)]
-#[derive(PartialEq)]
-pub struct CodeLocation {
-    /// Starts at line 1.
-    line: u32,
-    /// Starts at column 1.
-    column: u32,
-}
-
 #[derive(Debug)]
 #[derive(PartialEq)]
 pub struct Issue {
@@ -28,7 +23,7 @@ impl Issue {
     fn new_unintentional_dot(code_location: CodeLocation) -> Issue {
         Issue {
             severity: Severity::WARNING,
-            code_location: code_location,
+            code_location,
             description: "Possible unintentional dot. It looks like you accidentally used a dot \
                           as part of your documentation. A dot or full-stop (.) is a command in \
                           the BrainFuck Jousting language. It tells the bot to do nothing that \
@@ -36,17 +31,20 @@ impl Issue {
                 .to_string(),
         }
     }
+}
 
-    pub fn to_string(&self) -> String {
+impl fmt::Display for Issue {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         let severity_str: &str = match self.severity {
             Severity::WARNING => "WARNING",
             Severity::ERROR => "ERROR",
         };
-        format!("{} on line {}, column {} {}",
-                severity_str,
-                self.code_location.line,
-                self.code_location.column,
-                self.description)
+        write!(f,
+               "{} on line {}, column {} {}",
+               severity_str,
+               self.code_location.line,
+               self.code_location.column,
+               self.description)
     }
 }
 
@@ -69,7 +67,7 @@ fn check_bracket_mismatch(program: &str) -> Vec<Issue> {
 fn check_unintended_dot(program: &str) -> Vec<Issue> {
     program.chars()
         .skip(1)
-        .scan((FollowsAfter::Nothing, program.chars().nth(0)),
+        .scan((FollowsAfter::Nothing, program.chars().next()),
               |&mut (ref follows, previous_char), current_char| {
             let state = if is_valid_instruction(previous_char.unwrap()) {
                 FollowsAfter::Instruction
@@ -104,10 +102,7 @@ enum FollowsAfter {
 
 //TODO: Move. Tests. Doc.
 fn is_valid_instruction(character: char) -> bool {
-    match character {
-        '<' | '>' | '+' | '-' | '[' | ']' | '.' => true,
-        _ => false,
-    }
+    matches!(character, '<' | '>' | '+' | '-' | '[' | ']' | '.')
 }
 
 fn check_comma(program: &str) -> Vec<Issue> {
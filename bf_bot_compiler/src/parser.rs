@@ -1,94 +1,410 @@
-use bf_bot_core::bf::Instruction;
-
-//TODO: Take &str?
-pub fn parse_bot(source_code: String) -> Vec<Instruction> {
-    let (bracket_stack, instructions): (Vec<usize>, Vec<Instruction>) = source_code.chars()
-        .enumerate()
-        .fold((vec![], vec![]),
-              |(mut bracket_stack, mut instructions), (index, character)| {
+//! Turns `BrainFuck` source code into the program of a `Bot`.
+//!
+//! Besides the plain `BrainFuck` instructions, the parser understands the abbreviated syntax that
+//! is used by most warriors on BF Joust hills. It is expanded into a flat list of instructions:
+//!
+//! - `(a)*n` repeats `a` n times.
+//! - `(a{b}c)%n` repeats `a` n times, then runs `b` once, then repeats `c` n times. This makes it
+//!   possible to write nested loops compactly, as in `([{}])%3`, which becomes `[[[]]]`.
+//!
+//! Repetition counts must be non-negative numbers.
+use std::error::Error;
+use std::fmt;
+use std::iter::Peekable;
+use std::str::Chars;
+
+use bf_bot_core::bf::{Bot, Instruction};
+use bf_bot_core::error::BfError;
+use code_location::CodeLocation;
+
+/// The maximum number of instructions that a program may expand into. Guards against source
+/// code like `(((+)*1000)*1000)*1000` that would exhaust the available memory.
+const MAX_EXPANDED_LENGTH: usize = 1_000_000;
+
+/// The maximum number of repetitions that may be nested inside each other. The expander recurses
+/// once for every level, so this guards against source code like ten thousand `(` characters that
+/// would overflow the stack.
+const MAX_NESTING_DEPTH: usize = 256;
+
+/// The different reasons why a piece of source code can fail to parse.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ParseErrorKind {
+    /// A closing bracket or brace was found that has no opening counterpart to match it.
+    UnmatchedClosingBracket,
+    /// An opening bracket or brace was never closed.
+    UnmatchedOpeningBracket,
+    /// An inner block `{...}` that is not placed directly inside a `(...)%n` repetition, or a
+    /// second inner block inside the same repetition.
+    MisplacedInnerBlock,
+    /// A closing round bracket was not followed by an asterisk or a percent sign.
+    MissingRepetitionOperator,
+    /// The repetition operator after a closing round bracket was not followed by a number.
+    MissingNrIterations,
+    /// The program expands into more than `MAX_EXPANDED_LENGTH` instructions.
+    ProgramTooLong,
+    /// More than `MAX_NESTING_DEPTH` repetitions are nested inside each other.
+    NestedTooDeeply,
+}
+
+impl ParseErrorKind {
+    fn describe(&self) -> &'static str {
+        match *self {
+            ParseErrorKind::UnmatchedClosingBracket => "Unmatched closing bracket",
+            ParseErrorKind::UnmatchedOpeningBracket => "Unmatched opening bracket",
+            ParseErrorKind::MisplacedInnerBlock => "Misplaced inner block",
+            ParseErrorKind::MissingRepetitionOperator => {
+                "Expected an asterisk or percent sign after closing bracket"
+            }
+            ParseErrorKind::MissingNrIterations => {
+                "Expected a number of iterations after closing bracket"
+            }
+            ParseErrorKind::ProgramTooLong => {
+                "Program expands to too many instructions after closing bracket"
+            }
+            ParseErrorKind::NestedTooDeeply => {
+                "Repetitions are nested too deeply at opening bracket"
+            }
+        }
+    }
+}
+
+/// Describes why the source code of a bot could not be parsed, and which character is to blame.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ParseError {
+    pub kind: ParseErrorKind,
+    /// The offending character.
+    pub character: char,
+    /// The location of the offending character in the source code.
+    pub location: CodeLocation,
+}
+
+impl ParseError {
+    pub fn new(kind: ParseErrorKind, character: char, location: CodeLocation) -> ParseError {
+        ParseError {
+            kind,
+            character,
+            location,
+        }
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f,
+               "{} '{}' on {}.",
+               self.kind.describe(),
+               self.character,
+               self.location)
+    }
+}
+
+impl Error for ParseError {}
+
+impl From<ParseError> for BfError {
+    fn from(error: ParseError) -> BfError {
+        BfError::Parse(Box::new(error))
+    }
+}
+
+/// Parses the given source code and constructs a `Bot` that runs the resulting program. The
+/// `ParseError` that explains why the source code is invalid is the `source` of the error.
+///
+/// # Examples
+///
+/// ```
+/// use bf_bot_compiler::parser;
+/// assert_eq!(parser::parse(">+[-]").unwrap().get_program().len(), 5);
+/// assert_eq!(parser::parse("(>)*3([{}-])%2").unwrap().get_program().len(), 9);
+/// assert!(parser::parse(">+[-").is_err());
+/// ```
+pub fn parse(source_code: &str) -> Result<Bot, BfError> {
+    Ok(parse_bot(source_code).map(Bot::new)?)
+}
+
+/// Types that can be constructed from `BrainFuck` source code.
+pub trait FromSource: Sized {
+    fn from_source(source_code: &str) -> Result<Self, BfError>;
+}
+
+/// Makes it possible to write `Bot::from_source(...)`, which is the counterpart of
+/// `Bot::to_source()`.
+///
+/// # Examples
+///
+/// ```
+/// extern crate bf_bot_core;
+/// extern crate bf_bot_compiler;
+/// use bf_bot_core::bf::Bot;
+/// use bf_bot_compiler::parser::FromSource;
+/// let bot = Bot::from_source("(>)*9 [-] this is a comment").unwrap();
+/// assert_eq!(bot.to_source(), ">>>>>>>>>[-]");
+/// assert_eq!(Bot::from_source(&bot.to_source()).unwrap(), bot);
+/// ```
+impl FromSource for Bot {
+    fn from_source(source_code: &str) -> Result<Bot, BfError> {
+        parse(source_code)
+    }
+}
+
+/// Parses the given source code into a list of instructions. Any character that is not part of
+/// the language is treated as a comment.
+pub fn parse_bot(source_code: &str) -> Result<Vec<Instruction>, ParseError> {
+    let tokens = Expander::new(source_code).expand()?;
+    resolve_loops(&tokens)
+}
+
+/// A character that stands for an instruction, together with its location in the source code.
+/// After expansion, several tokens may share the same location.
+#[derive(Debug, Clone, Copy)]
+struct Token {
+    character: char,
+    location: CodeLocation,
+}
+
+/// The character that caused `Expander::read_sequence` to stop reading.
+enum Terminator {
+    EndOfInput,
+    /// A `)`, `{` or `}` character, together with its location.
+    Character(char, CodeLocation),
+}
+
+/// Reads the source code and expands all repetitions, which leaves a flat list of tokens.
+struct Expander<'a> {
+    chars: Peekable<Chars<'a>>,
+    /// The location of the next character that will be read.
+    location: CodeLocation,
+    /// The number of repetitions that are being read at the moment.
+    nesting_depth: usize,
+}
+
+impl<'a> Expander<'a> {
+    fn new(source_code: &str) -> Expander<'_> {
+        Expander {
+            chars: source_code.chars().peekable(),
+            location: CodeLocation::default(),
+            nesting_depth: 0,
+        }
+    }
+
+    fn expand(mut self) -> Result<Vec<Token>, ParseError> {
+        match self.read_sequence()? {
+            (tokens, Terminator::EndOfInput) => Ok(tokens),
+            (_, Terminator::Character('{', location)) => {
+                Err(ParseError::new(ParseErrorKind::MisplacedInnerBlock, '{', location))
+            }
+            (_, Terminator::Character(character, location)) => {
+                Err(ParseError::new(ParseErrorKind::UnmatchedClosingBracket, character, location))
+            }
+        }
+    }
+
+    /// Consumes the next character, returning it together with its location.
+    fn next_char(&mut self) -> Option<(char, CodeLocation)> {
+        let location = self.location;
+        self.chars.next().map(|character| {
+            self.location = location.advance(character);
+            (character, location)
+        })
+    }
+
+    /// Reads tokens until the end of the input, or until a character is found that ends the
+    /// current part of a repetition. Nested repetitions are expanded along the way.
+    fn read_sequence(&mut self) -> Result<(Vec<Token>, Terminator), ParseError> {
+        let mut tokens = vec![];
+        while let Some((character, location)) = self.next_char() {
             match character {
-                '<' => instructions.push(Instruction::MoveBack),
-                '>' => instructions.push(Instruction::MoveForward),
-                '+' => instructions.push(Instruction::Increment),
-                '-' => instructions.push(Instruction::Decrement),
-                '.' => instructions.push(Instruction::SkipExecution),
-                '[' => {
-                    open_bracket(&mut bracket_stack,
-                                 &mut instructions,
-                                 start_while_not_zero_placeholder)
+                '<' | '>' | '+' | '-' | '.' | '[' | ']' => {
+                    tokens.push(Token {
+                        character,
+                        location,
+                    })
+                }
+                '(' => {
+                    if self.nesting_depth == MAX_NESTING_DEPTH {
+                        return Err(ParseError::new(ParseErrorKind::NestedTooDeeply,
+                                                   '(',
+                                                   location));
+                    }
+                    self.nesting_depth += 1;
+                    let expansion = self.read_repetition(location)?;
+                    self.nesting_depth -= 1;
+                    append_checked(&mut tokens, &expansion, 1, location)?;
                 }
-                ']' => close_square_bracket(&mut bracket_stack, &mut instructions),
-                '(' => open_bracket(&mut bracket_stack, &mut instructions, start_for_placeholder),
-                ')' => {
-                    close_round_bracket(&mut bracket_stack, &mut instructions, &source_code, index)
+                ')' | '{' | '}' => {
+                    return Ok((tokens, Terminator::Character(character, location)));
                 }
                 _ => {
                     //Comment character, ignore.
                 }
-            };
-            (bracket_stack, instructions)
-        });
-    assert!(bracket_stack.is_empty(), "Unmatched opening bracket(s).");
-    instructions
-}
+            }
+        }
+        Ok((tokens, Terminator::EndOfInput))
+    }
 
-fn open_bracket(bracket_stack: &mut Vec<usize>,
-                instructions: &mut Vec<Instruction>,
-                make_instruction: fn() -> Instruction) {
-    bracket_stack.push(instructions.len());
-    instructions.push(make_instruction());
-}
+    /// Reads the rest of a repetition whose opening bracket was found at the given location, up to
+    /// and including the number of iterations, and returns its expansion.
+    fn read_repetition(&mut self,
+                       opening_location: CodeLocation)
+                       -> Result<Vec<Token>, ParseError> {
+        let (before, inner_block, closing_location) = match self.read_sequence()? {
+            (before, Terminator::Character(')', location)) => (before, None, location),
+            (before, Terminator::Character('{', brace_location)) => {
+                let inner = self.read_inner_block(brace_location)?;
+                let (after, location) = self.read_after_inner_block(opening_location)?;
+                (before, Some((inner, after, brace_location)), location)
+            }
+            (_, Terminator::Character(character, location)) => {
+                return Err(ParseError::new(ParseErrorKind::UnmatchedClosingBracket,
+                                           character,
+                                           location))
+            }
+            (_, Terminator::EndOfInput) => {
+                return Err(ParseError::new(ParseErrorKind::UnmatchedOpeningBracket,
+                                           '(',
+                                           opening_location))
+            }
+        };
+        let (operator, nr_iterations) = self.read_nr_iterations(closing_location)?;
+        let mut expansion = vec![];
+        append_checked(&mut expansion, &before, nr_iterations, closing_location)?;
+        if let Some((inner, after, brace_location)) = inner_block {
+            if operator != '%' {
+                return Err(ParseError::new(ParseErrorKind::MisplacedInnerBlock,
+                                           '{',
+                                           brace_location));
+            }
+            append_checked(&mut expansion, &inner, 1, closing_location)?;
+            append_checked(&mut expansion, &after, nr_iterations, closing_location)?;
+        }
+        Ok(expansion)
+    }
 
-fn close_square_bracket(bracket_stack: &mut Vec<usize>, instructions: &mut Vec<Instruction>) {
-    let opening_index: Option<usize> = bracket_stack.pop();
-    match opening_index {
-        Some(value) => {
-            assert_eq!(instructions[value], start_while_not_zero_placeholder());
-            instructions[value] =
-                Instruction::StartWhileNotZero { target_pointer: instructions.len() };
-            instructions.push(Instruction::EndWhileNotZero { target_pointer: value });
+    /// Reads the contents of an inner block whose opening brace was found at the given location.
+    fn read_inner_block(&mut self,
+                        opening_location: CodeLocation)
+                        -> Result<Vec<Token>, ParseError> {
+        match self.read_sequence()? {
+            (inner, Terminator::Character('}', _)) => Ok(inner),
+            (_, Terminator::Character('{', location)) => {
+                Err(ParseError::new(ParseErrorKind::MisplacedInnerBlock, '{', location))
+            }
+            (_, Terminator::Character(character, location)) => {
+                Err(ParseError::new(ParseErrorKind::UnmatchedClosingBracket, character, location))
+            }
+            (_, Terminator::EndOfInput) => {
+                Err(ParseError::new(ParseErrorKind::UnmatchedOpeningBracket,
+                                    '{',
+                                    opening_location))
+            }
         }
-        None => panic!("Unmatched square closing bracket."),
     }
-}
 
-//TODO: Give only the remaining part of the string slice, to cut performance down to O(N).
-fn close_round_bracket(bracket_stack: &mut Vec<usize>,
-                       instructions: &mut Vec<Instruction>,
-                       source_code: &str,
-                       index: usize) {
-    let opening_index: Option<usize> = bracket_stack.pop();
-    match opening_index {
-        Some(value) => {
-            assert_eq!(instructions[value], start_for_placeholder());
-            instructions[value] = Instruction::StartFor { target_pointer: instructions.len() };
-            instructions.push(Instruction::EndFor {
-                target_pointer: value,
-                nr_iterations: get_nr_iterations(source_code, index),
-            });
+    /// Reads the part of a repetition that follows its inner block, up to the closing round
+    /// bracket. Returns the tokens together with the location of that closing bracket.
+    fn read_after_inner_block(&mut self,
+                              opening_location: CodeLocation)
+                              -> Result<(Vec<Token>, CodeLocation), ParseError> {
+        match self.read_sequence()? {
+            (after, Terminator::Character(')', location)) => Ok((after, location)),
+            (_, Terminator::Character('{', location)) => {
+                Err(ParseError::new(ParseErrorKind::MisplacedInnerBlock, '{', location))
+            }
+            (_, Terminator::Character(character, location)) => {
+                Err(ParseError::new(ParseErrorKind::UnmatchedClosingBracket, character, location))
+            }
+            (_, Terminator::EndOfInput) => {
+                Err(ParseError::new(ParseErrorKind::UnmatchedOpeningBracket,
+                                    '(',
+                                    opening_location))
+            }
         }
-        None => panic!("Unmatched square closing bracket."),
     }
-}
 
-fn get_nr_iterations(source_code: &str, index: usize) -> usize {
-    let mut chars = source_code.chars().skip(index + 1);
-    assert_eq!(chars.next(),
-               Some('*'),
-               "Error. ')' must be followed by an asterisk.");
-    chars.take_while(|character| character.is_digit(10))
-        .collect::<String>()
-        .parse::<usize>()
-        .expect("Error. A for loop '(...)*' should be followed by a number that signifies its \
-                 number of iterations.")
+    /// Reads the `*n` or `%n` suffix that must follow the closing round bracket at the given
+    /// location, which was just consumed. Returns the operator and the number of iterations.
+    fn read_nr_iterations(&mut self,
+                          closing_location: CodeLocation)
+                          -> Result<(char, usize), ParseError> {
+        let operator = match self.chars.peek() {
+            Some(&character) if character == '*' || character == '%' => character,
+            _ => {
+                return Err(ParseError::new(ParseErrorKind::MissingRepetitionOperator,
+                                           ')',
+                                           closing_location))
+            }
+        };
+        self.next_char();
+        let mut digits = String::new();
+        while let Some(&character) = self.chars.peek() {
+            if !character.is_ascii_digit() {
+                break;
+            }
+            digits.push(character);
+            self.next_char();
+        }
+        digits.parse::<usize>()
+            .map(|nr_iterations| (operator, nr_iterations))
+            .map_err(|_| {
+                ParseError::new(ParseErrorKind::MissingNrIterations, ')', closing_location)
+            })
+    }
 }
 
-fn start_while_not_zero_placeholder() -> Instruction {
-    Instruction::StartWhileNotZero { target_pointer: <usize>::max_value() }
+/// Appends the given tokens to the expansion the given number of times, unless that would make
+/// the expansion longer than `MAX_EXPANDED_LENGTH`.
+fn append_checked(expansion: &mut Vec<Token>,
+                  tokens: &[Token],
+                  nr_iterations: usize,
+                  location: CodeLocation)
+                  -> Result<(), ParseError> {
+    let added_length = tokens.len().saturating_mul(nr_iterations);
+    if expansion.len().saturating_add(added_length) > MAX_EXPANDED_LENGTH {
+        return Err(ParseError::new(ParseErrorKind::ProgramTooLong, ')', location));
+    }
+    for _ in 0..nr_iterations {
+        expansion.extend_from_slice(tokens);
+    }
+    Ok(())
 }
 
-fn start_for_placeholder() -> Instruction {
-    Instruction::StartFor { target_pointer: <usize>::max_value() }
+/// Turns the expanded tokens into instructions, pointing each loop bracket at its counterpart.
+fn resolve_loops(tokens: &[Token]) -> Result<Vec<Instruction>, ParseError> {
+    let mut bracket_stack: Vec<(usize, &Token)> = vec![];
+    let mut instructions = Vec::with_capacity(tokens.len());
+    for token in tokens {
+        let instruction = match token.character {
+            '<' => Instruction::MoveBack,
+            '>' => Instruction::MoveForward,
+            '+' => Instruction::Increment,
+            '-' => Instruction::Decrement,
+            '.' => Instruction::SkipExecution,
+            '[' => {
+                bracket_stack.push((instructions.len(), token));
+                Instruction::JumpIfZero(usize::MAX)
+            }
+            ']' => {
+                match bracket_stack.pop() {
+                    Some((opening_index, _)) => {
+                        instructions[opening_index] = Instruction::JumpIfZero(instructions.len());
+                        Instruction::JumpIfNonZero(opening_index)
+                    }
+                    None => {
+                        return Err(ParseError::new(ParseErrorKind::UnmatchedClosingBracket,
+                                                   ']',
+                                                   token.location))
+                    }
+                }
+            }
+            _ => unreachable!("The expander only emits instruction characters."),
+        };
+        instructions.push(instruction);
+    }
+    match bracket_stack.pop() {
+        Some((_, token)) => {
+            Err(ParseError::new(ParseErrorKind::UnmatchedOpeningBracket, '[', token.location))
+        }
+        None => Ok(instructions),
+    }
 }
 
 #[cfg(test)]
@@ -96,19 +412,20 @@ fn start_for_placeholder() -> Instruction {
 mod tests {
     use super::*;
     use bf_bot_core::bf::Instruction;
+    use code_location::CodeLocation;
 
     #[test]
     fn parseBot_emptyBotCode_shouldReturnEmptyVec() {
         let input: String = "".to_string();
         let expected: Vec<Instruction> = vec![];
-        assert_eq!(&expected, &parse_bot(input));
+        assert_eq!(Ok(expected), parse_bot(&input));
     }
 
     #[test]
     fn parseBot_dot_shouldReturnSkipExecution() {
         let input: String = ".".to_string();
         let expected: Vec<Instruction> = vec![Instruction::SkipExecution];
-        assert_eq!(&expected, &parse_bot(input));
+        assert_eq!(Ok(expected), parse_bot(&input));
     }
 
     /// Note that this test is not exhaustive, the program should ignore all ascii
@@ -118,7 +435,7 @@ mod tests {
         let input: String = "abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789"
             .to_string();
         let expected: Vec<Instruction> = vec![];
-        assert_eq!(&expected, &parse_bot(input));
+        assert_eq!(Ok(expected), parse_bot(&input));
     }
 
     /// In Brainfuck, the comma character is a valid instruction. It accepts a byte of input and
@@ -128,149 +445,355 @@ mod tests {
     fn parseBot_comma_shouldReturnEmptyVec() {
         let input: String = ",".to_string();
         let expected: Vec<Instruction> = vec![];
-        assert_eq!(&expected, &parse_bot(input));
+        assert_eq!(Ok(expected), parse_bot(&input));
     }
 
     #[test]
     fn parseBot_smallerThanSign_shouldReturnMoveBack() {
         let input: String = "<".to_string();
         let expected: Vec<Instruction> = vec![Instruction::MoveBack];
-        assert_eq!(&expected, &parse_bot(input));
+        assert_eq!(Ok(expected), parse_bot(&input));
     }
 
     #[test]
     fn parseBot_greaterThanSign_shouldReturnMoveForward() {
         let input: String = ">".to_string();
         let expected: Vec<Instruction> = vec![Instruction::MoveForward];
-        assert_eq!(&expected, &parse_bot(input));
+        assert_eq!(Ok(expected), parse_bot(&input));
     }
 
     #[test]
     fn parseBot_minusSign_shouldReturnDecrement() {
         let input: String = "-".to_string();
         let expected: Vec<Instruction> = vec![Instruction::Decrement];
-        assert_eq!(&expected, &parse_bot(input));
+        assert_eq!(Ok(expected), parse_bot(&input));
     }
 
     #[test]
     fn parseBot_plusSign_shouldReturnIncrement() {
         let input: String = "+".to_string();
         let expected: Vec<Instruction> = vec![Instruction::Increment];
-        assert_eq!(&expected, &parse_bot(input));
+        assert_eq!(Ok(expected), parse_bot(&input));
     }
 
     #[test]
-    #[should_panic]
-    fn parseBot_unmatchedSquareOpeningBracket_shouldPanic() {
+    fn parseBot_unmatchedSquareOpeningBracket_returnsError() {
         let input: String = "[".to_string();
-        parse_bot(input);
+        let expected = ParseError::new(ParseErrorKind::UnmatchedOpeningBracket,
+                                       '[',
+                                       CodeLocation::new(1, 1));
+        assert_eq!(Err(expected), parse_bot(&input));
     }
 
     #[test]
-    #[should_panic]
-    fn parseBot_unmatchedSquareClosingBracket_shouldPanic() {
+    fn parseBot_unmatchedSquareClosingBracket_returnsError() {
         let input: String = "]".to_string();
-        parse_bot(input);
+        let expected = ParseError::new(ParseErrorKind::UnmatchedClosingBracket,
+                                       ']',
+                                       CodeLocation::new(1, 1));
+        assert_eq!(Err(expected), parse_bot(&input));
     }
 
     #[test]
-    fn parseBot_squareBrackets_returnsWhileNotZeroLoop() {
+    fn parseBot_squareBrackets_returnsLoop() {
         let input: String = "[]".to_string();
-        let expected: Vec<Instruction> = vec![Instruction::StartWhileNotZero { target_pointer: 1 },
-                                              Instruction::EndWhileNotZero { target_pointer: 0 }];
-        assert_eq!(&expected, &parse_bot(input));
+        let expected: Vec<Instruction> = vec![Instruction::JumpIfZero(1),
+                                              Instruction::JumpIfNonZero(0)];
+        assert_eq!(Ok(expected), parse_bot(&input));
     }
 
     #[test]
-    fn parseBot_nestedSquareBrackets_returnsNestedWhileNotZeroLoop() {
+    fn parseBot_nestedSquareBrackets_returnsNestedLoop() {
         let input: String = "[[]]".to_string();
-        let expected: Vec<Instruction> = vec![Instruction::StartWhileNotZero { target_pointer: 3 },
-                                              Instruction::StartWhileNotZero { target_pointer: 2 },
-                                              Instruction::EndWhileNotZero { target_pointer: 1 },
-                                              Instruction::EndWhileNotZero { target_pointer: 0 }];
-        assert_eq!(&expected, &parse_bot(input));
+        let expected: Vec<Instruction> = vec![Instruction::JumpIfZero(3),
+                                              Instruction::JumpIfZero(2),
+                                              Instruction::JumpIfNonZero(1),
+                                              Instruction::JumpIfNonZero(0)];
+        assert_eq!(Ok(expected), parse_bot(&input));
     }
 
     #[test]
-    #[should_panic]
-    fn parseBot_unmatchedNestedSquareOpeningBracket_shouldPanic() {
+    fn parseBot_unmatchedNestedSquareOpeningBracket_returnsError() {
         let input: String = "[[]".to_string();
-        parse_bot(input);
+        let expected = ParseError::new(ParseErrorKind::UnmatchedOpeningBracket,
+                                       '[',
+                                       CodeLocation::new(1, 1));
+        assert_eq!(Err(expected), parse_bot(&input));
     }
 
     #[test]
-    #[should_panic]
-    fn parseBot_unmatchedNestedSquareClosingBracket_shouldPanic() {
+    fn parseBot_unmatchedNestedSquareClosingBracket_returnsError() {
         let input: String = "[]]".to_string();
-        parse_bot(input);
+        let expected = ParseError::new(ParseErrorKind::UnmatchedClosingBracket,
+                                       ']',
+                                       CodeLocation::new(1, 3));
+        assert_eq!(Err(expected), parse_bot(&input));
     }
 
     #[test]
-    #[should_panic]
-    fn parseBot_unmatchedRoundOpeningBracket_shouldPanic() {
+    fn parseBot_unmatchedRoundOpeningBracket_returnsError() {
         let input: String = "(".to_string();
-        parse_bot(input);
+        let expected = ParseError::new(ParseErrorKind::UnmatchedOpeningBracket,
+                                       '(',
+                                       CodeLocation::new(1, 1));
+        assert_eq!(Err(expected), parse_bot(&input));
     }
 
     #[test]
-    #[should_panic]
-    fn parseBot_unmatchedRoundClosingBracket_shouldPanic() {
+    fn parseBot_unmatchedRoundClosingBracket_returnsError() {
         let input: String = ")".to_string();
-        parse_bot(input);
+        let expected = ParseError::new(ParseErrorKind::UnmatchedClosingBracket,
+                                       ')',
+                                       CodeLocation::new(1, 1));
+        assert_eq!(Err(expected), parse_bot(&input));
     }
 
     #[test]
-    #[should_panic]
-    fn parseBot_wrongTypeOfBracketRoundSquare_shouldPanic() {
+    fn parseBot_wrongTypeOfBracketRoundSquare_returnsError() {
         let input: String = "(]".to_string();
-        parse_bot(input);
+        let expected = ParseError::new(ParseErrorKind::UnmatchedOpeningBracket,
+                                       '(',
+                                       CodeLocation::new(1, 1));
+        assert_eq!(Err(expected), parse_bot(&input));
     }
 
     #[test]
-    #[should_panic]
-    fn parseBot_wrongTypeOfBracketSquareRound_shouldPanic() {
+    fn parseBot_wrongTypeOfBracketSquareRound_returnsError() {
         let input: String = "[)".to_string();
-        parse_bot(input);
+        let expected = ParseError::new(ParseErrorKind::UnmatchedClosingBracket,
+                                       ')',
+                                       CodeLocation::new(1, 2));
+        assert_eq!(Err(expected), parse_bot(&input));
     }
 
     #[test]
-    #[should_panic]
-    fn parseBot_roundClosingBraceNotFollowedByAsterisk_shouldPanic() {
+    fn parseBot_roundClosingBraceNotFollowedByAsterisk_returnsError() {
         let input: String = "()10".to_string();
-        parse_bot(input);
+        let expected = ParseError::new(ParseErrorKind::MissingRepetitionOperator,
+                                       ')',
+                                       CodeLocation::new(1, 2));
+        assert_eq!(Err(expected), parse_bot(&input));
+    }
+
+    #[test]
+    fn parseBot_roundBrackets_repeatsBody() {
+        let input: String = "(+)*1".to_string();
+        let expected: Vec<Instruction> = vec![Instruction::Increment; 1];
+        assert_eq!(Ok(expected), parse_bot(&input));
+    }
+
+    #[test]
+    fn parseBot_roundBracketsDifferentNumberIterations_repeatsBody() {
+        let input: String = "(+)*2".to_string();
+        let expected: Vec<Instruction> = vec![Instruction::Increment; 2];
+        assert_eq!(Ok(expected), parse_bot(&input));
+    }
+
+    #[test]
+    fn parseBot_roundBracketsNrIterationsDoubleDigits_repeatsBody() {
+        let input: String = "(+)*10".to_string();
+        let expected: Vec<Instruction> = vec![Instruction::Increment; 10];
+        assert_eq!(Ok(expected), parse_bot(&input));
+    }
+
+    #[test]
+    fn parseBot_asteriskNotFollowedByNumber_returnsError() {
+        let input: String = "()*a".to_string();
+        let expected = ParseError::new(ParseErrorKind::MissingNrIterations,
+                                       ')',
+                                       CodeLocation::new(1, 2));
+        assert_eq!(Err(expected), parse_bot(&input));
+    }
+
+    #[test]
+    fn parseBot_errorOnSecondLine_reportsLineAndColumn() {
+        let input: String = "[-]  clear\n>>]".to_string();
+        let expected = ParseError::new(ParseErrorKind::UnmatchedClosingBracket,
+                                       ']',
+                                       CodeLocation::new(2, 3));
+        assert_eq!(Err(expected), parse_bot(&input));
+    }
+
+    #[test]
+    fn parse_validSourceCode_returnsBotWithProgram() {
+        let bot = parse(">-").unwrap();
+        let expected: Vec<Instruction> = vec![Instruction::MoveForward, Instruction::Decrement];
+        assert_eq!(&expected, bot.get_program());
+    }
+
+    #[test]
+    fn parseError_display_mentionsCharacterAndLocation() {
+        let error = ParseError::new(ParseErrorKind::UnmatchedOpeningBracket,
+                                    '[',
+                                    CodeLocation::new(3, 7));
+        assert_eq!("Unmatched opening bracket '[' on line 3, column 7.",
+                   error.to_string());
+    }
+
+    #[test]
+    fn parseBot_repetitionOfZero_removesBody() {
+        let input: String = "(+)*0-".to_string();
+        let expected: Vec<Instruction> = vec![Instruction::Decrement];
+        assert_eq!(Ok(expected), parse_bot(&input));
+    }
+
+    #[test]
+    fn parseBot_nestedRepetitions_multipliesCounts() {
+        let input: String = "((+)*2>)*3".to_string();
+        assert_eq!(Ok(9), parse_bot(&input).map(|instructions| instructions.len()));
+    }
+
+    #[test]
+    fn parseBot_repetitionContainingLoop_repeatsLoop() {
+        let input: String = "([-])*2".to_string();
+        let expected: Vec<Instruction> = vec![Instruction::JumpIfZero(2),
+                                              Instruction::Decrement,
+                                              Instruction::JumpIfNonZero(0),
+                                              Instruction::JumpIfZero(5),
+                                              Instruction::Decrement,
+                                              Instruction::JumpIfNonZero(3)];
+        assert_eq!(Ok(expected), parse_bot(&input));
+    }
+
+    #[test]
+    fn parseBot_percentWithoutInnerBlock_repeatsBody() {
+        let input: String = "(>)%3".to_string();
+        let expected: Vec<Instruction> = vec![Instruction::MoveForward; 3];
+        assert_eq!(Ok(expected), parse_bot(&input));
+    }
+
+    #[test]
+    fn parseBot_percentWithInnerBlock_repeatsOuterPartsAroundInnerBlock() {
+        let input: String = "(+{.}-)%2".to_string();
+        let expected: Vec<Instruction> = vec![Instruction::Increment,
+                                              Instruction::Increment,
+                                              Instruction::SkipExecution,
+                                              Instruction::Decrement,
+                                              Instruction::Decrement];
+        assert_eq!(Ok(expected), parse_bot(&input));
+    }
+
+    #[test]
+    fn parseBot_loopBracketsSplitByInnerBlock_returnsNestedLoops() {
+        let input: String = "([{}])%2".to_string();
+        let expected: Vec<Instruction> = vec![Instruction::JumpIfZero(3),
+                                              Instruction::JumpIfZero(2),
+                                              Instruction::JumpIfNonZero(1),
+                                              Instruction::JumpIfNonZero(0)];
+        assert_eq!(Ok(expected), parse_bot(&input));
+    }
+
+    #[test]
+    fn parseBot_innerBlockWithAsterisk_returnsError() {
+        let input: String = "(+{-}+)*2".to_string();
+        let expected = ParseError::new(ParseErrorKind::MisplacedInnerBlock,
+                                       '{',
+                                       CodeLocation::new(1, 3));
+        assert_eq!(Err(expected), parse_bot(&input));
+    }
+
+    #[test]
+    fn parseBot_innerBlockOutsideRepetition_returnsError() {
+        let input: String = "+{-}".to_string();
+        let expected = ParseError::new(ParseErrorKind::MisplacedInnerBlock,
+                                       '{',
+                                       CodeLocation::new(1, 2));
+        assert_eq!(Err(expected), parse_bot(&input));
+    }
+
+    #[test]
+    fn parseBot_twoInnerBlocksInOneRepetition_returnsError() {
+        let input: String = "({}{})%2".to_string();
+        let expected = ParseError::new(ParseErrorKind::MisplacedInnerBlock,
+                                       '{',
+                                       CodeLocation::new(1, 4));
+        assert_eq!(Err(expected), parse_bot(&input));
+    }
+
+    #[test]
+    fn parseBot_unmatchedOpeningBrace_returnsError() {
+        let input: String = "(+{-)%2".to_string();
+        let expected = ParseError::new(ParseErrorKind::UnmatchedClosingBracket,
+                                       ')',
+                                       CodeLocation::new(1, 5));
+        assert_eq!(Err(expected), parse_bot(&input));
+    }
+
+    #[test]
+    fn parseBot_unclosedInnerBlock_returnsError() {
+        let input: String = "(+{-".to_string();
+        let expected = ParseError::new(ParseErrorKind::UnmatchedOpeningBracket,
+                                       '{',
+                                       CodeLocation::new(1, 3));
+        assert_eq!(Err(expected), parse_bot(&input));
+    }
+
+    #[test]
+    fn parseBot_expansionTooLong_returnsError() {
+        let input: String = "(((+)*1000)*1000)*1000".to_string();
+        assert_eq!(Err(ParseErrorKind::ProgramTooLong),
+                   parse_bot(&input).map_err(|error| error.kind));
+    }
+
+    #[test]
+    fn parseError_programTooLong_displaysFullSentence() {
+        let error = parse_bot("(((+)*1000)*1000)*1000").unwrap_err();
+        assert_eq!("Program expands to too many instructions after closing bracket ')' on line \
+                    1, column 17.",
+                   error.to_string());
     }
 
     #[test]
-    fn parseBot_roundBrackets_returnsForLoop() {
-        let input: String = "()*1".to_string();
-        let expected: Vec<Instruction> = vec![Instruction::StartFor { target_pointer: 1 },
-                                              Instruction::EndFor {
-                                                  target_pointer: 0,
-                                                  nr_iterations: 1,
-                                              }];
-        assert_eq!(&expected, &parse_bot(input));
+    fn parseBot_deepestAllowedNesting_parsesSuccessfully() {
+        let input = format!("{}+{}",
+                            "(".repeat(MAX_NESTING_DEPTH),
+                            ")*1".repeat(MAX_NESTING_DEPTH));
+        assert_eq!(Ok(vec![Instruction::Increment]), parse_bot(&input));
     }
 
     #[test]
-    fn parseBot_roundBracketsDifferentNumberIterations_returnsForLoop() {
-        let input: String = "()*2".to_string();
-        let expected: Vec<Instruction> = vec![Instruction::StartFor { target_pointer: 1 },
-                                              Instruction::EndFor {
-                                                  target_pointer: 0,
-                                                  nr_iterations: 2,
-                                              }];
-        assert_eq!(&expected, &parse_bot(input));
+    fn parseBot_nestedTooDeeply_returnsErrorInsteadOfOverflowingStack() {
+        let input = "(".repeat(10_000);
+        let expected = ParseError::new(ParseErrorKind::NestedTooDeeply,
+                                       '(',
+                                       CodeLocation::new(1, MAX_NESTING_DEPTH as u32 + 1));
+        assert_eq!(Err(expected), parse_bot(&input));
     }
 
     #[test]
-    fn parseBot_roundBracketsNrIterationsDoubleDigits_returnsForLoop() {
-        let input: String = "()*10".to_string();
-        let expected: Vec<Instruction> = vec![Instruction::StartFor { target_pointer: 1 },
-                                              Instruction::EndFor {
-                                                  target_pointer: 0,
-                                                  nr_iterations: 10,
-                                              }];
-        assert_eq!(&expected, &parse_bot(input));
+    fn parseBot_exampleBots_parseSuccessfully() {
+        assert!(parse_bot(include_str!("../../bots/DecoyBot.bf")).is_ok());
+        assert!(parse_bot(include_str!("../../bots/FastClearBot.bf")).is_ok());
+    }
+
+    #[test]
+    fn fromSource_toSource_roundTripsExampleBots() {
+        for source in &[include_str!("../../bots/DecoyBot.bf"),
+                        include_str!("../../bots/FastClearBot.bf")] {
+            let bot = Bot::from_source(source).unwrap();
+            assert_eq!(Bot::from_source(&bot.to_source()).unwrap(), bot);
+        }
     }
 
+    #[test]
+    fn toSource_fromSource_keepsCanonicalSourceIntact() {
+        let canonical = "<>+-.[[-]>]";
+        assert_eq!(Bot::from_source(canonical).unwrap().to_source(), canonical);
+    }
+
+    #[test]
+    fn toSource_stripsCommentsAndExpandsRepetitions() {
+        let bot = Bot::from_source("(+)*3 increment thrice\n(-{.}>)%2").unwrap();
+        assert_eq!(bot.to_source(), "+++--.>>");
+    }
+
+    #[test]
+    fn parse_invalid_parseErrorIsTheSource() {
+        let error = parse("[").unwrap_err();
+        assert_eq!(error.to_string(),
+                   format!("Parse error: {}", parse_bot("[").unwrap_err()));
+        let source = error.source().and_then(|source| source.downcast_ref::<ParseError>());
+        assert_eq!(source.map(|source| source.kind),
+                   Some(ParseErrorKind::UnmatchedOpeningBracket));
+    }
 }
@@ -0,0 +1,48 @@
+//! Measures how fast the arena steps through the canonical pairings, with every execution engine
+//! that is enabled. Run with `cargo bench`, and add `--features closure-engine` to compare the
+//! engines.
+
+#[macro_use]
+extern crate criterion;
+extern crate bf_bot_core;
+
+use bf_bot_core::bench;
+use bf_bot_core::match_runner::Match;
+use bf_bot_core::simul_round::ExecutionEngine;
+use criterion::Criterion;
+use std::hint::black_box;
+
+const NR_STEPS: u32 = 10_000;
+const TAPE_LENGTH: u32 = 20;
+
+fn engines() -> Vec<ExecutionEngine> {
+    vec![ExecutionEngine::Bytecode,
+         #[cfg(feature = "closure-engine")]
+         ExecutionEngine::Closures]
+}
+
+fn stepping(c: &mut Criterion) {
+    let mut group = c.benchmark_group("stepping");
+    for pairing in bench::canonical_pairings() {
+        for engine in engines() {
+            let params = bench::bench_params(TAPE_LENGTH, engine);
+            group.bench_function(format!("{}/{:?}", pairing.name, engine), |b| {
+                b.iter(|| bench::run_steps(&pairing.bot_a, &pairing.bot_b, &params, NR_STEPS))
+            });
+        }
+    }
+    group.finish();
+}
+
+fn complete_match(c: &mut Criterion) {
+    let mut group = c.benchmark_group("complete match");
+    for pairing in bench::canonical_pairings() {
+        group.bench_function(pairing.name, |b| {
+            b.iter(|| black_box(Match::new(&pairing.bot_a, &pairing.bot_b).run().unwrap().score()))
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, stepping, complete_match);
+criterion_main!(benches);
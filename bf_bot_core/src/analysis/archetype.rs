@@ -0,0 +1,29 @@
+use std::fmt;
+
+/// The kind of strategy that a bot follows, as recognized by `classify`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Archetype {
+    /// Moves straight to where the enemy flag can first be, without looking at the cells on the
+    /// way, and clears what it finds there.
+    Rush,
+    /// Stays on its own half of the tape, protecting its flag or waiting for the enemy to make a
+    /// mistake.
+    Defensive,
+    /// Changes cells that are already zero, so it clears without checking whether there is
+    /// anything to clear.
+    CarelessClear,
+    /// Searches the tape from its own flag onwards, checking every cell for decoys and flags.
+    Scanner,
+}
+
+impl fmt::Display for Archetype {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match *self {
+            Archetype::Rush => "rush",
+            Archetype::Defensive => "defensive",
+            Archetype::CarelessClear => "careless clear",
+            Archetype::Scanner => "scanner",
+        };
+        write!(f, "{}", name)
+    }
+}
@@ -0,0 +1,113 @@
+use analysis::Archetype;
+use bf::Bot;
+use engine::{Arena, Orientation};
+use simul_game::{MAX_TAPE_LENGTH, MIN_TAPE_LENGTH};
+use simul_round::RoundParams;
+
+/// The maximum number of steps of the probe round.
+const PROBE_MAX_STEPS: u32 = 10_000;
+
+/// The number of cells on the enemy half of the tape that a bot must change, while they are
+/// zero, to be considered careless.
+const MIN_CARELESS_WRITES: usize = 3;
+
+/// Guesses the strategy of the given bot, by watching it play alone on the longest tape against an
+/// opponent that does nothing. The first of these rules that applies decides:
+///
+/// * A bot that never gets past the middle of the tape is defensive.
+/// * A bot that changes cells on the enemy half, other than the flag, is a careless clear: the
+///   enemy built nothing there, so the cells were zero.
+/// * A bot that checks most of the cells right in front of its own flag, where the enemy flag can
+///   never be, is a scanner.
+/// * Any other bot is a rush.
+///
+/// # Examples
+///
+/// ```
+/// use bf_bot_core::analysis::{self, Archetype};
+/// use bf_bot_core::bf::{Bot, Instruction};
+/// let idle = Bot::new(vec![Instruction::SkipExecution]);
+/// assert_eq!(analysis::classify(&idle), Archetype::Defensive);
+/// ```
+pub fn classify(bot: &Bot) -> Archetype {
+    let idle = Bot::new(vec![]);
+    let round_params = RoundParams::new(MAX_TAPE_LENGTH, false, PROBE_MAX_STEPS);
+    let mut arena = Arena::new(bot, &idle, &round_params).expect("The longest tape is valid.");
+    arena.attach_heatmap();
+    let mut furthest_position = 0;
+    let mut outcome = arena.step();
+    while !outcome.round_is_finished() {
+        furthest_position = furthest_position.max(arena.get_start_bot_pointers().position);
+        outcome = arena.step();
+    }
+    furthest_position = furthest_position.max(arena.get_start_bot_pointers().position);
+    let heatmap = arena.take_heatmap().expect("A heatmap was attached.");
+    let counts = heatmap.of_bot(Orientation::Normal);
+    let middle = MAX_TAPE_LENGTH as usize / 2;
+    let enemy_flag = MAX_TAPE_LENGTH as usize - 1;
+    if (furthest_position as usize) < middle {
+        return Archetype::Defensive;
+    }
+    let careless_writes = (middle..enemy_flag).filter(|&index| counts.writes[index] > 0).count();
+    if careless_writes >= MIN_CARELESS_WRITES {
+        return Archetype::CarelessClear;
+    }
+    // The cells between the own flag and the first cell that can hold the enemy flag.
+    let near_cells = 1..MIN_TAPE_LENGTH as usize - 1;
+    let nr_near_cells = near_cells.len();
+    let nr_checked = near_cells.filter(|&index| counts.reads[index] > 0).count();
+    if 2 * nr_checked > nr_near_cells {
+        Archetype::Scanner
+    } else {
+        Archetype::Rush
+    }
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod tests {
+    use super::*;
+    use bench::{self, Pairing};
+    use bf::Instruction;
+
+    fn make_rush_vs_turtle() -> Pairing {
+        bench::canonical_pairings()
+            .into_iter()
+            .find(|pairing| pairing.name == "rush vs turtle")
+            .unwrap()
+    }
+
+    #[test]
+    fn classify_rushOfBenchmarks_isRush() {
+        assert_eq!(classify(&make_rush_vs_turtle().bot_a), Archetype::Rush);
+    }
+
+    #[test]
+    fn classify_turtleOfBenchmarks_isDefensive() {
+        assert_eq!(classify(&make_rush_vs_turtle().bot_b), Archetype::Defensive);
+    }
+
+    #[test]
+    fn classify_blindClear_isCarelessClear() {
+        // Its program, in BrainFuck: (>-)*29
+        let program = (0..29)
+            .flat_map(|_| vec![Instruction::MoveForward, Instruction::Decrement])
+            .collect();
+        assert_eq!(classify(&Bot::new(program)), Archetype::CarelessClear);
+    }
+
+    #[test]
+    fn classify_checksEveryCell_isScanner() {
+        // Its program, in BrainFuck: (>[-])*29
+        let program = (0..29)
+            .flat_map(|repetition| {
+                let start = 4 * repetition;
+                vec![Instruction::MoveForward,
+                     Instruction::JumpIfZero(start + 3),
+                     Instruction::Decrement,
+                     Instruction::JumpIfNonZero(start + 1)]
+            })
+            .collect();
+        assert_eq!(classify(&Bot::new(program)), Archetype::Scanner);
+    }
+}
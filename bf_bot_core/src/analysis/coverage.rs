@@ -0,0 +1,156 @@
+use std::ops::Range;
+
+use bf::{Bot, Instruction};
+use engine::{Arena, ArenaObserver, StepView};
+use simul_game::AllRounds;
+
+/// How often every instruction of a bot was executed in a set of rounds.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Coverage {
+    /// The number of times each instruction was executed, by its index in the program.
+    pub execution_counts: Vec<u64>,
+    /// The number of rounds that were played.
+    pub nr_rounds: usize,
+}
+
+impl Coverage {
+    /// Returns true if the instruction at the given index was executed at least once.
+    pub fn is_executed(&self, index: usize) -> bool {
+        self.execution_counts.get(index).is_some_and(|&count| count > 0)
+    }
+
+    /// Returns the fraction of the instructions that were executed at least once, or 1 for an
+    /// empty program.
+    pub fn fraction_executed(&self) -> f64 {
+        if self.execution_counts.is_empty() {
+            return 1.0;
+        }
+        let nr_executed = self.execution_counts.iter().filter(|&&count| count > 0).count();
+        nr_executed as f64 / self.execution_counts.len() as f64
+    }
+
+    /// Returns the ranges of consecutive instructions that were never executed, in the order in
+    /// which they appear in the program.
+    pub fn dead_regions(&self) -> Vec<Range<usize>> {
+        let mut regions = vec![];
+        let mut start = None;
+        for (index, &count) in self.execution_counts.iter().enumerate() {
+            match (start, count) {
+                (None, 0) => start = Some(index),
+                (Some(region_start), count) if count > 0 => {
+                    regions.push(region_start..index);
+                    start = None;
+                }
+                _ => {}
+            }
+        }
+        if let Some(region_start) = start {
+            regions.push(region_start..self.execution_counts.len());
+        }
+        regions
+    }
+}
+
+/// Counts the instructions that a bot executes, from the code pointers after every step.
+struct ExecutionCounter<'c> {
+    program: &'c [Instruction],
+    counts: &'c mut [u64],
+    /// The index of the instruction that the bot executes in the next step.
+    next: usize,
+}
+
+impl<'c> ArenaObserver for ExecutionCounter<'c> {
+    fn on_step(&mut self, step: &StepView) {
+        let code_pointer = step.start_bot.code_pointer;
+        // The code pointer stands still once the program has ended, or while the bot waits in a
+        // loop without a body, whose `]` jumps back to itself.
+        let waits_in_empty_loop = match self.program.get(self.next) {
+            Some(&Instruction::JumpIfNonZero(target)) => target + 1 == self.next,
+            _ => false,
+        };
+        if code_pointer != self.next || waits_in_empty_loop {
+            if let Some(count) = self.counts.get_mut(self.next) {
+                *count += 1;
+            }
+        }
+        self.next = code_pointer;
+    }
+}
+
+/// Plays complete matches of the given bot against every opponent, and counts how often every
+/// instruction of the bot is executed. Instructions that never execute are junk against these
+/// opponents: removing them doesn't change a single round, so they are safe to simplify away.
+///
+/// Without opponents, no rounds are played and nothing is executed.
+///
+/// # Examples
+///
+/// ```
+/// use bf_bot_core::analysis;
+/// use bf_bot_core::bf::{Bot, Instruction};
+/// // `<+-`: the bot leaves the tape in its first step, so the rest never runs.
+/// let bot = Bot::new(vec![Instruction::MoveBack, Instruction::Increment, Instruction::Decrement]);
+/// let coverage = analysis::coverage(&bot, &[Bot::new(vec![])]);
+/// assert_eq!(coverage.execution_counts, vec![42, 0, 0]);
+/// assert_eq!(coverage.dead_regions(), vec![1..3]);
+/// ```
+pub fn coverage(bot: &Bot, opponents: &[Bot]) -> Coverage {
+    let mut execution_counts = vec![0; bot.get_program().len()];
+    let mut nr_rounds = 0;
+    for opponent in opponents {
+        for round_params in AllRounds::new() {
+            let mut counter = ExecutionCounter {
+                program: bot.get_program(),
+                counts: &mut execution_counts,
+                next: 0,
+            };
+            let mut arena = Arena::new(bot, opponent, &round_params)
+                .expect("The standard rounds are valid.");
+            arena.attach_observer(Box::new(&mut counter));
+            arena.finish();
+            nr_rounds += 1;
+        }
+    }
+    Coverage {
+        execution_counts,
+        nr_rounds,
+    }
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn coverage_loopNeverEntered_bodyDead() {
+        // `>[+]-`: the cell next to the flag starts at zero, so the loop body is skipped.
+        let bot = Bot::new(vec![Instruction::MoveForward,
+                                Instruction::JumpIfZero(3),
+                                Instruction::Increment,
+                                Instruction::JumpIfNonZero(1),
+                                Instruction::Decrement]);
+        let coverage = coverage(&bot, &[Bot::new(vec![])]);
+        assert_eq!(coverage.nr_rounds, 42);
+        assert_eq!(coverage.dead_regions(), vec![2..4]);
+        assert_eq!(coverage.fraction_executed(), 0.6);
+    }
+
+    #[test]
+    fn coverage_emptyLoop_everyWaitCounted() {
+        // `+[]`: the bot waits in the loop until the round is a draw after 100000 steps.
+        let bot = Bot::new(vec![Instruction::Increment,
+                                Instruction::JumpIfZero(2),
+                                Instruction::JumpIfNonZero(1)]);
+        let coverage = coverage(&bot, &[Bot::new(vec![])]);
+        assert_eq!(coverage.execution_counts[1], 42);
+        assert!(coverage.execution_counts[2] > 1000);
+    }
+
+    #[test]
+    fn coverage_noOpponents_nothingExecuted() {
+        let coverage = coverage(&Bot::new(vec![Instruction::Increment]), &[]);
+        assert_eq!(coverage.nr_rounds, 0);
+        assert!(!coverage.is_executed(0));
+    }
+}
@@ -0,0 +1,234 @@
+use bench;
+use bf::{Bot, Instruction};
+use engine::Arena;
+use error::BfError;
+use reference_bots::{self, ProgramBuilder};
+use simul_game::{MAX_TAPE_LENGTH, MIN_TAPE_LENGTH};
+use simul_round::{RoundParams, RoundStatus};
+
+/// The number of kinds of probes that `probe` varies.
+const NR_PROBE_KINDS: usize = 4;
+
+/// A round in which two bots that were checked for equivalence had different outcomes.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Difference {
+    pub opponent: Bot,
+    pub round_params: RoundParams,
+    pub status_a: RoundStatus,
+    pub status_b: RoundStatus,
+}
+
+/// The result of comparing two bots with `equivalent`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Equivalence {
+    /// The number of rounds that each bot played.
+    pub nr_rounds: usize,
+    /// The first round with different outcomes, if any.
+    pub difference: Option<Difference>,
+}
+
+impl Equivalence {
+    /// Returns true if the bots had the same outcome in every round.
+    pub fn is_equivalent(&self) -> bool {
+        self.difference.is_none()
+    }
+}
+
+/// Checks whether two bots behave the same, by letting both play against a battery of probe
+/// opponents on every tape length and with both polarities, and comparing the outcome of every
+/// round. The rounds are played with the given parameters, apart from the tape length and the
+/// polarity.
+///
+/// The battery consists of an idle bot, the reference bots, the bots of the benchmark pairings
+/// and the given number of extra probes: variations of rushes, decoy rushes, flag defenders and
+/// careless clears, with different distances, numbers of decoys and timings. More probes make a
+/// false positive less likely, at the cost of more rounds. Only the outcomes count, so two bots
+/// that win the same rounds in a different number of cycles are equivalent.
+///
+/// The comparison stops at the first difference. Fails if the given parameters are not valid for
+/// every standard tape length.
+///
+/// # Examples
+///
+/// ```
+/// use bf_bot_core::analysis;
+/// use bf_bot_core::bf::{Bot, Instruction};
+/// use bf_bot_core::simul_round::RoundParams;
+/// let params = RoundParams { early_termination: true, ..RoundParams::new(10, false, 10_000) };
+/// // `+-` changes nothing about the outcome of an idle bot, but `<` does.
+/// let idle = Bot::new(vec![]);
+/// let wiggle = Bot::new(vec![Instruction::Increment, Instruction::Decrement]);
+/// let suicide = Bot::new(vec![Instruction::MoveBack]);
+/// assert!(analysis::equivalent(&idle, &wiggle, &params, 4).unwrap().is_equivalent());
+/// assert!(!analysis::equivalent(&idle, &suicide, &params, 4).unwrap().is_equivalent());
+/// ```
+pub fn equivalent(bot_a: &Bot,
+                  bot_b: &Bot,
+                  params: &RoundParams,
+                  samples: usize)
+                  -> Result<Equivalence, BfError> {
+    let mut nr_rounds = 0;
+    for opponent in probe_battery(samples) {
+        for tape_length in MIN_TAPE_LENGTH..MAX_TAPE_LENGTH + 1 {
+            for &invert_polarity in &[false, true] {
+                let round_params = RoundParams {
+                    tape_length,
+                    invert_polarity,
+                    ..params.clone()
+                };
+                let status_a = play(bot_a, &opponent, &round_params)?;
+                let status_b = play(bot_b, &opponent, &round_params)?;
+                nr_rounds += 1;
+                if status_a != status_b {
+                    return Ok(Equivalence {
+                        nr_rounds,
+                        difference: Some(Difference {
+                            opponent,
+                            round_params,
+                            status_a,
+                            status_b,
+                        }),
+                    });
+                }
+            }
+        }
+    }
+    Ok(Equivalence {
+        nr_rounds,
+        difference: None,
+    })
+}
+
+fn play(bot: &Bot, opponent: &Bot, round_params: &RoundParams) -> Result<RoundStatus, BfError> {
+    Ok(Arena::new(bot, opponent, round_params)?.run_to_completion().result.status())
+}
+
+/// Returns the distinct opponents that bots are compared against.
+fn probe_battery(samples: usize) -> Vec<Bot> {
+    let mut battery = vec![Bot::new(vec![])];
+    let reference = reference_bots::starter_hill().into_iter().map(|warrior| warrior.bot);
+    let benchmark = bench::canonical_pairings()
+        .into_iter()
+        .flat_map(|pairing| vec![pairing.bot_a, pairing.bot_b]);
+    for bot in reference.chain(benchmark).chain((0..samples).map(probe)) {
+        if !battery.contains(&bot) {
+            battery.push(bot);
+        }
+    }
+    battery
+}
+
+/// Returns the probe with the given number. Consecutive numbers cycle through the kinds of
+/// probes, and every round of the cycle varies their parameters.
+fn probe(number: usize) -> Bot {
+    let variation = number / NR_PROBE_KINDS;
+    let builder = ProgramBuilder::default();
+    let builder = match number % NR_PROBE_KINDS {
+        // A rush that starts clearing at a varying distance, in either direction.
+        0 => {
+            let clear = if variation.is_multiple_of(2) {
+                Instruction::Decrement
+            } else {
+                Instruction::Increment
+            };
+            builder.repeat(Instruction::MoveForward, 1 + variation % 20)
+                .repeat_part(MAX_TAPE_LENGTH as usize, |builder| {
+                    builder.open_loop()
+                        .repeat(clear, 1)
+                        .close_loop()
+                        .repeat(Instruction::MoveForward, 1)
+                })
+        }
+        // A varying number of decoys of a varying size, followed by a rush.
+        1 => {
+            let nr_decoys = 1 + variation % 5;
+            (0..nr_decoys)
+                .fold(builder, |builder, decoy| {
+                    let sign = if decoy.is_multiple_of(2) {
+                        Instruction::Increment
+                    } else {
+                        Instruction::Decrement
+                    };
+                    builder.repeat(Instruction::MoveForward, 1)
+                        .repeat(sign, 2 + 3 * (variation % 7))
+                })
+                .repeat(Instruction::MoveForward, 9 - nr_decoys.min(8))
+                .repeat_part(MAX_TAPE_LENGTH as usize, |builder| {
+                    builder.open_loop()
+                        .repeat(Instruction::Decrement, 1)
+                        .close_loop()
+                        .repeat(Instruction::MoveForward, 1)
+                })
+        }
+        // A defender that waits a varying number of cycles, and then keeps its flag alive.
+        2 => {
+            builder.repeat(Instruction::SkipExecution, variation % 50)
+                .open_loop()
+                .repeat(Instruction::Increment, 1)
+                .repeat(Instruction::Decrement, 1)
+                .close_loop()
+        }
+        // A careless clear that changes every cell a varying number of times.
+        _ => {
+            builder.repeat(Instruction::MoveForward, 9)
+                .repeat_part(MAX_TAPE_LENGTH as usize, |builder| {
+                    builder.repeat(Instruction::Decrement, 64 + 16 * (variation % 9))
+                        .repeat(Instruction::MoveForward, 1)
+                })
+        }
+    };
+    builder.build()
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod tests {
+    use super::*;
+    use bf::UnmatchedBracketPolicy;
+    use simul_round::Rules;
+
+    fn params() -> RoundParams {
+        RoundParams { early_termination: true, ..RoundParams::new(10, false, 10_000) }
+    }
+
+    #[test]
+    fn equivalent_sameBot_everyRoundPlayed() {
+        let bot = reference_bots::fast_rush();
+        let equivalence = equivalent(&bot, &bot, &params(), 8).unwrap();
+        assert!(equivalence.is_equivalent());
+        assert_eq!(equivalence.nr_rounds, probe_battery(8).len() * 42);
+    }
+
+    #[test]
+    fn equivalent_differentBots_firstDifferenceReported() {
+        let difference = equivalent(&reference_bots::fast_rush(),
+                                    &reference_bots::sit_and_wait(),
+                                    &params(),
+                                    0)
+            .unwrap()
+            .difference
+            .unwrap();
+        // The rush beats the idle bot, which draws against itself.
+        assert_eq!(difference.opponent, Bot::new(vec![]));
+        assert_eq!((difference.status_a, difference.status_b),
+                   (RoundStatus::StartBotWon, RoundStatus::Draw));
+    }
+
+    #[test]
+    fn equivalent_tooManyFlags_invalidParams() {
+        let bot = reference_bots::fast_rush();
+        let params = RoundParams { rules: Rules::MultipleFlags { nr_flags: 6 }, ..params() };
+        assert!(equivalent(&bot, &bot, &params, 0).is_err());
+    }
+
+    #[test]
+    fn probeBattery_probesAreDistinctAndValid() {
+        let nr_fixed = probe_battery(0).len();
+        let battery = probe_battery(20);
+        assert_eq!(battery.len(), nr_fixed + 20);
+        for bot in battery {
+            let program = bot.get_program().to_vec();
+            assert!(Bot::new_checked(program, UnmatchedBracketPolicy::Reject).is_ok());
+        }
+    }
+}
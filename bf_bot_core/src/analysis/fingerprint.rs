@@ -0,0 +1,118 @@
+use bf::Bot;
+use engine::{Arena, Orientation};
+use match_runner::Match;
+use simul_game::{MAX_TAPE_LENGTH, MIN_TAPE_LENGTH};
+use simul_round::{RoundParams, RoundStatus};
+use tournament::Hill;
+
+/// The number of configurations in a complete match: every tape length, with both polarities.
+const NR_CONFIGURATIONS: usize = 2 * (MAX_TAPE_LENGTH - MIN_TAPE_LENGTH + 1) as usize;
+
+/// The number of values in every fingerprint.
+pub const FINGERPRINT_LENGTH: usize = NR_CONFIGURATIONS + 2;
+
+/// The maximum number of steps of the round in which decoys are counted.
+const DECOY_PROBE_MAX_STEPS: u32 = 10_000;
+
+/// Returns a vector of `FINGERPRINT_LENGTH` numbers that summarizes how the given bot plays
+/// against the bots on the given hill. Bots that play alike have fingerprints that lie close
+/// together, so the fingerprints can be clustered. The values are, in order:
+///
+/// * For every configuration of a complete match, normal polarity first and from the shortest to
+///   the longest tape: the average outcome against the bots on the hill, from -1 if the bot lost
+///   to all of them to 1 if it beat all of them.
+/// * The average number of steps of all those rounds.
+/// * The number of decoys the bot builds: the cells between the flags that it leaves non-zero,
+///   when it plays alone on the longest tape.
+///
+/// An empty hill yields zero for the outcomes and the number of steps.
+///
+/// # Examples
+///
+/// ```
+/// use bf_bot_core::analysis;
+/// use bf_bot_core::bf::{Bot, Instruction};
+/// use bf_bot_core::tournament::Hill;
+/// let mut hill = Hill::new(5);
+/// hill.submit("suicidal", Bot::new(vec![Instruction::MoveBack]));
+/// let fingerprint = analysis::fingerprint(&Bot::new(vec![]), &hill);
+/// assert_eq!(fingerprint.len(), analysis::FINGERPRINT_LENGTH);
+/// assert_eq!(fingerprint[0], 1.0);
+/// ```
+pub fn fingerprint(bot: &Bot, hill: &Hill) -> Vec<f64> {
+    let mut outcomes = vec![0.0; NR_CONFIGURATIONS];
+    let mut total_steps = 0.0;
+    let mut nr_rounds = 0;
+    for entry in hill.get_entries() {
+        let result = Match::new(bot, &entry.bot).run().expect("The standard rounds are valid.");
+        for record in result.rounds {
+            let outcome = match record.result.status() {
+                RoundStatus::StartBotWon => 1.0,
+                RoundStatus::EndBotWon => -1.0,
+                _ => 0.0,
+            };
+            outcomes[configuration_index(&record.params)] += outcome;
+            total_steps += f64::from(record.result.nr_steps);
+            nr_rounds += 1;
+        }
+    }
+    let mut fingerprint = outcomes.into_iter()
+        .map(|outcome| outcome / hill.len().max(1) as f64)
+        .collect::<Vec<_>>();
+    fingerprint.push(total_steps / nr_rounds.max(1) as f64);
+    fingerprint.push(count_decoys(bot) as f64);
+    fingerprint
+}
+
+fn configuration_index(params: &RoundParams) -> usize {
+    let polarity_offset = if params.invert_polarity {
+        NR_CONFIGURATIONS / 2
+    } else {
+        0
+    };
+    polarity_offset + (params.tape_length - MIN_TAPE_LENGTH) as usize
+}
+
+/// Plays the bot against an opponent that does nothing, and counts the cells between the flags
+/// that the bot changed and left non-zero.
+fn count_decoys(bot: &Bot) -> usize {
+    let idle = Bot::new(vec![]);
+    let round_params = RoundParams::new(MAX_TAPE_LENGTH, false, DECOY_PROBE_MAX_STEPS);
+    let mut arena = Arena::new(bot, &idle, &round_params).expect("The longest tape is valid.");
+    arena.attach_heatmap();
+    arena.finish();
+    let heatmap = arena.take_heatmap().expect("A heatmap was attached.");
+    let writes = &heatmap.of_bot(Orientation::Normal).writes;
+    let tape = arena.get_tape();
+    (1..tape.len() - 1).filter(|&index| writes[index] > 0 && tape[index] != 0).count()
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod tests {
+    use super::*;
+    use bf::Instruction;
+
+    #[test]
+    fn fingerprint_emptyHill_onlyCountsDecoys() {
+        // Its program, in BrainFuck: >+>->+
+        let bot = Bot::new(vec![Instruction::MoveForward,
+                                Instruction::Increment,
+                                Instruction::MoveForward,
+                                Instruction::Decrement,
+                                Instruction::MoveForward,
+                                Instruction::Increment]);
+        let fingerprint = fingerprint(&bot, &Hill::new(5));
+        assert!(fingerprint[..FINGERPRINT_LENGTH - 1].iter().all(|&value| value == 0.0));
+        assert_eq!(fingerprint[FINGERPRINT_LENGTH - 1], 3.0);
+    }
+
+    #[test]
+    fn fingerprint_againstSuicidalBot_winsQuickly() {
+        let mut hill = Hill::new(5);
+        hill.submit("suicidal", Bot::new(vec![Instruction::MoveBack]));
+        let fingerprint = fingerprint(&Bot::new(vec![]), &hill);
+        assert!(fingerprint[..NR_CONFIGURATIONS].iter().all(|&value| value == 1.0));
+        assert_eq!(fingerprint[NR_CONFIGURATIONS], 1.0);
+    }
+}
@@ -0,0 +1,286 @@
+use std::fmt;
+
+use bf::{Bot, Instruction};
+use simul_game::{MAX_TAPE_LENGTH, MIN_TAPE_LENGTH};
+
+/// The value that the flags start with. A bot that changes the enemy flag fewer times than this
+/// can't zero it.
+const FLAG_START_VALUE: u32 = 128;
+
+/// How bad a problem that `lint` found is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Severity {
+    /// The bot wastes cycles or leaves an opening, but can still win.
+    Note,
+    /// The bot can't win, unless its opponent loses by its own doing.
+    Warning,
+    /// The bot loses rounds, whatever its opponent does.
+    Error,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match *self {
+            Severity::Note => "note",
+            Severity::Warning => "warning",
+            Severity::Error => "error",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// A problem that `lint` can find.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LintKind {
+    /// Before its first loop, the bot moves back from its own flag and off the tape.
+    LeavesTapeBackward,
+    /// Before its first loop, the bot moves past the enemy flag, without changing it often enough
+    /// to zero it, and off the tape on the given number of tape lengths.
+    RunsOffTape { nr_tape_lengths: u32 },
+    /// A loop without a body, `[]`, at the end of the program. It does nothing but wait until its
+    /// cell becomes zero, after which the bot ends anyway. An empty loop with more instructions
+    /// after it is a common way to wait for the opponent, and is not reported.
+    EmptyLoop,
+    /// The program has no `+` or `-`, so it can neither build decoys to defend its flag nor clear
+    /// the enemy flag.
+    NeverChangesCells,
+    /// The program has no `>`, so it can never reach the enemy flag.
+    NeverMovesForward,
+    /// Two adjacent instructions that undo each other, such as `+-` or `<>`.
+    CancellingInstructions,
+    /// Before its first loop, the bot walks up to the nearest cell that can hold the enemy flag
+    /// without changing its own flag or any cell in front of it, so nothing holds up an opponent
+    /// that rushes its flag.
+    LeavesFlagUndefended,
+}
+
+impl LintKind {
+    pub fn severity(&self) -> Severity {
+        match *self {
+            LintKind::LeavesTapeBackward | LintKind::RunsOffTape { .. } => Severity::Error,
+            LintKind::NeverChangesCells | LintKind::NeverMovesForward => Severity::Warning,
+            LintKind::EmptyLoop |
+            LintKind::CancellingInstructions |
+            LintKind::LeavesFlagUndefended => Severity::Note,
+        }
+    }
+}
+
+impl fmt::Display for LintKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            LintKind::LeavesTapeBackward => {
+                write!(f, "the bot leaves the tape behind its own flag in every round")
+            }
+            LintKind::RunsOffTape { nr_tape_lengths } => {
+                write!(f,
+                       "the bot runs past the enemy flag and off the tape on {} tape lengths",
+                       nr_tape_lengths)
+            }
+            LintKind::EmptyLoop => {
+                write!(f, "the empty loop at the end only waits before the bot ends anyway")
+            }
+            LintKind::NeverChangesCells => {
+                write!(f, "the bot never changes a cell, so it can't clear the enemy flag")
+            }
+            LintKind::NeverMovesForward => {
+                write!(f, "the bot never moves forward, so it can't reach the enemy flag")
+            }
+            LintKind::CancellingInstructions => {
+                write!(f, "the two instructions cancel each other out")
+            }
+            LintKind::LeavesFlagUndefended => {
+                write!(f, "the bot leaves its own flag without setting up any decoys")
+            }
+        }
+    }
+}
+
+/// A problem with a bot, and where in the program it is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Diagnostic {
+    pub kind: LintKind,
+    /// The index of the instruction where the problem shows, if it concerns a particular part of
+    /// the program rather than the program as a whole.
+    pub index: Option<usize>,
+}
+
+impl Diagnostic {
+    pub fn severity(&self) -> Severity {
+        self.kind.severity()
+    }
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.index {
+            Some(index) => write!(f, "{} at instruction {}: {}", self.severity(), index, self.kind),
+            None => write!(f, "{}: {}", self.severity(), self.kind),
+        }
+    }
+}
+
+/// Finds problems that show in the program of the given bot without playing it: moves that take it
+/// off the tape before anything can stop it, loops that idle, strategies that can't win, flags that
+/// are left undefended and instructions that are wasted. Problems with the program as a whole come
+/// first, followed by the others in the order of the program.
+///
+/// Only the instructions before the first loop are certain to be executed exactly as written, so
+/// the moves off the tape and the undefended flags are only detected there.
+///
+/// # Examples
+///
+/// ```
+/// use bf_bot_core::analysis::{self, LintKind};
+/// use bf_bot_core::bf::{Bot, Instruction};
+/// let diagnostics = analysis::lint(&Bot::new(vec![Instruction::MoveBack]));
+/// assert_eq!(diagnostics[0].kind, LintKind::NeverChangesCells);
+/// assert_eq!(diagnostics[2].kind, LintKind::LeavesTapeBackward);
+/// assert_eq!(diagnostics[2].to_string(),
+///            "error at instruction 0: the bot leaves the tape behind its own flag in every \
+///             round");
+/// ```
+pub fn lint(bot: &Bot) -> Vec<Diagnostic> {
+    let program = bot.get_program();
+    let mut diagnostics = vec![];
+    let at = |kind, index| Diagnostic { kind, index: Some(index) };
+    let changes_cells = program.iter()
+        .any(|instruction| *instruction == Instruction::Increment ||
+                           *instruction == Instruction::Decrement);
+    if !changes_cells {
+        diagnostics.push(Diagnostic { kind: LintKind::NeverChangesCells, index: None });
+    }
+    if !program.contains(&Instruction::MoveForward) {
+        diagnostics.push(Diagnostic { kind: LintKind::NeverMovesForward, index: None });
+    }
+
+    // Follows the bot until its first loop, counting how often it changes every cell, and finds
+    // the tape lengths whose enemy flag it passes without having been able to zero it, and
+    // whether it leaves its own side of the tape without touching it.
+    let mut position = 0;
+    let mut nr_changes = vec![0; MAX_TAPE_LENGTH as usize];
+    let mut lost_tape_lengths = vec![];
+    let mut first_lost_at = None;
+    for (index, instruction) in program.iter().enumerate() {
+        match *instruction {
+            Instruction::MoveBack if position == 0 => {
+                diagnostics.push(at(LintKind::LeavesTapeBackward, index));
+                break;
+            }
+            Instruction::MoveBack => position -= 1,
+            Instruction::MoveForward => position += 1,
+            Instruction::Increment | Instruction::Decrement => nr_changes[position] += 1,
+            Instruction::JumpIfZero(_) => break,
+            _ => {}
+        }
+        let tape_length = position as u32;
+        let nearest_enemy_flag = MIN_TAPE_LENGTH as usize - 1;
+        if *instruction == Instruction::MoveForward && position == nearest_enemy_flag &&
+           nr_changes[..nearest_enemy_flag].iter().all(|&nr| nr == 0) {
+            diagnostics.push(at(LintKind::LeavesFlagUndefended, index));
+        }
+        if *instruction == Instruction::MoveForward && tape_length >= MIN_TAPE_LENGTH &&
+           nr_changes[position - 1] < FLAG_START_VALUE &&
+           !lost_tape_lengths.contains(&tape_length) {
+            lost_tape_lengths.push(tape_length);
+            first_lost_at = first_lost_at.or(Some(index));
+        }
+        if tape_length >= MAX_TAPE_LENGTH {
+            break;
+        }
+    }
+    if let Some(index) = first_lost_at {
+        let nr_tape_lengths = lost_tape_lengths.len() as u32;
+        diagnostics.push(at(LintKind::RunsOffTape { nr_tape_lengths }, index));
+    }
+
+    for (index, pair) in program.windows(2).enumerate() {
+        let kind = match (pair[0], pair[1]) {
+            (Instruction::JumpIfZero(_), Instruction::JumpIfNonZero(_))
+                if index + 2 == program.len() => LintKind::EmptyLoop,
+            (Instruction::Increment, Instruction::Decrement) |
+            (Instruction::Decrement, Instruction::Increment) |
+            (Instruction::MoveBack, Instruction::MoveForward) |
+            (Instruction::MoveForward, Instruction::MoveBack) => LintKind::CancellingInstructions,
+            _ => continue,
+        };
+        diagnostics.push(at(kind, index));
+    }
+    diagnostics.sort_by_key(|diagnostic| diagnostic.index.map_or((0, 0), |index| (1, index)));
+    diagnostics
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod tests {
+    use super::*;
+
+    fn kinds(diagnostics: &[Diagnostic]) -> Vec<LintKind> {
+        diagnostics.iter().map(|diagnostic| diagnostic.kind).collect()
+    }
+
+    #[test]
+    fn lint_referenceBots_noErrors() {
+        for warrior in ::reference_bots::starter_hill() {
+            if warrior.name != "suicide" {
+                assert!(lint(&warrior.bot).iter().all(|d| d.severity() < Severity::Error),
+                        "{}",
+                        warrior.name);
+            }
+        }
+    }
+
+    #[test]
+    fn lint_longRushWithoutLoop_runsOffShortTapes() {
+        let mut program = vec![Instruction::MoveForward; 12];
+        program.push(Instruction::Decrement);
+        let diagnostics = lint(&Bot::new(program));
+        assert_eq!(diagnostics[1],
+                   Diagnostic {
+                       kind: LintKind::RunsOffTape { nr_tape_lengths: 3 },
+                       index: Some(9),
+                   });
+        assert_eq!(kinds(&diagnostics),
+                   vec![LintKind::LeavesFlagUndefended,
+                        LintKind::RunsOffTape { nr_tape_lengths: 3 }]);
+    }
+
+    #[test]
+    fn lint_rushWithoutDecoys_leavesFlagUndefended() {
+        // `>>>>>>>>>[-]`
+        let mut program = vec![Instruction::MoveForward; 9];
+        program.extend(vec![Instruction::JumpIfZero(11),
+                            Instruction::Decrement,
+                            Instruction::JumpIfNonZero(9)]);
+        assert_eq!(lint(&Bot::new(program)),
+                   vec![Diagnostic {
+                            kind: LintKind::LeavesFlagUndefended,
+                            index: Some(8),
+                        }]);
+        let decoy_rush = ::reference_bots::decoy_rush();
+        assert!(!kinds(&lint(&decoy_rush)).contains(&LintKind::LeavesFlagUndefended));
+    }
+
+    #[test]
+    fn lint_emptyLoopAndCancellingPair_reportedInProgramOrder() {
+        // `>+-[]`
+        let bot = Bot::new(vec![Instruction::MoveForward,
+                                Instruction::Increment,
+                                Instruction::Decrement,
+                                Instruction::JumpIfZero(4),
+                                Instruction::JumpIfNonZero(3)]);
+        assert_eq!(kinds(&lint(&bot)),
+                   vec![LintKind::CancellingInstructions, LintKind::EmptyLoop]);
+        assert_eq!(lint(&bot)[1].index, Some(3));
+    }
+
+    #[test]
+    fn lint_emptyLoopBeforeMoreInstructions_notReported() {
+        // `>[]-`
+        let bot = Bot::new(vec![Instruction::MoveForward,
+                                Instruction::JumpIfZero(2),
+                                Instruction::JumpIfNonZero(1),
+                                Instruction::Decrement]);
+        assert_eq!(lint(&bot), vec![]);
+    }
+}
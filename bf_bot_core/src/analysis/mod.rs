@@ -0,0 +1,22 @@
+//! Describes how bots behave, rather than how strong they are, so that bots with similar
+//! strategies can be recognized. Evolution uses this to keep its population diverse, and reports
+//! use it to explain what kind of bots evolved. It also points out the parts of a program that
+//! are wasted or harmful.
+
+pub use self::fingerprint::{fingerprint, FINGERPRINT_LENGTH};
+mod fingerprint;
+
+pub use self::archetype::Archetype;
+mod archetype;
+
+pub use self::classify::classify;
+mod classify;
+
+pub use self::coverage::{coverage, Coverage};
+mod coverage;
+
+pub use self::lint::{lint, Diagnostic, LintKind, Severity};
+mod lint;
+
+pub use self::equivalence::{equivalent, Difference, Equivalence};
+mod equivalence;
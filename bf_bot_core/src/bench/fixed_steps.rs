@@ -0,0 +1,40 @@
+use bf::Bot;
+use engine::Arena;
+use error::BfError;
+use simul_round::{ExecutionEngine, RoundParams, StepOutcome};
+
+/// Returns the parameters that benchmarks play with: the given tape length and engine, normal
+/// polarity, and no early termination, because detecting cycles would dominate the measurement.
+pub fn bench_params(tape_length: u32, engine: ExecutionEngine) -> RoundParams {
+    RoundParams { engine, ..RoundParams::new(tape_length, false, 100_000) }
+}
+
+/// Plays rounds between the given bots until exactly `nr_steps` steps have been executed,
+/// starting a new round in the same arena whenever one ends. Returns the number of rounds that
+/// ended, so that the compiler can't optimize the work away. Fails if the parameters are not
+/// valid.
+///
+/// # Examples
+///
+/// ```
+/// use bf_bot_core::bench;
+/// use bf_bot_core::simul_round::ExecutionEngine;
+/// let pairing = &bench::canonical_pairings()[0];
+/// let params = bench::bench_params(10, ExecutionEngine::Bytecode);
+/// assert!(bench::run_steps(&pairing.bot_a, &pairing.bot_b, &params, 10_000).unwrap() > 0);
+/// ```
+pub fn run_steps(bot_a: &Bot,
+                 bot_b: &Bot,
+                 params: &RoundParams,
+                 nr_steps: u32)
+                 -> Result<u32, BfError> {
+    let mut arena = Arena::new(bot_a, bot_b, params)?;
+    let mut nr_rounds = 0;
+    for _ in 0..nr_steps {
+        if let StepOutcome::Finished(_) = arena.step() {
+            nr_rounds += 1;
+            arena.reset(bot_a, bot_b, params)?;
+        }
+    }
+    Ok(nr_rounds)
+}
@@ -0,0 +1,10 @@
+//! Helpers for measuring how fast rounds are played. They play a few well-known pairings of
+//! strategies for a fixed number of steps, so that the work done doesn't depend on how quickly a
+//! round happens to end. The benchmarks of this crate are built on them, and they can be used to
+//! compare execution engines.
+
+pub use self::pairing::{canonical_pairings, Pairing};
+mod pairing;
+
+pub use self::fixed_steps::{bench_params, run_steps};
+mod fixed_steps;
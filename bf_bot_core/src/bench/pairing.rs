@@ -0,0 +1,125 @@
+use bf::{Bot, Instruction};
+use reference_bots::ProgramBuilder;
+
+/// Two bots that are benchmarked against each other, with a name for reports.
+#[derive(Debug, Clone)]
+pub struct Pairing {
+    pub name: &'static str,
+    pub bot_a: Bot,
+    pub bot_b: Bot,
+}
+
+impl Pairing {
+    fn new(name: &'static str, builder_a: ProgramBuilder, builder_b: ProgramBuilder) -> Pairing {
+        Pairing {
+            name,
+            bot_a: builder_a.build(),
+            bot_b: builder_b.build(),
+        }
+    }
+}
+
+/// Returns the pairings that benchmarks play: a rush against a turtle, a decoy builder against a
+/// clearer that is slowed down by decoys, and two rushes against each other.
+///
+/// # Examples
+///
+/// ```
+/// use bf_bot_core::bench;
+/// assert!(bench::canonical_pairings().iter().any(|pairing| pairing.name == "rush vs turtle"));
+/// ```
+pub fn canonical_pairings() -> Vec<Pairing> {
+    vec![Pairing::new("rush vs turtle", rush(), turtle()),
+         Pairing::new("decoy vs fast clear", decoy(), fast_clear()),
+         Pairing::new("rush vs fast clear", rush(), fast_clear())]
+}
+
+/// `>>>>>>>>>([-]>)*21`: skips the cells that can't hold the flag of the opponent, then clears
+/// every cell it finds.
+fn rush() -> ProgramBuilder {
+    ProgramBuilder::default()
+        .repeat(Instruction::MoveForward, 9)
+        .repeat_part(21, |builder| {
+            builder.open_loop()
+                .repeat(Instruction::Decrement, 1)
+                .close_loop()
+                .repeat(Instruction::MoveForward, 1)
+        })
+}
+
+/// `>(+)*10>(-)*10<<[+-]`: puts up two decoys and keeps its own flag alive by wiggling it.
+fn turtle() -> ProgramBuilder {
+    ProgramBuilder::default()
+        .repeat(Instruction::MoveForward, 1)
+        .repeat(Instruction::Increment, 10)
+        .repeat(Instruction::MoveForward, 1)
+        .repeat(Instruction::Decrement, 10)
+        .repeat(Instruction::MoveBack, 2)
+        .open_loop()
+        .repeat(Instruction::Increment, 1)
+        .repeat(Instruction::Decrement, 1)
+        .close_loop()
+}
+
+/// `>(+)*10>(-)*10(>+>-)*3(>[-].)*21`: builds decoys of both polarities, then clears cells one at
+/// a time, like DecoyBot.bf.
+fn decoy() -> ProgramBuilder {
+    ProgramBuilder::default()
+        .repeat(Instruction::MoveForward, 1)
+        .repeat(Instruction::Increment, 10)
+        .repeat(Instruction::MoveForward, 1)
+        .repeat(Instruction::Decrement, 10)
+        .repeat_part(3, |builder| {
+            builder.repeat(Instruction::MoveForward, 1)
+                .repeat(Instruction::Increment, 1)
+                .repeat(Instruction::MoveForward, 1)
+                .repeat(Instruction::Decrement, 1)
+        })
+        .repeat_part(21, |builder| {
+            builder.repeat(Instruction::MoveForward, 1)
+                .open_loop()
+                .repeat(Instruction::Decrement, 1)
+                .close_loop()
+                .repeat(Instruction::SkipExecution, 1)
+        })
+}
+
+/// `>>>>>>>>>([+++[-]]>)*21`: like FastClearBot.bf, a clear that first pushes a decoy the other
+/// way.
+fn fast_clear() -> ProgramBuilder {
+    ProgramBuilder::default()
+        .repeat(Instruction::MoveForward, 9)
+        .repeat_part(21, |builder| {
+            builder.open_loop()
+                .repeat(Instruction::Increment, 3)
+                .open_loop()
+                .repeat(Instruction::Decrement, 1)
+                .close_loop()
+                .close_loop()
+                .repeat(Instruction::MoveForward, 1)
+        })
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn canonicalPairings_matchDescribedSources() {
+        let rush = format!("{}{}", ">".repeat(9), "[-]>".repeat(21));
+        let fast_clear = format!("{}{}", ">".repeat(9), "[+++[-]]>".repeat(21));
+        let decoy = format!(">{}>{}{}{}",
+                            "+".repeat(10),
+                            "-".repeat(10),
+                            ">+>-".repeat(3),
+                            ">[-].".repeat(21));
+        let turtle = format!(">{}>{}<<[+-]", "+".repeat(10), "-".repeat(10));
+        let sources = canonical_pairings()
+            .iter()
+            .map(|pairing| (pairing.bot_a.to_source(), pairing.bot_b.to_source()))
+            .collect::<Vec<_>>();
+        assert_eq!(sources,
+                   vec![(rush.clone(), turtle), (decoy, fast_clear.clone()), (rush, fast_clear)]);
+    }
+}
@@ -1,17 +1,219 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use bf::{Bytecode, ProgramError, UnmatchedBracketPolicy};
+use error::BfError;
 use bf::instruction::Instruction;
 
 /// Represents a Bot. Holds variables that are related to the Bot in general.
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct Bot {
     program: Vec<Instruction>,
+    /// The program, compiled once so that every round can execute it directly.
+    bytecode: Bytecode,
 }
 
 impl Bot {
+    /// Constructs a bot that runs the given program, without checking its brackets. A jump whose
+    /// target lies outside the program ends the program when it is taken. Any other jump goes
+    /// where it points, even if that is not the matching bracket.
     pub fn new(program: Vec<Instruction>) -> Bot {
-        Bot { program: program }
+        let bytecode = Bytecode::compile(&program);
+        Bot { program, bytecode }
+    }
+
+    /// Constructs a bot after checking that every bracket of the program has a matching bracket.
+    /// The policy decides what happens to the program if one doesn't.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bf_bot_core::bf::{Bot, Instruction, ProgramError, UnmatchedBracketPolicy};
+    /// use bf_bot_core::error::BfError;
+    /// let program = vec![Instruction::Increment, Instruction::JumpIfNonZero(5)];
+    /// match Bot::new_checked(program.clone(), UnmatchedBracketPolicy::Reject) {
+    ///     Err(BfError::Program(error)) => {
+    ///         assert_eq!(error, ProgramError::UnmatchedBracket { index: 1 })
+    ///     }
+    ///     other => panic!("Expected an unmatched bracket, got {:?}", other),
+    /// }
+    /// assert!(Bot::new_checked(program, UnmatchedBracketPolicy::Terminate).is_ok());
+    /// ```
+    pub fn new_checked(mut program: Vec<Instruction>,
+                       policy: UnmatchedBracketPolicy)
+                       -> Result<Bot, BfError> {
+        let unmatched = Bot::unmatched_brackets(&program);
+        if let Some(&index) = unmatched.first() {
+            if policy == UnmatchedBracketPolicy::Reject {
+                return Err(ProgramError::UnmatchedBracket { index }.into());
+            }
+        }
+        // Pointing past the end makes a taken jump end the program.
+        let end = program.len();
+        for index in unmatched {
+            program[index] = match program[index] {
+                Instruction::JumpIfZero(_) => Instruction::JumpIfZero(end),
+                Instruction::JumpIfNonZero(_) => Instruction::JumpIfNonZero(end),
+                instruction => instruction,
+            };
+        }
+        Ok(Bot::new(program))
+    }
+
+    /// Returns the indices of all brackets without a matching bracket, in ascending order.
+    fn unmatched_brackets(program: &[Instruction]) -> Vec<usize> {
+        let target_of = |index: usize| program.get(index).cloned();
+        (0..program.len())
+            .filter(|&index| match program[index] {
+                Instruction::JumpIfZero(target) => {
+                    target <= index || target_of(target) != Some(Instruction::JumpIfNonZero(index))
+                }
+                Instruction::JumpIfNonZero(target) => {
+                    target >= index || target_of(target) != Some(Instruction::JumpIfZero(index))
+                }
+                _ => false,
+            })
+            .collect()
     }
 
     pub fn get_program(&self) -> &Vec<Instruction> {
         &self.program
     }
+
+    pub fn get_bytecode(&self) -> &Bytecode {
+        &self.bytecode
+    }
+
+    /// Returns the number of loops that the instruction at the given index is nested in. The
+    /// brackets of a loop count as inside of it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bf_bot_core::bf::{Bot, Instruction};
+    /// let bot = Bot::new(vec![Instruction::MoveForward,
+    ///                         Instruction::JumpIfZero(3),
+    ///                         Instruction::Decrement,
+    ///                         Instruction::JumpIfNonZero(1)]);
+    /// assert_eq!(bot.loop_depth(0), 0);
+    /// assert_eq!(bot.loop_depth(2), 1);
+    /// assert_eq!(bot.loop_depth(3), 1);
+    /// assert_eq!(bot.loop_depth(4), 0);
+    /// ```
+    pub fn loop_depth(&self, index: usize) -> usize {
+        let mut depth = 0usize;
+        for (i, instruction) in self.program.iter().enumerate().take(index + 1) {
+            match *instruction {
+                Instruction::JumpIfZero(_) => depth += 1,
+                Instruction::JumpIfNonZero(_) if i < index => depth = depth.saturating_sub(1),
+                _ => {}
+            }
+        }
+        depth
+    }
+
+    /// Returns the program of this bot as plain `BrainFuck` source code, without any comments or
+    /// whitespace. Parsing the source code yields the same program.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bf_bot_core::bf::{Bot, Instruction};
+    /// let bot = Bot::new(vec![Instruction::MoveForward,
+    ///                         Instruction::JumpIfZero(3),
+    ///                         Instruction::Decrement,
+    ///                         Instruction::JumpIfNonZero(1)]);
+    /// assert_eq!(bot.to_source(), ">[-]");
+    /// ```
+    pub fn to_source(&self) -> String {
+        self.program
+            .iter()
+            .map(|instruction| match *instruction {
+                Instruction::MoveBack => '<',
+                Instruction::MoveForward => '>',
+                Instruction::Increment => '+',
+                Instruction::Decrement => '-',
+                Instruction::SkipExecution => '.',
+                Instruction::JumpIfZero(_) => '[',
+                Instruction::JumpIfNonZero(_) => ']',
+            })
+            .collect()
+    }
+
+    /// Returns a hash of the source code of this bot, that stays the same across runs, versions
+    /// and platforms, so that it can be stored. Bots with the same source code have the same
+    /// fingerprint.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bf_bot_core::bf::{Bot, Instruction};
+    /// let bot = Bot::new(vec![Instruction::Increment]);
+    /// assert_eq!(bot.fingerprint(), Bot::new(vec![Instruction::Increment]).fingerprint());
+    /// assert_ne!(bot.fingerprint(), Bot::new(vec![Instruction::Decrement]).fingerprint());
+    /// ```
+    pub fn fingerprint(&self) -> u64 {
+        // 64-bit FNV-1a: simple, and unlike the hasher of the standard library, fixed forever.
+        self.to_source().bytes().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+            (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+        })
+    }
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn newChecked_matchedBrackets_accepted() {
+        let program = vec![Instruction::JumpIfZero(3),
+                           Instruction::JumpIfZero(2),
+                           Instruction::JumpIfNonZero(1),
+                           Instruction::JumpIfNonZero(0)];
+        let bot = Bot::new_checked(program.clone(), UnmatchedBracketPolicy::Reject).unwrap();
+        assert_eq!(bot.get_program(), &program);
+    }
+
+    #[test]
+    fn newChecked_crossedBrackets_rejectedAtFirstOne() {
+        let program = vec![Instruction::JumpIfZero(2),
+                           Instruction::JumpIfZero(3),
+                           Instruction::JumpIfNonZero(1),
+                           Instruction::JumpIfNonZero(0)];
+        match Bot::new_checked(program, UnmatchedBracketPolicy::Reject) {
+            Err(BfError::Program(error)) => {
+                assert_eq!(error, ProgramError::UnmatchedBracket { index: 0 })
+            }
+            other => panic!("Expected an unmatched bracket, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn newChecked_terminate_unmatchedJumpsPastTheEnd() {
+        let program = vec![Instruction::JumpIfNonZero(0), Instruction::JumpIfZero(7)];
+        let bot = Bot::new_checked(program, UnmatchedBracketPolicy::Terminate).unwrap();
+        assert_eq!(bot.get_program(),
+                   &vec![Instruction::JumpIfNonZero(2), Instruction::JumpIfZero(2)]);
+    }
+
+    #[test]
+    fn toSource_emptyProgram_emptyString() {
+        assert_eq!(Bot::new(vec![]).to_source(), "");
+    }
+
+    #[test]
+    fn toSource_everySimpleInstruction_oneCharacterEach() {
+        let bot = Bot::new(vec![Instruction::MoveBack,
+                                Instruction::MoveForward,
+                                Instruction::Increment,
+                                Instruction::Decrement,
+                                Instruction::SkipExecution]);
+        assert_eq!(bot.to_source(), "<>+-.");
+    }
+
+    #[test]
+    fn fingerprint_emptyProgram_fnvOffsetBasis() {
+        assert_eq!(Bot::new(vec![]).fingerprint(), 0xcbf2_9ce4_8422_2325);
+    }
 }
@@ -0,0 +1,213 @@
+use alloc::vec::Vec;
+
+use bf::Instruction;
+
+/// What an operation of the bytecode does, each time it is executed.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum OpCode {
+    MoveBack,
+    MoveForward,
+    Increment,
+    Decrement,
+    SkipExecution,
+    /// Continues at the operation at the given index if the current cell is zero. Unlike the
+    /// target of `Instruction::JumpIfZero`, this is an index into the bytecode, and it is the
+    /// operation after the matching bracket rather than the bracket itself. An index past the last
+    /// operation ends the program.
+    JumpIfZero(usize),
+    /// Continues at the operation at the given index if the current cell is not zero.
+    JumpIfNonZero(usize),
+}
+
+/// A run of identical instructions, fused into a single operation.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Op {
+    pub op_code: OpCode,
+    /// The number of instructions that were fused. Always 1 for jumps.
+    pub count: u32,
+    /// The index in the program of the first fused instruction.
+    pub source_index: usize,
+}
+
+impl Op {
+    /// Moves past one execution of this operation, which is the one at the given index and has
+    /// been executed `progress` times before. Taken jumps don't use this, they continue at their
+    /// target instead.
+    pub fn advance(&self, op_index: &mut usize, progress: &mut u32) {
+        *progress += 1;
+        if *progress >= self.count {
+            *op_index += 1;
+            *progress = 0;
+        }
+    }
+}
+
+/// The program of a bot, compiled into a form that is quicker to execute. Runs of identical
+/// instructions, like `+++` or `>>>>`, are fused into a single operation, and jump targets are
+/// resolved to indices into the bytecode. A run is never fused across the point at which a jump
+/// continues, so that every jump lands at the start of an operation.
+///
+/// Fusing does not change the timing of a program: a fused operation still takes one cycle for
+/// every instruction it replaced, because the other bot may observe the cell in between. What it
+/// saves is decoding every single instruction and checking where the program ends.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Bytecode {
+    ops: Vec<Op>,
+    /// The number of instructions in the original program.
+    program_length: usize,
+}
+
+impl Bytecode {
+    /// Compiles the given program.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bf_bot_core::bf::{Bytecode, Instruction};
+    /// let bytecode = Bytecode::compile(&[Instruction::MoveForward,
+    ///                                    Instruction::MoveForward,
+    ///                                    Instruction::Decrement]);
+    /// assert_eq!(bytecode.get_ops().len(), 2);
+    /// assert_eq!(bytecode.get_ops()[0].count, 2);
+    /// ```
+    pub fn compile(program: &[Instruction]) -> Bytecode {
+        // The index of the instruction after the target of a jump, if it exists.
+        let continue_index = |target: usize| target.checked_add(1).filter(|&i| i < program.len());
+        let mut continue_points = vec![false; program.len()];
+        for instruction in program {
+            if let Instruction::JumpIfZero(target) | Instruction::JumpIfNonZero(target) =
+                *instruction {
+                if let Some(index) = continue_index(target) {
+                    continue_points[index] = true;
+                }
+            }
+        }
+        // The index of the operation that every instruction ends up in.
+        let mut op_indices = Vec::with_capacity(program.len());
+        let mut ops: Vec<Op> = vec![];
+        for (index, instruction) in program.iter().enumerate() {
+            let op_code = match *instruction {
+                Instruction::MoveBack => OpCode::MoveBack,
+                Instruction::MoveForward => OpCode::MoveForward,
+                Instruction::Increment => OpCode::Increment,
+                Instruction::Decrement => OpCode::Decrement,
+                Instruction::SkipExecution => OpCode::SkipExecution,
+                // The targets are resolved below, once every instruction has an operation.
+                Instruction::JumpIfZero(target) => OpCode::JumpIfZero(target),
+                Instruction::JumpIfNonZero(target) => OpCode::JumpIfNonZero(target),
+            };
+            match ops.last_mut() {
+                Some(last) if last.op_code == op_code && Bytecode::is_fusable(op_code) &&
+                               !continue_points[index] => {
+                    last.count += 1;
+                }
+                _ => {
+                    ops.push(Op {
+                        op_code,
+                        count: 1,
+                        source_index: index,
+                    })
+                }
+            }
+            op_indices.push(ops.len() - 1);
+        }
+        let nr_ops = ops.len();
+        let continue_at = |target: usize| continue_index(target).map_or(nr_ops, |i| op_indices[i]);
+        for op in &mut ops {
+            op.op_code = match op.op_code {
+                OpCode::JumpIfZero(target) => OpCode::JumpIfZero(continue_at(target)),
+                OpCode::JumpIfNonZero(target) => OpCode::JumpIfNonZero(continue_at(target)),
+                op_code => op_code,
+            };
+        }
+        Bytecode {
+            ops,
+            program_length: program.len(),
+        }
+    }
+
+    fn is_fusable(op_code: OpCode) -> bool {
+        !matches!(op_code, OpCode::JumpIfZero(_) | OpCode::JumpIfNonZero(_))
+    }
+
+    pub fn get_ops(&self) -> &[Op] {
+        &self.ops
+    }
+
+    /// Returns the index in the program of the instruction that is executed after the given
+    /// operation has been executed the given number of times.
+    pub fn source_index(&self, op_index: usize, progress: u32) -> usize {
+        match self.ops.get(op_index) {
+            Some(op) => op.source_index + progress as usize,
+            None => self.program_length,
+        }
+    }
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod tests {
+    use super::*;
+
+    /// The program, in BrainFuck: >>[--]<
+    fn make_program() -> Vec<Instruction> {
+        vec![Instruction::MoveForward,
+             Instruction::MoveForward,
+             Instruction::JumpIfZero(5),
+             Instruction::Decrement,
+             Instruction::Decrement,
+             Instruction::JumpIfNonZero(2),
+             Instruction::MoveBack]
+    }
+
+    #[test]
+    fn compile_runsOfIdenticalInstructions_fused() {
+        let counts = Bytecode::compile(&make_program())
+            .get_ops()
+            .iter()
+            .map(|op| op.count)
+            .collect::<Vec<_>>();
+        assert_eq!(counts, vec![2, 1, 2, 1, 1]);
+    }
+
+    #[test]
+    fn compile_jumps_continueAfterMatchingBracket() {
+        let bytecode = Bytecode::compile(&make_program());
+        assert_eq!(bytecode.get_ops()[1].op_code, OpCode::JumpIfZero(4));
+        assert_eq!(bytecode.get_ops()[3].op_code, OpCode::JumpIfNonZero(2));
+    }
+
+    #[test]
+    fn compile_jumpIntoRun_runSplit() {
+        let program = vec![Instruction::Decrement,
+                           Instruction::Decrement,
+                           Instruction::Decrement,
+                           Instruction::JumpIfNonZero(0)];
+        let bytecode = Bytecode::compile(&program);
+        assert_eq!(bytecode.get_ops().iter().map(|op| op.count).collect::<Vec<_>>(),
+                   vec![1, 2, 1]);
+        assert_eq!(bytecode.get_ops()[2].op_code, OpCode::JumpIfNonZero(1));
+    }
+
+    #[test]
+    fn compile_targetPastTheEnd_endsProgram() {
+        let program = vec![Instruction::Increment, Instruction::JumpIfNonZero(usize::MAX)];
+        assert_eq!(Bytecode::compile(&program).get_ops()[1].op_code, OpCode::JumpIfNonZero(2));
+    }
+
+    #[test]
+    fn compile_adjacentJumps_neverFused() {
+        let program = vec![Instruction::JumpIfZero(3),
+                           Instruction::JumpIfZero(2),
+                           Instruction::JumpIfNonZero(1),
+                           Instruction::JumpIfNonZero(0)];
+        assert_eq!(Bytecode::compile(&program).get_ops().len(), 4);
+    }
+
+    #[test]
+    fn sourceIndex_midwayAndPastTheEnd() {
+        let bytecode = Bytecode::compile(&make_program());
+        assert_eq!(bytecode.source_index(2, 1), 4);
+        assert_eq!(bytecode.source_index(5, 0), 7);
+    }
+}
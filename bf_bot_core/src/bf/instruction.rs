@@ -1,15 +1,21 @@
-#[derive(Debug, PartialEq)]
+/// A single instruction of a BF Joust program. The repetition syntax of BF Joust, `(a)*n` and
+/// `(a{b}c)%n`, is expanded by the parser, so every program consists of these instructions only.
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub enum Instruction {
+    /// `<`: Moves the bot one cell towards its own flag.
     MoveBack,
+    /// `>`: Moves the bot one cell towards the enemy flag.
     MoveForward,
+    /// `+`: Increments the current cell.
     Increment,
+    /// `-`: Decrements the current cell.
     Decrement,
-    StartWhileNotZero { target_pointer: usize },
-    EndWhileNotZero { target_pointer: usize },
+    /// `[`: Jumps to the instruction at the given index if the current cell is zero. The index is
+    /// that of the matching `]`, execution continues at the instruction after it.
+    JumpIfZero(usize),
+    /// `]`: Jumps to the instruction at the given index if the current cell is not zero. The index
+    /// is that of the matching `[`, execution continues at the instruction after it.
+    JumpIfNonZero(usize),
+    /// `.`: Does nothing for one cycle.
     SkipExecution,
-    StartFor { target_pointer: usize },
-    EndFor {
-        target_pointer: usize,
-        nr_iterations: usize,
-    },
 }
@@ -1,5 +1,17 @@
+pub use self::bytecode::{Bytecode, Op, OpCode};
+mod bytecode;
+
 pub use self::bot::Bot;
 mod bot;
 
 pub use self::instruction::Instruction;
 mod instruction;
+
+pub use self::named_bot::NamedBot;
+mod named_bot;
+
+pub use self::program_error::ProgramError;
+mod program_error;
+
+pub use self::unmatched_bracket_policy::UnmatchedBracketPolicy;
+mod unmatched_bracket_policy;
@@ -0,0 +1,26 @@
+use alloc::string::{String, ToString};
+
+use bf::Bot;
+
+/// A bot together with the name it goes by, and optionally the name of its author, so that
+/// results and standings can be reported by name.
+#[derive(Debug, Clone, PartialEq)]
+pub struct NamedBot {
+    pub name: String,
+    pub author: Option<String>,
+    pub bot: Bot,
+}
+
+impl NamedBot {
+    pub fn new(name: &str, bot: Bot) -> NamedBot {
+        NamedBot {
+            name: name.to_string(),
+            author: None,
+            bot,
+        }
+    }
+
+    pub fn with_author(self, author: &str) -> NamedBot {
+        NamedBot { author: Some(author.to_string()), ..self }
+    }
+}
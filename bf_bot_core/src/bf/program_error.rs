@@ -0,0 +1,22 @@
+use core::error::Error;
+use core::fmt;
+
+/// Describes why `Bot::new_checked` rejected a program.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ProgramError {
+    /// The bracket at the given index has no matching bracket: its target lies outside the
+    /// program, or is not a bracket of the opposite kind that targets it in return.
+    UnmatchedBracket { index: usize },
+}
+
+impl fmt::Display for ProgramError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            ProgramError::UnmatchedBracket { index } => {
+                write!(f, "Unmatched bracket at instruction {}.", index)
+            }
+        }
+    }
+}
+
+impl Error for ProgramError {}
@@ -0,0 +1,11 @@
+/// What `Bot::new_checked` does with a bracket that has no matching bracket.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum UnmatchedBracketPolicy {
+    /// The program is rejected with a `ProgramError`.
+    #[default]
+    Reject,
+    /// The program is accepted, and the bot terminates if it takes the jump of an unmatched
+    /// bracket: there is nowhere to jump to. A jump that is not taken has no effect, as usual.
+    /// This is what `Bot::new` does with jumps whose target lies outside the program.
+    Terminate,
+}
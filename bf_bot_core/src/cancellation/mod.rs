@@ -0,0 +1,4 @@
+//! Stopping long computations, such as tournaments and evolution runs, from another thread.
+
+pub use self::token::CancellationToken;
+mod token;
@@ -0,0 +1,36 @@
+use alloc::sync::Arc;
+use core::sync::atomic::{AtomicBool, Ordering};
+
+/// A request to stop, shared between the code that makes it, such as a Ctrl-C handler or a stop
+/// button, and the computation that honours it. Cancellation is cooperative: a computation checks
+/// the token at points where it can stop cleanly, and returns what it has achieved so far.
+///
+/// # Examples
+///
+/// ```
+/// use bf_bot_core::cancellation::CancellationToken;
+/// let token = CancellationToken::new();
+/// let handle = token.clone();
+/// assert!(!token.is_cancelled());
+/// handle.cancel();
+/// assert!(token.is_cancelled());
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken {
+    cancelled: Arc<AtomicBool>,
+}
+
+impl CancellationToken {
+    pub fn new() -> CancellationToken {
+        CancellationToken::default()
+    }
+
+    /// Asks every computation that holds a clone of this token to stop.
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+}
@@ -0,0 +1,17 @@
+use engine::Orientation;
+
+/// Stops the debugger as soon as a bot is about to execute a certain instruction.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Breakpoint {
+    /// The bot that the breakpoint applies to: `Normal` for the start bot, `Reversed` for the end
+    /// bot.
+    pub bot: Orientation,
+    /// The index of the instruction in the program of the bot.
+    pub code_index: usize,
+}
+
+impl Breakpoint {
+    pub fn new(bot: Orientation, code_index: usize) -> Breakpoint {
+        Breakpoint { bot, code_index }
+    }
+}
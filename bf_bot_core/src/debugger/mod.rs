@@ -0,0 +1,12 @@
+//! Plays a round under the control of the user, for finding out why a bot behaves the way it
+//! does. The debugger wraps an `Arena` and runs it until a condition is met, such as a number of
+//! steps, a bot reaching a certain instruction, or a flag being changed.
+
+pub use self::breakpoint::Breakpoint;
+mod breakpoint;
+
+pub use self::stop_reason::StopReason;
+mod stop_reason;
+
+pub use self::session::Debugger;
+mod session;
@@ -0,0 +1,298 @@
+use std::fmt::Write;
+
+use bf::Bot;
+use debugger::{Breakpoint, StopReason};
+use error::BfError;
+use engine::{Arena, BotSnapshot, Orientation, RoundSnapshot};
+use simul_round::{RoundParams, RoundResult, StepOutcome};
+
+/// Runs a round step by step, stopping at breakpoints and other conditions. Every command that
+/// runs the round also stops at the breakpoints, and as soon as the round has ended.
+#[derive(Debug)]
+pub struct Debugger<'a> {
+    arena: Arena<'a>,
+    breakpoints: Vec<Breakpoint>,
+    /// The result of the round, once it has ended.
+    result: Option<RoundResult>,
+}
+
+impl<'a> Debugger<'a> {
+    /// Prepares a round between the given bots, without executing any step yet. Fails if the
+    /// round parameters are not valid.
+    pub fn new(bot_a: &'a Bot,
+               bot_b: &'a Bot,
+               round_params: &RoundParams)
+               -> Result<Debugger<'a>, BfError> {
+        Ok(Debugger {
+            arena: Arena::new(bot_a, bot_b, round_params)?,
+            breakpoints: vec![],
+            result: None,
+        })
+    }
+
+    pub fn add_breakpoint(&mut self, breakpoint: Breakpoint) {
+        if !self.breakpoints.contains(&breakpoint) {
+            self.breakpoints.push(breakpoint);
+        }
+    }
+
+    /// Removes the given breakpoint, and returns whether it existed.
+    pub fn remove_breakpoint(&mut self, breakpoint: &Breakpoint) -> bool {
+        let nr_breakpoints = self.breakpoints.len();
+        self.breakpoints.retain(|existing| existing != breakpoint);
+        self.breakpoints.len() < nr_breakpoints
+    }
+
+    pub fn get_breakpoints(&self) -> &[Breakpoint] {
+        &self.breakpoints
+    }
+
+    /// Returns the result of the round, if it has ended.
+    pub fn get_result(&self) -> Option<&RoundResult> {
+        self.result.as_ref()
+    }
+
+    pub fn snapshot(&self) -> RoundSnapshot<'_> {
+        self.arena.snapshot()
+    }
+
+    /// Executes the given number of steps. Executes nothing if the number of steps is zero.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bf_bot_core::bf::{Bot, Instruction};
+    /// use bf_bot_core::debugger::{Debugger, StopReason};
+    /// use bf_bot_core::simul_round::RoundParams;
+    /// let bot = Bot::new(vec![Instruction::MoveForward; 5]);
+    /// let mut debugger = Debugger::new(&bot, &bot, &RoundParams::new(10, false, 1000)).unwrap();
+    /// assert_eq!(debugger.step(3), StopReason::StepsExecuted);
+    /// assert_eq!(debugger.snapshot().start_bot.position, 3);
+    /// ```
+    pub fn step(&mut self, nr_steps: u32) -> StopReason {
+        if nr_steps == 0 {
+            return match self.result {
+                Some(ref result) => StopReason::Finished(result.clone()),
+                None => StopReason::StepsExecuted,
+            };
+        }
+        let mut nr_executed = 0;
+        self.run_until(|_| {
+            nr_executed += 1;
+            if nr_executed >= nr_steps {
+                Some(StopReason::StepsExecuted)
+            } else {
+                None
+            }
+        })
+    }
+
+    /// Runs until a breakpoint is hit or the round ends.
+    pub fn run(&mut self) -> StopReason {
+        self.run_until(|_| None)
+    }
+
+    /// Runs until the value of any flag changes. The rules of the round decide which cells hold
+    /// the flags.
+    pub fn run_until_flag_changed(&mut self) -> StopReason {
+        let tape = self.arena.get_tape();
+        let flag_cells = self.arena.get_rules().flag_cells(tape.len());
+        let flags_before = flag_cells.iter().map(|&index| tape[index]).collect::<Vec<_>>();
+        self.run_until(|arena| {
+            let tape = arena.get_tape();
+            flag_cells.iter()
+                .zip(flags_before.iter())
+                .find(|&(&index, &before)| tape[index] != before)
+                .map(|(&index, _)| StopReason::FlagChanged { index })
+        })
+    }
+
+    /// Runs until the given bot arrives at the given cell of the tape.
+    pub fn run_until_position(&mut self, bot: Orientation, position: i32) -> StopReason {
+        self.run_until(|arena| {
+            if Debugger::bot_snapshot(&arena.snapshot(), bot).position == position {
+                Some(StopReason::PositionReached { bot, position })
+            } else {
+                None
+            }
+        })
+    }
+
+    /// Describes the current state of the round in text: the step, the tape, both bots and the
+    /// breakpoints.
+    pub fn dump(&self) -> String {
+        let snapshot = self.arena.snapshot();
+        let mut text = String::new();
+        writeln!(text, "Step {}", snapshot.step_nr).unwrap();
+        let cells = snapshot.tape.iter().map(|cell| cell.to_string()).collect::<Vec<_>>();
+        writeln!(text, "Tape: {}", cells.join(" ")).unwrap();
+        let bots = [("Start bot", Orientation::Normal), ("End bot", Orientation::Reversed)];
+        for &(name, bot) in &bots {
+            let bot_snapshot = Debugger::bot_snapshot(&snapshot, bot);
+            writeln!(text,
+                     "{}: at cell {}, next instruction {}, loop depth {}",
+                     name,
+                     bot_snapshot.position,
+                     bot_snapshot.code_pointer,
+                     bot_snapshot.loop_depth)
+                .unwrap();
+        }
+        for breakpoint in &self.breakpoints {
+            let name = bots.iter().find(|&&(_, bot)| bot == breakpoint.bot).unwrap().0;
+            writeln!(text,
+                     "Breakpoint: {} at instruction {}",
+                     name.to_lowercase(),
+                     breakpoint.code_index)
+                .unwrap();
+        }
+        if let Some(ref result) = self.result {
+            writeln!(text, "Finished: {:?}", result.status()).unwrap();
+        }
+        text
+    }
+
+    /// Executes steps until the given condition, which is passed the arena after each step,
+    /// returns a reason to stop.
+    fn run_until<F>(&mut self, mut condition: F) -> StopReason
+        where F: FnMut(&Arena) -> Option<StopReason>
+    {
+        loop {
+            if let Some(ref result) = self.result {
+                return StopReason::Finished(result.clone());
+            }
+            if let StepOutcome::Finished(result) = self.arena.step() {
+                self.result = Some(result);
+                continue;
+            }
+            if let Some(breakpoint) = self.hit_breakpoint() {
+                return StopReason::Breakpoint(breakpoint);
+            }
+            if let Some(reason) = condition(&self.arena) {
+                return reason;
+            }
+        }
+    }
+
+    fn hit_breakpoint(&self) -> Option<Breakpoint> {
+        let snapshot = self.arena.snapshot();
+        self.breakpoints
+            .iter()
+            .find(|breakpoint| {
+                Debugger::bot_snapshot(&snapshot, breakpoint.bot).code_pointer ==
+                breakpoint.code_index
+            })
+            .cloned()
+    }
+
+    fn bot_snapshot(snapshot: &RoundSnapshot, bot: Orientation) -> BotSnapshot {
+        match bot {
+            Orientation::Normal => snapshot.start_bot,
+            Orientation::Reversed => snapshot.end_bot,
+        }
+    }
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod tests {
+    use super::*;
+    use bf::Instruction;
+    use simul_round::{LossReason, Rules};
+
+    fn make_params() -> RoundParams {
+        RoundParams::new(10, false, 1000)
+    }
+
+    /// Its program, in BrainFuck: >>>>>>>>>[-]
+    fn make_clearer() -> Bot {
+        let mut program = vec![Instruction::MoveForward; 9];
+        program.extend(vec![Instruction::JumpIfZero(11),
+                            Instruction::Decrement,
+                            Instruction::JumpIfNonZero(9)]);
+        Bot::new(program)
+    }
+
+    #[test]
+    fn runUntilPosition_clearer_stopsAtEnemyFlag() {
+        let clearer = make_clearer();
+        let idle = Bot::new(vec![]);
+        let mut debugger = Debugger::new(&clearer, &idle, &make_params()).unwrap();
+        assert_eq!(debugger.run_until_position(Orientation::Normal, 9),
+                   StopReason::PositionReached {
+                       bot: Orientation::Normal,
+                       position: 9,
+                   });
+        assert_eq!(debugger.snapshot().step_nr, 9);
+    }
+
+    #[test]
+    fn runUntilFlagChanged_clearer_stopsAtFirstDecrement() {
+        let clearer = make_clearer();
+        let idle = Bot::new(vec![]);
+        let mut debugger = Debugger::new(&clearer, &idle, &make_params()).unwrap();
+        assert_eq!(debugger.run_until_flag_changed(),
+                   StopReason::FlagChanged { index: 9 });
+        assert_eq!(debugger.snapshot().tape[9], 127);
+    }
+
+    #[test]
+    fn runUntilFlagChanged_multipleFlags_stopsAtInnerFlag() {
+        // >-
+        let bot = Bot::new(vec![Instruction::MoveForward, Instruction::Decrement]);
+        let idle = Bot::new(vec![]);
+        let round_params = RoundParams {
+            rules: Rules::MultipleFlags { nr_flags: 2 },
+            ..make_params()
+        };
+        let mut debugger = Debugger::new(&bot, &idle, &round_params).unwrap();
+        assert_eq!(debugger.run_until_flag_changed(),
+                   StopReason::FlagChanged { index: 1 });
+    }
+
+    #[test]
+    fn step_zeroSteps_executesNothing() {
+        let clearer = make_clearer();
+        let idle = Bot::new(vec![]);
+        let mut debugger = Debugger::new(&clearer, &idle, &make_params()).unwrap();
+        assert_eq!(debugger.step(0), StopReason::StepsExecuted);
+        assert_eq!(debugger.snapshot().step_nr, 0);
+    }
+
+    #[test]
+    fn run_breakpoint_stopsBeforeInstruction() {
+        let clearer = make_clearer();
+        let idle = Bot::new(vec![]);
+        let mut debugger = Debugger::new(&clearer, &idle, &make_params()).unwrap();
+        let breakpoint = Breakpoint::new(Orientation::Normal, 10);
+        debugger.add_breakpoint(breakpoint);
+        assert_eq!(debugger.run(), StopReason::Breakpoint(breakpoint));
+        assert_eq!(debugger.snapshot().start_bot.code_pointer, 10);
+        assert!(debugger.remove_breakpoint(&breakpoint));
+    }
+
+    #[test]
+    fn step_afterRoundEnded_reportsResult() {
+        let suicidal = Bot::new(vec![Instruction::MoveBack]);
+        let idle = Bot::new(vec![]);
+        let mut debugger = Debugger::new(&suicidal, &idle, &make_params()).unwrap();
+        match debugger.step(5) {
+            StopReason::Finished(result) => {
+                assert_eq!(result.bot_a_loss, Some(LossReason::OffTape))
+            }
+            reason => panic!("Expected the round to end, but got {:?}.", reason),
+        }
+        assert_eq!(debugger.step(1), StopReason::Finished(debugger.get_result().unwrap().clone()));
+    }
+
+    #[test]
+    fn dump_start_describesTapeAndBots() {
+        let clearer = make_clearer();
+        let idle = Bot::new(vec![]);
+        let debugger = Debugger::new(&clearer, &idle, &make_params()).unwrap();
+        let dump = debugger.dump();
+        let lines = dump.lines().collect::<Vec<_>>();
+        assert_eq!(lines[0], "Step 0");
+        assert_eq!(lines[1], "Tape: -128 0 0 0 0 0 0 0 0 -128");
+        assert_eq!(lines[3], "End bot: at cell 9, next instruction 0, loop depth 0");
+    }
+}
@@ -0,0 +1,18 @@
+use debugger::Breakpoint;
+use engine::Orientation;
+use simul_round::RoundResult;
+
+/// Why the debugger stopped running the round.
+#[derive(Debug, Clone, PartialEq)]
+pub enum StopReason {
+    /// The requested number of steps has been executed.
+    StepsExecuted,
+    /// A bot is about to execute the instruction of the breakpoint.
+    Breakpoint(Breakpoint),
+    /// The value of the flag at the given index of the tape changed during the last step.
+    FlagChanged { index: usize },
+    /// The bot arrived at the requested cell during the last step.
+    PositionReached { bot: Orientation, position: i32 },
+    /// The round has ended. Nothing can be executed any more.
+    Finished(RoundResult),
+}
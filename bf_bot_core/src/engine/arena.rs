@@ -1,113 +1,480 @@
-use bf::Bot;
-use engine::{BotInPlay, Mutation, Polarity, Orientation};
-use simul_round::{RoundResult, RoundParams};
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+use core::fmt;
+use core::hash::{Hash, Hasher};
+
+use bf::{Bot, Instruction};
+use error::BfError;
+use engine::{ArenaObserver, BotInPlay, BotPointers, BotProgress, BotSnapshot, CellChange, Mutation,
+             Polarity, Orientation, Recorder, Referee, Replay, RoundSnapshot, RuleSet, StepView,
+             TapeHasher, TapeHeatmap};
+use simul_round::{RoundOutcome, RoundResult, RoundParams, StepOutcome};
 
 #[derive(Debug)]
 pub struct Arena<'a> {
-    max_steps: u32,
     step_nr: u32,
-    pub tape: Vec<i8>, //FIXME: make this not public. Had to do this to give unit tests access.
+    pub tape: Vec<i32>, //FIXME: make this not public. Had to do this to give unit tests access.
     start_bot: BotInPlay<'a>,
     end_bot: BotInPlay<'a>,
+    /// Applies the rules of the round, including those that decide the initial tape and when the
+    /// bots lose.
+    referee: Referee,
+    /// True if the rules follow the round parameters, rather than having been given explicitly.
+    rules_from_params: bool,
+    /// Only present if a recorder was attached.
+    recorder: Option<Recorder>,
+    /// Only present if an observer was attached.
+    observer: Option<AttachedObserver<'a>>,
+    /// Only present if a heatmap was attached.
+    heatmap: Option<TapeHeatmap>,
+}
+
+/// Wraps an observer, so that the arena can still be debug-printed.
+struct AttachedObserver<'a>(Box<dyn ArenaObserver + 'a>);
+
+impl<'a> fmt::Debug for AttachedObserver<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "AttachedObserver")
+    }
 }
 
 impl<'a> Arena<'a> {
-    pub fn new<'b>(bot1: &'b Bot, bot2: &'b Bot, round_params: &RoundParams) -> Arena<'b> {
-        let polarity = if round_params.invert_polarity {
+    /// Prepares a round between the given bots under the rules of the round parameters. Fails if
+    /// the round parameters are not valid.
+    pub fn new<'b>(bot1: &'b Bot,
+                   bot2: &'b Bot,
+                   round_params: &RoundParams)
+                   -> Result<Arena<'b>, BfError> {
+        round_params.validate()?;
+        Ok(Arena {
+            rules_from_params: true,
+            ..Arena::with_rules(bot1, bot2, round_params, round_params.rules.rule_set())
+        })
+    }
+
+    /// Prepares a round between the given bots under the given rules, which decide what the tape
+    /// looks like at the start and when either bot has lost. These replace the rules of the round
+    /// parameters.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the rules can't lay out the tape of the round parameters, such as a tape that has
+    /// no room for both flags.
+    pub fn with_rules<'b>(bot1: &'b Bot,
+                          bot2: &'b Bot,
+                          round_params: &RoundParams,
+                          rules: Box<dyn RuleSet>)
+                          -> Arena<'b> {
+        let (start_bot, end_bot) = Arena::make_bots(bot1, bot2, round_params);
+        let mut tape = vec![];
+        rules.write_initial_tape(round_params, &mut tape);
+        Arena {
+            step_nr: 0,
+            tape,
+            start_bot,
+            end_bot,
+            referee: Referee::new(round_params, rules),
+            rules_from_params: false,
+            recorder: None,
+            observer: None,
+            heatmap: None,
+        }
+    }
+
+    /// Plays a round in which the given bot starts at the start of the tape and its opponent does
+    /// nothing, and returns every step of it. This tests a bot on its own, for example to see how
+    /// many decoys it lays in its first steps: the tape after any step is given by
+    /// `Replay::tape_at`. The round ends once the bot has ended its program or left the tape, or
+    /// has zeroed the flag of its idle opponent. Fails if the round parameters are not valid.
+    ///
+    /// This is faster than a round against a bot without instructions: only the given bot is
+    /// stepped, against a tape that nothing else changes except noise. The rules of the round
+    /// parameters still apply in full, so the replay is the same.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bf_bot_core::bf::{Bot, Instruction};
+    /// use bf_bot_core::engine::Arena;
+    /// use bf_bot_core::simul_round::RoundParams;
+    /// // `>+>-`
+    /// let bot = Bot::new(vec![Instruction::MoveForward,
+    ///                         Instruction::Increment,
+    ///                         Instruction::MoveForward,
+    ///                         Instruction::Decrement]);
+    /// let replay = Arena::solo(&bot, &RoundParams::new(10, false, 200)).unwrap();
+    /// assert_eq!(replay.steps.len(), 4);
+    /// assert_eq!(replay.tape_at(4)[..4], [-128, 1, -1, 0]);
+    /// ```
+    pub fn solo(bot: &Bot, round_params: &RoundParams) -> Result<Replay, BfError> {
+        round_params.validate()?;
+        let idle_bot = Bot::new(vec![]);
+        let (mut bot_in_play, idle_bot_in_play) = Arena::make_bots(bot, &idle_bot, round_params);
+        let idle_pointers = Arena::pointers_of(&idle_bot_in_play);
+        let idle_progress = Arena::progress_of(&idle_bot_in_play);
+        let idle_position = idle_bot_in_play.get_raw_pos();
+        let mut referee = Referee::new(round_params, round_params.rules.rule_set());
+        let mut tape = vec![];
+        referee.get_rules().write_initial_tape(round_params, &mut tape);
+        let mut recorder = Recorder::new(&tape);
+        let mut step_nr = 0;
+        loop {
+            let result = if referee.ended_before_cycle(step_nr,
+                                                       &tape,
+                                                       Arena::progress_of(&bot_in_play),
+                                                       idle_progress) {
+                RoundResult::draw()
+            } else {
+                referee.start_cycle(step_nr, &tape);
+                let cell_is_zero =
+                    referee.senses_zero(Orientation::Normal, bot_in_play.get_pos(), step_nr, &tape);
+                let mutation = if referee.acts(Orientation::Normal, step_nr) {
+                    Arena::step_bot(&mut bot_in_play, cell_is_zero)
+                } else {
+                    None
+                };
+                step_nr += 1;
+                // Without a second bot, changes never collide.
+                if let Some(mutation) = mutation {
+                    referee.add(&mut tape, mutation.get_index(), mutation.get_addend().into());
+                }
+                if let Some((index, addend)) = referee.perturbation(step_nr, tape.len()) {
+                    referee.add(&mut tape, index, addend);
+                }
+                recorder.record_step(&tape, Arena::pointers_of(&bot_in_play), idle_pointers);
+                referee.end_cycle(step_nr,
+                                  &tape,
+                                  bot_in_play.get_raw_pos(),
+                                  idle_position,
+                                  || (bot_in_play.get_code_pointer(), idle_pointers.code_pointer))
+            };
+            if result.round_is_finished() {
+                recorder.record_result(result.at_step(step_nr, Arena::tape_hash(&tape)));
+                return Ok(recorder.into_replay());
+            }
+        }
+    }
+
+    /// Prepares the arena for a new round between the given bots, as if it was newly constructed,
+    /// but without allocating a new tape. Rules that were given to `with_rules` and an attached
+    /// observer stay, and an attached recorder or heatmap starts over. Fails, leaving the arena as
+    /// it was, if the round parameters are not valid.
+    pub fn reset(&mut self,
+                 bot1: &'a Bot,
+                 bot2: &'a Bot,
+                 round_params: &RoundParams)
+                 -> Result<(), BfError> {
+        round_params.validate()?;
+        let (start_bot, end_bot) = Arena::make_bots(bot1, bot2, round_params);
+        self.step_nr = 0;
+        let rules = if self.rules_from_params {
+            Some(round_params.rules.rule_set())
+        } else {
+            None
+        };
+        self.referee.reset(round_params, rules);
+        self.referee.get_rules().write_initial_tape(round_params, &mut self.tape);
+        self.start_bot = start_bot;
+        self.end_bot = end_bot;
+        if self.recorder.is_some() {
+            self.attach_recorder();
+        }
+        if self.heatmap.is_some() {
+            self.attach_heatmap();
+        }
+        Ok(())
+    }
+
+    fn make_bots<'b>(bot1: &'b Bot,
+                     bot2: &'b Bot,
+                     round_params: &RoundParams)
+                     -> (BotInPlay<'b>, BotInPlay<'b>) {
+        let (start_reversed, end_reversed) =
+            round_params.polarity_mode.reversed_bots(round_params.invert_polarity);
+        let polarity = |reversed| if reversed {
             Polarity::Reversed
         } else {
             Polarity::Normal
         };
-        Arena {
-            max_steps: round_params.max_steps,
-            step_nr: 0,
-            tape: Arena::make_tape(round_params.tape_length as usize),
-            start_bot: BotInPlay::new(bot1,
-                                      round_params.tape_length as i32,
-                                      Orientation::Normal,
-                                      Polarity::Normal),
-            end_bot: BotInPlay::new(bot2,
-                                    round_params.tape_length as i32,
-                                    Orientation::Reversed,
-                                    polarity),
+        (BotInPlay::new(bot1,
+                        round_params.tape_length as i32,
+                        Orientation::Normal,
+                        polarity(start_reversed),
+                        round_params.engine),
+         BotInPlay::new(bot2,
+                        round_params.tape_length as i32,
+                        Orientation::Reversed,
+                        polarity(end_reversed),
+                        round_params.engine))
+    }
+
+    /// Returns the values of all cells. Regardless of the cell width, the values are stored as
+    /// `i32`; `u8` cells for example hold values from 0 through 255.
+    pub fn get_tape(&self) -> &Vec<i32> {
+        &self.tape
+    }
+
+    /// Returns the rules of the round, which decide where the flags are and when the bots lose.
+    pub fn get_rules(&self) -> &dyn RuleSet {
+        self.referee.get_rules()
+    }
+
+    /// Returns the number of cycles that have been executed so far.
+    pub fn get_step_nr(&self) -> u32 {
+        self.step_nr
+    }
+
+    pub fn get_start_bot_pointers(&self) -> BotPointers {
+        Arena::pointers_of(&self.start_bot)
+    }
+
+    pub fn get_end_bot_pointers(&self) -> BotPointers {
+        Arena::pointers_of(&self.end_bot)
+    }
+
+    /// Returns the current state of the round: the tape and where both bots are, on the tape and
+    /// in their programs.
+    pub fn snapshot(&self) -> RoundSnapshot<'_> {
+        RoundSnapshot {
+            step_nr: self.step_nr,
+            tape: &self.tape,
+            start_bot: Arena::snapshot_of(&self.start_bot),
+            end_bot: Arena::snapshot_of(&self.end_bot),
         }
     }
 
-    fn make_tape(length: usize) -> Vec<i8> {
-        let mut tape = vec!(0i8; length);
-        tape[0] = i8::min_value();
-        tape[length - 1] = i8::min_value();
-        tape
+    fn snapshot_of(bot_in_play: &BotInPlay) -> BotSnapshot {
+        let code_pointer = bot_in_play.get_code_pointer();
+        BotSnapshot {
+            code_pointer,
+            position: bot_in_play.get_raw_pos(),
+            loop_depth: bot_in_play.get_bot().loop_depth(code_pointer),
+        }
     }
 
-    pub fn get_tape(&self) -> &Vec<i8> {
-        &self.tape
+    /// Starts recording every subsequent step of the round. The recording can be retrieved with
+    /// `take_replay`.
+    pub fn attach_recorder(&mut self) {
+        self.recorder = Some(Recorder::new(&self.tape));
+    }
+
+    /// Stops recording and returns what was recorded so far, if a recorder was attached.
+    pub fn take_replay(&mut self) -> Option<Replay> {
+        self.recorder.take().map(Recorder::into_replay)
+    }
+
+    /// Starts counting how often each bot reads and writes each cell from now on. The counts can
+    /// be retrieved with `take_heatmap`.
+    pub fn attach_heatmap(&mut self) {
+        self.heatmap = Some(TapeHeatmap::new(self.tape.len()));
     }
 
-    pub fn step(&mut self) -> RoundResult {
-        if self.exceeded_max_steps() || self.sink_state_detected() {
+    /// Stops counting and returns the counts so far, if a heatmap was attached.
+    pub fn take_heatmap(&mut self) -> Option<TapeHeatmap> {
+        self.heatmap.take()
+    }
+
+    /// Notifies the given observer of everything that happens from now on. Replaces any observer
+    /// that was attached before.
+    pub fn attach_observer(&mut self, observer: Box<dyn ArenaObserver + 'a>) {
+        self.observer = Some(AttachedObserver(observer));
+    }
+
+    /// Detaches the observer and returns it, if one was attached.
+    pub fn detach_observer(&mut self) -> Option<Box<dyn ArenaObserver + 'a>> {
+        self.observer.take().map(|AttachedObserver(observer)| observer)
+    }
+
+    /// Executes a single cycle, in which both bots execute one instruction, and decides whether
+    /// the round has ended.
+    pub fn step(&mut self) -> StepOutcome {
+        let result = self.play_cycle();
+        if !result.round_is_finished() {
+            return StepOutcome::Continue;
+        }
+        let result = result.at_step(self.step_nr, Arena::tape_hash(&self.tape));
+        if let Some(ref mut recorder) = self.recorder {
+            recorder.record_result(result.clone());
+        }
+        if let Some(AttachedObserver(ref mut observer)) = self.observer {
+            observer.on_round_end(&result);
+        }
+        StepOutcome::Finished(result)
+    }
+
+    /// Plays the round until it has ended and returns the final result.
+    pub fn run_to_completion(mut self) -> RoundOutcome {
+        self.finish()
+    }
+
+    /// Plays the remainder of the round and returns the final result. Unlike `run_to_completion`,
+    /// this leaves the arena available for inspection afterwards.
+    pub fn finish(&mut self) -> RoundOutcome {
+        loop {
+            if let StepOutcome::Finished(result) = self.step() {
+                return RoundOutcome {
+                    result,
+                    nr_steps: self.step_nr,
+                };
+            }
+        }
+    }
+
+    fn play_cycle(&mut self) -> RoundResult {
+        if self.referee.ended_before_cycle(self.step_nr,
+                                           &self.tape,
+                                           Arena::progress_of(&self.start_bot),
+                                           Arena::progress_of(&self.end_bot)) {
             return RoundResult::draw();
         }
-        let flag_a_previously_zeroed = self.flag_a_zeroed();
-        let flag_b_previously_zeroed = self.flag_b_zeroed();
+        self.referee.start_cycle(self.step_nr, &self.tape);
         self.execute_instructions();
-        self.generate_result(flag_a_previously_zeroed, flag_b_previously_zeroed)
+        self.record_step();
+        let start_bot = &self.start_bot;
+        let end_bot = &self.end_bot;
+        self.referee.end_cycle(self.step_nr,
+                               &self.tape,
+                               start_bot.get_raw_pos(),
+                               end_bot.get_raw_pos(),
+                               || (start_bot.get_code_pointer(), end_bot.get_code_pointer()))
     }
 
-    //FIXME: Code duplication.
-    fn execute_instructions(&mut self) {
-        let optional_cell_mutation_1 = Arena::step_bot(&mut self.start_bot, &self.tape);
-        let optional_cell_mutation_2 = Arena::step_bot(&mut self.end_bot, &self.tape);
-        if let Some(mutation) = optional_cell_mutation_1 {
-            self.tape[mutation.get_index()] = self.tape[mutation.get_index()]
-                .wrapping_add(mutation.get_addend());
+    fn progress_of(bot_in_play: &BotInPlay) -> BotProgress {
+        BotProgress {
+            program_has_ended: bot_in_play.program_has_ended(),
+            nr_executed_instructions: bot_in_play.get_nr_executed_instructions(),
         }
-        if let Some(mutation) = optional_cell_mutation_2 {
-            self.tape[mutation.get_index()] = self.tape[mutation.get_index()]
-                .wrapping_add(mutation.get_addend());
+    }
+
+    fn record_step(&mut self) {
+        if let Some(ref mut recorder) = self.recorder {
+            recorder.record_step(&self.tape,
+                                 Arena::pointers_of(&self.start_bot),
+                                 Arena::pointers_of(&self.end_bot));
+        }
+        if let Some(AttachedObserver(ref mut observer)) = self.observer {
+            observer.on_step(&StepView {
+                step_nr: self.step_nr,
+                tape: &self.tape,
+                start_bot: Arena::pointers_of(&self.start_bot),
+                end_bot: Arena::pointers_of(&self.end_bot),
+            });
         }
-        self.step_nr += 1;
     }
 
-    /// Make the given BotInPlay execute the next instruction.
-    fn step_bot(bot_in_play: &mut BotInPlay, tape: &[i8]) -> Option<Mutation> {
-        if bot_in_play.program_has_ended() {
-            return None;
+    fn pointers_of(bot_in_play: &BotInPlay) -> BotPointers {
+        BotPointers {
+            code_pointer: bot_in_play.get_code_pointer(),
+            position: bot_in_play.get_raw_pos(),
+        }
+    }
+
+    fn tape_hash(tape: &[i32]) -> u64 {
+        let mut hasher = TapeHasher::default();
+        tape.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    fn execute_instructions(&mut self) {
+        if let Some(ref mut heatmap) = self.heatmap {
+            Arena::record_read(heatmap, Orientation::Normal, &self.start_bot);
+            Arena::record_read(heatmap, Orientation::Reversed, &self.end_bot);
+        }
+        let start_cell_is_zero = self.senses_zero(Orientation::Normal);
+        let end_cell_is_zero = self.senses_zero(Orientation::Reversed);
+        let optional_cell_mutation_1 = if self.referee.acts(Orientation::Normal, self.step_nr) {
+            #[cfg(feature = "tracing")]
+            self.trace_instruction(Orientation::Normal);
+            Arena::step_bot(&mut self.start_bot, start_cell_is_zero)
+        } else {
+            None
+        };
+        let optional_cell_mutation_2 = if self.referee.acts(Orientation::Reversed, self.step_nr) {
+            #[cfg(feature = "tracing")]
+            self.trace_instruction(Orientation::Reversed);
+            Arena::step_bot(&mut self.end_bot, end_cell_is_zero)
+        } else {
+            None
+        };
+        self.step_nr += 1;
+        let index_1 = optional_cell_mutation_1.as_ref().map(Mutation::get_index);
+        let index_2 = optional_cell_mutation_2.as_ref().map(Mutation::get_index);
+        let (apply_1, apply_2) = self.referee.applied_changes(self.step_nr, index_1, index_2);
+        if let Some(mutation) = optional_cell_mutation_1.filter(|_| apply_1) {
+            self.apply_mutation(Orientation::Normal, &mutation);
+        }
+        if let Some(mutation) = optional_cell_mutation_2.filter(|_| apply_2) {
+            self.apply_mutation(Orientation::Reversed, &mutation);
+        }
+        if let Some((index, addend)) = self.referee.perturbation(self.step_nr, self.tape.len()) {
+            self.referee.add(&mut self.tape, index, addend);
         }
-        let current_cell_is_zero = tape[bot_in_play.get_pos()] == 0;
-        let option = bot_in_play.execute_code(current_cell_is_zero);
-        bot_in_play.increment_code_pointer();
-        option
     }
 
-    fn generate_result(&self,
-                       flag_a_previously_zeroed: bool,
-                       flag_b_previously_zeroed: bool)
-                       -> RoundResult {
-        let start_bot_lost = self.start_bot.bot_is_off_tape(&(self.tape.len() as i32)) ||
-                             (flag_a_previously_zeroed && self.flag_a_zeroed());
-        let end_bot_lost = self.end_bot.bot_is_off_tape(&(self.tape.len() as i32)) ||
-                           (flag_b_previously_zeroed && self.flag_b_zeroed());
-        RoundResult::new(start_bot_lost, end_bot_lost)
+    /// Changes the tape as the bot with the given orientation instructed.
+    fn apply_mutation(&mut self, bot: Orientation, mutation: &Mutation) {
+        let index = mutation.get_index();
+        self.referee.add(&mut self.tape, index, mutation.get_addend().into());
+        if let Some(ref mut heatmap) = self.heatmap {
+            heatmap.record_write(bot, index);
+        }
+        if let Some(AttachedObserver(ref mut observer)) = self.observer {
+            let change = CellChange {
+                index,
+                new_value: self.tape[index],
+            };
+            observer.on_mutation(self.step_nr, bot, change);
+        }
     }
 
-    fn exceeded_max_steps(&self) -> bool {
-        self.step_nr >= self.max_steps
+    /// Counts a read of the cell under the given bot, if its next instruction is a loop condition.
+    fn record_read(heatmap: &mut TapeHeatmap, bot: Orientation, bot_in_play: &BotInPlay) {
+        if bot_in_play.program_has_ended() {
+            return;
+        }
+        let instruction = bot_in_play.get_bot().get_program()[bot_in_play.get_code_pointer()];
+        if let Instruction::JumpIfZero(_) | Instruction::JumpIfNonZero(_) = instruction {
+            heatmap.record_read(bot, bot_in_play.get_pos());
+        }
     }
 
-    /// Returns true if it detects that the game is in a sink state; meaning that both bots have
-    /// ended their programs and neither flag is currently zero.
-    fn sink_state_detected(&self) -> bool {
-        let neither_flag_is_zero = !self.flag_a_zeroed() && !self.flag_b_zeroed();
-        let both_ended = self.start_bot.program_has_ended() && self.end_bot.program_has_ended();
-        neither_flag_is_zero && both_ended
+    /// Returns whether the bot with the given orientation senses that the cell it stands on is
+    /// zero. Without fog of war, that is simply whether the cell is zero.
+    fn senses_zero(&mut self, bot: Orientation) -> bool {
+        let position = match bot {
+            Orientation::Normal => self.start_bot.get_pos(),
+            Orientation::Reversed => self.end_bot.get_pos(),
+        };
+        self.referee.senses_zero(bot, position, self.step_nr, &self.tape)
     }
 
-    fn flag_a_zeroed(&self) -> bool {
-        self.tape[0] == 0
+    /// Logs the instruction that the bot with the given orientation is about to execute, with the
+    /// cell it stands on.
+    #[cfg(feature = "tracing")]
+    fn trace_instruction(&self, bot: Orientation) {
+        let (name, bot_in_play) = match bot {
+            Orientation::Normal => ("start", &self.start_bot),
+            Orientation::Reversed => ("end", &self.end_bot),
+        };
+        if bot_in_play.program_has_ended() {
+            return;
+        }
+        let code_pointer = bot_in_play.get_code_pointer();
+        let position = bot_in_play.get_pos();
+        trace!("step {}, {} bot: instruction {} {:?}, tape position {}, cell value {}",
+               self.step_nr,
+               name,
+               code_pointer,
+               bot_in_play.get_bot().get_program()[code_pointer],
+               position,
+               self.tape[position]);
     }
 
-    fn flag_b_zeroed(&self) -> bool {
-        self.tape[self.tape.len() - 1] == 0
+    /// Make the given BotInPlay execute the next instruction.
+    fn step_bot(bot_in_play: &mut BotInPlay, current_cell_is_zero: bool) -> Option<Mutation> {
+        if bot_in_play.program_has_ended() {
+            return None;
+        }
+        bot_in_play.execute_code(current_cell_is_zero)
     }
 }
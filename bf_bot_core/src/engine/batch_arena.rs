@@ -0,0 +1,432 @@
+use alloc::vec::Vec;
+use core::hash::{Hash, Hasher};
+use core::mem;
+
+#[cfg(feature = "tracing")]
+use bf::Instruction;
+use bf::{Bot, Bytecode, OpCode};
+use cancellation::CancellationToken;
+use error::BfError;
+use engine::{BotProgress, Orientation, Referee, TapeHasher};
+use simul_round::{RoundParams, RoundResult};
+
+/// The state of one bot in every round of a batch. Every field holds one value per round, so that
+/// stepping all rounds walks through a handful of contiguous arrays.
+#[derive(Debug)]
+struct BatchBots<'a> {
+    bytecode: &'a Bytecode,
+    /// The original program, to name the instructions in the log.
+    #[cfg(feature = "tracing")]
+    program: &'a [Instruction],
+    positions: Vec<i32>,
+    op_indices: Vec<usize>,
+    /// The number of times that the current operation has already been executed.
+    progress: Vec<u32>,
+    nr_executed_instructions: Vec<u32>,
+    /// The change of the position on `>`: 1 for the start bot, -1 for the end bot.
+    forward: i32,
+    /// The change of a cell on `+`: 1 for normal polarity, -1 for reversed polarity.
+    increments: Vec<i32>,
+}
+
+impl<'a> BatchBots<'a> {
+    fn new(bot: &'a Bot, positions: Vec<i32>, forward: i32, increments: Vec<i32>) -> BatchBots<'a> {
+        let nr_rounds = positions.len();
+        BatchBots {
+            bytecode: bot.get_bytecode(),
+            #[cfg(feature = "tracing")]
+            program: bot.get_program(),
+            positions,
+            op_indices: vec![0; nr_rounds],
+            progress: vec![0; nr_rounds],
+            nr_executed_instructions: vec![0; nr_rounds],
+            forward,
+            increments,
+        }
+    }
+
+    fn program_has_ended(&self, round: usize) -> bool {
+        self.op_indices[round] >= self.bytecode.get_ops().len()
+    }
+
+    fn progress(&self, round: usize) -> BotProgress {
+        BotProgress {
+            program_has_ended: self.program_has_ended(round),
+            nr_executed_instructions: self.nr_executed_instructions[round],
+        }
+    }
+
+    fn code_pointer(&self, round: usize) -> usize {
+        self.bytecode.source_index(self.op_indices[round], self.progress[round])
+    }
+
+    /// Logs the instruction that the bot with the given name is about to execute in the given
+    /// round, with the cell it stands on.
+    #[cfg(feature = "tracing")]
+    fn trace_instruction(&self, round: usize, step_nr: u32, name: &str, tape: &[i32]) {
+        if self.program_has_ended(round) {
+            return;
+        }
+        let code_pointer = self.code_pointer(round);
+        let position = self.positions[round] as usize;
+        trace!("round {}, step {}, {} bot: instruction {} {:?}, tape position {}, cell value {}",
+               round,
+               step_nr,
+               name,
+               code_pointer,
+               self.program[code_pointer],
+               position,
+               tape[position]);
+    }
+
+    /// Executes the next instruction of the bot in the given round, given whether it senses that
+    /// the cell it stands on is zero. Returns the index of the cell to change and the amount to
+    /// add to it, if any.
+    fn step(&mut self, round: usize, current_cell_is_zero: bool) -> Option<(usize, i32)> {
+        let ops = self.bytecode.get_ops();
+        let op_index = self.op_indices[round];
+        if op_index >= ops.len() {
+            return None;
+        }
+        self.nr_executed_instructions[round] += 1;
+        let position = self.positions[round];
+        let mut mutation = None;
+        match ops[op_index].op_code {
+            OpCode::MoveBack => self.positions[round] -= self.forward,
+            OpCode::MoveForward => self.positions[round] += self.forward,
+            OpCode::Increment => mutation = Some((position as usize, self.increments[round])),
+            OpCode::Decrement => mutation = Some((position as usize, -self.increments[round])),
+            OpCode::JumpIfZero(continue_at) if current_cell_is_zero => {
+                self.op_indices[round] = continue_at;
+                self.progress[round] = 0;
+                return None;
+            }
+            OpCode::JumpIfNonZero(continue_at) if !current_cell_is_zero => {
+                self.op_indices[round] = continue_at;
+                self.progress[round] = 0;
+                return None;
+            }
+            OpCode::JumpIfZero(_) | OpCode::JumpIfNonZero(_) | OpCode::SkipExecution => {}
+        }
+        ops[op_index].advance(&mut self.op_indices[round], &mut self.progress[round]);
+        mutation
+    }
+}
+
+/// Plays several rounds between the same two bots at once, typically all rounds of a match. The
+/// rounds advance in lockstep, one cycle at a time, and the state of all of them is kept in
+/// parallel arrays rather than in one `Arena` per round. A round that has ended drops out, the
+/// others continue.
+///
+/// The results are exactly those that an `Arena` would produce for each round: both apply the
+/// rules through a `Referee`. The execution engine of the round parameters is ignored, since the
+/// batch executes the bytecode itself.
+#[derive(Debug)]
+pub struct BatchArena<'a> {
+    params: Vec<RoundParams>,
+    referees: Vec<Referee>,
+    /// The tapes of all rounds, one after the other.
+    tapes: Vec<i32>,
+    /// The index in `tapes` at which the tape of every round starts.
+    tape_starts: Vec<usize>,
+    start_bots: BatchBots<'a>,
+    end_bots: BatchBots<'a>,
+    results: Vec<Option<RoundResult>>,
+    /// The rounds that have not ended yet.
+    ongoing: Vec<usize>,
+    step_nr: u32,
+}
+
+impl<'a> BatchArena<'a> {
+    /// Prepares a round between the given bots for each of the round parameters. Fails if any of
+    /// the round parameters are not valid.
+    pub fn new<'b>(bot_a: &'b Bot,
+                   bot_b: &'b Bot,
+                   params: Vec<RoundParams>)
+                   -> Result<BatchArena<'b>, BfError> {
+        let mut tapes = vec![];
+        let mut tape_starts = vec![];
+        for round_params in &params {
+            tape_starts.push(tapes.len());
+            tapes.extend(round_params.initial_tape()?);
+        }
+        let reversed_bots = params.iter()
+            .map(|round_params| {
+                round_params.polarity_mode.reversed_bots(round_params.invert_polarity)
+            })
+            .collect::<Vec<_>>();
+        let increment = |reversed: bool| if reversed { -1 } else { 1 };
+        Ok(BatchArena {
+            tapes,
+            tape_starts,
+            start_bots: BatchBots::new(bot_a,
+                                       vec![0; params.len()],
+                                       1,
+                                       reversed_bots.iter().map(|r| increment(r.0)).collect()),
+            end_bots: BatchBots::new(bot_b,
+                                     params.iter().map(|p| p.tape_length as i32 - 1).collect(),
+                                     -1,
+                                     reversed_bots.iter().map(|r| increment(r.1)).collect()),
+            referees: params.iter()
+                .map(|round_params| Referee::new(round_params, round_params.rules.rule_set()))
+                .collect(),
+            results: vec![None; params.len()],
+            ongoing: (0..params.len()).collect(),
+            step_nr: 0,
+            params,
+        })
+    }
+
+    /// Plays every round until it has ended and returns the results, in the order of the round
+    /// parameters.
+    pub fn run_to_completion(mut self) -> Vec<RoundResult> {
+        while !self.ongoing.is_empty() {
+            self.step();
+        }
+        self.results
+            .into_iter()
+            .map(|result| result.expect("Every round has ended."))
+            .collect()
+    }
+
+    /// Like `run_to_completion`, but stops as soon as the given token is cancelled. The rounds that
+    /// had not ended by then are returned as ongoing, with the number of steps played so far.
+    pub fn run_until_cancelled(self, cancellation: &CancellationToken) -> Vec<RoundResult> {
+        self.run_until(|| if cancellation.is_cancelled() {
+            Some(RoundResult::round_ongoing())
+        } else {
+            None
+        })
+    }
+
+    /// Like `run_to_completion`, but asks `stop` before every cycle whether to stop playing. Once
+    /// it returns a result, every round that had not ended by then gets that result, at the number
+    /// of steps played so far.
+    pub fn run_until<F>(mut self, mut stop: F) -> Vec<RoundResult>
+        where F: FnMut() -> Option<RoundResult>
+    {
+        while !self.ongoing.is_empty() {
+            if let Some(result) = stop() {
+                for round in mem::take(&mut self.ongoing) {
+                    self.results[round] =
+                        Some(result.clone().at_step(self.step_nr, self.tape_hash(round)));
+                }
+                break;
+            }
+            self.step();
+        }
+        self.results
+            .into_iter()
+            .map(|result| result.expect("Every round has ended or been stopped."))
+            .collect()
+    }
+
+    /// Executes a single cycle in every round that is still ongoing.
+    fn step(&mut self) {
+        let previously_ongoing = mem::take(&mut self.ongoing);
+        for round in previously_ongoing {
+            match self.play_cycle(round) {
+                Some(result) => {
+                    #[cfg(feature = "tracing")]
+                    self.trace_result(round, &result);
+                    self.results[round] = Some(result);
+                }
+                None => self.ongoing.push(round),
+            }
+        }
+        self.step_nr += 1;
+    }
+
+    /// Reports the end of the given round as a `tracing` event. The rounds of a batch are played
+    /// interleaved, so unlike a round that is played on its own, they can't each have a span.
+    #[cfg(feature = "tracing")]
+    fn trace_result(&self, round: usize, result: &RoundResult) {
+        let params = &self.params[round];
+        ::tracing::debug!(round,
+                          tape_length = params.tape_length,
+                          invert_polarity = params.invert_polarity,
+                          winner = ?result.status(),
+                          nr_steps = result.nr_steps,
+                          "round ended");
+    }
+
+    /// Plays one cycle of the given round and returns its final result if it has ended. This
+    /// mirrors `Arena::step`.
+    fn play_cycle(&mut self, round: usize) -> Option<RoundResult> {
+        let tape_start = self.tape_starts[round];
+        let tape_length = self.params[round].tape_length as usize;
+        let step_nr = self.step_nr;
+        let referee = &mut self.referees[round];
+        let tape = &mut self.tapes[tape_start..tape_start + tape_length];
+        let start_bots = &mut self.start_bots;
+        let end_bots = &mut self.end_bots;
+        if referee.ended_before_cycle(step_nr,
+                                      tape,
+                                      start_bots.progress(round),
+                                      end_bots.progress(round)) {
+            return Some(RoundResult::draw().at_step(step_nr, BatchArena::hash(tape)));
+        }
+        referee.start_cycle(step_nr, tape);
+        let start_cell_is_zero = referee.senses_zero(Orientation::Normal,
+                                                     start_bots.positions[round] as usize,
+                                                     step_nr,
+                                                     tape);
+        let end_cell_is_zero = referee.senses_zero(Orientation::Reversed,
+                                                   end_bots.positions[round] as usize,
+                                                   step_nr,
+                                                   tape);
+        let start_mutation = if referee.acts(Orientation::Normal, step_nr) {
+            #[cfg(feature = "tracing")]
+            start_bots.trace_instruction(round, step_nr, "start", tape);
+            start_bots.step(round, start_cell_is_zero)
+        } else {
+            None
+        };
+        let end_mutation = if referee.acts(Orientation::Reversed, step_nr) {
+            #[cfg(feature = "tracing")]
+            end_bots.trace_instruction(round, step_nr, "end", tape);
+            end_bots.step(round, end_cell_is_zero)
+        } else {
+            None
+        };
+        let (apply_start, apply_end) =
+            referee.applied_changes(step_nr + 1,
+                                    start_mutation.map(|(index, _)| index),
+                                    end_mutation.map(|(index, _)| index));
+        let perturbation = referee.perturbation(step_nr + 1, tape_length);
+        let changes = start_mutation.filter(|_| apply_start)
+            .into_iter()
+            .chain(end_mutation.filter(|_| apply_end))
+            .chain(perturbation);
+        for (index, addend) in changes {
+            referee.add(tape, index, addend);
+        }
+        let result = referee.end_cycle(step_nr + 1,
+                                       tape,
+                                       start_bots.positions[round],
+                                       end_bots.positions[round],
+                                       || {
+                                           (start_bots.code_pointer(round),
+                                            end_bots.code_pointer(round))
+                                       });
+        if result.round_is_finished() {
+            Some(result.at_step(step_nr + 1, BatchArena::hash(tape)))
+        } else {
+            None
+        }
+    }
+
+    fn tape(&self, round: usize) -> &[i32] {
+        let tape_start = self.tape_starts[round];
+        &self.tapes[tape_start..tape_start + self.params[round].tape_length as usize]
+    }
+
+    fn tape_hash(&self, round: usize) -> u64 {
+        BatchArena::hash(self.tape(round))
+    }
+
+    fn hash(tape: &[i32]) -> u64 {
+        let mut hasher = TapeHasher::default();
+        tape.hash(&mut hasher);
+        hasher.finish()
+    }
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod tests {
+    use super::*;
+    use bf::Instruction;
+    use engine::Arena;
+    use simul_game::AllRounds;
+    use simul_round::{CellWidth, CollisionRule, FlagInit, InstructionBudgets, PolarityMode};
+
+    /// Its program, in BrainFuck: >>>>>>>>>[-]+[>[---]+]
+    fn make_attacker() -> Bot {
+        let mut program = vec![Instruction::MoveForward; 9];
+        program.extend(vec![Instruction::JumpIfZero(11),
+                            Instruction::Decrement,
+                            Instruction::JumpIfNonZero(9),
+                            Instruction::Increment,
+                            Instruction::JumpIfZero(21),
+                            Instruction::MoveForward,
+                            Instruction::JumpIfZero(19),
+                            Instruction::Decrement,
+                            Instruction::Decrement,
+                            Instruction::Decrement,
+                            Instruction::JumpIfNonZero(15),
+                            Instruction::Increment,
+                            Instruction::JumpIfNonZero(13)]);
+        Bot::new(program)
+    }
+
+    /// Its program, in BrainFuck: >+[]<
+    fn make_defender() -> Bot {
+        Bot::new(vec![Instruction::MoveForward,
+                      Instruction::Increment,
+                      Instruction::JumpIfZero(3),
+                      Instruction::JumpIfNonZero(2),
+                      Instruction::MoveBack])
+    }
+
+    /// Checks that the batch yields the same results as playing each round in its own arena.
+    fn assert_same_as_arena(bot_a: &Bot, bot_b: &Bot, params: Vec<RoundParams>) {
+        let expected = params.iter()
+            .map(|round_params| {
+                Arena::new(bot_a, bot_b, round_params).unwrap().run_to_completion().result
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(BatchArena::new(bot_a, bot_b, params).unwrap().run_to_completion(), expected);
+    }
+
+    #[test]
+    fn runToCompletion_completeMatch_sameResultsAsArena() {
+        let (attacker, defender) = (make_attacker(), make_defender());
+        assert_same_as_arena(&attacker, &defender, AllRounds::new().collect());
+        assert_same_as_arena(&defender, &attacker, AllRounds::new().collect());
+        assert_same_as_arena(&attacker, &attacker, AllRounds::new().collect());
+    }
+
+    #[test]
+    fn runToCompletion_earlyTerminationAndBudgets_sameResultsAsArena() {
+        let (attacker, defender) = (make_attacker(), make_defender());
+        let params = AllRounds::new()
+            .with_early_termination()
+            .map(|round_params| {
+                RoundParams {
+                    instruction_budgets: Some(InstructionBudgets {
+                        start_bot: 500,
+                        end_bot: 20_000,
+                    }),
+                    ..round_params
+                }
+            })
+            .collect::<Vec<_>>();
+        assert_same_as_arena(&defender, &attacker, params.clone());
+        assert_same_as_arena(&attacker, &defender, params);
+    }
+
+    #[test]
+    fn runToCompletion_otherCellsAndFlags_sameResultsAsArena() {
+        let (attacker, defender) = (make_attacker(), make_defender());
+        let params = AllRounds::new()
+            .map(|round_params| {
+                RoundParams {
+                    cell_width: CellWidth::U8,
+                    flag_init: FlagInit::Custom(7),
+                    polarity_mode: PolarityMode::Both,
+                    collision_rule: CollisionRule::Random { seed: 5 },
+                    max_steps: 3_000,
+                    ..round_params
+                }
+            })
+            .collect::<Vec<_>>();
+        assert_same_as_arena(&attacker, &defender, params);
+    }
+
+    #[test]
+    fn runToCompletion_noRounds_noResults() {
+        let bot = make_defender();
+        assert!(BatchArena::new(&bot, &bot, vec![]).unwrap().run_to_completion().is_empty());
+    }
+}
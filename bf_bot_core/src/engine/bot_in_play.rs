@@ -1,5 +1,8 @@
-use bf::{Bot, Instruction};
+use bf::{Bot, OpCode};
+#[cfg(feature = "closure-engine")]
+use engine::ClosureProgram;
 use engine::{Mutation, Orientation, Polarity};
+use simul_round::ExecutionEngine;
 
 /// Represents a Bot during one specific game. This struct holds variables whose lifetime do not
 /// exceed that of a single game.
@@ -9,9 +12,13 @@ pub struct BotInPlay<'a> {
     bot: &'a Bot,
     /// Position of the bot on the tape.
     pos: i32,
-    /// The index of the instruction that was last executed.
-    /// This value always starts at 0 and is incremented at the end of each round.
-    code_pointer: usize,
+    /// The index of the operation of the bytecode that is executed next.
+    op_index: usize,
+    /// The number of times that the current operation has already been executed. An operation
+    /// that fuses several instructions is executed once for each of them.
+    progress: u32,
+    /// The number of instructions that this bot has executed so far.
+    nr_executed_instructions: u32,
     /// The starting position of the bot during this game.
     /// This doesn't just determine the initial value of the bot's position; it is also used to
     /// determine what the MoveBack and MoveForward instructions mean.
@@ -20,69 +27,118 @@ pub struct BotInPlay<'a> {
     orientation: Orientation,
     /// The polarity of the bot during this game.
     polarity: Polarity,
+    /// Only present if the closure engine executes this bot.
+    #[cfg(feature = "closure-engine")]
+    closures: Option<ClosureProgram>,
 }
 
 impl<'a> BotInPlay<'a> {
-    pub fn new(bot: &Bot, length: i32, orientation: Orientation, polarity: Polarity) -> BotInPlay {
+    #[cfg_attr(not(feature = "closure-engine"), allow(unused_variables))]
+    pub fn new(bot: &'a Bot,
+               length: i32,
+               orientation: Orientation,
+               polarity: Polarity,
+               engine: ExecutionEngine)
+               -> BotInPlay<'a> {
         BotInPlay {
-            bot: bot,
+            #[cfg(feature = "closure-engine")]
+            closures: match engine {
+                ExecutionEngine::Bytecode => None,
+                ExecutionEngine::Closures => {
+                    Some(ClosureProgram::compile(bot.get_bytecode(), &orientation, &polarity))
+                }
+            },
+            bot,
             pos: if orientation == Orientation::Normal {
                 0
             } else {
                 length - 1
             },
-            code_pointer: 0,
-            orientation: orientation,
-            polarity: polarity,
+            op_index: 0,
+            progress: 0,
+            nr_executed_instructions: 0,
+            orientation,
+            polarity,
         }
     }
 
+    /// Returns this bot, placed at the given position instead of the end of the tape that its
+    /// orientation decides.
+    pub fn starting_at(self, pos: i32) -> BotInPlay<'a> {
+        BotInPlay { pos, ..self }
+    }
+
     /// Returns the current position of the bot as a usize. It is not allowed to call this method
     /// if the bot is not currently on the tape.
     pub fn get_pos(&self) -> usize {
         self.pos as usize
     }
 
+    /// Returns the position of the bot, which may lie outside the tape.
+    pub fn get_raw_pos(&self) -> i32 {
+        self.pos
+    }
+
+    /// Returns the index in the program of the instruction that is executed next.
+    pub fn get_code_pointer(&self) -> usize {
+        self.bot.get_bytecode().source_index(self.op_index, self.progress)
+    }
+
     pub fn program_has_ended(&self) -> bool {
-        self.code_pointer >= self.bot.get_program().len()
+        self.op_index >= self.bot.get_bytecode().get_ops().len()
     }
 
+    pub fn get_nr_executed_instructions(&self) -> u32 {
+        self.nr_executed_instructions
+    }
+
+    /// Executes the next instruction and moves on to the one after it, or to the target of a jump
+    /// that is taken.
     pub fn execute_code(&mut self, current_cell_is_zero: bool) -> Option<Mutation> {
-        match self.bot.get_program()[self.code_pointer] {
-            Instruction::MoveBack => {
+        self.nr_executed_instructions += 1;
+        #[cfg(feature = "closure-engine")]
+        {
+            if let Some(ref closures) = self.closures {
+                return closures.execute(&mut self.pos,
+                                        &mut self.op_index,
+                                        &mut self.progress,
+                                        current_cell_is_zero);
+            }
+        }
+        let op = self.bot.get_bytecode().get_ops()[self.op_index];
+        let mutation = match op.op_code {
+            OpCode::MoveBack => {
                 self.pos += self.orientation.calc_movement_relative_to_tape(-1);
                 None
             }
-            Instruction::MoveForward => {
+            OpCode::MoveForward => {
                 self.pos += self.orientation.calc_movement_relative_to_tape(1);
                 None
             }
-            Instruction::Increment => {
+            OpCode::Increment => {
                 Some(Mutation::new(self.pos as usize,
                                    self.polarity.mutation_relative_to_tape(1)))
             }
-            Instruction::Decrement => {
+            OpCode::Decrement => {
                 Some(Mutation::new(self.pos as usize,
                                    self.polarity.mutation_relative_to_tape(-1)))
             }
-            Instruction::StartWhileNotZero { target_pointer } => {
-                if current_cell_is_zero {
-                    self.code_pointer = target_pointer;
-                }
-                None
+            OpCode::JumpIfZero(continue_at) if current_cell_is_zero => {
+                return self.jump(continue_at);
             }
-            Instruction::EndWhileNotZero { target_pointer } => {
-                if !current_cell_is_zero {
-                    self.code_pointer = target_pointer;
-                }
-                None
+            OpCode::JumpIfNonZero(continue_at) if !current_cell_is_zero => {
+                return self.jump(continue_at);
             }
-            _ => None,
-        }
+            OpCode::JumpIfZero(_) | OpCode::JumpIfNonZero(_) | OpCode::SkipExecution => None,
+        };
+        op.advance(&mut self.op_index, &mut self.progress);
+        mutation
     }
 
-    pub fn increment_code_pointer(&mut self) {
-        self.code_pointer += 1;
+    fn jump(&mut self, continue_at: usize) -> Option<Mutation> {
+        self.op_index = continue_at;
+        self.progress = 0;
+        None
     }
 
     pub fn bot_is_off_tape(&self, tape_length: &i32) -> bool {
@@ -0,0 +1,154 @@
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+use core::fmt;
+
+use bf::{Bytecode, OpCode};
+use engine::{Mutation, Orientation, Polarity};
+
+/// Executes a single operation and moves on to the next one: it receives the position of the
+/// bot, the index of the current operation, the number of times it was executed before, and
+/// whether the current cell is zero.
+type CompiledOp =
+    Box<dyn Fn(&mut i32, &mut usize, &mut u32, bool) -> Option<Mutation> + Send + Sync>;
+
+/// The bytecode of a bot, compiled into one closure per operation for a specific orientation and
+/// polarity. This spares the interpreter from matching on the op code and from converting every
+/// movement and mutation to the perspective of the tape.
+pub struct ClosureProgram {
+    ops: Vec<CompiledOp>,
+}
+
+impl ClosureProgram {
+    pub fn compile(bytecode: &Bytecode,
+                   orientation: &Orientation,
+                   polarity: &Polarity)
+                   -> ClosureProgram {
+        let back = orientation.calc_movement_relative_to_tape(-1);
+        let forward = orientation.calc_movement_relative_to_tape(1);
+        let increment = polarity.mutation_relative_to_tape(1);
+        let decrement = polarity.mutation_relative_to_tape(-1);
+        let ops = bytecode.get_ops()
+            .iter()
+            .map(|&op| -> CompiledOp {
+                match op.op_code {
+                    OpCode::MoveBack => {
+                        Box::new(move |pos: &mut i32, op_index: &mut usize, progress: &mut u32, _| {
+                            *pos += back;
+                            op.advance(op_index, progress);
+                            None
+                        })
+                    }
+                    OpCode::MoveForward => {
+                        Box::new(move |pos: &mut i32, op_index: &mut usize, progress: &mut u32, _| {
+                            *pos += forward;
+                            op.advance(op_index, progress);
+                            None
+                        })
+                    }
+                    OpCode::Increment => {
+                        Box::new(move |pos: &mut i32, op_index: &mut usize, progress: &mut u32, _| {
+                            op.advance(op_index, progress);
+                            Some(Mutation::new(*pos as usize, increment))
+                        })
+                    }
+                    OpCode::Decrement => {
+                        Box::new(move |pos: &mut i32, op_index: &mut usize, progress: &mut u32, _| {
+                            op.advance(op_index, progress);
+                            Some(Mutation::new(*pos as usize, decrement))
+                        })
+                    }
+                    OpCode::SkipExecution => {
+                        Box::new(move |_: &mut i32, op_index: &mut usize, progress: &mut u32, _| {
+                            op.advance(op_index, progress);
+                            None
+                        })
+                    }
+                    OpCode::JumpIfZero(continue_at) => {
+                        Box::new(move |_: &mut i32, op_index: &mut usize, progress: &mut u32,
+                                       is_zero: bool| {
+                            ClosureProgram::jump_if(is_zero, continue_at, op_index, progress);
+                            None
+                        })
+                    }
+                    OpCode::JumpIfNonZero(continue_at) => {
+                        Box::new(move |_: &mut i32, op_index: &mut usize, progress: &mut u32,
+                                       is_zero: bool| {
+                            ClosureProgram::jump_if(!is_zero, continue_at, op_index, progress);
+                            None
+                        })
+                    }
+                }
+            })
+            .collect();
+        ClosureProgram { ops }
+    }
+
+    fn jump_if(condition: bool, continue_at: usize, op_index: &mut usize, progress: &mut u32) {
+        *op_index = if condition { continue_at } else { *op_index + 1 };
+        *progress = 0;
+    }
+
+    /// Executes the operation at the given index once and moves on.
+    pub fn execute(&self,
+                   pos: &mut i32,
+                   op_index: &mut usize,
+                   progress: &mut u32,
+                   current_cell_is_zero: bool)
+                   -> Option<Mutation> {
+        (self.ops[*op_index])(pos, op_index, progress, current_cell_is_zero)
+    }
+}
+
+impl fmt::Debug for ClosureProgram {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "ClosureProgram {{ {} ops }}", self.ops.len())
+    }
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod tests {
+    use bf::{Bot, Instruction};
+    use simul_game::AllRounds;
+    use simul_round::{play, ExecutionEngine, RoundParams};
+
+    /// Its program, in BrainFuck: >>>>>>>>>[-]+[>[---]+]
+    fn make_bot() -> Bot {
+        let mut program = vec![Instruction::MoveForward; 9];
+        program.extend(vec![Instruction::JumpIfZero(11),
+                            Instruction::Decrement,
+                            Instruction::JumpIfNonZero(9),
+                            Instruction::Increment,
+                            Instruction::JumpIfZero(21),
+                            Instruction::MoveForward,
+                            Instruction::JumpIfZero(19),
+                            Instruction::Decrement,
+                            Instruction::Decrement,
+                            Instruction::Decrement,
+                            Instruction::JumpIfNonZero(15),
+                            Instruction::Increment,
+                            Instruction::JumpIfNonZero(13)]);
+        Bot::new(program)
+    }
+
+    /// Its program, in BrainFuck: >+[]<
+    fn make_defender() -> Bot {
+        Bot::new(vec![Instruction::MoveForward,
+                      Instruction::Increment,
+                      Instruction::JumpIfZero(3),
+                      Instruction::JumpIfNonZero(2),
+                      Instruction::MoveBack])
+    }
+
+    #[test]
+    fn play_closures_sameResultsAsBytecode() {
+        let (bot_a, bot_b) = (make_bot(), make_defender());
+        for round_params in AllRounds::new() {
+            let closure_params = RoundParams { engine: ExecutionEngine::Closures, ..round_params };
+            assert_eq!(play(&bot_a, &bot_b, &closure_params).unwrap(),
+                       play(&bot_a, &bot_b, &round_params).unwrap());
+            assert_eq!(play(&bot_b, &bot_a, &closure_params).unwrap(),
+                       play(&bot_b, &bot_a, &round_params).unwrap());
+        }
+    }
+}
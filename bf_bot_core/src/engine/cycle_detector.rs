@@ -0,0 +1,112 @@
+use alloc::vec::Vec;
+
+/// Everything that determines how a round continues from a certain step onwards.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ArenaState {
+    pub tape: Vec<i32>,
+    /// Position and code pointer of the start bot.
+    pub start_bot: (i32, usize),
+    /// Position and code pointer of the end bot.
+    pub end_bot: (i32, usize),
+}
+
+/// Detects whether a round has entered a loop, using Brent's algorithm: the state of the arena is
+/// saved at steps that are powers of two and every subsequent state is compared to the saved one.
+/// Since the round is deterministic, a repeated state means that it will keep repeating until the
+/// maximum number of steps is reached. A loop is detected within a small multiple of the number
+/// of steps it takes to enter and complete the loop, at the cost of storing only a single state.
+#[derive(Debug)]
+pub struct CycleDetector {
+    saved_state: Option<ArenaState>,
+    /// The number of the step at which the next state is saved.
+    next_checkpoint: u32,
+}
+
+impl CycleDetector {
+    pub fn new() -> CycleDetector {
+        CycleDetector {
+            saved_state: None,
+            next_checkpoint: 1,
+        }
+    }
+
+    /// Checks the state of the arena after the given step, given the tape and the position and
+    /// code pointer of both bots. Returns true if this state was seen before. The state is
+    /// compared with the saved one in place, and only copied when a checkpoint is reached.
+    pub fn repeats(&mut self,
+                   step_nr: u32,
+                   tape: &[i32],
+                   start_bot: (i32, usize),
+                   end_bot: (i32, usize))
+                   -> bool {
+        if let Some(ref saved_state) = self.saved_state {
+            if saved_state.start_bot == start_bot && saved_state.end_bot == end_bot &&
+               saved_state.tape[..] == *tape {
+                return true;
+            }
+        }
+        if step_nr >= self.next_checkpoint {
+            match self.saved_state {
+                Some(ref mut saved_state) => {
+                    // Reuses the allocation of the previous checkpoint.
+                    saved_state.tape.clear();
+                    saved_state.tape.extend_from_slice(tape);
+                    saved_state.start_bot = start_bot;
+                    saved_state.end_bot = end_bot;
+                }
+                None => {
+                    self.saved_state = Some(ArenaState {
+                        tape: tape.to_vec(),
+                        start_bot,
+                        end_bot,
+                    })
+                }
+            }
+            self.next_checkpoint = self.next_checkpoint.saturating_mul(2);
+        }
+        false
+    }
+}
+
+impl Default for CycleDetector {
+    fn default() -> CycleDetector {
+        CycleDetector::new()
+    }
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn repeats_stateNeverRepeats_false() {
+        let mut detector = CycleDetector::new();
+        assert!((1..100).all(|step_nr| {
+            !detector.repeats(step_nr, &[step_nr as i32], (0, 0), (0, 0))
+        }));
+    }
+
+    #[test]
+    fn repeats_constantState_detectedAtSecondStep() {
+        let mut detector = CycleDetector::new();
+        assert!(!detector.repeats(1, &[0], (0, 0), (0, 0)));
+        assert!(detector.repeats(2, &[0], (0, 0), (0, 0)));
+    }
+
+    #[test]
+    fn repeats_sameTapeOtherCodePointer_false() {
+        let mut detector = CycleDetector::new();
+        assert!(!detector.repeats(1, &[0], (0, 0), (0, 0)));
+        assert!(!detector.repeats(2, &[0], (0, 1), (0, 0)));
+    }
+
+    #[test]
+    fn repeats_longLoop_eventuallyDetected() {
+        let mut detector = CycleDetector::new();
+        let first_detection = (1..1000).find(|&step_nr| {
+            detector.repeats(step_nr, &[(step_nr % 37) as i32], (0, 0), (0, 0))
+        });
+        assert!(first_detection.is_some());
+    }
+}
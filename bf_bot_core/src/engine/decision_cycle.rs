@@ -0,0 +1,152 @@
+use simul_round::{LossReason, RoundResult};
+
+/// The number of consecutive cycles that a flag has to end at zero for its bot to lose.
+const CYCLES_TO_CAPTURE_FLAG: u32 = 2;
+
+/// Counts for how many consecutive cycles a flag has been zero.
+#[derive(Debug, Default)]
+pub struct FlagCounter {
+    consecutive_zero_cycles: u32,
+}
+
+impl FlagCounter {
+    pub fn observe(&mut self, flag_is_zero: bool) {
+        self.consecutive_zero_cycles = if flag_is_zero {
+            self.consecutive_zero_cycles.saturating_add(1)
+        } else {
+            0
+        };
+    }
+
+    fn flag_captured(&self) -> bool {
+        self.consecutive_zero_cycles >= CYCLES_TO_CAPTURE_FLAG
+    }
+
+    /// Returns why the owner of this flag lost, if it did. Leaving the tape takes precedence.
+    pub fn loss(&self, bot_off_tape: bool) -> Option<LossReason> {
+        if bot_off_tape {
+            Some(LossReason::OffTape)
+        } else if self.flag_captured() {
+            Some(LossReason::FlagZeroed)
+        } else {
+            None
+        }
+    }
+}
+
+/// The decision cycle concludes every step of a round and decides whether either bot has lost,
+/// following the standard BF Joust rules:
+///
+/// - A bot loses if its flag is zero at the end of two consecutive cycles.
+/// - A bot loses if it moves its tape pointer off the tape.
+/// - If both bots lose during the same cycle, the round is a draw.
+///
+/// The state of the tape before the first cycle counts as the end of cycle zero.
+#[derive(Debug, Default)]
+pub struct DecisionCycle {
+    start_flag: FlagCounter,
+    end_flag: FlagCounter,
+}
+
+impl DecisionCycle {
+    pub fn new() -> DecisionCycle {
+        DecisionCycle::default()
+    }
+
+    /// Records the state of both flags at the end of a cycle.
+    pub fn observe_flags(&mut self, start_flag_is_zero: bool, end_flag_is_zero: bool) {
+        self.start_flag.observe(start_flag_is_zero);
+        self.end_flag.observe(end_flag_is_zero);
+    }
+
+    /// Decides the outcome of the cycle whose flags were observed last, given whether either bot
+    /// has left the tape during that cycle. The result is not yet tied to a step.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bf_bot_core::engine::DecisionCycle;
+    /// use bf_bot_core::simul_round::RoundStatus;
+    /// let mut decision_cycle = DecisionCycle::new();
+    /// decision_cycle.observe_flags(true, false);
+    /// assert_eq!(decision_cycle.decide(false, false).status(), RoundStatus::Ongoing);
+    /// decision_cycle.observe_flags(true, false);
+    /// assert_eq!(decision_cycle.decide(false, false).status(), RoundStatus::EndBotWon);
+    /// ```
+    pub fn decide(&self, start_bot_off_tape: bool, end_bot_off_tape: bool) -> RoundResult {
+        RoundResult::new(self.start_flag.loss(start_bot_off_tape),
+                         self.end_flag.loss(end_bot_off_tape))
+    }
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod tests {
+    use super::*;
+    use simul_round::RoundStatus;
+
+    #[test]
+    fn decide_noFlagObserved_roundOngoing() {
+        assert_eq!(DecisionCycle::new().decide(false, false).status(),
+                   RoundStatus::Ongoing);
+    }
+
+    #[test]
+    fn decide_flagZeroForOneCycle_roundOngoing() {
+        let mut decision_cycle = DecisionCycle::new();
+        decision_cycle.observe_flags(false, true);
+        assert_eq!(decision_cycle.decide(false, false).status(),
+                   RoundStatus::Ongoing);
+    }
+
+    #[test]
+    fn decide_flagZeroForTwoCycles_ownerLoses() {
+        let mut decision_cycle = DecisionCycle::new();
+        decision_cycle.observe_flags(false, true);
+        decision_cycle.observe_flags(false, true);
+        assert_eq!(decision_cycle.decide(false, false).status(),
+                   RoundStatus::StartBotWon);
+    }
+
+    #[test]
+    fn decide_flagRestoredInBetween_counterStartsOver() {
+        let mut decision_cycle = DecisionCycle::new();
+        decision_cycle.observe_flags(true, false);
+        decision_cycle.observe_flags(false, false);
+        decision_cycle.observe_flags(true, false);
+        assert_eq!(decision_cycle.decide(false, false).status(),
+                   RoundStatus::Ongoing);
+    }
+
+    #[test]
+    fn decide_bothFlagsCapturedInSameCycle_draw() {
+        let mut decision_cycle = DecisionCycle::new();
+        decision_cycle.observe_flags(true, true);
+        decision_cycle.observe_flags(true, true);
+        assert_eq!(decision_cycle.decide(false, false).status(), RoundStatus::Draw);
+    }
+
+    #[test]
+    fn decide_oneBotOffTapeWhileOtherFlagCaptured_draw() {
+        let mut decision_cycle = DecisionCycle::new();
+        decision_cycle.observe_flags(true, false);
+        decision_cycle.observe_flags(true, false);
+        assert_eq!(decision_cycle.decide(false, true).status(), RoundStatus::Draw);
+    }
+
+    #[test]
+    fn decide_botOffTape_loses() {
+        assert_eq!(DecisionCycle::new().decide(true, false).status(),
+                   RoundStatus::EndBotWon);
+    }
+
+    #[test]
+    fn decide_flagZeroedAndOffTape_offTapeIsTheReason() {
+        let mut decision_cycle = DecisionCycle::new();
+        decision_cycle.observe_flags(true, true);
+        decision_cycle.observe_flags(true, true);
+        let result = decision_cycle.decide(true, false);
+        assert_eq!(result.bot_a_loss, Some(LossReason::OffTape));
+        assert_eq!(result.bot_b_loss, Some(LossReason::FlagZeroed));
+    }
+}
@@ -0,0 +1,262 @@
+use alloc::boxed::Box;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::error::Error;
+use core::fmt;
+
+use bf::Bot;
+use engine::{Arena, BatchArena};
+use error::BfError;
+use simul_round::{ExecutionEngine, RoundParams, RoundResult};
+
+/// A way of playing a round. Every backend must yield exactly the same result for the same bots
+/// and parameters, including the number of steps and the hash of the tape.
+pub trait Backend {
+    /// A short description of the backend, used to report mismatches.
+    fn name(&self) -> String;
+
+    /// Plays a complete round between the given bots. Fails if the parameters are not valid.
+    fn play(&self,
+            bot_a: &Bot,
+            bot_b: &Bot,
+            params: &RoundParams)
+            -> Result<RoundResult, BfError>;
+}
+
+/// Plays a round in an `Arena`, with the given execution engine instead of the one in the
+/// parameters.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct EngineBackend(pub ExecutionEngine);
+
+impl Backend for EngineBackend {
+    fn name(&self) -> String {
+        format!("Arena with {:?}", self.0)
+    }
+
+    fn play(&self,
+            bot_a: &Bot,
+            bot_b: &Bot,
+            params: &RoundParams)
+            -> Result<RoundResult, BfError> {
+        let params = RoundParams { engine: self.0, ..params.clone() };
+        Ok(Arena::new(bot_a, bot_b, &params)?.run_to_completion().result)
+    }
+}
+
+/// Plays a round as a batch of one in a `BatchArena`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BatchBackend;
+
+impl Backend for BatchBackend {
+    fn name(&self) -> String {
+        "BatchArena".to_string()
+    }
+
+    fn play(&self,
+            bot_a: &Bot,
+            bot_b: &Bot,
+            params: &RoundParams)
+            -> Result<RoundResult, BfError> {
+        Ok(BatchArena::new(bot_a, bot_b, vec![params.clone()])?.run_to_completion().remove(0))
+    }
+}
+
+/// Describes a backend that disagreed with the reference backend, an `Arena` with the bytecode
+/// engine.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BackendMismatch {
+    pub backend: String,
+    pub expected: RoundResult,
+    pub actual: RoundResult,
+}
+
+impl fmt::Display for BackendMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f,
+               "{} yielded {:?}, but the reference yielded {:?}.",
+               self.backend,
+               self.actual,
+               self.expected)
+    }
+}
+
+impl Error for BackendMismatch {}
+
+/// Returns every backend that this crate provides, with the reference backend first.
+pub fn builtin_backends() -> Vec<Box<dyn Backend>> {
+    vec![Box::new(EngineBackend(ExecutionEngine::Bytecode)),
+         #[cfg(feature = "closure-engine")]
+         Box::new(EngineBackend(ExecutionEngine::Closures)),
+         Box::new(BatchBackend)]
+}
+
+/// Plays a round between the given bots on every backend of this crate, and returns its result
+/// if all of them agree. Otherwise, returns a simulation error whose source is the
+/// `BackendMismatch` of the first backend that disagreed with the reference.
+///
+/// # Examples
+///
+/// ```
+/// use bf_bot_core::bf::{Bot, Instruction};
+/// use bf_bot_core::engine::verify_backends;
+/// use bf_bot_core::simul_round::{RoundParams, RoundResult};
+/// let suicidal = Bot::new(vec![Instruction::MoveBack]);
+/// let result = verify_backends(&Bot::new(vec![]), &suicidal, &RoundParams::new(10, false, 1000));
+/// assert_eq!(result.unwrap().status(), RoundResult::start_bot_wins().status());
+/// ```
+pub fn verify_backends(bot_a: &Bot,
+                       bot_b: &Bot,
+                       params: &RoundParams)
+                       -> Result<RoundResult, BfError> {
+    verify_backends_with(bot_a, bot_b, params, &[])
+}
+
+/// Like `verify_backends`, but also checks the given backends, so that they can be tested against
+/// the ones of this crate.
+pub fn verify_backends_with(bot_a: &Bot,
+                            bot_b: &Bot,
+                            params: &RoundParams,
+                            extra_backends: &[&dyn Backend])
+                            -> Result<RoundResult, BfError> {
+    let builtin = builtin_backends();
+    let mut backends = builtin.iter()
+        .map(|backend| backend.as_ref())
+        .chain(extra_backends.iter().cloned());
+    let reference = backends.next().unwrap();
+    let expected = reference.play(bot_a, bot_b, params)?;
+    for backend in backends {
+        let actual = backend.play(bot_a, bot_b, params)?;
+        if actual != expected {
+            return Err(BackendMismatch {
+                           backend: backend.name(),
+                           expected,
+                           actual,
+                       }
+                       .into());
+        }
+    }
+    Ok(expected)
+}
+
+/// Checks every backend against the others on many random pairs of bots and every kind of
+/// round. The programs are well-formed, but otherwise arbitrary, so they exercise odd corners like
+/// empty loops and programs that end early.
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod tests {
+    use super::*;
+    use bf::Instruction;
+    use reference_bots::ProgramBuilder;
+    use simul_round::{split_mix, CellWidth, CollisionRule, FlagInit, Noise, PolarityMode, Rules,
+                      StartDelays};
+
+    /// A small, deterministic source of random numbers, so that failures can be reproduced from
+    /// the seed.
+    struct Random(u64);
+
+    impl Random {
+        fn below(&mut self, bound: u64) -> u64 {
+            self.0 = self.0.wrapping_add(1);
+            split_mix::mix(self.0) % bound
+        }
+    }
+
+    fn random_bot(random: &mut Random, length: usize) -> Bot {
+        let mut builder = ProgramBuilder::default();
+        let mut nr_instructions = 0;
+        let mut nr_open_loops = 0;
+        while nr_instructions < length || nr_open_loops > 0 {
+            let closing = nr_open_loops > 0 && (nr_instructions >= length || random.below(6) == 0);
+            builder = if closing {
+                nr_open_loops -= 1;
+                builder.close_loop()
+            } else {
+                match random.below(7) {
+                    0 => builder.repeat(Instruction::MoveBack, 1),
+                    1 | 2 => builder.repeat(Instruction::MoveForward, 1),
+                    3 => builder.repeat(Instruction::Increment, 1),
+                    4 => builder.repeat(Instruction::Decrement, 1),
+                    5 => builder.repeat(Instruction::SkipExecution, 1),
+                    _ => {
+                        nr_open_loops += 1;
+                        builder.open_loop()
+                    }
+                }
+            };
+            nr_instructions += 1;
+        }
+        builder.build()
+    }
+
+    fn random_params(random: &mut Random) -> RoundParams {
+        RoundParams {
+            early_termination: random.below(2) == 0,
+            cell_width: [CellWidth::U8, CellWidth::I8, CellWidth::I16][random.below(3) as usize],
+            flag_init: [FlagInit::Standard, FlagInit::Custom(1)][random.below(2) as usize],
+            polarity_mode: [PolarityMode::EndBot, PolarityMode::StartBot, PolarityMode::Both]
+                [random.below(3) as usize],
+            collision_rule: [CollisionRule::Sum,
+                             CollisionRule::StartBotFirst,
+                             CollisionRule::EndBotFirst,
+                             CollisionRule::Random { seed: random.below(1000) }]
+                [random.below(4) as usize],
+            rules: [Rules::Standard, Rules::MultipleFlags { nr_flags: 3 }]
+                [random.below(2) as usize],
+            fog_of_war: [None, Some(2)][random.below(2) as usize],
+            noise: [None, Some(Noise { per_million: 50_000, seed: 9 })][random.below(2) as usize],
+            start_delays: StartDelays {
+                start_bot: random.below(3) as u32,
+                end_bot: random.below(3) as u32,
+            },
+            ..RoundParams::new(10 + random.below(21) as u32,
+                               random.below(2) == 0,
+                               random.below(2000) as u32)
+        }
+    }
+
+    #[test]
+    fn verifyBackends_randomBotsAndParams_allAgree() {
+        let mut random = Random(42);
+        for _ in 0..200 {
+            let length_a = random.below(30) as usize;
+            let bot_a = random_bot(&mut random, length_a);
+            let length_b = random.below(30) as usize;
+            let bot_b = random_bot(&mut random, length_b);
+            let params = random_params(&mut random);
+            if let Err(mismatch) = verify_backends(&bot_a, &bot_b, &params) {
+                panic!("{} Bots: {} vs {}, {:?}",
+                       mismatch,
+                       bot_a.to_source(),
+                       bot_b.to_source(),
+                       params);
+            }
+        }
+    }
+
+    /// A backend that always reports a draw.
+    struct DrawBackend;
+
+    impl Backend for DrawBackend {
+        fn name(&self) -> String {
+            "draws".to_string()
+        }
+
+        fn play(&self, _: &Bot, _: &Bot, _: &RoundParams) -> Result<RoundResult, BfError> {
+            Ok(RoundResult::draw())
+        }
+    }
+
+    #[test]
+    fn verifyBackendsWith_disagreeingBackend_reportsIt() {
+        let suicidal = Bot::new(vec![Instruction::MoveBack]);
+        let error = verify_backends_with(&Bot::new(vec![]),
+                                         &suicidal,
+                                         &RoundParams::new(10, false, 1000),
+                                         &[&DrawBackend])
+            .unwrap_err();
+        let mismatch = error.source().and_then(|source| source.downcast_ref::<BackendMismatch>());
+        let mismatch = mismatch.expect("The source is the mismatch.");
+        assert_eq!(mismatch.backend, "draws");
+        assert_eq!(mismatch.actual, RoundResult::draw());
+    }
+}
@@ -0,0 +1,94 @@
+use alloc::vec::Vec;
+
+use engine::Orientation;
+
+/// Limits what the bots can sense: a bot only senses whether the cell it stands on is zero if it
+/// also stood on that cell during one of the preceding `memory` steps. Otherwise the cell seems
+/// non-zero to it. A bot that has just moved to a cell it hasn't seen in a while thus has to wait a
+/// step before its loops can react to the cell.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FogOfWar {
+    memory: u32,
+    /// For both bots, the last step at which they stood on every cell, if they ever did.
+    last_visits: [Vec<Option<u32>>; 2],
+}
+
+impl FogOfWar {
+    pub fn new(memory: u32, tape_length: usize) -> FogOfWar {
+        FogOfWar {
+            memory,
+            last_visits: [vec![None; tape_length], vec![None; tape_length]],
+        }
+    }
+
+    /// Returns whether the given bot, which stands on the cell at the given position during the
+    /// given step, senses that the cell is zero, and remembers that it stood there.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bf_bot_core::engine::{FogOfWar, Orientation};
+    /// let mut fog_of_war = FogOfWar::new(2, 10);
+    /// assert!(!fog_of_war.senses_zero(Orientation::Normal, 3, 0, 0));
+    /// assert!(fog_of_war.senses_zero(Orientation::Normal, 3, 2, 0));
+    /// assert!(!fog_of_war.senses_zero(Orientation::Normal, 3, 5, 0));
+    /// ```
+    pub fn senses_zero(&mut self,
+                       bot: Orientation,
+                       position: usize,
+                       step_nr: u32,
+                       cell_value: i32)
+                       -> bool {
+        let bot_index = match bot {
+            Orientation::Normal => 0,
+            Orientation::Reversed => 1,
+        };
+        let last_visit = &mut self.last_visits[bot_index][position];
+        let memory = self.memory;
+        let visible = last_visit.is_some_and(|visit| step_nr - visit <= memory);
+        *last_visit = Some(step_nr);
+        visible && cell_value == 0
+    }
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod tests {
+    use super::*;
+    use bf::{Bot, Instruction};
+    use engine::{Arena, BatchArena};
+    use simul_round::{RoundParams, RoundStatus};
+
+    #[test]
+    fn sensesZero_otherBotVisited_notVisible() {
+        let mut fog_of_war = FogOfWar::new(5, 10);
+        fog_of_war.senses_zero(Orientation::Reversed, 4, 0, 0);
+        assert!(!fog_of_war.senses_zero(Orientation::Normal, 4, 1, 0));
+    }
+
+    #[test]
+    fn sensesZero_nonZeroCell_neverZero() {
+        let mut fog_of_war = FogOfWar::new(5, 10);
+        fog_of_war.senses_zero(Orientation::Normal, 4, 0, 7);
+        assert!(!fog_of_war.senses_zero(Orientation::Normal, 4, 1, 7));
+    }
+
+    #[test]
+    fn play_skipLoopOnArrival_loopEnteredInTheFog() {
+        // `>[>]` moves past the empty cell in clear sight, but in the fog the bot thinks the cell
+        // holds something, so it enters the loop and walks on until it leaves the tape.
+        let bot_a = Bot::new(vec![Instruction::MoveForward,
+                                  Instruction::JumpIfZero(3),
+                                  Instruction::MoveForward,
+                                  Instruction::JumpIfNonZero(1)]);
+        let bot_b = Bot::new(vec![]);
+        let clear = RoundParams::new(10, false, 1000);
+        let foggy = RoundParams { fog_of_war: Some(1), ..clear.clone() };
+        assert_eq!(Arena::new(&bot_a, &bot_b, &clear).unwrap().run_to_completion().result.status(),
+                   RoundStatus::Draw);
+        let result = Arena::new(&bot_a, &bot_b, &foggy).unwrap().run_to_completion().result;
+        assert_eq!(result.status(), RoundStatus::EndBotWon);
+        let batch = BatchArena::new(&bot_a, &bot_b, vec![clear, foggy]).unwrap();
+        assert_eq!(batch.run_to_completion()[1], result);
+    }
+}
@@ -0,0 +1,411 @@
+use alloc::vec::Vec;
+
+use bf::Bot;
+use error::BfError;
+use engine::decision_cycle::FlagCounter;
+use engine::{BotInPlay, Mutation, Orientation, Polarity};
+use simul_round::{CellWidth, ExecutionEngine, LossReason};
+
+/// The flag of a bot in a free-for-all: where it lies on the tape, and which way the bot that
+/// defends it moves forward. The bot starts on its flag.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct HomeFlag {
+    pub position: u32,
+    pub orientation: Orientation,
+}
+
+/// Specifies the conditions of a round between any number of bots.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FreeForAllParams {
+    pub tape_length: u32,
+    pub max_steps: u32,
+    /// The type of the cells on the tape. Every flag starts at its standard value.
+    pub cell_width: CellWidth,
+    /// The flag of every bot, in the order of the bots.
+    pub homes: Vec<HomeFlag>,
+}
+
+impl FreeForAllParams {
+    /// Constructs the parameters for a round between the given number of bots, whose flags are
+    /// spread evenly over the tape, from the first cell to the last. The bots in the first half of
+    /// the tape move forward towards its end, the others towards its start. For two bots, this is
+    /// the layout of a standard round. Fails if there are fewer than two bots, or fewer cells than
+    /// bots.
+    pub fn evenly_spaced(nr_bots: u32,
+                         tape_length: u32,
+                         max_steps: u32)
+                         -> Result<FreeForAllParams, BfError> {
+        if nr_bots < 2 {
+            return Err(BfError::InvalidParams("A free-for-all needs at least two bots."
+                .to_string()));
+        }
+        if tape_length < nr_bots {
+            return Err(BfError::InvalidParams(format!("A tape of {} cells has no room for the \
+                                                       flags of {} bots.",
+                                                      tape_length,
+                                                      nr_bots)));
+        }
+        let homes = (0..nr_bots)
+            .map(|bot| {
+                let position = bot * (tape_length - 1) / (nr_bots - 1);
+                let orientation = if position < tape_length / 2 {
+                    Orientation::Normal
+                } else {
+                    Orientation::Reversed
+                };
+                HomeFlag {
+                    position,
+                    orientation,
+                }
+            })
+            .collect();
+        Ok(FreeForAllParams {
+            tape_length,
+            max_steps,
+            cell_width: CellWidth::default(),
+            homes,
+        })
+    }
+
+    /// Returns an error if a round between the given number of bots can't be played with these
+    /// parameters: every bot needs its own flag on the tape.
+    pub fn validate(&self, nr_bots: usize) -> Result<(), BfError> {
+        if self.homes.len() != nr_bots {
+            return Err(BfError::InvalidParams(format!("{} bots can't share {} flags.",
+                                                      nr_bots,
+                                                      self.homes.len())));
+        }
+        let mut positions = self.homes.iter().map(|home| home.position).collect::<Vec<_>>();
+        positions.sort_unstable();
+        if let Some(&position) = positions.iter().find(|&&position| position >= self.tape_length) {
+            return Err(BfError::InvalidParams(format!("The flag at {} lies off a tape of {} \
+                                                       cells.",
+                                                      position,
+                                                      self.tape_length)));
+        }
+        if let Some(pair) = positions.windows(2).find(|pair| pair[0] == pair[1]) {
+            return Err(BfError::InvalidParams(format!("Two bots share the flag at {}.", pair[0])));
+        }
+        Ok(())
+    }
+}
+
+/// Why and when a bot dropped out of a free-for-all.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Elimination {
+    pub reason: LossReason,
+    pub step_nr: u32,
+}
+
+/// The result of a free-for-all, with one entry per bot, in the order of the bots.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FreeForAllResult {
+    /// The place of every bot, starting at 1 for the winner. Bots that dropped out during the same
+    /// cycle share a place, as do the bots that were still in play when the round ended, and the
+    /// places after a shared place are skipped: two bots that share first place are followed by a
+    /// bot in third place.
+    pub placements: Vec<u32>,
+    /// How every bot dropped out, or `None` if it was still in play when the round ended.
+    pub eliminations: Vec<Option<Elimination>>,
+    /// The number of cycles that were executed.
+    pub nr_steps: u32,
+}
+
+/// Plays a round between any number of bots on a single tape, each defending its own flag.
+///
+/// The rules generalize those of the standard two-bot `Arena`. In every cycle, every bot that is
+/// still in play executes one instruction, and changes to the same cell are summed. A bot drops
+/// out if its flag is zero at the end of two consecutive cycles, or if it moves off the tape;
+/// after that, its program stops and its flag is just a cell. The round ends as soon as at most
+/// one bot is left in play, when the maximum number of steps is reached, or when the programs of
+/// all bots in play have ended while none of their flags is zero.
+#[derive(Debug)]
+pub struct FreeForAllArena<'a> {
+    max_steps: u32,
+    step_nr: u32,
+    cell_width: CellWidth,
+    tape: Vec<i32>,
+    bots: Vec<BotInPlay<'a>>,
+    flag_positions: Vec<usize>,
+    flag_counters: Vec<FlagCounter>,
+    eliminations: Vec<Option<Elimination>>,
+}
+
+impl<'a> FreeForAllArena<'a> {
+    /// Prepares a round between the given bots, which get the flags of the parameters in order.
+    /// Fails if there isn't exactly one flag per bot, or if two flags share a cell or a flag lies
+    /// off the tape.
+    pub fn new<'b>(bots: &[&'b Bot],
+                   params: &FreeForAllParams)
+                   -> Result<FreeForAllArena<'b>, BfError> {
+        params.validate(bots.len())?;
+        let mut tape = vec![0; params.tape_length as usize];
+        for home in &params.homes {
+            tape[home.position as usize] = params.cell_width.initial_flag_value();
+        }
+        let bots_in_play = bots.iter()
+            .zip(&params.homes)
+            .map(|(bot, home)| {
+                BotInPlay::new(bot,
+                               params.tape_length as i32,
+                               home.orientation,
+                               Polarity::Normal,
+                               ExecutionEngine::default())
+                    .starting_at(home.position as i32)
+            })
+            .collect();
+        Ok(FreeForAllArena {
+            max_steps: params.max_steps,
+            step_nr: 0,
+            cell_width: params.cell_width,
+            tape,
+            bots: bots_in_play,
+            flag_positions: params.homes.iter().map(|home| home.position as usize).collect(),
+            flag_counters: bots.iter().map(|_| FlagCounter::default()).collect(),
+            eliminations: vec![None; bots.len()],
+        })
+    }
+
+    pub fn get_tape(&self) -> &[i32] {
+        &self.tape
+    }
+
+    /// Returns the number of cycles that have been executed so far.
+    pub fn get_step_nr(&self) -> u32 {
+        self.step_nr
+    }
+
+    /// Executes a single cycle and returns the result if the round has ended.
+    pub fn step(&mut self) -> Option<FreeForAllResult> {
+        if self.step_nr == 0 {
+            // The initial state of the tape counts as the end of cycle zero.
+            self.observe_flags();
+        }
+        if self.step_nr >= self.max_steps || self.sink_state_detected() {
+            return Some(self.result());
+        }
+        self.execute_instructions();
+        self.observe_flags();
+        for bot in self.in_play() {
+            let off_tape = self.bots[bot].bot_is_off_tape(&(self.tape.len() as i32));
+            if let Some(reason) = self.flag_counters[bot].loss(off_tape) {
+                self.eliminations[bot] = Some(Elimination {
+                    reason,
+                    step_nr: self.step_nr,
+                });
+            }
+        }
+        if self.in_play().len() <= 1 {
+            Some(self.result())
+        } else {
+            None
+        }
+    }
+
+    /// Plays the round until it has ended and returns the final result.
+    pub fn run_to_completion(mut self) -> FreeForAllResult {
+        loop {
+            if let Some(result) = self.step() {
+                return result;
+            }
+        }
+    }
+
+    /// Returns the indices of the bots that are still in play.
+    fn in_play(&self) -> Vec<usize> {
+        (0..self.bots.len()).filter(|&bot| self.eliminations[bot].is_none()).collect()
+    }
+
+    /// Lets every bot in play execute an instruction. All of them read the tape as it was before
+    /// the cycle.
+    fn execute_instructions(&mut self) {
+        let mut mutations = vec![];
+        for bot in self.in_play() {
+            let bot_in_play = &mut self.bots[bot];
+            if bot_in_play.program_has_ended() {
+                continue;
+            }
+            let current_cell_is_zero = self.tape[bot_in_play.get_pos()] == 0;
+            mutations.extend(bot_in_play.execute_code(current_cell_is_zero));
+        }
+        for mutation in mutations {
+            self.apply_mutation(&mutation);
+        }
+        self.step_nr += 1;
+    }
+
+    fn apply_mutation(&mut self, mutation: &Mutation) {
+        let index = mutation.get_index();
+        self.tape[index] = self.cell_width.add(self.tape[index], mutation.get_addend().into());
+    }
+
+    fn observe_flags(&mut self) {
+        for bot in self.in_play() {
+            let flag_is_zero = self.tape[self.flag_positions[bot]] == 0;
+            self.flag_counters[bot].observe(flag_is_zero);
+        }
+    }
+
+    /// Returns true if the programs of all bots in play have ended, and none of their flags is
+    /// zero, so that nothing can change any more.
+    fn sink_state_detected(&self) -> bool {
+        self.in_play().into_iter().all(|bot| {
+            self.bots[bot].program_has_ended() && self.tape[self.flag_positions[bot]] != 0
+        })
+    }
+
+    fn result(&self) -> FreeForAllResult {
+        // A bot that is still in play outlasts every bot that isn't.
+        let dropped_out_at = |bot: usize| {
+            self.eliminations[bot].map_or(u32::MAX, |elimination| elimination.step_nr)
+        };
+        let placements = (0..self.bots.len())
+            .map(|bot| {
+                let nr_outlasting = (0..self.bots.len())
+                    .filter(|&other| dropped_out_at(other) > dropped_out_at(bot))
+                    .count();
+                nr_outlasting as u32 + 1
+            })
+            .collect();
+        FreeForAllResult {
+            placements,
+            eliminations: self.eliminations.clone(),
+            nr_steps: self.step_nr,
+        }
+    }
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod tests {
+    use super::*;
+    use bf::Instruction;
+    use engine::Arena;
+    use simul_round::{RoundParams, RoundStatus};
+
+    fn make_suicidal_bot(nr_steps_back: usize) -> Bot {
+        Bot::new(vec![Instruction::MoveBack; nr_steps_back])
+    }
+
+    #[test]
+    fn evenlySpaced_fourBots_flagsSpreadOverTape() {
+        let params = FreeForAllParams::evenly_spaced(4, 16, 1000).unwrap();
+        let positions = params.homes.iter().map(|home| home.position).collect::<Vec<_>>();
+        assert_eq!(positions, vec![0, 5, 10, 15]);
+        assert_eq!(params.homes[1].orientation, Orientation::Normal);
+        assert_eq!(params.homes[2].orientation, Orientation::Reversed);
+    }
+
+    #[test]
+    fn evenlySpaced_tooFewBotsOrCells_invalidParams() {
+        assert!(matches!(FreeForAllParams::evenly_spaced(1, 10, 1000),
+                         Err(BfError::InvalidParams(_))));
+        assert!(matches!(FreeForAllParams::evenly_spaced(4, 3, 1000),
+                         Err(BfError::InvalidParams(_))));
+    }
+
+    #[test]
+    fn new_fourBots_flagsOnTape() {
+        let idle = Bot::new(vec![]);
+        let arena = FreeForAllArena::new(&[&idle, &idle, &idle, &idle],
+                                         &FreeForAllParams::evenly_spaced(4, 10, 1000).unwrap())
+            .unwrap();
+        let flags = arena.get_tape().iter().filter(|&&cell| cell == -128).count();
+        assert_eq!(flags, 4);
+    }
+
+    #[test]
+    fn runToCompletion_botsLeaveTapeOneByOne_placedInReverseOrder() {
+        // The first bot starts at the start of the tape and leaves it in the first cycle. The bot
+        // at cell 5 leaves it after six cycles, and the one at cell 10 moves forward over the
+        // start of the tape after eleven.
+        let first = make_suicidal_bot(1);
+        let second = make_suicidal_bot(6);
+        let third = Bot::new(vec![Instruction::MoveForward; 11]);
+        let idle = Bot::new(vec![]);
+        let result = FreeForAllArena::new(&[&first, &second, &third, &idle],
+                                          &FreeForAllParams::evenly_spaced(4, 16, 1000).unwrap())
+            .unwrap()
+            .run_to_completion();
+        assert_eq!(result.placements, vec![4, 3, 2, 1]);
+        assert_eq!(result.eliminations[0],
+                   Some(Elimination {
+                       reason: LossReason::OffTape,
+                       step_nr: 1,
+                   }));
+        assert_eq!(result.eliminations[3], None);
+        assert_eq!(result.nr_steps, 11);
+    }
+
+    #[test]
+    fn runToCompletion_sameCycle_placeShared() {
+        let suicidal = make_suicidal_bot(1);
+        let idle = Bot::new(vec![]);
+        let result = FreeForAllArena::new(&[&suicidal, &idle, &idle, &suicidal],
+                                          &FreeForAllParams::evenly_spaced(4, 16, 1000).unwrap())
+            .unwrap()
+            .run_to_completion();
+        assert_eq!(result.placements, vec![3, 1, 1, 3]);
+        assert_eq!(result.nr_steps, 1);
+    }
+
+    #[test]
+    fn runToCompletion_flagCaptured_ownerDropsOut() {
+        // The second bot moves onto the flag of the third and clears it.
+        let idle = Bot::new(vec![]);
+        let attacker = Bot::new(vec![Instruction::MoveForward,
+                                     Instruction::MoveForward,
+                                     Instruction::JumpIfZero(4),
+                                     Instruction::Increment,
+                                     Instruction::JumpIfNonZero(2)]);
+        let params = FreeForAllParams {
+            homes: vec![HomeFlag {
+                            position: 0,
+                            orientation: Orientation::Normal,
+                        },
+                        HomeFlag {
+                            position: 3,
+                            orientation: Orientation::Normal,
+                        },
+                        HomeFlag {
+                            position: 5,
+                            orientation: Orientation::Reversed,
+                        }],
+            ..FreeForAllParams::evenly_spaced(3, 10, 10_000).unwrap()
+        };
+        let result = FreeForAllArena::new(&[&idle, &attacker, &idle], &params)
+            .unwrap()
+            .run_to_completion();
+        assert_eq!(result.placements, vec![1, 1, 3]);
+        assert_eq!(result.eliminations[2].map(|elimination| elimination.reason),
+                   Some(LossReason::FlagZeroed));
+    }
+
+    #[test]
+    fn runToCompletion_twoBots_sameAsArena() {
+        let bot_a = Bot::new(vec![Instruction::MoveForward; 9]);
+        let bot_b = make_suicidal_bot(3);
+        let result = FreeForAllArena::new(&[&bot_a, &bot_b],
+                                          &FreeForAllParams::evenly_spaced(2, 10, 1000).unwrap())
+            .unwrap()
+            .run_to_completion();
+        let outcome = Arena::new(&bot_a, &bot_b, &RoundParams::new(10, false, 1000))
+            .unwrap()
+            .run_to_completion();
+        assert_eq!(outcome.result.status(), RoundStatus::StartBotWon);
+        assert_eq!(result.placements, vec![1, 2]);
+        assert_eq!(result.nr_steps, outcome.nr_steps);
+    }
+
+    #[test]
+    fn new_flagMissingOffTapeOrShared_invalidParams() {
+        let idle = Bot::new(vec![]);
+        let mut params = FreeForAllParams::evenly_spaced(2, 10, 100).unwrap();
+        assert!(FreeForAllArena::new(&[&idle, &idle], &params).is_ok());
+        assert!(FreeForAllArena::new(&[&idle, &idle, &idle], &params).is_err());
+        params.homes[1].position = 10;
+        assert!(FreeForAllArena::new(&[&idle, &idle], &params).is_err());
+        params.homes[1].position = 0;
+        assert!(FreeForAllArena::new(&[&idle, &idle], &params).is_err());
+    }
+}
@@ -0,0 +1,79 @@
+use alloc::vec::Vec;
+
+use engine::Orientation;
+
+/// How often a single bot accessed each cell of the tape.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct CellCounts {
+    /// The number of times the bot evaluated a loop condition on each cell, by executing `[` or
+    /// `]` while standing on it.
+    pub reads: Vec<u32>,
+    /// The number of times the bot changed each cell with `+` or `-`. Changes that were discarded
+    /// because of a collision don't count.
+    pub writes: Vec<u32>,
+}
+
+impl CellCounts {
+    fn new(tape_length: usize) -> CellCounts {
+        CellCounts {
+            reads: vec![0; tape_length],
+            writes: vec![0; tape_length],
+        }
+    }
+
+    /// Returns the number of different cells that were read or written at least once.
+    pub fn nr_cells_touched(&self) -> usize {
+        self.reads
+            .iter()
+            .zip(self.writes.iter())
+            .filter(|&(&reads, &writes)| reads > 0 || writes > 0)
+            .count()
+    }
+
+    /// Returns the number of different cells that were written at least once.
+    pub fn nr_cells_written(&self) -> usize {
+        self.writes.iter().filter(|&&writes| writes > 0).count()
+    }
+}
+
+/// Statistics about which cells of the tape each bot accessed during a round, and how often. The
+/// arena only collects them if asked to, with `Arena::attach_heatmap`.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct TapeHeatmap {
+    pub start_bot: CellCounts,
+    pub end_bot: CellCounts,
+}
+
+impl TapeHeatmap {
+    pub fn new(tape_length: usize) -> TapeHeatmap {
+        TapeHeatmap {
+            start_bot: CellCounts::new(tape_length),
+            end_bot: CellCounts::new(tape_length),
+        }
+    }
+
+    /// Returns the counts of the bot with the given orientation.
+    pub fn of_bot(&self, bot: Orientation) -> &CellCounts {
+        match bot {
+            Orientation::Normal => &self.start_bot,
+            Orientation::Reversed => &self.end_bot,
+        }
+    }
+
+    pub fn record_read(&mut self, bot: Orientation, index: usize) {
+        self.of_bot_mut(bot).reads[index] += 1;
+    }
+
+    pub fn record_write(&mut self, bot: Orientation, index: usize) {
+        self.of_bot_mut(bot).writes[index] += 1;
+    }
+
+    fn of_bot_mut(&mut self, bot: Orientation) -> &mut CellCounts {
+        match bot {
+            Orientation::Normal => &mut self.start_bot,
+            Orientation::Reversed => &mut self.end_bot,
+        }
+    }
+}
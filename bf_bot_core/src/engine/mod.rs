@@ -1,3 +1,8 @@
+#[cfg(feature = "closure-engine")]
+pub use self::closure_program::ClosureProgram;
+#[cfg(feature = "closure-engine")]
+mod closure_program;
+
 pub use self::bot_in_play::BotInPlay;
 mod bot_in_play;
 
@@ -10,5 +15,49 @@ mod orientation;
 pub use self::polarity::Polarity;
 mod polarity;
 
+pub use self::decision_cycle::DecisionCycle;
+mod decision_cycle;
+
+pub use self::cycle_detector::{ArenaState, CycleDetector};
+mod cycle_detector;
+
+pub use self::tape_hasher::TapeHasher;
+mod tape_hasher;
+
+pub use self::recorder::{BotPointers, CellChange, Recorder, Replay, StepRecord};
+mod recorder;
+
+pub use self::heatmap::{CellCounts, TapeHeatmap};
+mod heatmap;
+
+pub use self::snapshot::{BotSnapshot, RoundSnapshot};
+mod snapshot;
+
+pub use self::observer::{ArenaObserver, StepView};
+mod observer;
+
+pub use self::rule_set::{BfJoustRules, RuleSet};
+mod rule_set;
+
+pub use self::multiple_flag_rules::MultipleFlagRules;
+mod multiple_flag_rules;
+
+pub use self::fog_of_war::FogOfWar;
+mod fog_of_war;
+
+pub use self::referee::{BotProgress, Referee};
+mod referee;
+
 pub use self::arena::Arena;
 mod arena;
+
+pub use self::free_for_all::{Elimination, FreeForAllArena, FreeForAllParams, FreeForAllResult,
+                             HomeFlag};
+mod free_for_all;
+
+pub use self::batch_arena::BatchArena;
+mod batch_arena;
+
+pub use self::differential::{builtin_backends, verify_backends, verify_backends_with, Backend,
+                             BackendMismatch, BatchBackend, EngineBackend};
+mod differential;
@@ -0,0 +1,129 @@
+use alloc::vec::Vec;
+
+use engine::{BfJoustRules, RuleSet};
+use simul_round::RoundParams;
+
+/// A variant of the standard rules in which each bot defends several flags: the cells at its own
+/// end of the tape. All of them start at the value of a standard flag, and a bot only loses once
+/// all of its flags have been zero at the end of two consecutive cycles. Each bot still starts on
+/// the outermost of its flags.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MultipleFlagRules {
+    nr_flags: usize,
+}
+
+impl MultipleFlagRules {
+    /// Constructs the rules for the given number of flags per bot, which must be at least one.
+    pub fn new(nr_flags: u32) -> MultipleFlagRules {
+        assert!(nr_flags > 0, "Each bot needs at least one flag.");
+        MultipleFlagRules { nr_flags: nr_flags as usize }
+    }
+}
+
+impl RuleSet for MultipleFlagRules {
+    fn write_initial_tape(&self, round_params: &RoundParams, tape: &mut Vec<i32>) {
+        assert!(round_params.tape_length as usize > 2 * self.nr_flags,
+                "A tape of {} cells has no room for {} flags per bot.",
+                round_params.tape_length,
+                self.nr_flags);
+        BfJoustRules.write_initial_tape(round_params, tape);
+        let (start_flag, end_flag) = (tape[0], tape[tape.len() - 1]);
+        let length = tape.len();
+        for cell in &mut tape[..self.nr_flags] {
+            *cell = start_flag;
+        }
+        for cell in &mut tape[length - self.nr_flags..] {
+            *cell = end_flag;
+        }
+    }
+
+    fn flags_zeroed(&self, tape: &[i32]) -> (bool, bool) {
+        let all_zero = |flags: &[i32]| flags.iter().all(|&flag| flag == 0);
+        (all_zero(&tape[..self.nr_flags]), all_zero(&tape[tape.len() - self.nr_flags..]))
+    }
+
+    fn flag_cells(&self, tape_length: usize) -> Vec<usize> {
+        (0..self.nr_flags).chain(tape_length - self.nr_flags..tape_length).collect()
+    }
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod tests {
+    use super::*;
+    use bf::{Bot, Instruction};
+    use engine::{Arena, BatchArena};
+    use simul_round::{LossReason, RoundStatus, Rules};
+
+    fn make_round_params(nr_flags: u32) -> RoundParams {
+        RoundParams {
+            rules: Rules::MultipleFlags { nr_flags },
+            ..RoundParams::new(10, false, 10_000)
+        }
+    }
+
+    /// A bot that clears the cell it stands on and then moves forward, the given number of times.
+    fn make_clearing_bot(nr_cells: usize) -> Bot {
+        let mut program = vec![];
+        for _ in 0..nr_cells {
+            let start = program.len();
+            program.extend(vec![Instruction::JumpIfZero(start + 2),
+                                Instruction::Decrement,
+                                Instruction::JumpIfNonZero(start)]);
+            program.push(Instruction::MoveForward);
+        }
+        Bot::new(program)
+    }
+
+    #[test]
+    fn writeInitialTape_threeFlags_firstAndLastThreeCells() {
+        assert_eq!(make_round_params(3).initial_tape().unwrap(),
+                   vec![-128, -128, -128, 0, 0, 0, 0, -128, -128, -128]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn writeInitialTape_flagsFillTape_panics() {
+        make_round_params(5).initial_tape().unwrap();
+    }
+
+    #[test]
+    fn flagsZeroed_someFlagsZero_notZeroed() {
+        let rules = MultipleFlagRules::new(2);
+        assert_eq!(rules.flags_zeroed(&[0, 1, 0, 0, 0]), (false, true));
+        assert_eq!(rules.flags_zeroed(&[0, 0, 5, 1, 0]), (true, false));
+    }
+
+    #[test]
+    fn flagCells_twoFlags_twoCellsAtEitherEnd() {
+        assert_eq!(MultipleFlagRules::new(2).flag_cells(10), vec![0, 1, 8, 9]);
+    }
+
+    #[test]
+    fn play_ownFlagsCleared_lostOnlyOnceAllAreZero() {
+        // The start bot clears its own flags, one after the other.
+        let idle = Bot::new(vec![]);
+        let one_flag = Arena::new(&make_clearing_bot(1), &idle, &make_round_params(2)).unwrap()
+            .run_to_completion()
+            .result;
+        assert_eq!(one_flag.status(), RoundStatus::Draw);
+        let two_flags = Arena::new(&make_clearing_bot(2), &idle, &make_round_params(2)).unwrap()
+            .run_to_completion()
+            .result;
+        assert_eq!(two_flags.status(), RoundStatus::EndBotWon);
+        assert_eq!(two_flags.bot_a_loss, Some(LossReason::FlagZeroed));
+    }
+
+    #[test]
+    fn play_batchArena_sameResultAsArena() {
+        let bot_a = make_clearing_bot(3);
+        let bot_b = Bot::new(vec![Instruction::MoveForward; 6]);
+        let params = vec![make_round_params(1), make_round_params(3)];
+        let expected = params.iter()
+            .map(|round_params| {
+                Arena::new(&bot_a, &bot_b, round_params).unwrap().run_to_completion().result
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(BatchArena::new(&bot_a, &bot_b, params).unwrap().run_to_completion(), expected);
+    }
+}
@@ -10,8 +10,8 @@ pub struct Mutation {
 impl Mutation {
     pub fn new(index: usize, addend: i8) -> Mutation {
         Mutation {
-            index: index,
-            addend: addend,
+            index,
+            addend,
         }
     }
 
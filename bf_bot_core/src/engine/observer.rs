@@ -0,0 +1,125 @@
+use engine::{BotPointers, CellChange, Orientation};
+use simul_round::RoundResult;
+
+/// The state of the arena after a step.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct StepView<'t> {
+    /// The number of cycles that have been executed so far, including this one.
+    pub step_nr: u32,
+    pub tape: &'t [i32],
+    pub start_bot: BotPointers,
+    pub end_bot: BotPointers,
+}
+
+/// Receives notifications of what happens in an arena, once attached to it with
+/// `Arena::attach_observer`. Every callback does nothing by default, so observers only need to
+/// implement the ones they are interested in.
+///
+/// To inspect an observer after the round, attach a mutable reference to it rather than the
+/// observer itself.
+pub trait ArenaObserver {
+    /// Called after every cycle in which both bots executed an instruction.
+    fn on_step(&mut self, _step: &StepView) {}
+
+    /// Called whenever a bot changes the value of a cell. The bot is identified by its
+    /// orientation: the bot with the normal orientation starts at the start of the tape.
+    fn on_mutation(&mut self, _step_nr: u32, _bot: Orientation, _change: CellChange) {}
+
+    /// Called once, when the round has ended.
+    fn on_round_end(&mut self, _result: &RoundResult) {}
+}
+
+impl<T> ArenaObserver for &mut T
+    where T: ArenaObserver + ?Sized
+{
+    fn on_step(&mut self, step: &StepView) {
+        (**self).on_step(step)
+    }
+
+    fn on_mutation(&mut self, step_nr: u32, bot: Orientation, change: CellChange) {
+        (**self).on_mutation(step_nr, bot, change)
+    }
+
+    fn on_round_end(&mut self, result: &RoundResult) {
+        (**self).on_round_end(result)
+    }
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod tests {
+    use super::*;
+    use bf::{Bot, Instruction};
+    use engine::Arena;
+    use simul_round::RoundParams;
+
+    #[derive(Default)]
+    struct CountingObserver {
+        steps: Vec<u32>,
+        mutations: Vec<(u32, Orientation, CellChange)>,
+        results: Vec<RoundResult>,
+    }
+
+    impl ArenaObserver for CountingObserver {
+        fn on_step(&mut self, step: &StepView) {
+            self.steps.push(step.step_nr);
+        }
+
+        fn on_mutation(&mut self, step_nr: u32, bot: Orientation, change: CellChange) {
+            self.mutations.push((step_nr, bot, change));
+        }
+
+        fn on_round_end(&mut self, result: &RoundResult) {
+            self.results.push(result.clone());
+        }
+    }
+
+    fn run_observed(observer: &mut CountingObserver) -> u32 {
+        let bot_a = Bot::new(vec![Instruction::Increment]);
+        let bot_b = Bot::new(vec![Instruction::MoveForward, Instruction::Decrement]);
+        let mut arena = Arena::new(&bot_a, &bot_b, &RoundParams::new(10, false, 5)).unwrap();
+        arena.attach_observer(Box::new(observer));
+        arena.finish().nr_steps
+    }
+
+    #[test]
+    fn onStep_calledAfterEveryCycle() {
+        let mut observer = CountingObserver::default();
+        let nr_steps = run_observed(&mut observer);
+        assert_eq!(observer.steps, (1..nr_steps + 1).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn onMutation_reportsWhichBotChangedWhichCell() {
+        let mut observer = CountingObserver::default();
+        run_observed(&mut observer);
+        let changes = observer.mutations
+            .iter()
+            .map(|&(step_nr, bot, change)| (step_nr, bot, change.index))
+            .collect::<Vec<_>>();
+        assert_eq!(changes, vec![(1, Orientation::Normal, 0), (2, Orientation::Reversed, 8)]);
+    }
+
+    #[test]
+    fn onRoundEnd_calledOnceWithTheFinalResult() {
+        let mut observer = CountingObserver::default();
+        run_observed(&mut observer);
+        assert_eq!(observer.results.len(), 1);
+        assert!(observer.results[0].round_is_finished());
+    }
+
+    #[test]
+    fn detachObserver_stopsNotifications() {
+        let bot = Bot::new(vec![Instruction::Increment]);
+        let mut observer = CountingObserver::default();
+        {
+            let mut arena = Arena::new(&bot, &bot, &RoundParams::new(10, false, 5)).unwrap();
+            arena.attach_observer(Box::new(&mut observer));
+            arena.step();
+            assert!(arena.detach_observer().is_some());
+            arena.finish();
+        }
+        assert_eq!(observer.steps, vec![1]);
+        assert!(observer.results.is_empty());
+    }
+}
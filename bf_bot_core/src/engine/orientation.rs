@@ -1,7 +1,7 @@
 /// The orientation of a `BotInPlay` is determined by its starting position on the tape.
 /// Orientation doesn't affect the gameplay from the bot's perspective, each bot may write their
 /// code as though they start at cell zero.
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy)]
 #[derive(PartialEq)]
 pub enum Orientation {
     /// Bot starts off at the start of the tape. To advance forward means to move in the positive
@@ -0,0 +1,161 @@
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
+use simul_round::RoundResult;
+
+/// The pointers of a single bot after a step.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct BotPointers {
+    /// The index of the next instruction that the bot will execute.
+    pub code_pointer: usize,
+    /// The position of the bot on the tape. This is outside the tape if the bot has left it.
+    pub position: i32,
+}
+
+/// A cell whose value changed during a step.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct CellChange {
+    pub index: usize,
+    pub new_value: i32,
+}
+
+/// What happened during a single step of a round.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct StepRecord {
+    pub start_bot: BotPointers,
+    pub end_bot: BotPointers,
+    /// The cells that changed during this step. Usually there are at most two of them.
+    pub tape_diff: Vec<CellChange>,
+}
+
+/// A step-by-step trace of a round. Only the changes to the tape are stored for every step, but
+/// the complete tape at any step can be reconstructed with `tape_at`.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Replay {
+    /// The tape at the moment recording started.
+    pub initial_tape: Vec<i32>,
+    pub steps: Vec<StepRecord>,
+    /// The outcome of the round, if it ended while it was being recorded.
+    pub result: Option<RoundResult>,
+    /// The names of the start bot and the end bot, if they are known.
+    pub bot_names: Option<(String, String)>,
+}
+
+impl Replay {
+    /// Labels the replay with the names of the start bot and the end bot.
+    pub fn with_bot_names(self, start_bot: &str, end_bot: &str) -> Replay {
+        Replay { bot_names: Some((start_bot.to_string(), end_bot.to_string())), ..self }
+    }
+
+    /// Returns the tape as it was after the given number of recorded steps. Step zero gives the
+    /// initial tape.
+    ///
+    /// # Panics
+    ///
+    /// Panics if fewer steps than requested were recorded.
+    pub fn tape_at(&self, nr_steps: usize) -> Vec<i32> {
+        let mut tape = self.initial_tape.clone();
+        for change in self.steps[..nr_steps].iter().flat_map(|step| step.tape_diff.iter()) {
+            tape[change.index] = change.new_value;
+        }
+        tape
+    }
+}
+
+/// Records a round while it is being played. Attach one to an `Arena` to obtain a `Replay`.
+#[derive(Debug)]
+pub struct Recorder {
+    previous_tape: Vec<i32>,
+    replay: Replay,
+}
+
+impl Recorder {
+    /// Starts recording from the given state of the tape.
+    pub fn new(tape: &[i32]) -> Recorder {
+        Recorder {
+            previous_tape: tape.to_vec(),
+            replay: Replay {
+                initial_tape: tape.to_vec(),
+                steps: vec![],
+                result: None,
+                bot_names: None,
+            },
+        }
+    }
+
+    /// Records the state of the arena after a step.
+    pub fn record_step(&mut self, tape: &[i32], start_bot: BotPointers, end_bot: BotPointers) {
+        let tape_diff = tape.iter()
+            .zip(self.previous_tape.iter())
+            .enumerate()
+            .filter(|&(_, (new, old))| new != old)
+            .map(|(index, (&new_value, _))| CellChange { index, new_value })
+            .collect::<Vec<_>>();
+        for change in &tape_diff {
+            self.previous_tape[change.index] = change.new_value;
+        }
+        self.replay.steps.push(StepRecord {
+            start_bot,
+            end_bot,
+            tape_diff,
+        });
+    }
+
+    /// Records the outcome of the round.
+    pub fn record_result(&mut self, result: RoundResult) {
+        self.replay.result = Some(result);
+    }
+
+    pub fn get_replay(&self) -> &Replay {
+        &self.replay
+    }
+
+    pub fn into_replay(self) -> Replay {
+        self.replay
+    }
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod tests {
+    use super::*;
+
+    fn pointers(position: i32) -> BotPointers {
+        BotPointers {
+            code_pointer: 0,
+            position,
+        }
+    }
+
+    #[test]
+    fn recordStep_storesOnlyChangedCells() {
+        let mut recorder = Recorder::new(&[5, 0, 0, 5]);
+        recorder.record_step(&[5, 1, 0, 4], pointers(1), pointers(3));
+        assert_eq!(recorder.get_replay().steps[0].tape_diff,
+                   vec![CellChange {
+                            index: 1,
+                            new_value: 1,
+                        },
+                        CellChange {
+                            index: 3,
+                            new_value: 4,
+                        }]);
+    }
+
+    #[test]
+    fn tapeAt_reconstructsTapeAfterEveryStep() {
+        let tapes = [vec![5, 0, 0, 5], vec![5, 1, 0, 5], vec![5, 1, 0, 5], vec![4, 2, 0, 5]];
+        let mut recorder = Recorder::new(&tapes[0]);
+        for tape in &tapes[1..] {
+            recorder.record_step(tape, pointers(0), pointers(3));
+        }
+        let replay = recorder.into_replay();
+        for (nr_steps, tape) in tapes.iter().enumerate() {
+            assert_eq!(&replay.tape_at(nr_steps), tape);
+        }
+    }
+}
@@ -0,0 +1,237 @@
+use alloc::boxed::Box;
+use core::mem;
+
+use engine::{BfJoustRules, CycleDetector, DecisionCycle, FogOfWar, Orientation,
+             RuleSet};
+use simul_round::{CellWidth, CollisionRule, InstructionBudgets, Noise, RoundParams, RoundResult,
+                  StartDelays};
+
+/// How far a bot has come in its program, as far as the referee needs to know.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BotProgress {
+    pub program_has_ended: bool,
+    pub nr_executed_instructions: u32,
+}
+
+/// Applies the rules of one round that don't depend on how the bots execute their programs: when
+/// the round ends, what the bots sense, which bot acts when, how the changes of a cycle are
+/// applied to the tape and who has lost. `Arena` and `BatchArena` both keep one per round and
+/// only execute the instructions themselves, so that they play by exactly the same rules.
+///
+/// A cycle goes: `ended_before_cycle`, `start_cycle`, `senses_zero` and `acts` for both bots,
+/// executing their instructions, `applied_changes`, `add` for the changes and the
+/// `perturbation`, and finally `end_cycle`.
+#[derive(Debug)]
+pub struct Referee {
+    max_steps: u32,
+    instruction_budgets: Option<InstructionBudgets>,
+    cell_width: CellWidth,
+    collision_rule: CollisionRule,
+    decision_cycle: DecisionCycle,
+    /// Decides the initial tape and when the bots lose.
+    rules: Box<dyn RuleSet>,
+    /// Only present if early termination is enabled for this round.
+    cycle_detector: Option<CycleDetector>,
+    /// Only present if the bots can't sense every cell in this round.
+    fog_of_war: Option<FogOfWar>,
+    noise: Option<Noise>,
+    start_delays: StartDelays,
+}
+
+impl Referee {
+    /// Referees a round with the given parameters under the given rules, which replace the rules
+    /// of the round parameters.
+    pub fn new(round_params: &RoundParams, rules: Box<dyn RuleSet>) -> Referee {
+        Referee {
+            max_steps: round_params.max_steps,
+            instruction_budgets: round_params.instruction_budgets,
+            cell_width: round_params.cell_width,
+            collision_rule: round_params.collision_rule,
+            decision_cycle: DecisionCycle::new(),
+            rules,
+            cycle_detector: if round_params.detects_loops() {
+                Some(CycleDetector::new())
+            } else {
+                None
+            },
+            fog_of_war: round_params.fog_of_war
+                .map(|memory| FogOfWar::new(memory, round_params.tape_length as usize)),
+            noise: round_params.noise,
+            start_delays: round_params.start_delays,
+        }
+    }
+
+    /// Starts over for a new round with the given parameters. The rules stay, unless new ones are
+    /// given.
+    pub fn reset(&mut self, round_params: &RoundParams, rules: Option<Box<dyn RuleSet>>) {
+        let rules = match rules {
+            Some(rules) => rules,
+            // The placeholder takes no allocation, since the standard rules have no fields.
+            None => mem::replace(&mut self.rules, Box::new(BfJoustRules)),
+        };
+        *self = Referee::new(round_params, rules);
+    }
+
+    pub fn get_rules(&self) -> &dyn RuleSet {
+        &*self.rules
+    }
+
+    /// Returns true if the round ends as a draw before the cycle that starts at the given step,
+    /// because it reached the maximum number of steps, a bot used up its budget, or it is in a
+    /// sink state: both bots have ended their programs and neither flag is zero.
+    pub fn ended_before_cycle(&self,
+                              step_nr: u32,
+                              tape: &[i32],
+                              start_bot: BotProgress,
+                              end_bot: BotProgress)
+                              -> bool {
+        if step_nr >= self.max_steps {
+            return true;
+        }
+        let exhausted = |bot: BotProgress, budget: u32| {
+            !bot.program_has_ended && bot.nr_executed_instructions >= budget
+        };
+        if let Some(InstructionBudgets { start_bot: start_budget, end_bot: end_budget }) =
+            self.instruction_budgets {
+            if exhausted(start_bot, start_budget) || exhausted(end_bot, end_budget) {
+                return true;
+            }
+        }
+        let (start_flag_zeroed, end_flag_zeroed) = self.rules.flags_zeroed(tape);
+        !start_flag_zeroed && !end_flag_zeroed && start_bot.program_has_ended &&
+        end_bot.program_has_ended
+    }
+
+    /// Prepares the cycle that starts at the given step.
+    pub fn start_cycle(&mut self, step_nr: u32, tape: &[i32]) {
+        if step_nr == 0 {
+            // The initial state of the tape counts as the end of cycle zero.
+            self.observe_flags(tape);
+        }
+    }
+
+    /// Returns whether the bot with the given orientation senses that the cell at the given
+    /// position is zero, in the cycle that starts at the given step. Without fog of war, that is
+    /// simply whether the cell is zero.
+    pub fn senses_zero(&mut self,
+                       bot: Orientation,
+                       position: usize,
+                       step_nr: u32,
+                       tape: &[i32])
+                       -> bool {
+        let cell_value = tape[position];
+        match self.fog_of_war {
+            Some(ref mut fog_of_war) => fog_of_war.senses_zero(bot, position, step_nr, cell_value),
+            None => cell_value == 0,
+        }
+    }
+
+    /// Returns whether the bot with the given orientation executes an instruction in the cycle
+    /// that starts at the given step, rather than waiting for its start delay to pass.
+    pub fn acts(&self, bot: Orientation, step_nr: u32) -> bool {
+        match bot {
+            Orientation::Normal => step_nr >= self.start_delays.start_bot,
+            Orientation::Reversed => step_nr >= self.start_delays.end_bot,
+        }
+    }
+
+    /// Returns whether the changes of the start bot and the end bot are applied, given the cells
+    /// they change in the cycle that ends at the given step.
+    pub fn applied_changes(&self,
+                           step_nr: u32,
+                           start_index: Option<usize>,
+                           end_index: Option<usize>)
+                           -> (bool, bool) {
+        match (start_index, end_index) {
+            (Some(start_index), Some(end_index)) if start_index == end_index => {
+                self.collision_rule.applied_changes(step_nr)
+            }
+            _ => (true, true),
+        }
+    }
+
+    /// Adds the given amount to a cell, wrapping around at the cell width.
+    pub fn add(&self, tape: &mut [i32], index: usize, addend: i32) {
+        tape[index] = self.cell_width.add(tape[index], addend);
+    }
+
+    /// Returns the change that noise makes to the tape in the cycle that ends at the given step,
+    /// if any.
+    pub fn perturbation(&self, step_nr: u32, tape_length: usize) -> Option<(usize, i32)> {
+        self.noise.and_then(|noise| noise.perturbation(step_nr, tape_length))
+    }
+
+    /// Decides whether the round has ended with the cycle that ends at the given step, given the
+    /// tape and the positions of the bots after it. A round that has entered a loop is a draw if
+    /// early termination is enabled; the code pointers of the bots are only asked for when they
+    /// are needed to tell.
+    pub fn end_cycle<F>(&mut self,
+                        step_nr: u32,
+                        tape: &[i32],
+                        start_position: i32,
+                        end_position: i32,
+                        code_pointers: F)
+                        -> RoundResult
+        where F: FnOnce() -> (usize, usize)
+    {
+        self.observe_flags(tape);
+        let start_bot_out = self.rules.bot_is_out(start_position, tape);
+        let end_bot_out = self.rules.bot_is_out(end_position, tape);
+        let result = self.decision_cycle.decide(start_bot_out, end_bot_out);
+        if result.round_is_finished() {
+            return result;
+        }
+        let loop_detected = match self.cycle_detector {
+            Some(ref mut detector) => {
+                let (start_code_pointer, end_code_pointer) = code_pointers();
+                detector.repeats(step_nr,
+                                 tape,
+                                 (start_position, start_code_pointer),
+                                 (end_position, end_code_pointer))
+            }
+            None => false,
+        };
+        if loop_detected {
+            RoundResult::draw()
+        } else {
+            result
+        }
+    }
+
+    fn observe_flags(&mut self, tape: &[i32]) {
+        let (start_flag_zeroed, end_flag_zeroed) = self.rules.flags_zeroed(tape);
+        self.decision_cycle.observe_flags(start_flag_zeroed, end_flag_zeroed);
+    }
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod tests {
+    use super::*;
+    use simul_round::RoundStatus;
+
+    fn make_referee() -> Referee {
+        let round_params = RoundParams {
+            early_termination: true,
+            collision_rule: CollisionRule::StartBotFirst,
+            ..RoundParams::new(10, false, 100)
+        };
+        Referee::new(&round_params, round_params.rules.rule_set())
+    }
+
+    #[test]
+    fn appliedChanges_sameCell_collisionRuleDecides() {
+        let referee = make_referee();
+        assert_eq!(referee.applied_changes(1, Some(4), Some(4)), (true, false));
+        assert_eq!(referee.applied_changes(1, Some(4), Some(5)), (true, true));
+    }
+
+    #[test]
+    fn endCycle_endBotOffTape_codePointersNotAsked() {
+        let mut referee = make_referee();
+        let tape = RoundParams::new(10, false, 100).initial_tape().unwrap();
+        referee.start_cycle(0, &tape);
+        let result = referee.end_cycle(1, &tape, 0, 10, || panic!("The round has ended."));
+        assert_eq!(result.status(), RoundStatus::StartBotWon);
+    }
+}
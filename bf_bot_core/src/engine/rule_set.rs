@@ -0,0 +1,110 @@
+use alloc::vec::Vec;
+use core::fmt;
+
+use simul_round::RoundParams;
+
+/// The rules that decide what the tape looks like at the start of a round and when a bot has
+/// lost. The arena plays the bots and asks the rules about the tape at the end of every cycle.
+///
+/// The timing of the standard rules is kept for every rule set: a bot loses if its flags count as
+/// zeroed at the end of two consecutive cycles, or if it leaves the playing field, and the round is
+/// a draw if both bots lose during the same cycle.
+pub trait RuleSet: fmt::Debug + Send + Sync {
+    /// Overwrites the given tape with the tape at the start of the round, which is
+    /// `round_params.tape_length` cells long. This should reuse the memory of the given tape where
+    /// possible.
+    fn write_initial_tape(&self, round_params: &RoundParams, tape: &mut Vec<i32>);
+
+    /// Returns whether the flags of the start bot and of the end bot count as zeroed.
+    fn flags_zeroed(&self, tape: &[i32]) -> (bool, bool);
+
+    /// Returns the indices of the cells that hold the flags of either bot, on a tape of the given
+    /// length. By default, these are the cells at both ends of the tape.
+    fn flag_cells(&self, tape_length: usize) -> Vec<usize> {
+        vec![0, tape_length - 1]
+    }
+
+    /// Returns true if a bot at the given position has left the playing field, which loses it the
+    /// round. By default, that is the case if it has moved off the tape.
+    fn bot_is_out(&self, position: i32, tape: &[i32]) -> bool {
+        position < 0 || position >= tape.len() as i32
+    }
+}
+
+/// The standard BF Joust rules: the flag of each bot is the cell at its own end of the tape, and
+/// all cells in between start at zero.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct BfJoustRules;
+
+impl RuleSet for BfJoustRules {
+    fn write_initial_tape(&self, round_params: &RoundParams, tape: &mut Vec<i32>) {
+        let length = round_params.tape_length as usize;
+        let reversed_bots = round_params.polarity_mode.reversed_bots(round_params.invert_polarity);
+        let (start_flag, end_flag) =
+            round_params.flag_init.flag_values(round_params.cell_width, reversed_bots);
+        tape.clear();
+        tape.resize(length, 0);
+        tape[0] = start_flag;
+        tape[length - 1] = end_flag;
+    }
+
+    fn flags_zeroed(&self, tape: &[i32]) -> (bool, bool) {
+        (tape[0] == 0, tape[tape.len() - 1] == 0)
+    }
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod tests {
+    use super::*;
+    use bf::{Bot, Instruction};
+    use engine::Arena;
+    use simul_round::{LossReason, RoundStatus};
+
+    /// A variant in which the cell next to the start flag is poisoned: a bot that stands on it at
+    /// the end of a cycle loses.
+    #[derive(Debug)]
+    struct PoisonedCellRules;
+
+    impl RuleSet for PoisonedCellRules {
+        fn write_initial_tape(&self, round_params: &RoundParams, tape: &mut Vec<i32>) {
+            BfJoustRules.write_initial_tape(round_params, tape);
+        }
+
+        fn flags_zeroed(&self, tape: &[i32]) -> (bool, bool) {
+            BfJoustRules.flags_zeroed(tape)
+        }
+
+        fn bot_is_out(&self, position: i32, tape: &[i32]) -> bool {
+            position == 1 || BfJoustRules.bot_is_out(position, tape)
+        }
+    }
+
+    #[test]
+    fn writeInitialTape_standardRules_flagsAtBothEnds() {
+        let mut tape = vec![5; 3];
+        BfJoustRules.write_initial_tape(&RoundParams::new(12, false, 100), &mut tape);
+        assert_eq!(tape.len(), 12);
+        assert_eq!((tape[0], tape[11]), (-128, -128));
+        assert!(tape[1..11].iter().all(|&cell| cell == 0));
+    }
+
+    #[test]
+    fn flagsZeroed_standardRules_onlyEndCells() {
+        assert_eq!(BfJoustRules.flags_zeroed(&[0, 0, 3]), (true, false));
+        assert_eq!(BfJoustRules.flags_zeroed(&[3, 0, 0]), (false, true));
+    }
+
+    #[test]
+    fn arena_poisonedCell_endBotLosesWhenItStepsOnIt() {
+        let bot_a = Bot::new(vec![]);
+        let bot_b = Bot::new(vec![Instruction::MoveForward; 8]);
+        let round_params = RoundParams::new(10, false, 100);
+        let result = Arena::with_rules(&bot_a, &bot_b, &round_params, Box::new(PoisonedCellRules))
+            .run_to_completion()
+            .result;
+        assert_eq!(result.status(), RoundStatus::StartBotWon);
+        assert_eq!(result.bot_b_loss, Some(LossReason::OffTape));
+        assert_eq!(result.nr_steps, 8);
+    }
+}
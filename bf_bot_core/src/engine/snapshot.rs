@@ -0,0 +1,21 @@
+/// The state of one bot at a certain step.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BotSnapshot {
+    /// The index of the instruction that the bot executes next.
+    pub code_pointer: usize,
+    /// The index of the cell the bot is on. Lies outside the tape if the bot left it.
+    pub position: i32,
+    /// The number of loops that the next instruction is nested in.
+    pub loop_depth: usize,
+}
+
+/// The public state of an arena at a certain step, for tools that display a round as it is
+/// played.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RoundSnapshot<'t> {
+    /// The number of cycles that have been executed so far.
+    pub step_nr: u32,
+    pub tape: &'t [i32],
+    pub start_bot: BotSnapshot,
+    pub end_bot: BotSnapshot,
+}
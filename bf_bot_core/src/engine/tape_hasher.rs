@@ -0,0 +1,49 @@
+use core::hash::Hasher;
+
+/// Hashes the tape at the end of a round into `RoundResult::tape_hash`, so that the results of the
+/// same round played by different engines can be compared without keeping the tapes. It is 64-bit
+/// FNV-1a, because the hasher of the standard library is not available without `std`. Its hashes
+/// are only compared between plays of the same round, so they need not be resistant to collisions
+/// that are crafted on purpose.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TapeHasher {
+    hash: u64,
+}
+
+impl Default for TapeHasher {
+    fn default() -> TapeHasher {
+        TapeHasher { hash: 0xcbf2_9ce4_8422_2325 }
+    }
+}
+
+impl Hasher for TapeHasher {
+    fn finish(&self) -> u64 {
+        self.hash
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.hash = (self.hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod tests {
+    use super::*;
+    use core::hash::Hash;
+
+    fn hash(tape: &[i32]) -> u64 {
+        let mut hasher = TapeHasher::default();
+        tape.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    #[test]
+    fn finish_differentTapes_differentHashes() {
+        assert_eq!(hash(&[-128, 0, 0, -128]), hash(&[-128, 0, 0, -128]));
+        assert_ne!(hash(&[-128, 0, 0, -128]), hash(&[-128, 0, 1, -128]));
+        assert_ne!(hash(&[0, 0]), hash(&[0, 0, 0]));
+    }
+}
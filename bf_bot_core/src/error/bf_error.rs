@@ -0,0 +1,98 @@
+use alloc::boxed::Box;
+use alloc::string::String;
+use core::error::Error;
+use core::fmt;
+#[cfg(feature = "std")]
+use std::io;
+
+use bf::ProgramError;
+use engine::BackendMismatch;
+
+/// Every way in which the fallible functions of this crate can fail. The variants hold a
+/// description of the problem, or the more specific error that caused it.
+#[derive(Debug)]
+pub enum BfError {
+    /// The source code of a bot could not be parsed. Parsing is done by the compiler crate, which
+    /// wraps its own error in this variant; it is also the `source` of this error, so that it can
+    /// be downcast to find out where the source code went wrong.
+    Parse(Box<dyn Error + Send + Sync>),
+    /// A program was rejected because its brackets don't match.
+    Program(ProgramError),
+    /// The parameters of a round, match or tournament are not valid, for example a tape that is
+    /// too short to hold the flags.
+    InvalidParams(String),
+    /// A simulation didn't go as it should, for example because two backends disagreed about the
+    /// result of a round. The error that describes what went wrong is the `source` of this error.
+    Simulation(Box<dyn Error + Send + Sync>),
+    /// Reading or writing failed. Only exists with the `std` feature.
+    #[cfg(feature = "std")]
+    Io(io::Error),
+}
+
+impl fmt::Display for BfError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            BfError::Parse(ref error) => write!(f, "Parse error: {}", error),
+            BfError::Program(ref error) => write!(f, "Invalid program: {}", error),
+            BfError::InvalidParams(ref message) => write!(f, "Invalid parameters: {}", message),
+            BfError::Simulation(ref error) => write!(f, "Simulation error: {}", error),
+            #[cfg(feature = "std")]
+            BfError::Io(ref error) => write!(f, "I/O error: {}", error),
+        }
+    }
+}
+
+impl Error for BfError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match *self {
+            BfError::Parse(ref error) | BfError::Simulation(ref error) => Some(&**error),
+            BfError::Program(ref error) => Some(error),
+            #[cfg(feature = "std")]
+            BfError::Io(ref error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<ProgramError> for BfError {
+    fn from(error: ProgramError) -> BfError {
+        BfError::Program(error)
+    }
+}
+
+#[cfg(feature = "std")]
+impl From<io::Error> for BfError {
+    fn from(error: io::Error) -> BfError {
+        BfError::Io(error)
+    }
+}
+
+impl From<BackendMismatch> for BfError {
+    fn from(mismatch: BackendMismatch) -> BfError {
+        BfError::Simulation(Box::new(mismatch))
+    }
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn source_programError_isTheCause() {
+        let error = BfError::from(ProgramError::UnmatchedBracket { index: 3 });
+        assert_eq!(error.to_string(),
+                   "Invalid program: Unmatched bracket at instruction 3.");
+        assert!(error.source().is_some());
+        assert!(BfError::InvalidParams("tape".to_string()).source().is_none());
+    }
+
+    #[test]
+    fn from_ioError_keepsItsKind() {
+        let error = BfError::from(io::Error::new(io::ErrorKind::NotFound, "gone"));
+        match error {
+            BfError::Io(ref error) => assert_eq!(error.kind(), io::ErrorKind::NotFound),
+            ref other => panic!("Expected an I/O error, got {:?}", other),
+        }
+    }
+}
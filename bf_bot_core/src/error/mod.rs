@@ -0,0 +1,2 @@
+pub use self::bf_error::BfError;
+mod bf_error;
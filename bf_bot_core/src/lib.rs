@@ -1,4 +1,38 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+#[cfg(feature = "serde")]
+#[macro_use]
+extern crate serde;
+
+#[cfg(feature = "tracing")]
+#[macro_use]
+extern crate log;
+// Its macros are used by their full path, since some have the same names as those of `log`.
+#[cfg(feature = "tracing")]
+extern crate tracing;
+
+#[cfg_attr(not(feature = "std"), macro_use)]
+extern crate alloc;
+// In Rust 2015, `core` is only in scope by itself in `no_std` mode.
+#[cfg(feature = "std")]
+extern crate core;
+
 pub mod bf;
+#[cfg(feature = "std")]
 pub mod simul_game;
 pub mod simul_round;
 pub mod engine;
+#[cfg(feature = "std")]
+pub mod match_runner;
+#[cfg(feature = "std")]
+pub mod tournament;
+#[cfg(feature = "std")]
+pub mod rating;
+#[cfg(feature = "std")]
+pub mod bench;
+#[cfg(feature = "std")]
+pub mod debugger;
+#[cfg(feature = "std")]
+pub mod analysis;
+pub mod reference_bots;
+pub mod cancellation;
+pub mod error;
@@ -0,0 +1,51 @@
+use match_runner::MatchResult;
+
+impl MatchResult {
+    /// Returns the outcome of this match in exactly the format that the egojoust interpreter
+    /// prints, so that scripts that manage a hill with egojoust can use this crate instead: the
+    /// grid line of the normal polarity, a space, the grid line of the inverted polarity, a space
+    /// and the score of bot A, followed by a newline. The score is always the one of egojoust,
+    /// regardless of the scoring scheme of this result.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bf_bot_core::match_runner::{MatchResult, RoundRecord};
+    /// use bf_bot_core::simul_round::{RoundParams, RoundResult};
+    /// let round = |length, invert_polarity, result| RoundRecord {
+    ///     params: RoundParams::new(length, invert_polarity, 1000),
+    ///     result,
+    /// };
+    /// let match_result = MatchResult::new(vec![round(10, false, RoundResult::start_bot_wins()),
+    ///                                          round(11, false, RoundResult::draw()),
+    ///                                          round(10, true, RoundResult::end_bot_wins()),
+    ///                                          round(11, true, RoundResult::end_bot_wins())]);
+    /// assert_eq!(match_result.egojoust_output(), "<X >> -1\n");
+    /// ```
+    pub fn egojoust_output(&self) -> String {
+        format!("{} {} {}\n",
+                self.grid_line(false),
+                self.grid_line(true),
+                self.score())
+    }
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod tests {
+    use super::*;
+    use match_runner::{RoundRecord, ScoringScheme};
+    use simul_round::{RoundParams, RoundResult};
+
+    #[test]
+    fn egojoustOutput_chainbfScheme_stillPrintsEgojoustScore() {
+        let match_result = MatchResult {
+            scoring_scheme: ScoringScheme::Chainbf,
+            ..MatchResult::new(vec![RoundRecord {
+                                        params: RoundParams::new(10, false, 1000),
+                                        result: RoundResult::start_bot_wins(),
+                                    }])
+        };
+        assert_eq!(match_result.egojoust_output(), "<  1\n");
+    }
+}
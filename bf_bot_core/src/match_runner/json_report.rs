@@ -0,0 +1,155 @@
+use std::fmt::Write;
+
+use match_runner::{MatchResult, RoundRecord, ScoringScheme};
+use simul_round::{LossReason, RoundStatus};
+
+/// The version of the layout of the report produced by `MatchResult::to_json_report`. It is
+/// increased whenever a field is removed or changes its meaning, so that consumers can reject
+/// reports they don't understand. Adding a field does not increase it.
+pub const JSON_REPORT_SCHEMA_VERSION: u32 = 1;
+
+impl MatchResult {
+    /// Returns a JSON document that describes this match: the names of the bots, the totals, and
+    /// for every round the configuration it was played in, the winner, the number of steps it took
+    /// and why each bot lost. Winners are one of the strings `"bot_a"`, `"bot_b"`, `"draw"` or
+    /// `"ongoing"`, losses one of `"flag_zeroed"`, `"off_tape"`, `"timeout"` or `"out_of_time"`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bf_bot_core::match_runner::{MatchResult, RoundRecord};
+    /// use bf_bot_core::simul_round::{RoundParams, RoundResult};
+    /// let match_result = MatchResult::new(vec![RoundRecord {
+    ///                                              params: RoundParams::new(10, false, 1000),
+    ///                                              result: RoundResult::start_bot_wins(),
+    ///                                          }]);
+    /// let report = match_result.to_json_report();
+    /// assert!(report.starts_with("{\"schema_version\":1,"));
+    /// assert!(report.contains("\"winner\":\"bot_a\""));
+    /// ```
+    pub fn to_json_report(&self) -> String {
+        let mut json = String::new();
+        write!(json, "{{\"schema_version\":{}", JSON_REPORT_SCHEMA_VERSION).unwrap();
+        write!(json, ",\"bot_a_name\":{}", optional_string(&self.bot_a_name)).unwrap();
+        write!(json, ",\"bot_b_name\":{}", optional_string(&self.bot_b_name)).unwrap();
+        write!(json,
+               ",\"scoring_scheme\":\"{}\"",
+               scoring_scheme_name(self.scoring_scheme))
+            .unwrap();
+        write!(json,
+               ",\"bot_a_wins\":{},\"bot_b_wins\":{},\"draws\":{}",
+               self.nr_bot_a_wins(),
+               self.nr_bot_b_wins(),
+               self.nr_draws())
+            .unwrap();
+        write!(json, ",\"score\":{},\"points\":{}", self.score(), self.points()).unwrap();
+        json.push_str(",\"rounds\":[");
+        for (index, record) in self.rounds.iter().enumerate() {
+            if index > 0 {
+                json.push(',');
+            }
+            write_round(&mut json, record);
+        }
+        json.push_str("]}");
+        json
+    }
+}
+
+fn write_round(json: &mut String, record: &RoundRecord) {
+    let winner = match record.result.status() {
+        RoundStatus::Ongoing => "ongoing",
+        RoundStatus::StartBotWon => "bot_a",
+        RoundStatus::EndBotWon => "bot_b",
+        RoundStatus::Draw => "draw",
+    };
+    write!(json,
+           "{{\"tape_length\":{},\"invert_polarity\":{},\"winner\":\"{}\",\"nr_steps\":{}",
+           record.params.tape_length,
+           record.params.invert_polarity,
+           winner,
+           record.result.nr_steps)
+        .unwrap();
+    write!(json,
+           ",\"bot_a_loss\":{},\"bot_b_loss\":{}}}",
+           loss_reason(record.result.bot_a_loss),
+           loss_reason(record.result.bot_b_loss))
+        .unwrap();
+}
+
+fn scoring_scheme_name(scoring_scheme: ScoringScheme) -> &'static str {
+    match scoring_scheme {
+        ScoringScheme::Egojoust => "egojoust",
+        ScoringScheme::Chainbf => "chainbf",
+    }
+}
+
+fn loss_reason(loss: Option<LossReason>) -> &'static str {
+    match loss {
+        None => "null",
+        Some(LossReason::FlagZeroed) => "\"flag_zeroed\"",
+        Some(LossReason::OffTape) => "\"off_tape\"",
+        Some(LossReason::Timeout) => "\"timeout\"",
+        Some(LossReason::OutOfTime) => "\"out_of_time\"",
+    }
+}
+
+fn optional_string(value: &Option<String>) -> String {
+    match *value {
+        Some(ref value) => quoted(value),
+        None => "null".to_string(),
+    }
+}
+
+/// Returns the given text as a JSON string, including the quotes.
+pub fn quoted(text: &str) -> String {
+    let mut quoted = String::with_capacity(text.len() + 2);
+    quoted.push('"');
+    for character in text.chars() {
+        match character {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\r' => quoted.push_str("\\r"),
+            '\t' => quoted.push_str("\\t"),
+            c if (c as u32) < 0x20 => write!(quoted, "\\u{:04x}", c as u32).unwrap(),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod tests {
+    use super::*;
+    use simul_round::{RoundParams, RoundResult};
+
+    #[test]
+    fn toJsonReport_singleDraw_describesRound() {
+        let match_result = MatchResult::new(vec![RoundRecord {
+                                                     params: RoundParams::new(12, true, 1000),
+                                                     result: RoundResult::draw().at_step(1000, 0),
+                                                 }]);
+        assert_eq!(match_result.to_json_report(),
+                   "{\"schema_version\":1,\"bot_a_name\":null,\"bot_b_name\":null,\
+                    \"scoring_scheme\":\"egojoust\",\"bot_a_wins\":0,\"bot_b_wins\":0,\
+                    \"draws\":1,\"score\":0,\"points\":0,\"rounds\":[{\"tape_length\":12,\
+                    \"invert_polarity\":true,\"winner\":\"draw\",\"nr_steps\":1000,\
+                    \"bot_a_loss\":\"timeout\",\"bot_b_loss\":\"timeout\"}]}");
+    }
+
+    #[test]
+    fn toJsonReport_names_escaped() {
+        let match_result = MatchResult {
+            bot_a_name: Some("say \"hi\"\\".to_string()),
+            ..MatchResult::new(vec![])
+        };
+        assert!(match_result.to_json_report().contains("\"bot_a_name\":\"say \\\"hi\\\"\\\\\""));
+    }
+
+    #[test]
+    fn quoted_controlCharacter_escapedAsUnicode() {
+        assert_eq!(quoted("a\u{1}"), "\"a\\u0001\"");
+    }
+}
@@ -0,0 +1,239 @@
+use std::fmt;
+
+use match_runner::ScoringScheme;
+use simul_round::{LossReason, RoundParams, RoundResult, RoundStatus};
+
+/// The outcome of a single round of a match, together with the conditions it was played under.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct RoundRecord {
+    pub params: RoundParams,
+    pub result: RoundResult,
+}
+
+/// The result of a match between bot A and bot B, consisting of the outcome of each round.
+#[derive(Debug, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct MatchResult {
+    pub rounds: Vec<RoundRecord>,
+    /// The scheme that `points` uses.
+    pub scoring_scheme: ScoringScheme,
+    /// The name of bot A, if the match was played between named bots.
+    pub bot_a_name: Option<String>,
+    /// The name of bot B, if the match was played between named bots.
+    pub bot_b_name: Option<String>,
+}
+
+impl MatchResult {
+    /// Constructs a match result that is scored with the default scoring scheme.
+    pub fn new(rounds: Vec<RoundRecord>) -> MatchResult {
+        MatchResult {
+            rounds,
+            scoring_scheme: ScoringScheme::default(),
+            bot_a_name: None,
+            bot_b_name: None,
+        }
+    }
+
+    /// Returns true if every round has ended. Only a match that was cancelled can have rounds that
+    /// are still ongoing.
+    pub fn is_complete(&self) -> bool {
+        self.rounds.iter().all(|record| record.result.status() != RoundStatus::Ongoing)
+    }
+
+    /// Returns true if the match used up its time budget, so that some of its rounds were cut
+    /// short and scored as draws.
+    pub fn ran_out_of_time(&self) -> bool {
+        self.rounds.iter().any(|record| record.result.bot_a_loss == Some(LossReason::OutOfTime))
+    }
+
+    /// Returns the number of rounds that were won by bot A.
+    pub fn nr_bot_a_wins(&self) -> usize {
+        self.count_rounds(|result| result.has_winner() && result.bot_b_lost())
+    }
+
+    /// Returns the number of rounds that were won by bot B.
+    pub fn nr_bot_b_wins(&self) -> usize {
+        self.count_rounds(|result| result.has_winner() && result.bot_a_lost())
+    }
+
+    /// Returns the number of rounds that ended without a winner.
+    pub fn nr_draws(&self) -> usize {
+        self.count_rounds(|result| !result.has_winner())
+    }
+
+    /// Returns the score of the match from the perspective of bot A: every round won by bot A
+    /// counts as one point, every round won by bot B counts as minus one point. A positive score
+    /// thus means that bot A won the match, a negative score that bot B won.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bf_bot_core::match_runner::{MatchResult, RoundRecord};
+    /// use bf_bot_core::simul_round::{RoundParams, RoundResult};
+    /// let params = RoundParams::new(10, false, 1000);
+    /// let round = |result| RoundRecord { params: params.clone(), result };
+    /// let match_result = MatchResult::new(vec![round(RoundResult::start_bot_wins()),
+    ///                                          round(RoundResult::start_bot_wins()),
+    ///                                          round(RoundResult::end_bot_wins()),
+    ///                                          round(RoundResult::draw())]);
+    /// assert_eq!(match_result.score(), 1);
+    /// ```
+    pub fn score(&self) -> i32 {
+        self.nr_bot_a_wins() as i32 - self.nr_bot_b_wins() as i32
+    }
+
+    /// Returns the points that bot A earned in this match, according to its scoring scheme.
+    pub fn points(&self) -> f64 {
+        self.scoring_scheme.points(self)
+    }
+
+    /// Returns the outcome of every round that was played with the given polarity, ordered by tape
+    /// length, as used on BF Joust hills: `<` if bot A (on the left) won, `>` if bot B (on the
+    /// right) won and `X` for a draw.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bf_bot_core::match_runner::{MatchResult, RoundRecord};
+    /// use bf_bot_core::simul_round::{RoundParams, RoundResult};
+    /// let round = |length, result| RoundRecord {
+    ///     params: RoundParams::new(length, false, 1000),
+    ///     result,
+    /// };
+    /// let match_result = MatchResult::new(vec![round(11, RoundResult::draw()),
+    ///                                          round(10, RoundResult::end_bot_wins()),
+    ///                                          round(12, RoundResult::start_bot_wins())]);
+    /// assert_eq!(match_result.grid_line(false), ">X<");
+    /// assert_eq!(match_result.grid_line(true), "");
+    /// ```
+    pub fn grid_line(&self, invert_polarity: bool) -> String {
+        let mut rounds = self.rounds
+            .iter()
+            .filter(|record| record.params.invert_polarity == invert_polarity)
+            .collect::<Vec<_>>();
+        rounds.sort_by_key(|record| record.params.tape_length);
+        rounds.iter()
+            .map(|record| match record.result.status() {
+                RoundStatus::StartBotWon => '<',
+                RoundStatus::EndBotWon => '>',
+                _ => 'X',
+            })
+            .collect()
+    }
+
+    fn count_rounds<F>(&self, predicate: F) -> usize
+        where F: Fn(&RoundResult) -> bool
+    {
+        self.rounds.iter().filter(|record| predicate(&record.result)).count()
+    }
+}
+
+/// Prints the summary of the match that BF Joust hills show: the tape lengths, the grid lines of
+/// both polarities, the number of rounds that either bot won, the score and the verdict. Bots
+/// without a name are called A and B.
+///
+/// # Examples
+///
+/// ```
+/// use bf_bot_core::match_runner::{MatchResult, RoundRecord};
+/// use bf_bot_core::simul_round::{RoundParams, RoundResult};
+/// let round = |length, invert_polarity, result| RoundRecord {
+///     params: RoundParams::new(length, invert_polarity, 1000),
+///     result,
+/// };
+/// let match_result = MatchResult::new(vec![round(10, false, RoundResult::start_bot_wins()),
+///                                          round(11, false, RoundResult::draw()),
+///                                          round(10, true, RoundResult::start_bot_wins()),
+///                                          round(11, true, RoundResult::end_bot_wins())]);
+/// assert_eq!(match_result.to_string(),
+///            "A vs B, tape lengths 10 to 11\n\
+///             normal polarity:   <X\n\
+///             inverted polarity: <>\n\
+///             A wins: 2, B wins: 1, draws: 1\n\
+///             Score: 1, A wins the match\n");
+/// ```
+impl fmt::Display for MatchResult {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let bot_a_name = self.bot_a_name.as_ref().map_or("A", |name| name.as_str());
+        let bot_b_name = self.bot_b_name.as_ref().map_or("B", |name| name.as_str());
+        let tape_lengths = self.rounds.iter().map(|record| record.params.tape_length);
+        match (tape_lengths.clone().min(), tape_lengths.max()) {
+            (Some(min), Some(max)) => {
+                writeln!(f, "{} vs {}, tape lengths {} to {}", bot_a_name, bot_b_name, min, max)?
+            }
+            _ => writeln!(f, "{} vs {}", bot_a_name, bot_b_name)?,
+        }
+        writeln!(f, "normal polarity:   {}", self.grid_line(false))?;
+        writeln!(f, "inverted polarity: {}", self.grid_line(true))?;
+        writeln!(f,
+                 "{} wins: {}, {} wins: {}, draws: {}",
+                 bot_a_name,
+                 self.nr_bot_a_wins(),
+                 bot_b_name,
+                 self.nr_bot_b_wins(),
+                 self.nr_draws())?;
+        let score = self.score();
+        if score > 0 {
+            writeln!(f, "Score: {}, {} wins the match", score, bot_a_name)
+        } else if score < 0 {
+            writeln!(f, "Score: {}, {} wins the match", score, bot_b_name)
+        } else {
+            writeln!(f, "Score: 0, the match is a draw")
+        }
+    }
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod tests {
+    use super::*;
+
+    fn make_record(result: RoundResult) -> RoundRecord {
+        RoundRecord {
+            params: RoundParams::new(10, false, 1000),
+            result,
+        }
+    }
+
+    #[test]
+    fn counts_mixedResults_countEachOutcome() {
+        let match_result = MatchResult::new(vec![make_record(RoundResult::start_bot_wins()),
+                                                 make_record(RoundResult::end_bot_wins()),
+                                                 make_record(RoundResult::end_bot_wins()),
+                                                 make_record(RoundResult::draw())]);
+        assert_eq!(match_result.nr_bot_a_wins(), 1);
+        assert_eq!(match_result.nr_bot_b_wins(), 2);
+        assert_eq!(match_result.nr_draws(), 1);
+        assert_eq!(match_result.score(), -1);
+    }
+
+    #[test]
+    fn score_onlyDraws_isZero() {
+        let match_result = MatchResult::new(vec![make_record(RoundResult::draw()),
+                                                 make_record(RoundResult::draw())]);
+        assert_eq!(match_result.score(), 0);
+    }
+
+    #[test]
+    fn points_chainbfScheme_isNormalized() {
+        let match_result = MatchResult {
+            scoring_scheme: ScoringScheme::Chainbf,
+            ..MatchResult::new(vec![make_record(RoundResult::end_bot_wins()),
+                                    make_record(RoundResult::draw())])
+        };
+        assert_eq!(match_result.points(), -0.5);
+    }
+
+    #[test]
+    fn display_namedBotsNoRounds_drawWithoutTapeLengths() {
+        let match_result = MatchResult {
+            bot_a_name: Some("left".to_string()),
+            bot_b_name: Some("right".to_string()),
+            ..MatchResult::default()
+        };
+        assert_eq!(match_result.to_string(),
+                   "left vs right\nnormal polarity:   \ninverted polarity: \n\
+                    left wins: 0, right wins: 0, draws: 0\nScore: 0, the match is a draw\n");
+    }
+}
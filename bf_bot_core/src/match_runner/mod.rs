@@ -0,0 +1,17 @@
+//! Runs a complete match between two bots: one round for every tape length and polarity, as is
+//! customary in BF Joust tournaments. Unlike `simul_game`, which only keeps a running total, the
+//! result of a match records the outcome of every single round.
+
+pub use self::runner::Match;
+mod runner;
+
+pub use self::match_result::{MatchResult, RoundRecord};
+mod match_result;
+
+pub use self::json_report::{quoted, JSON_REPORT_SCHEMA_VERSION};
+mod json_report;
+
+mod egojoust_report;
+
+pub use self::scoring_scheme::ScoringScheme;
+mod scoring_scheme;
@@ -0,0 +1,280 @@
+use bf::{Bot, NamedBot};
+use cancellation::CancellationToken;
+use engine::BatchArena;
+use error::BfError;
+use match_runner::{MatchResult, RoundRecord, ScoringScheme};
+use simul_game::{AllRounds, TapeLengths};
+use simul_round::{RoundParams, RoundResult};
+use std::time::{Duration, Instant};
+
+/// A match between two bots. Bot A is the bot that starts at the start of the tape, bot B starts
+/// at the end of the tape and is the one whose polarity is inverted in half of the rounds.
+#[derive(Debug)]
+pub struct Match<'a> {
+    bot_a: &'a Bot,
+    bot_b: &'a Bot,
+    scoring_scheme: ScoringScheme,
+    tape_lengths: TapeLengths,
+    /// The names of bot A and bot B, if they have names.
+    names: Option<(String, String)>,
+    cancellation: Option<CancellationToken>,
+    time_budget: Option<Duration>,
+}
+
+impl<'a> Match<'a> {
+    /// Constructs a complete match that is scored with the default scoring scheme.
+    pub fn new(bot_a: &'a Bot, bot_b: &'a Bot) -> Match<'a> {
+        Match {
+            bot_a,
+            bot_b,
+            scoring_scheme: ScoringScheme::default(),
+            tape_lengths: TapeLengths::default(),
+            names: None,
+            cancellation: None,
+            time_budget: None,
+        }
+    }
+
+    /// Constructs a complete match between two named bots. Their names are recorded in the
+    /// result.
+    pub fn between(bot_a: &'a NamedBot, bot_b: &'a NamedBot) -> Match<'a> {
+        Match {
+            names: Some((bot_a.name.clone(), bot_b.name.clone())),
+            ..Match::new(&bot_a.bot, &bot_b.bot)
+        }
+    }
+
+    /// Selects the scoring scheme of the results of this match.
+    pub fn with_scoring_scheme(self, scoring_scheme: ScoringScheme) -> Match<'a> {
+        Match { scoring_scheme, ..self }
+    }
+
+    /// Selects the tape lengths that `run` plays on, instead of those of a complete match.
+    pub fn with_tape_lengths(self, tape_lengths: TapeLengths) -> Match<'a> {
+        Match { tape_lengths, ..self }
+    }
+
+    /// Stops playing as soon as the given token is cancelled. The rounds that had not ended by
+    /// then are ongoing in the result.
+    pub fn with_cancellation(self, cancellation: CancellationToken) -> Match<'a> {
+        Match { cancellation: Some(cancellation), ..self }
+    }
+
+    /// Limits the wall-clock time that `run` and `run_rounds` may take, so that a pairing with a
+    /// huge maximum number of steps can't stall a tournament. The rounds that had not ended when
+    /// the time ran out are draws, which both bots lost with `LossReason::OutOfTime`.
+    ///
+    /// This needs a clock, which `wasm32-unknown-unknown` doesn't have: there, a match with a time
+    /// budget panics. Everything else in this crate works on that target.
+    pub fn with_time_budget(self, time_budget: Duration) -> Match<'a> {
+        Match { time_budget: Some(time_budget), ..self }
+    }
+
+    /// Plays a round for both polarities of every tape length and returns the result. Unless
+    /// other tape lengths were selected, those are the 42 rounds of a complete match. Fails if a
+    /// selected tape length is too short to play on.
+    pub fn run(&self) -> Result<MatchResult, BfError> {
+        self.run_rounds(AllRounds::new().with_tape_lengths(&self.tape_lengths))
+    }
+
+    /// Plays one round for each of the provided round parameters and returns the result. All
+    /// rounds are played at once, in a `BatchArena`. Fails if any of the round parameters are not
+    /// valid, in which case no round is played.
+    pub fn run_rounds<I>(&self, rounds: I) -> Result<MatchResult, BfError>
+        where I: Iterator<Item = RoundParams>
+    {
+        let params = rounds.collect::<Vec<_>>();
+        let arena = BatchArena::new(self.bot_a, self.bot_b, params.clone())?;
+        #[cfg(feature = "tracing")]
+        let span = self.tracing_span(params.len());
+        let deadline = self.time_budget.map(|time_budget| Instant::now() + time_budget);
+        let results = if self.cancellation.is_none() && deadline.is_none() {
+            arena.run_to_completion()
+        } else {
+            arena.run_until(|| {
+                if self.cancellation.as_ref().is_some_and(|token| token.is_cancelled()) {
+                    Some(RoundResult::round_ongoing())
+                } else if deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+                    Some(RoundResult::out_of_time())
+                } else {
+                    None
+                }
+            })
+        };
+        let (bot_a_name, bot_b_name) = match self.names {
+            Some((ref bot_a_name, ref bot_b_name)) => {
+                (Some(bot_a_name.clone()), Some(bot_b_name.clone()))
+            }
+            None => (None, None),
+        };
+        let result = MatchResult {
+            rounds: params.into_iter()
+                .zip(results)
+                .map(|(params, result)| RoundRecord { params, result })
+                .collect(),
+            scoring_scheme: self.scoring_scheme,
+            bot_a_name,
+            bot_b_name,
+        };
+        #[cfg(feature = "tracing")]
+        span.record("score", result.score());
+        Ok(result)
+    }
+
+    /// Enters the span of a match with the given number of rounds. Its score is recorded once the
+    /// match has been played.
+    #[cfg(feature = "tracing")]
+    fn tracing_span(&self, nr_rounds: usize) -> ::tracing::span::EnteredSpan {
+        let (bot_a_name, bot_b_name) = match self.names {
+            Some((ref bot_a_name, ref bot_b_name)) => (bot_a_name.as_str(), bot_b_name.as_str()),
+            None => ("A", "B"),
+        };
+        ::tracing::info_span!("match",
+                              bot_a = bot_a_name,
+                              bot_b = bot_b_name,
+                              nr_rounds,
+                              score = ::tracing::field::Empty)
+            .entered()
+    }
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod tests {
+    use super::*;
+    use bf::Instruction;
+
+    /// Its program, in BrainFuck: <
+    fn make_suicidal_bot() -> Bot {
+        Bot::new(vec![Instruction::MoveBack])
+    }
+
+    /// Its program, in BrainFuck: [-]
+    fn make_flag_clearing_bot() -> Bot {
+        Bot::new(vec![Instruction::JumpIfZero(2),
+                      Instruction::Decrement,
+                      Instruction::JumpIfNonZero(0)])
+    }
+
+    #[test]
+    fn run_playsAllConfigurations() {
+        let bot_a = make_suicidal_bot();
+        let bot_b = make_suicidal_bot();
+        assert_eq!(Match::new(&bot_a, &bot_b).run().unwrap().rounds.len(), 42);
+    }
+
+    #[test]
+    fn run_botASuicidal_botBWinsEveryRound() {
+        let bot_a = make_suicidal_bot();
+        let bot_b = Bot::new(vec![]);
+        let result = Match::new(&bot_a, &bot_b).run().unwrap();
+        assert_eq!(result.nr_bot_b_wins(), 42);
+        assert_eq!(result.score(), -42);
+    }
+
+    #[test]
+    fn run_botAClearsOwnFlag_botBWinsEveryRound() {
+        let bot_a = make_flag_clearing_bot();
+        let bot_b = Bot::new(vec![]);
+        let result = Match::new(&bot_a, &bot_b).run().unwrap();
+        assert_eq!(result.nr_bot_b_wins(), 42);
+    }
+
+    #[test]
+    fn run_chainbfScheme_pointsAreNormalized() {
+        let bot_a = make_suicidal_bot();
+        let bot_b = Bot::new(vec![]);
+        let result = Match::new(&bot_a, &bot_b)
+            .with_scoring_scheme(ScoringScheme::Chainbf)
+            .run()
+            .unwrap();
+        assert_eq!(result.points(), -1.0);
+    }
+
+    #[test]
+    fn run_customTapeLengths_playsBothPolaritiesOfEach() {
+        let bot_a = make_suicidal_bot();
+        let bot_b = make_suicidal_bot();
+        let result = Match::new(&bot_a, &bot_b)
+            .with_tape_lengths(TapeLengths::Range { min: 12, max: 14 })
+            .run()
+            .unwrap();
+        assert_eq!(result.rounds.len(), 6);
+    }
+
+    #[test]
+    fn run_namedBots_namesRecorded() {
+        let bot_a = NamedBot::new("suicidal", make_suicidal_bot());
+        let bot_b = NamedBot::new("idle", Bot::new(vec![]));
+        let result = Match::between(&bot_a, &bot_b).run().unwrap();
+        assert_eq!(result.bot_a_name, Some("suicidal".to_string()));
+        assert_eq!(result.bot_b_name, Some("idle".to_string()));
+        assert_eq!(result.nr_bot_b_wins(), 42);
+    }
+
+    #[test]
+    fn run_cancelled_roundsOngoing() {
+        let bot_a = make_suicidal_bot();
+        let bot_b = Bot::new(vec![]);
+        let cancellation = CancellationToken::new();
+        cancellation.cancel();
+        let result = Match::new(&bot_a, &bot_b).with_cancellation(cancellation).run().unwrap();
+        assert_eq!(result.rounds.len(), 42);
+        assert!(!result.is_complete());
+        assert_eq!(result.nr_bot_b_wins(), 0);
+    }
+
+    #[test]
+    fn run_notCancelled_complete() {
+        let bot_a = make_suicidal_bot();
+        let bot_b = Bot::new(vec![]);
+        let result = Match::new(&bot_a, &bot_b)
+            .with_cancellation(CancellationToken::new())
+            .run()
+            .unwrap();
+        assert!(result.is_complete());
+        assert_eq!(result.nr_bot_b_wins(), 42);
+    }
+
+    #[test]
+    fn run_noTimeLeft_drawsOutOfTime() {
+        let bot_a = make_suicidal_bot();
+        let bot_b = Bot::new(vec![]);
+        let result =
+            Match::new(&bot_a, &bot_b).with_time_budget(Duration::from_secs(0)).run().unwrap();
+        assert!(result.is_complete());
+        assert!(result.ran_out_of_time());
+        assert_eq!(result.nr_draws(), 42);
+    }
+
+    #[test]
+    fn run_enoughTime_playedNormally() {
+        let bot_a = make_suicidal_bot();
+        let bot_b = Bot::new(vec![]);
+        let result =
+            Match::new(&bot_a, &bot_b).with_time_budget(Duration::from_secs(60)).run().unwrap();
+        assert!(!result.ran_out_of_time());
+        assert_eq!(result.nr_bot_b_wins(), 42);
+    }
+
+    #[test]
+    fn runRounds_noRounds_returnsEmptyResult() {
+        let bot_a = make_suicidal_bot();
+        let bot_b = make_suicidal_bot();
+        let result = Match::new(&bot_a, &bot_b).run_rounds(vec![].into_iter()).unwrap();
+        assert_eq!(result, MatchResult::new(vec![]));
+    }
+
+    #[test]
+    fn run_tapeTooShort_invalidParamsAndNoRoundsPlayed() {
+        let bot = make_suicidal_bot();
+        let result = Match::new(&bot, &bot)
+            .with_tape_lengths(TapeLengths::List(vec![10, 1]))
+            .run();
+        match result {
+            Err(BfError::InvalidParams(_)) => {}
+            other => panic!("Expected invalid parameters, got {:?}", other),
+        }
+        assert!(Match::new(&bot, &bot).run().is_ok());
+    }
+}
@@ -0,0 +1,57 @@
+use match_runner::MatchResult;
+
+/// The ways in which the outcome of a match can be turned into points. Every round won by bot A
+/// counts as a win, every round won by bot B as a loss, and every other round as a draw. The
+/// schemes only differ in how they normalize the sum.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum ScoringScheme {
+    /// The scoring of egojoust: a win is worth one point, a loss minus one point and a draw
+    /// nothing. A complete match thus yields between -42 and 42 points. This is the default.
+    #[default]
+    Egojoust,
+    /// The scoring of chainbf: like egojoust, but divided by the number of rounds played, so that
+    /// the points always lie between -1 and 1, regardless of the number of configurations.
+    Chainbf,
+}
+
+impl ScoringScheme {
+    /// Returns the points that bot A earned in the given match.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bf_bot_core::match_runner::{MatchResult, RoundRecord, ScoringScheme};
+    /// use bf_bot_core::simul_round::{RoundParams, RoundResult};
+    /// let params = RoundParams::new(10, false, 1000);
+    /// let round = |result| RoundRecord { params: params.clone(), result };
+    /// let match_result = MatchResult::new(vec![round(RoundResult::start_bot_wins()),
+    ///                                          round(RoundResult::start_bot_wins()),
+    ///                                          round(RoundResult::end_bot_wins()),
+    ///                                          round(RoundResult::draw())]);
+    /// assert_eq!(ScoringScheme::Egojoust.points(&match_result), 1.0);
+    /// assert_eq!(ScoringScheme::Chainbf.points(&match_result), 0.25);
+    /// ```
+    pub fn points(&self, match_result: &MatchResult) -> f64 {
+        let score = f64::from(match_result.nr_bot_a_wins() as i32 -
+                              match_result.nr_bot_b_wins() as i32);
+        match *self {
+            ScoringScheme::Egojoust => score,
+            ScoringScheme::Chainbf if match_result.rounds.is_empty() => 0.0,
+            ScoringScheme::Chainbf => score / match_result.rounds.len() as f64,
+        }
+    }
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn points_noRounds_isZeroForEveryScheme() {
+        let match_result = MatchResult::new(vec![]);
+        assert_eq!(ScoringScheme::Egojoust.points(&match_result), 0.0);
+        assert_eq!(ScoringScheme::Chainbf.points(&match_result), 0.0);
+    }
+}
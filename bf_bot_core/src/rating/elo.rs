@@ -0,0 +1,106 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+
+use match_runner::MatchResult;
+use rating;
+
+/// The rating of a bot that has not played yet.
+const DEFAULT_INITIAL_RATING: f64 = 1500.0;
+/// The maximum change of a rating after a single match.
+const DEFAULT_K_FACTOR: f64 = 32.0;
+
+/// Elo ratings. After every match, both bots exchange rating points in proportion to how much
+/// better or worse they did than their ratings predicted.
+#[derive(Debug, Clone)]
+pub struct Elo<K>
+    where K: Eq + Hash + Clone
+{
+    k_factor: f64,
+    initial_rating: f64,
+    ratings: HashMap<K, f64>,
+}
+
+impl<K> Elo<K>
+    where K: Eq + Hash + Clone
+{
+    /// Constructs Elo ratings with the customary initial rating of 1500 and a K-factor of 32.
+    pub fn new() -> Elo<K> {
+        Elo {
+            k_factor: DEFAULT_K_FACTOR,
+            initial_rating: DEFAULT_INITIAL_RATING,
+            ratings: HashMap::new(),
+        }
+    }
+
+    /// Sets the maximum change of a rating after a single match.
+    pub fn with_k_factor(self, k_factor: f64) -> Elo<K> {
+        Elo { k_factor, ..self }
+    }
+
+    /// Returns the rating of the given bot, or the initial rating if it has not played yet.
+    pub fn rating(&self, bot: &K) -> f64 {
+        *self.ratings.get(bot).unwrap_or(&self.initial_rating)
+    }
+
+    /// Returns the ratings of all bots that have played.
+    pub fn get_ratings(&self) -> &HashMap<K, f64> {
+        &self.ratings
+    }
+
+    /// Returns the score that bot A is expected to achieve against bot B, between 0 and 1.
+    pub fn expected_score(&self, bot_a: &K, bot_b: &K) -> f64 {
+        1.0 / (1.0 + 10f64.powf((self.rating(bot_b) - self.rating(bot_a)) / 400.0))
+    }
+
+    /// Updates the ratings of both bots after a match in which bot A achieved the given score:
+    /// 1 for a win, 0 for a loss and 0.5 for a draw.
+    pub fn record(&mut self, bot_a: &K, bot_b: &K, score_a: f64) {
+        let change = self.k_factor * (score_a - self.expected_score(bot_a, bot_b));
+        let rating_a = self.rating(bot_a) + change;
+        let rating_b = self.rating(bot_b) - change;
+        self.ratings.insert(bot_a.clone(), rating_a);
+        self.ratings.insert(bot_b.clone(), rating_b);
+    }
+
+    /// Updates the ratings of both bots after the given match. See `rating::match_score`.
+    pub fn record_match(&mut self, bot_a: &K, bot_b: &K, match_result: &MatchResult) {
+        self.record(bot_a, bot_b, rating::match_score(match_result));
+    }
+}
+
+impl<K> Default for Elo<K>
+    where K: Eq + Hash + Clone
+{
+    fn default() -> Elo<K> {
+        Elo::new()
+    }
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn record_equalRatingsAndWin_exchangesHalfTheKFactor() {
+        let mut elo = Elo::new();
+        elo.record(&"a", &"b", 1.0);
+        assert_eq!(elo.rating(&"a"), 1516.0);
+        assert_eq!(elo.rating(&"b"), 1484.0);
+    }
+
+    #[test]
+    fn record_expectedDraw_changesNothing() {
+        let mut elo = Elo::new();
+        elo.record(&"a", &"b", 0.5);
+        assert_eq!(elo.rating(&"a"), 1500.0);
+    }
+
+    #[test]
+    fn expectedScore_higherRating_favoursThatBot() {
+        let mut elo = Elo::new().with_k_factor(400.0);
+        elo.record(&0, &1, 1.0);
+        assert!(elo.expected_score(&0, &1) > 0.9);
+        assert!((elo.expected_score(&0, &1) + elo.expected_score(&1, &0) - 1.0).abs() < 1e-12);
+    }
+}
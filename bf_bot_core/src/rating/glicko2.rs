@@ -0,0 +1,233 @@
+use std::collections::HashMap;
+use std::f64::consts::PI;
+use std::hash::Hash;
+
+use match_runner::MatchResult;
+use rating;
+
+/// Converts between the Glicko scale, on which ratings are shown, and the internal Glicko-2 scale.
+const SCALE: f64 = 173.7178;
+/// Limits how much the volatility may change in a single rating period. Glickman recommends
+/// values between 0.3 and 1.2.
+const DEFAULT_TAU: f64 = 0.5;
+/// The precision with which the new volatility is computed.
+const CONVERGENCE_TOLERANCE: f64 = 0.000_001;
+
+/// The Glicko-2 rating of a single bot, on the Glicko scale.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Glicko2Rating {
+    pub rating: f64,
+    /// How uncertain the rating is. About 95% of the time, the true strength of the bot lies
+    /// within two deviations of its rating.
+    pub deviation: f64,
+    /// How erratic the performance of the bot is.
+    pub volatility: f64,
+}
+
+impl Default for Glicko2Rating {
+    /// The rating of a bot that has not played yet.
+    fn default() -> Glicko2Rating {
+        Glicko2Rating {
+            rating: 1500.0,
+            deviation: 350.0,
+            volatility: 0.06,
+        }
+    }
+}
+
+/// Glicko-2 ratings, as described by Mark Glickman. Matches are collected into rating periods; at
+/// the end of each period, all ratings are updated at once. Bots that did not play in a period
+/// become less certain of their rating.
+#[derive(Debug, Clone)]
+pub struct Glicko2<K>
+    where K: Eq + Hash + Clone
+{
+    tau: f64,
+    ratings: HashMap<K, Glicko2Rating>,
+    /// The matches of the current rating period: both bots and the score of the first.
+    pending: Vec<(K, K, f64)>,
+}
+
+impl<K> Glicko2<K>
+    where K: Eq + Hash + Clone
+{
+    pub fn new() -> Glicko2<K> {
+        Glicko2 {
+            tau: DEFAULT_TAU,
+            ratings: HashMap::new(),
+            pending: vec![],
+        }
+    }
+
+    /// Sets the system constant that limits how much volatilities change.
+    pub fn with_tau(self, tau: f64) -> Glicko2<K> {
+        Glicko2 { tau, ..self }
+    }
+
+    /// Adds a bot with the given rating, such as one carried over from an earlier run.
+    pub fn insert(&mut self, bot: K, rating: Glicko2Rating) {
+        self.ratings.insert(bot, rating);
+    }
+
+    /// Returns the rating of the given bot, or the initial rating if it has not played yet.
+    pub fn rating(&self, bot: &K) -> Glicko2Rating {
+        self.ratings.get(bot).cloned().unwrap_or_default()
+    }
+
+    /// Returns the ratings of all bots that have played.
+    pub fn get_ratings(&self) -> &HashMap<K, Glicko2Rating> {
+        &self.ratings
+    }
+
+    /// Adds a match in which bot A achieved the given score to the current rating period: 1 for a
+    /// win, 0 for a loss and 0.5 for a draw. The ratings change only when the period ends.
+    pub fn record(&mut self, bot_a: &K, bot_b: &K, score_a: f64) {
+        self.pending.push((bot_a.clone(), bot_b.clone(), score_a));
+    }
+
+    /// Adds the given match to the current rating period. See `rating::match_score`.
+    pub fn record_match(&mut self, bot_a: &K, bot_b: &K, match_result: &MatchResult) {
+        self.record(bot_a, bot_b, rating::match_score(match_result));
+    }
+
+    /// Ends the current rating period and updates the ratings of all bots.
+    pub fn end_period(&mut self) {
+        for (bot_a, bot_b, _) in &self.pending {
+            self.ratings.entry(bot_a.clone()).or_default();
+            self.ratings.entry(bot_b.clone()).or_default();
+        }
+        let mut games: HashMap<K, Vec<(Glicko2Rating, f64)>> = HashMap::new();
+        for &(ref bot_a, ref bot_b, score_a) in &self.pending {
+            games.entry(bot_a.clone()).or_default().push((self.ratings[bot_b], score_a));
+            games.entry(bot_b.clone()).or_default().push((self.ratings[bot_a], 1.0 - score_a));
+        }
+        let tau = self.tau;
+        for (bot, rating) in &mut self.ratings {
+            *rating = match games.get(bot) {
+                Some(games) => update(rating, games, tau),
+                None => {
+                    let deviation = rating.deviation / SCALE;
+                    Glicko2Rating {
+                        deviation: (deviation.powi(2) + rating.volatility.powi(2)).sqrt() * SCALE,
+                        ..*rating
+                    }
+                }
+            };
+        }
+        self.pending.clear();
+    }
+}
+
+impl<K> Default for Glicko2<K>
+    where K: Eq + Hash + Clone
+{
+    fn default() -> Glicko2<K> {
+        Glicko2::new()
+    }
+}
+
+fn g(deviation: f64) -> f64 {
+    1.0 / (1.0 + 3.0 * deviation.powi(2) / PI.powi(2)).sqrt()
+}
+
+/// Computes the new rating of a bot that played the given games, each consisting of the rating of
+/// the opponent at the start of the period and the score against it.
+fn update(rating: &Glicko2Rating, games: &[(Glicko2Rating, f64)], tau: f64) -> Glicko2Rating {
+    let mu = (rating.rating - 1500.0) / SCALE;
+    let phi = rating.deviation / SCALE;
+    let mut inverse_variance = 0.0;
+    let mut improvement_sum = 0.0;
+    for &(ref opponent, score) in games {
+        let opponent_mu = (opponent.rating - 1500.0) / SCALE;
+        let opponent_g = g(opponent.deviation / SCALE);
+        let expected = 1.0 / (1.0 + (-opponent_g * (mu - opponent_mu)).exp());
+        inverse_variance += opponent_g.powi(2) * expected * (1.0 - expected);
+        improvement_sum += opponent_g * (score - expected);
+    }
+    let variance = 1.0 / inverse_variance;
+    let delta = variance * improvement_sum;
+    let volatility = new_volatility(rating.volatility, phi, variance, delta, tau);
+    let pre_period_phi = (phi.powi(2) + volatility.powi(2)).sqrt();
+    let new_phi = 1.0 / (1.0 / pre_period_phi.powi(2) + 1.0 / variance).sqrt();
+    Glicko2Rating {
+        rating: (mu + new_phi.powi(2) * improvement_sum) * SCALE + 1500.0,
+        deviation: new_phi * SCALE,
+        volatility,
+    }
+}
+
+/// Finds the new volatility with the Illinois algorithm, as in step 5 of Glickman's description.
+fn new_volatility(volatility: f64, phi: f64, variance: f64, delta: f64, tau: f64) -> f64 {
+    let a = volatility.powi(2).ln();
+    let f = |x: f64| {
+        let ex = x.exp();
+        ex * (delta.powi(2) - phi.powi(2) - variance - ex) /
+        (2.0 * (phi.powi(2) + variance + ex).powi(2)) - (x - a) / tau.powi(2)
+    };
+    let mut lower = a;
+    let mut upper = if delta.powi(2) > phi.powi(2) + variance {
+        (delta.powi(2) - phi.powi(2) - variance).ln()
+    } else {
+        let mut k = 1.0;
+        while f(a - k * tau) < 0.0 {
+            k += 1.0;
+        }
+        a - k * tau
+    };
+    let mut f_lower = f(lower);
+    let mut f_upper = f(upper);
+    while (upper - lower).abs() > CONVERGENCE_TOLERANCE {
+        let new = lower + (lower - upper) * f_lower / (f_upper - f_lower);
+        let f_new = f(new);
+        if f_new * f_upper <= 0.0 {
+            lower = upper;
+            f_lower = f_upper;
+        } else {
+            f_lower /= 2.0;
+        }
+        upper = new;
+        f_upper = f_new;
+    }
+    (lower / 2.0).exp()
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod tests {
+    use super::*;
+
+    fn make_rating(rating: f64, deviation: f64) -> Glicko2Rating {
+        Glicko2Rating {
+            rating,
+            deviation,
+            volatility: 0.06,
+        }
+    }
+
+    /// The worked example from Glickman's description of the Glicko-2 system.
+    #[test]
+    fn endPeriod_glickmansExample_matchesPublishedResult() {
+        let mut glicko = Glicko2::new();
+        glicko.insert("player", make_rating(1500.0, 200.0));
+        glicko.insert("a", make_rating(1400.0, 30.0));
+        glicko.insert("b", make_rating(1550.0, 100.0));
+        glicko.insert("c", make_rating(1700.0, 300.0));
+        glicko.record(&"player", &"a", 1.0);
+        glicko.record(&"b", &"player", 1.0);
+        glicko.record(&"player", &"c", 0.0);
+        glicko.end_period();
+        let rating = glicko.rating(&"player");
+        assert!((rating.rating - 1464.06).abs() < 0.01);
+        assert!((rating.deviation - 151.52).abs() < 0.01);
+        assert!((rating.volatility - 0.05999).abs() < 0.00001);
+    }
+
+    #[test]
+    fn endPeriod_botDidNotPlay_deviationGrows() {
+        let mut glicko = Glicko2::new();
+        glicko.insert("idle", make_rating(1500.0, 50.0));
+        glicko.end_period();
+        assert!(glicko.rating(&"idle").deviation > 50.0);
+        assert_eq!(glicko.rating(&"idle").rating, 1500.0);
+    }
+}
@@ -0,0 +1,32 @@
+//! Ratings that summarize how strong bots are, based on the results of many matches. Unlike the
+//! points of a single tournament, ratings can be updated one match at a time, which makes it
+//! possible to follow the strength of bots over a long evolution run.
+//!
+//! Bots are identified by keys of any type, such as their names or their index in a population.
+
+pub use self::elo::Elo;
+mod elo;
+
+pub use self::glicko2::{Glicko2, Glicko2Rating};
+mod glicko2;
+
+use match_runner::{MatchResult, ScoringScheme};
+
+/// Returns the score of bot A in the given match, as used by rating systems: 1 if it won every
+/// round, 0 if it lost every round and 0.5 if both bots won equally many rounds.
+///
+/// # Examples
+///
+/// ```
+/// use bf_bot_core::match_runner::{MatchResult, RoundRecord};
+/// use bf_bot_core::rating;
+/// use bf_bot_core::simul_round::{RoundParams, RoundResult};
+/// let params = RoundParams::new(10, false, 1000);
+/// let round = |result| RoundRecord { params: params.clone(), result };
+/// let match_result = MatchResult::new(vec![round(RoundResult::start_bot_wins()),
+///                                          round(RoundResult::draw())]);
+/// assert_eq!(rating::match_score(&match_result), 0.75);
+/// ```
+pub fn match_score(match_result: &MatchResult) -> f64 {
+    (ScoringScheme::Chainbf.points(match_result) + 1.0) / 2.0
+}
@@ -0,0 +1,9 @@
+//! Simple, well-known warriors, to start a hill with and to test against. Each strategy is built
+//! from instructions rather than parsed, so that this crate doesn't need the compiler.
+
+pub use self::warriors::{careless_clear, decoy_rush, fast_rush, starter_hill, sit_and_wait,
+                         suicide};
+mod warriors;
+
+pub(crate) use self::program_builder::ProgramBuilder;
+mod program_builder;
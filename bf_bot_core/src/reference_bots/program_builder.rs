@@ -0,0 +1,74 @@
+use alloc::vec::Vec;
+
+use bf::{Bot, Instruction};
+
+/// Puts a program together instruction by instruction, filling in the targets of the loops. Besides
+/// the reference bots, it builds the probes of the equivalence check and the benchmark pairings.
+#[derive(Debug, Default)]
+pub(crate) struct ProgramBuilder {
+    program: Vec<Instruction>,
+    /// The indices of the loops that have been opened but not closed yet.
+    open_loops: Vec<usize>,
+}
+
+impl ProgramBuilder {
+    /// Appends the given instruction the given number of times.
+    pub fn repeat(mut self, instruction: Instruction, times: usize) -> ProgramBuilder {
+        self.program.extend((0..times).map(|_| instruction));
+        self
+    }
+
+    /// Appends the start of a loop, whose end is appended by `close_loop`.
+    pub fn open_loop(mut self) -> ProgramBuilder {
+        self.open_loops.push(self.program.len());
+        // The target is filled in when the loop is closed.
+        self.program.push(Instruction::JumpIfZero(0));
+        self
+    }
+
+    /// Appends the end of the innermost loop that is still open.
+    pub fn close_loop(mut self) -> ProgramBuilder {
+        let start = self.open_loops.pop().expect("A loop is open.");
+        self.program[start] = Instruction::JumpIfZero(self.program.len());
+        self.program.push(Instruction::JumpIfNonZero(start));
+        self
+    }
+
+    /// Appends the given part the given number of times.
+    pub fn repeat_part<F>(self, times: usize, part: F) -> ProgramBuilder
+        where F: Fn(ProgramBuilder) -> ProgramBuilder
+    {
+        (0..times).fold(self, |builder, _| part(builder))
+    }
+
+    pub fn build(self) -> Bot {
+        assert!(self.open_loops.is_empty(), "Every loop is closed.");
+        Bot::new(self.program)
+    }
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn closeLoop_nestedLoops_matchesBrackets() {
+        // `[+[-]]`
+        let bot = ProgramBuilder::default()
+            .open_loop()
+            .repeat(Instruction::Increment, 1)
+            .open_loop()
+            .repeat(Instruction::Decrement, 1)
+            .close_loop()
+            .close_loop()
+            .build();
+        assert_eq!(*bot.get_program(),
+                   vec![Instruction::JumpIfZero(5),
+                        Instruction::Increment,
+                        Instruction::JumpIfZero(4),
+                        Instruction::Decrement,
+                        Instruction::JumpIfNonZero(2),
+                        Instruction::JumpIfNonZero(0)]);
+    }
+}
@@ -0,0 +1,129 @@
+use alloc::vec::Vec;
+
+use bf::{Bot, Instruction, NamedBot};
+use reference_bots::ProgramBuilder;
+
+/// The distance from the flag of a bot to the nearest cell that can hold the enemy flag, on the
+/// shortest tape.
+const MIN_FLAG_DISTANCE: usize = 9;
+/// The number of cells that may hold the enemy flag, from the nearest to the furthest.
+const NR_FLAG_CELLS: usize = 21;
+
+/// `<`: walks off the tape straight away, and so loses every round against any bot that doesn't
+/// do the same.
+pub fn suicide() -> Bot {
+    ProgramBuilder::default().repeat(Instruction::MoveBack, 1).build()
+}
+
+/// The empty program: does nothing at all, and waits for the opponent to make a mistake. It wins
+/// against bots that leave the tape, and draws against bots that never reach its flag.
+pub fn sit_and_wait() -> Bot {
+    Bot::new(Vec::new())
+}
+
+/// `>>>>>>>>>([-]>)*21`: skips the cells that can't hold the enemy flag, then clears every cell
+/// it finds, checking whether each cell is zero before it moves on.
+pub fn fast_rush() -> Bot {
+    ProgramBuilder::default()
+        .repeat(Instruction::MoveForward, MIN_FLAG_DISTANCE)
+        .repeat_part(NR_FLAG_CELLS, |builder| {
+            builder.open_loop()
+                .repeat(Instruction::Decrement, 1)
+                .close_loop()
+                .repeat(Instruction::MoveForward, 1)
+        })
+        .build()
+}
+
+/// `>>>>>>>>>((-)*128.>)*21`: like the fast rush, but it decrements every cell 128 times without
+/// looking, which zeroes an untouched flag in either polarity, and then waits a cycle so that the
+/// flag stays zeroed long enough. It is fooled by any decoy, and wastes time on empty cells.
+pub fn careless_clear() -> Bot {
+    ProgramBuilder::default()
+        .repeat(Instruction::MoveForward, MIN_FLAG_DISTANCE)
+        .repeat_part(NR_FLAG_CELLS, |builder| {
+            builder.repeat(Instruction::Decrement, 128)
+                .repeat(Instruction::SkipExecution, 1)
+                .repeat(Instruction::MoveForward, 1)
+        })
+        .build()
+}
+
+/// `>(+)*10>(-)*10(>)*7([-]>)*21`: the standard decoy setup. It puts two decoys of opposite
+/// signs in front of its flag, so that a clearing opponent in either polarity is held up by one of
+/// them, and then rushes like the fast rush.
+pub fn decoy_rush() -> Bot {
+    ProgramBuilder::default()
+        .repeat(Instruction::MoveForward, 1)
+        .repeat(Instruction::Increment, 10)
+        .repeat(Instruction::MoveForward, 1)
+        .repeat(Instruction::Decrement, 10)
+        .repeat(Instruction::MoveForward, MIN_FLAG_DISTANCE - 2)
+        .repeat_part(NR_FLAG_CELLS, |builder| {
+            builder.open_loop()
+                .repeat(Instruction::Decrement, 1)
+                .close_loop()
+                .repeat(Instruction::MoveForward, 1)
+        })
+        .build()
+}
+
+/// Returns every reference bot, named, to start a hill with.
+///
+/// # Examples
+///
+/// ```
+/// use bf_bot_core::reference_bots;
+/// let hill = reference_bots::starter_hill();
+/// assert!(hill.iter().any(|warrior| warrior.name == "fast rush"));
+/// ```
+pub fn starter_hill() -> Vec<NamedBot> {
+    vec![NamedBot::new("suicide", suicide()),
+         NamedBot::new("sit and wait", sit_and_wait()),
+         NamedBot::new("fast rush", fast_rush()),
+         NamedBot::new("careless clear", careless_clear()),
+         NamedBot::new("decoy rush", decoy_rush())]
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod tests {
+    use super::*;
+    use match_runner::Match;
+
+    fn score(bot_a: &Bot, bot_b: &Bot) -> i32 {
+        Match::new(bot_a, bot_b).run().unwrap().score()
+    }
+
+    #[test]
+    fn toSource_referenceBots_asDocumented() {
+        assert_eq!(suicide().to_source(), "<");
+        assert_eq!(fast_rush().to_source(),
+                   format!("{}{}", ">".repeat(9), "[-]>".repeat(21)));
+        assert_eq!(decoy_rush().to_source(),
+                   format!(">{}>{}{}{}",
+                           "+".repeat(10),
+                           "-".repeat(10),
+                           ">".repeat(7),
+                           "[-]>".repeat(21)));
+    }
+
+    #[test]
+    fn match_suicide_losesEveryRound() {
+        for warrior in starter_hill().iter().filter(|warrior| warrior.name != "suicide") {
+            assert_eq!(score(&suicide(), &warrior.bot), -42, "against {}", warrior.name);
+        }
+    }
+
+    #[test]
+    fn match_clearersAgainstSitAndWait_winEveryRound() {
+        assert_eq!(score(&fast_rush(), &sit_and_wait()), 42);
+        assert_eq!(score(&careless_clear(), &sit_and_wait()), 42);
+        assert_eq!(score(&decoy_rush(), &sit_and_wait()), 42);
+    }
+
+    #[test]
+    fn match_decoyRushAgainstFastRush_decoysWin() {
+        assert!(score(&decoy_rush(), &fast_rush()) > 0);
+    }
+}
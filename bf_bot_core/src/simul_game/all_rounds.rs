@@ -1,59 +1,76 @@
 //! Iterator that returns `RoundParams`.
-//! This returns `RoundParams` for every round in a complete game; meaning 42 rounds, half with
-//! reversed polarity and covering tape lengths from 10 through 30.
+//! By default, this returns `RoundParams` for every round in a complete game; meaning 42 rounds,
+//! half with reversed polarity and covering tape lengths from 10 through 30.
 
+use simul_game::TapeLengths;
 use simul_round::RoundParams;
-
-/// A complete game consists of rounds with tape lengths from `MIN_TAPE_LENGTH` to
-/// `MAX_TAPE_LENGTH`. Both bounds are inclusive.
-const MIN_TAPE_LENGTH: u32 = 10;
-/// A complete game consists of rounds with tape lengths from `MIN_TAPE_LENGTH` to
-/// `MAX_TAPE_LENGTH`. Both bounds are inclusive.
-const MAX_TAPE_LENGTH: u32 = 30;
 /// Max steps in a round for a complete game.
 /// If an incomplete game is run for performance reasons, the max steps may be smaller than this
 /// value to save CPU time. However, if a smaller value than this is used, note that the result of
 /// the game may differ from reality.
 const COMPLETE_GAME_MAX_STEPS: u32 = 100_000;
 
-/// An iterator that returns all rounds in a complete game, covering all possible tape lengths and
-/// both polarities.
+/// An iterator that returns all rounds in a game, covering all of its tape lengths and both
+/// polarities. Unless other tape lengths are given, that is a complete game.
 pub struct AllRounds {
-    tape_length: u32,
+    tape_lengths: Vec<u32>,
+    /// The index of the tape length of the next round.
+    tape_length_index: usize,
     invert_polarity: bool,
+    early_termination: bool,
 }
 
 impl AllRounds {
     pub fn new() -> AllRounds {
         AllRounds {
-            tape_length: MIN_TAPE_LENGTH,
+            tape_lengths: TapeLengths::default().lengths(),
+            tape_length_index: 0,
             invert_polarity: false,
+            early_termination: false,
         }
     }
 
+    /// Plays the given tape lengths instead of those of a complete game.
+    pub fn with_tape_lengths(mut self, tape_lengths: &TapeLengths) -> AllRounds {
+        self.tape_lengths = tape_lengths.lengths();
+        self.tape_length_index = 0;
+        self
+    }
+
+    /// Enables early termination for every round. See `RoundParams::early_termination`.
+    pub fn with_early_termination(mut self) -> AllRounds {
+        self.early_termination = true;
+        self
+    }
+
     fn current_item(&self) -> RoundParams {
         RoundParams {
-            tape_length: self.tape_length,
-            invert_polarity: self.invert_polarity,
-            max_steps: COMPLETE_GAME_MAX_STEPS,
+            early_termination: self.early_termination,
+            ..RoundParams::new(self.tape_lengths[self.tape_length_index],
+                               self.invert_polarity,
+                               COMPLETE_GAME_MAX_STEPS)
         }
     }
 
     fn update_state(&mut self) {
-        self.tape_length = if self.invert_polarity {
-            self.tape_length + 1
-        } else {
-            self.tape_length
-        };
+        if self.invert_polarity {
+            self.tape_length_index += 1;
+        }
         self.invert_polarity = !self.invert_polarity;
     }
 }
 
+impl Default for AllRounds {
+    fn default() -> AllRounds {
+        AllRounds::new()
+    }
+}
+
 impl Iterator for AllRounds {
     type Item = RoundParams;
 
     fn next(&mut self) -> Option<RoundParams> {
-        if self.tape_length <= MAX_TAPE_LENGTH {
+        if self.tape_length_index < self.tape_lengths.len() {
             let params = self.current_item();
             self.update_state();
             Some(params)
@@ -90,4 +107,23 @@ mod tests {
         assert!(AllRounds::new().all(|round| round.tape_length <= 30));
     }
 
+    #[test]
+    fn allRounds_earlyTerminationDisabledByDefault() {
+        assert!(AllRounds::new().all(|round| !round.early_termination));
+    }
+
+    #[test]
+    fn withEarlyTermination_enablesItForAllRounds() {
+        assert!(AllRounds::new().with_early_termination().all(|round| round.early_termination));
+    }
+
+    #[test]
+    fn withTapeLengths_playsBothPolaritiesOfEachLengthInOrder() {
+        let rounds = AllRounds::new()
+            .with_tape_lengths(&TapeLengths::List(vec![15, 12]))
+            .map(|round| (round.tape_length, round.invert_polarity))
+            .collect::<Vec<_>>();
+        assert_eq!(rounds, vec![(15, false), (15, true), (12, false), (12, true)]);
+    }
+
 }
@@ -1,20 +1,30 @@
 use bf::Bot;
-use simul_round::{self, RoundParams};
+use engine::Arena;
+use error::BfError;
+use simul_round::RoundParams;
 use simul_game::game_result::GameResult;
 use simul_game::all_rounds::AllRounds;
 
 /// Compares two bots in a (complete) game and returns the result.
-pub fn run_complete(bot_a: &Bot, bot_b: &Bot) -> GameResult {
+pub fn run_complete(bot_a: &Bot, bot_b: &Bot) -> Result<GameResult, BfError> {
     run(bot_a, bot_b, AllRounds::new())
 }
 
 /// Compares two bots in a game consisting of the provided rounds. Returns the result of the game.
-pub fn run<I>(bot_a: &Bot, bot_b: &Bot, rounds: I) -> GameResult
+/// All rounds are played in the same arena, which is reset between rounds. Fails at the first
+/// round whose parameters are not valid.
+pub fn run<I>(bot_a: &Bot, bot_b: &Bot, rounds: I) -> Result<GameResult, BfError>
     where I: Iterator<Item = RoundParams>
 {
-    rounds.fold(GameResult::new(), |mut game_result, round_params| {
-        let round_result = simul_round::play(bot_a, bot_b, &round_params);
+    let mut arena: Option<Arena> = None;
+    let mut game_result = GameResult::new();
+    for round_params in rounds {
+        match arena {
+            Some(ref mut arena) => arena.reset(bot_a, bot_b, &round_params)?,
+            None => arena = Some(Arena::new(bot_a, bot_b, &round_params)?),
+        }
+        let round_result = arena.as_mut().expect("The arena was just set.").finish().result;
         game_result.add_result_to_total(&round_result);
-        game_result
-    })
+    }
+    Ok(game_result)
 }
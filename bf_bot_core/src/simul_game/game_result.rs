@@ -17,8 +17,8 @@ impl GameResult {
 
     pub fn add_result_to_total(&mut self, round_result: &RoundResult) {
         if round_result.has_winner() {
-            self.bot_a_points += if round_result.bot_a_lost { -1 } else { 1 };
-            self.bot_b_points += if round_result.bot_b_lost { -1 } else { 1 };
+            self.bot_a_points += if round_result.bot_a_lost() { -1 } else { 1 };
+            self.bot_b_points += if round_result.bot_b_lost() { -1 } else { 1 };
         }
     }
 }
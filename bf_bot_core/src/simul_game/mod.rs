@@ -17,4 +17,8 @@ mod game;
 
 pub mod game_result;
 
+pub use self::all_rounds::AllRounds;
 mod all_rounds;
+
+pub use self::tape_lengths::{TapeLengths, MAX_TAPE_LENGTH, MIN_TAPE_LENGTH};
+mod tape_lengths;
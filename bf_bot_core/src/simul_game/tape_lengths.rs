@@ -0,0 +1,63 @@
+/// The shortest tape length used in a complete game.
+pub const MIN_TAPE_LENGTH: u32 = 10;
+/// The longest tape length used in a complete game.
+pub const MAX_TAPE_LENGTH: u32 = 30;
+
+/// The tape lengths that a game is played on. Each tape length is played with both polarities.
+/// The default is the range from `MIN_TAPE_LENGTH` through `MAX_TAPE_LENGTH`, as in a complete
+/// game.
+///
+/// Tape lengths shorter than 2 leave no room for the two flags and are not supported.
+#[derive(Debug, Clone, PartialEq)]
+pub enum TapeLengths {
+    Single(u32),
+    /// All tape lengths from `min` through `max`. Both bounds are inclusive.
+    Range { min: u32, max: u32 },
+    /// The given tape lengths, in the given order.
+    List(Vec<u32>),
+}
+
+impl TapeLengths {
+    /// Returns every tape length, in the order in which they are played.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bf_bot_core::simul_game::TapeLengths;
+    /// assert_eq!(TapeLengths::Single(12).lengths(), vec![12]);
+    /// assert_eq!(TapeLengths::Range { min: 10, max: 12 }.lengths(), vec![10, 11, 12]);
+    /// assert_eq!(TapeLengths::List(vec![30, 10]).lengths(), vec![30, 10]);
+    /// ```
+    pub fn lengths(&self) -> Vec<u32> {
+        match *self {
+            TapeLengths::Single(length) => vec![length],
+            TapeLengths::Range { min, max } => (min..=max).collect(),
+            TapeLengths::List(ref lengths) => lengths.clone(),
+        }
+    }
+}
+
+impl Default for TapeLengths {
+    fn default() -> TapeLengths {
+        TapeLengths::Range {
+            min: MIN_TAPE_LENGTH,
+            max: MAX_TAPE_LENGTH,
+        }
+    }
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lengths_default_coversCompleteGame() {
+        assert_eq!(TapeLengths::default().lengths(), (10..=30).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn lengths_emptyRange_isEmpty() {
+        assert!(TapeLengths::Range { min: 20, max: 10 }.lengths().is_empty());
+    }
+}
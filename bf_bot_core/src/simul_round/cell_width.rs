@@ -0,0 +1,42 @@
+/// The type of the cells on the tape. Every width wraps around on overflow, so only the number of
+/// distinct values matters for the game, and whether zero lies at the bottom of the range or in
+/// the middle. Standard BF Joust uses 8-bit cells.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum CellWidth {
+    U8,
+    #[default]
+    I8,
+    I16,
+    I32,
+}
+
+impl CellWidth {
+    /// Adds the given amount to the value of a cell, wrapping around as a cell of this width does.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bf_bot_core::simul_round::CellWidth;
+    /// assert_eq!(CellWidth::U8.add(0, -1), 255);
+    /// assert_eq!(CellWidth::I8.add(127, 1), -128);
+    /// assert_eq!(CellWidth::I16.add(127, 1), 128);
+    /// assert_eq!(CellWidth::I32.add(i32::MIN, -1), i32::MAX);
+    /// ```
+    pub fn add(self, value: i32, addend: i32) -> i32 {
+        match self {
+            CellWidth::U8 => i32::from((value as u8).wrapping_add(addend as u8)),
+            CellWidth::I8 => i32::from((value as i8).wrapping_add(addend as i8)),
+            CellWidth::I16 => i32::from((value as i16).wrapping_add(addend as i16)),
+            CellWidth::I32 => value.wrapping_add(addend),
+        }
+    }
+
+    /// Returns the value that the flags start with: 128, which for `i8` cells is -128.
+    pub fn initial_flag_value(self) -> i32 {
+        match self {
+            CellWidth::I8 => i32::from(i8::MIN),
+            _ => 128,
+        }
+    }
+}
@@ -0,0 +1,74 @@
+use simul_round::split_mix;
+
+/// What happens when both bots change the same cell in the same cycle. Both bots decide what to
+/// do based on the tape as it was before the cycle, so neither bot should get to act first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum CollisionRule {
+    /// Both changes are applied, so the cell changes by their sum. A `+` and a `-` cancel each
+    /// other out. This is the standard rule.
+    #[default]
+    Sum,
+    /// Only the change of the start bot is applied.
+    StartBotFirst,
+    /// Only the change of the end bot is applied.
+    EndBotFirst,
+    /// Only one of both changes is applied, chosen at random in every cycle. The choices are
+    /// determined by the seed, so that a round can be replayed exactly.
+    Random { seed: u64 },
+}
+
+impl CollisionRule {
+    /// Returns whether the change of the start bot and the change of the end bot are applied, in
+    /// that order, when both change the same cell in the given cycle.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bf_bot_core::simul_round::CollisionRule;
+    /// assert_eq!(CollisionRule::Sum.applied_changes(1), (true, true));
+    /// assert_eq!(CollisionRule::StartBotFirst.applied_changes(1), (true, false));
+    /// let (start, end) = CollisionRule::Random { seed: 7 }.applied_changes(1);
+    /// assert!(start != end);
+    /// ```
+    pub fn applied_changes(self, step_nr: u32) -> (bool, bool) {
+        match self {
+            CollisionRule::Sum => (true, true),
+            CollisionRule::StartBotFirst => (true, false),
+            CollisionRule::EndBotFirst => (false, true),
+            CollisionRule::Random { seed } => {
+                let start_bot_first = split_mix::mix(seed ^ u64::from(step_nr)) & 1 == 0;
+                (start_bot_first, !start_bot_first)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_isSum() {
+        assert_eq!(CollisionRule::default(), CollisionRule::Sum);
+    }
+
+    #[test]
+    fn appliedChanges_random_favoursBothBotsAboutEqually() {
+        let rule = CollisionRule::Random { seed: 42 };
+        let nr_start_bot_first =
+            (0..1000).filter(|&step_nr| rule.applied_changes(step_nr).0).count();
+        assert!((400..=600).contains(&nr_start_bot_first));
+    }
+
+    #[test]
+    fn appliedChanges_random_sameSeedSameChoices() {
+        let choices = |seed| {
+            (0..100).map(|step_nr| CollisionRule::Random { seed }.applied_changes(step_nr))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(choices(3), choices(3));
+        assert_ne!(choices(3), choices(4));
+    }
+}
@@ -0,0 +1,14 @@
+/// How the arena executes the programs of the bots. Every engine yields exactly the same results,
+/// they only differ in speed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum ExecutionEngine {
+    /// Interprets the bytecode that every bot is compiled into when it is constructed.
+    #[default]
+    Bytecode,
+    /// Compiles the bytecode of both bots into a chain of closures at the start of every round,
+    /// with the orientation and polarity of the bot baked in. Only available with the
+    /// `closure-engine` feature.
+    #[cfg(feature = "closure-engine")]
+    Closures,
+}
@@ -0,0 +1,50 @@
+use simul_round::CellWidth;
+
+/// The values that the flags start with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum FlagInit {
+    /// The standard rules: both flags start at 128, which is -128 for `i8` cells.
+    #[default]
+    Standard,
+    /// Both flags start at the given value, as seen by their own bot. The flag of a bot with
+    /// reversed polarity thus starts at the negated value on the tape, so that both bots see the
+    /// enemy flag at the same value if exactly one of them has reversed polarity.
+    Custom(i32),
+    /// The flags start at the given values on the tape, without regard to polarity.
+    Asymmetric { start_flag: i32, end_flag: i32 },
+}
+
+impl FlagInit {
+    /// Returns the initial values of the start flag and the end flag on the tape, given whether
+    /// the start bot and the end bot play with reversed polarity. Values that don't fit in a cell
+    /// wrap around.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bf_bot_core::simul_round::{CellWidth, FlagInit};
+    /// assert_eq!(FlagInit::Standard.flag_values(CellWidth::I8, (false, true)), (-128, -128));
+    /// assert_eq!(FlagInit::Custom(100).flag_values(CellWidth::I8, (false, false)), (100, 100));
+    /// assert_eq!(FlagInit::Custom(100).flag_values(CellWidth::I8, (false, true)), (100, -100));
+    /// assert_eq!(FlagInit::Custom(100).flag_values(CellWidth::I8, (true, false)), (-100, 100));
+    /// assert_eq!(FlagInit::Custom(200).flag_values(CellWidth::I8, (false, false)), (-56, -56));
+    /// ```
+    pub fn flag_values(&self, cell_width: CellWidth, reversed_bots: (bool, bool)) -> (i32, i32) {
+        let wrap = |value: i32| cell_width.add(0, value);
+        let as_seen_by = |value: i32, reversed: bool| if reversed {
+            wrap(value.wrapping_neg())
+        } else {
+            wrap(value)
+        };
+        match *self {
+            FlagInit::Standard => {
+                (cell_width.initial_flag_value(), cell_width.initial_flag_value())
+            }
+            FlagInit::Custom(value) => {
+                (as_seen_by(value, reversed_bots.0), as_seen_by(value, reversed_bots.1))
+            }
+            FlagInit::Asymmetric { start_flag, end_flag } => (wrap(start_flag), wrap(end_flag)),
+        }
+    }
+}
@@ -1,8 +1,37 @@
-pub use self::round::play;
+pub use self::round::{play, play_recorded, play_with_heatmap};
 mod round;
 
-pub use self::round_params::RoundParams;
+pub use self::round_params::{InstructionBudgets, RoundParams, StartDelays};
 mod round_params;
 
-pub use self::round_result::RoundResult;
+pub use self::round_result::{LossReason, RoundResult, RoundStatus};
 mod round_result;
+
+pub use self::round_outcome::RoundOutcome;
+mod round_outcome;
+
+pub use self::step_outcome::StepOutcome;
+mod step_outcome;
+
+pub use self::collision_rule::CollisionRule;
+mod collision_rule;
+
+pub use self::cell_width::CellWidth;
+mod cell_width;
+
+pub use self::flag_init::FlagInit;
+mod flag_init;
+
+pub use self::execution_engine::ExecutionEngine;
+mod execution_engine;
+
+pub use self::polarity_mode::PolarityMode;
+mod polarity_mode;
+
+pub use self::rules::Rules;
+mod rules;
+
+pub use self::noise::Noise;
+mod noise;
+
+pub(crate) mod split_mix;
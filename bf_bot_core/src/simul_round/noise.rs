@@ -0,0 +1,91 @@
+use simul_round::split_mix;
+
+/// Random changes to the tape, independent of the bots. In every cycle, with the given
+/// probability, a random cell changes by 1 up or down after the bots have made their changes. The
+/// changes are determined by the seed, so that a round can be replayed exactly.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Noise {
+    /// The probability that a cell changes during a cycle, in parts per million.
+    pub per_million: u32,
+    pub seed: u64,
+}
+
+impl Noise {
+    /// Returns the index of the cell that changes during the given cycle and the amount that is
+    /// added to it, if any cell changes at all.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bf_bot_core::simul_round::Noise;
+    /// let always = Noise { per_million: 1_000_000, seed: 3 };
+    /// let (index, addend) = always.perturbation(1, 10).unwrap();
+    /// assert!(index < 10 && (addend == 1 || addend == -1));
+    /// assert_eq!(Noise { per_million: 0, seed: 3 }.perturbation(1, 10), None);
+    /// ```
+    pub fn perturbation(self, step_nr: u32, tape_length: usize) -> Option<(usize, i32)> {
+        let random = split_mix::mix(self.seed ^ u64::from(step_nr));
+        if random % 1_000_000 >= u64::from(self.per_million) {
+            return None;
+        }
+        let index = (random >> 20) % tape_length as u64;
+        let addend = if (random >> 60) & 1 == 0 { 1 } else { -1 };
+        Some((index as usize, addend))
+    }
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod tests {
+    use super::*;
+    use bf::{Bot, Instruction};
+    use engine::{Arena, BatchArena};
+    use simul_round::{RoundParams, RoundStatus};
+
+    #[test]
+    fn perturbation_tenPercent_aboutOneInTenCycles() {
+        let noise = Noise {
+            per_million: 100_000,
+            seed: 42,
+        };
+        let nr_perturbed =
+            (0..10_000).filter_map(|step_nr| noise.perturbation(step_nr, 30)).count();
+        assert!((800..=1200).contains(&nr_perturbed), "{}", nr_perturbed);
+    }
+
+    #[test]
+    fn perturbation_sameSeed_sameChanges() {
+        let changes = |seed| {
+            (0..100)
+                .map(|step_nr| Noise { per_million: 500_000, seed }.perturbation(step_nr, 30))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(changes(3), changes(3));
+        assert_ne!(changes(3), changes(4));
+    }
+
+    #[test]
+    fn play_idleBots_tapeChangesWithoutThem() {
+        let idle = Bot::new(vec![]);
+        let round_params = RoundParams {
+            noise: Some(Noise {
+                per_million: 1_000_000,
+                seed: 7,
+            }),
+            ..RoundParams::new(10, false, 100)
+        };
+        let mut arena = Arena::new(&idle, &idle, &round_params).unwrap();
+        let outcome = arena.finish();
+        // Idle bots are in a sink state from the start, so noise only makes a difference to bots
+        // that still play.
+        assert_eq!(outcome.result.status(), RoundStatus::Draw);
+        assert_eq!(outcome.nr_steps, 0);
+        let bot = Bot::new(vec![Instruction::SkipExecution; 50]);
+        let mut arena = Arena::new(&bot, &bot, &round_params).unwrap();
+        let outcome = arena.finish();
+        assert_ne!(arena.get_tape(), &round_params.initial_tape().unwrap());
+        assert_eq!(BatchArena::new(&bot, &bot, vec![round_params]).unwrap().run_to_completion()[0],
+                   outcome.result);
+    }
+}
@@ -0,0 +1,36 @@
+/// Which bots play with reversed polarity in rounds where `RoundParams::invert_polarity` is set.
+/// In rounds where it isn't set, both bots play with normal polarity.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum PolarityMode {
+    /// The customary convention: only the bot that starts at the end of the tape.
+    #[default]
+    EndBot,
+    /// Only the bot that starts at the start of the tape.
+    StartBot,
+    /// Both bots.
+    Both,
+}
+
+impl PolarityMode {
+    /// Returns whether the start bot and the end bot play with reversed polarity, in that order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bf_bot_core::simul_round::PolarityMode;
+    /// assert_eq!(PolarityMode::EndBot.reversed_bots(true), (false, true));
+    /// assert_eq!(PolarityMode::StartBot.reversed_bots(true), (true, false));
+    /// assert_eq!(PolarityMode::Both.reversed_bots(false), (false, false));
+    /// ```
+    pub fn reversed_bots(self, invert_polarity: bool) -> (bool, bool) {
+        if !invert_polarity {
+            return (false, false);
+        }
+        match self {
+            PolarityMode::EndBot => (false, true),
+            PolarityMode::StartBot => (true, false),
+            PolarityMode::Both => (true, true),
+        }
+    }
+}
@@ -1,29 +1,85 @@
 use bf::Bot;
+use error::BfError;
 use simul_round::RoundParams;
 use simul_round::RoundResult;
-use engine::Arena;
+#[cfg(test)]
+use simul_round::StepOutcome;
+use engine::{Arena, Replay, TapeHeatmap};
+
+/// Plays a round between the given bots and returns its result. Fails if the round parameters
+/// are not valid.
+pub fn play(bot_a: &Bot,
+            bot_b: &Bot,
+            round_params: &RoundParams)
+            -> Result<RoundResult, BfError> {
+    #[cfg(feature = "tracing")]
+    let span = ::tracing::info_span!("round",
+                                     tape_length = round_params.tape_length,
+                                     invert_polarity = round_params.invert_polarity,
+                                     winner = ::tracing::field::Empty)
+        .entered();
+    let result = Arena::new(bot_a, bot_b, round_params)?.run_to_completion().result;
+    #[cfg(feature = "tracing")]
+    span.record("winner", ::tracing::field::debug(result.status()));
+    Ok(result)
+}
 
-pub fn play(bot_a: &Bot, bot_b: &Bot, round_params: &RoundParams) -> RoundResult {
-    StepsIterator::new(bot_a, bot_b, round_params)
-        .find(|&ref outcome| outcome.round_is_finished())
-        .unwrap()
+/// Plays a round like `play` does, but also returns a step-by-step replay of it.
+pub fn play_recorded(bot_a: &Bot,
+                     bot_b: &Bot,
+                     round_params: &RoundParams)
+                     -> Result<(RoundResult, Replay), BfError> {
+    let mut arena = Arena::new(bot_a, bot_b, round_params)?;
+    arena.attach_recorder();
+    let result = arena.finish().result;
+    let replay = arena.take_replay().expect("A recorder was attached.");
+    Ok((result, replay))
 }
 
+/// Plays a round like `play` does, but also returns how often each bot read and wrote each cell.
+///
+/// # Examples
+///
+/// ```
+/// use bf_bot_core::bf::{Bot, Instruction};
+/// use bf_bot_core::simul_round::{self, RoundParams};
+/// let bot = Bot::new(vec![Instruction::MoveForward, Instruction::Increment]);
+/// let round_params = RoundParams::new(10, false, 100);
+/// let (_, heatmap) = simul_round::play_with_heatmap(&bot, &bot, &round_params).unwrap();
+/// assert_eq!(heatmap.start_bot.writes[1], 1);
+/// assert_eq!(heatmap.end_bot.writes[8], 1);
+/// ```
+pub fn play_with_heatmap(bot_a: &Bot,
+                         bot_b: &Bot,
+                         round_params: &RoundParams)
+                         -> Result<(RoundResult, TapeHeatmap), BfError> {
+    let mut arena = Arena::new(bot_a, bot_b, round_params)?;
+    arena.attach_heatmap();
+    let result = arena.finish().result;
+    let heatmap = arena.take_heatmap().expect("A heatmap was attached.");
+    Ok((result, heatmap))
+}
+
+/// Steps through a round one cycle at a time. Used by the tests to check the result of every
+/// single step.
+#[cfg(test)]
 #[derive(Debug)]
 struct StepsIterator<'a> {
     arena: Arena<'a>,
 }
 
+#[cfg(test)]
 impl<'a> StepsIterator<'a> {
     fn new<'b>(bot_a: &'b Bot, bot_b: &'b Bot, round_params: &RoundParams) -> StepsIterator<'b> {
-        StepsIterator { arena: Arena::new(bot_a, bot_b, round_params) }
+        StepsIterator { arena: Arena::new(bot_a, bot_b, round_params).unwrap() }
     }
 }
 
+#[cfg(test)]
 impl<'a> Iterator for StepsIterator<'a> {
-    type Item = RoundResult;
+    type Item = StepOutcome;
 
-    fn next(&mut self) -> Option<RoundResult> {
+    fn next(&mut self) -> Option<StepOutcome> {
         Some(self.arena.step())
     }
 }
@@ -32,8 +88,9 @@ impl<'a> Iterator for StepsIterator<'a> {
 #[allow(non_snake_case)]
 mod tests {
     use super::*;
-    use simul_round::{RoundResult, RoundParams};
-    use bf::Instruction;
+    use simul_round::{CellWidth, CollisionRule, FlagInit, InstructionBudgets, LossReason,
+                      PolarityMode, RoundParams, RoundStatus, StartDelays};
+    use bf::{Instruction, UnmatchedBracketPolicy};
 
     /// Constructs a Bot with an empty program.
     fn make_empty_bot() -> Bot {
@@ -54,12 +111,41 @@ mod tests {
         Bot::new(vec![Instruction::MoveBack])
     }
 
+    /// Constructs a Bot that walks up to the enemy flag, which is expected to be set to 1, and
+    /// decrements it once. It then executes the given instructions.
+    /// Its program, in BrainFuck: >>>>>>>>>- followed by the given instructions.
+    fn make_bot_decrementing_enemy_flag(then: Vec<Instruction>) -> Bot {
+        let mut program = vec![Instruction::MoveForward; 9];
+        program.push(Instruction::Decrement);
+        program.extend(then);
+        Bot::new(program)
+    }
+
+    /// Constructs a Bot that loops forever without changing anything, as long as it is standing on
+    /// a non-zero cell.
+    /// Its program, in BrainFuck: [.]
+    fn make_idle_looping_bot() -> Bot {
+        Bot::new(vec![Instruction::JumpIfZero(2),
+                      Instruction::SkipExecution,
+                      Instruction::JumpIfNonZero(0)])
+    }
+
+    /// Constructs a Bot that walks to the enemy flag and clears it.
+    /// Its program, in BrainFuck: >>>>>>>>>[-]
+    fn make_enemy_flag_clearing_bot() -> Bot {
+        let mut program = vec![Instruction::MoveForward; 9];
+        program.extend(vec![Instruction::JumpIfZero(11),
+                            Instruction::Decrement,
+                            Instruction::JumpIfNonZero(9)]);
+        Bot::new(program)
+    }
+
+    fn make_round_params_with_early_termination() -> RoundParams {
+        RoundParams { early_termination: true, ..make_round_params(100_000) }
+    }
+
     fn make_round_params(max_steps: u32) -> RoundParams {
-        RoundParams {
-            tape_length: 10,
-            invert_polarity: false,
-            max_steps: max_steps,
-        }
+        RoundParams::new(10, false, max_steps)
     }
 
     #[test]
@@ -68,7 +154,7 @@ mod tests {
         let bot_a = make_bot_idle_three_turns();
         let bot_b = make_bot_idle_three_turns();
         let mut steps_iter = StepsIterator::new(&bot_a, &bot_b, &round_params);
-        assert_eq!(steps_iter.next().unwrap(), RoundResult::draw());
+        assert_eq!(steps_iter.next().unwrap().status(), RoundStatus::Draw);
     }
 
     #[test]
@@ -77,8 +163,8 @@ mod tests {
         let bot_a = make_bot_idle_three_turns();
         let bot_b = make_bot_idle_three_turns();
         let mut steps_iter = StepsIterator::new(&bot_a, &bot_b, &round_params);
-        assert_eq!(steps_iter.next().unwrap(), RoundResult::round_ongoing());
-        assert_eq!(steps_iter.next().unwrap(), RoundResult::draw());
+        assert_eq!(steps_iter.next().unwrap().status(), RoundStatus::Ongoing);
+        assert_eq!(steps_iter.next().unwrap().status(), RoundStatus::Draw);
     }
 
     #[test]
@@ -88,7 +174,7 @@ mod tests {
         let bot_b = make_bot_idle_three_turns();
         let mut steps_iter = StepsIterator::new(&bot_a, &bot_b, &round_params);
         steps_iter.arena.tape[0] = 0;
-        assert_eq!(steps_iter.next().unwrap(), RoundResult::draw());
+        assert_eq!(steps_iter.next().unwrap().status(), RoundStatus::Draw);
     }
 
     #[test]
@@ -98,7 +184,7 @@ mod tests {
         let bot_b = make_bot_idle_three_turns();
         let mut steps_iter = StepsIterator::new(&bot_a, &bot_b, &round_params);
         steps_iter.arena.tape[0] = 0;
-        assert_eq!(steps_iter.next().unwrap(), RoundResult::end_bot_wins());
+        assert_eq!(steps_iter.next().unwrap().status(), RoundStatus::EndBotWon);
     }
 
     #[test]
@@ -107,7 +193,7 @@ mod tests {
         let bot_a = make_empty_bot();
         let bot_b = make_empty_bot();
         let mut steps_iter = StepsIterator::new(&bot_a, &bot_b, &round_params);
-        assert_eq!(steps_iter.next().unwrap(), RoundResult::draw());
+        assert_eq!(steps_iter.next().unwrap().status(), RoundStatus::Draw);
     }
 
     #[test]
@@ -116,8 +202,8 @@ mod tests {
         let bot_a = make_bot_idle_three_turns();
         let bot_b = make_bot_idle_three_turns();
         let mut steps_iter = StepsIterator::new(&bot_a, &bot_b, &round_params);
-        steps_iter.arena.tape = vec!(0i8; round_params.tape_length as usize);
-        assert_eq!(steps_iter.next().unwrap(), RoundResult::draw());
+        steps_iter.arena.tape = vec!(0; round_params.tape_length as usize);
+        assert_eq!(steps_iter.next().unwrap().status(), RoundStatus::Draw);
     }
 
     #[test]
@@ -127,8 +213,8 @@ mod tests {
         let bot_b = make_empty_bot();
         let mut steps_iter = StepsIterator::new(&bot_a, &bot_b, &round_params);
         steps_iter.arena.tape[0] = 0;
-        assert_eq!(steps_iter.next().unwrap(),
-                   RoundResult::end_bot_wins(),
+        assert_eq!(steps_iter.next().unwrap().status(),
+                   RoundStatus::EndBotWon,
                    "Expected end_bot_wins!");
     }
 
@@ -138,8 +224,8 @@ mod tests {
         let bot_a = make_suicidal_bot();
         let bot_b = make_empty_bot();
         let mut steps_iter = StepsIterator::new(&bot_a, &bot_b, &round_params);
-        assert_eq!(steps_iter.next().unwrap(),
-                   RoundResult::end_bot_wins(),
+        assert_eq!(steps_iter.next().unwrap().status(),
+                   RoundStatus::EndBotWon,
                    "Expected end_bot_wins!");
     }
 
@@ -149,8 +235,8 @@ mod tests {
         let bot_a = make_empty_bot();
         let bot_b = make_suicidal_bot();
         let mut steps_iter = StepsIterator::new(&bot_a, &bot_b, &round_params);
-        assert_eq!(steps_iter.next().unwrap(),
-                   RoundResult::start_bot_wins(),
+        assert_eq!(steps_iter.next().unwrap().status(),
+                   RoundStatus::StartBotWon,
                    "Expected start_bot_wins!");
     }
 
@@ -160,8 +246,8 @@ mod tests {
         let bot_a = make_suicidal_bot();
         let bot_b = make_suicidal_bot();
         let mut steps_iter = StepsIterator::new(&bot_a, &bot_b, &round_params);
-        assert_eq!(steps_iter.next().unwrap(),
-                   RoundResult::draw(),
+        assert_eq!(steps_iter.next().unwrap().status(),
+                   RoundStatus::Draw,
                    "Expected draw!");
     }
 
@@ -172,8 +258,501 @@ mod tests {
         let bot_b = make_suicidal_bot();
         let mut steps_iter = StepsIterator::new(&bot_a, &bot_b, &round_params);
         steps_iter.arena.tape[0] = 0;
-        assert_eq!(steps_iter.next().unwrap(),
-                   RoundResult::draw(),
+        assert_eq!(steps_iter.next().unwrap().status(),
+                   RoundStatus::Draw,
                    "Expected draw!");
     }
+
+    #[test]
+    fn play_flagZeroForOneCycle_noLoss() {
+        let round_params = make_round_params(100_000);
+        let bot_a = make_bot_decrementing_enemy_flag(vec![Instruction::Increment]);
+        let bot_b = make_empty_bot();
+        let mut steps_iter = StepsIterator::new(&bot_a, &bot_b, &round_params);
+        steps_iter.arena.tape[9] = 1;
+        assert_eq!(steps_iter.find(|result| result.round_is_finished()).unwrap().status(),
+                   RoundStatus::Draw);
+    }
+
+    #[test]
+    fn play_flagZeroForTwoCycles_ownerLosesAtSecondCycle() {
+        let round_params = make_round_params(100_000);
+        let bot_a = make_bot_decrementing_enemy_flag(vec![Instruction::SkipExecution]);
+        let bot_b = make_empty_bot();
+        let mut steps_iter = StepsIterator::new(&bot_a, &bot_b, &round_params);
+        steps_iter.arena.tape[9] = 1;
+        for _ in 0..10 {
+            assert_eq!(steps_iter.next().unwrap().status(), RoundStatus::Ongoing);
+        }
+        assert_eq!(steps_iter.next().unwrap().status(), RoundStatus::StartBotWon);
+    }
+
+    #[test]
+    fn play_bothFlagsCapturedInSameCycle_draw() {
+        let round_params = make_round_params(100_000);
+        let bot_a = make_bot_decrementing_enemy_flag(vec![Instruction::SkipExecution]);
+        let bot_b = make_bot_decrementing_enemy_flag(vec![Instruction::SkipExecution]);
+        let mut steps_iter = StepsIterator::new(&bot_a, &bot_b, &round_params);
+        steps_iter.arena.tape[0] = 1;
+        steps_iter.arena.tape[9] = 1;
+        for _ in 0..10 {
+            assert_eq!(steps_iter.next().unwrap().status(), RoundStatus::Ongoing);
+        }
+        assert_eq!(steps_iter.next().unwrap().status(), RoundStatus::Draw);
+    }
+
+    #[test]
+    fn play_flagCapturedWhileCapturerLeavesTape_draw() {
+        let round_params = make_round_params(100_000);
+        let bot_a = make_bot_decrementing_enemy_flag(vec![Instruction::MoveForward]);
+        let bot_b = make_empty_bot();
+        let mut steps_iter = StepsIterator::new(&bot_a, &bot_b, &round_params);
+        steps_iter.arena.tape[9] = 1;
+        assert_eq!(steps_iter.find(|result| result.round_is_finished()).unwrap().status(),
+                   RoundStatus::Draw);
+    }
+
+    #[test]
+    fn iter_earlyTerminationAndBothBotsLooping_drawWithinFewSteps() {
+        let round_params = make_round_params_with_early_termination();
+        let bot_a = make_idle_looping_bot();
+        let bot_b = make_idle_looping_bot();
+        let steps_iter = StepsIterator::new(&bot_a, &bot_b, &round_params);
+        let mut outcomes = steps_iter.skip_while(|result| !result.round_is_finished());
+        assert_eq!(outcomes.next().unwrap().status(), RoundStatus::Draw);
+        let steps_iter = StepsIterator::new(&bot_a, &bot_b, &round_params);
+        assert!(steps_iter.take_while(|result| !result.round_is_finished()).count() < 10);
+    }
+
+    #[test]
+    fn iter_noEarlyTerminationAndBothBotsLooping_drawAtMaxSteps() {
+        let round_params = make_round_params(1000);
+        let bot_a = make_idle_looping_bot();
+        let bot_b = make_idle_looping_bot();
+        let steps_iter = StepsIterator::new(&bot_a, &bot_b, &round_params);
+        assert_eq!(steps_iter.take_while(|result| !result.round_is_finished()).count(),
+                   1000);
+    }
+
+    #[test]
+    fn play_earlyTerminationWhileOpponentLoops_sameResultAsWithout() {
+        let bot_a = make_enemy_flag_clearing_bot();
+        let bot_b = make_idle_looping_bot();
+        let result = play(&bot_a, &bot_b, &make_round_params_with_early_termination()).unwrap();
+        assert_eq!(result.status(), RoundStatus::StartBotWon);
+        assert_eq!(play(&bot_a, &bot_b, &make_round_params(100_000)).unwrap().status(),
+                   RoundStatus::StartBotWon);
+    }
+
+    #[test]
+    fn playRecorded_recordsEveryStepAndTheResult() {
+        let round_params = make_round_params(100_000);
+        let bot_a = make_bot_decrementing_enemy_flag(vec![Instruction::SkipExecution]);
+        let bot_b = make_empty_bot();
+        let (result, replay) = play_recorded(&bot_a, &bot_b, &round_params).unwrap();
+        assert_eq!(result, play(&bot_a, &bot_b, &round_params).unwrap());
+        assert_eq!(replay.result, Some(result));
+        assert_eq!(replay.steps[8].start_bot.position, 9);
+        assert_eq!(replay.tape_at(10)[9], i32::from(i8::MAX));
+    }
+
+    #[test]
+    fn playWithHeatmap_clearerAgainstLooper_countsAccessesPerBot() {
+        let round_params = make_round_params(100_000);
+        let (result, heatmap) = play_with_heatmap(&make_enemy_flag_clearing_bot(),
+                                                  &make_idle_looping_bot(),
+                                                  &round_params).unwrap();
+        assert_eq!(result.status(), RoundStatus::StartBotWon);
+        assert_eq!(heatmap.start_bot.writes[9], 128);
+        assert_eq!(heatmap.start_bot.nr_cells_written(), 1);
+        assert!(heatmap.start_bot.reads[9] > 0);
+        assert_eq!(heatmap.end_bot.nr_cells_touched(), 1);
+        assert!(heatmap.end_bot.reads[9] > 0);
+    }
+
+    #[test]
+    fn runToCompletion_suicidalBot_endsAfterFirstStep() {
+        let bot_a = make_suicidal_bot();
+        let bot_b = make_empty_bot();
+        let outcome =
+            Arena::new(&bot_a, &bot_b, &make_round_params(100_000)).unwrap().run_to_completion();
+        assert_eq!(outcome.result.status(), RoundStatus::EndBotWon);
+        assert_eq!(outcome.nr_steps, 1);
+    }
+
+    #[test]
+    fn runToCompletion_idleBots_endsAtMaxSteps() {
+        let bot_a = make_idle_looping_bot();
+        let bot_b = make_idle_looping_bot();
+        let outcome =
+            Arena::new(&bot_a, &bot_b, &make_round_params(500)).unwrap().run_to_completion();
+        assert_eq!(outcome.result.status(), RoundStatus::Draw);
+        assert_eq!(outcome.nr_steps, 500);
+    }
+
+    #[test]
+    fn play_unmatchedBracketTaken_botTerminates() {
+        let program = vec![Instruction::MoveForward,
+                           Instruction::Increment,
+                           Instruction::JumpIfNonZero(0)];
+        let bot_a = Bot::new_checked(program, UnmatchedBracketPolicy::Terminate).unwrap();
+        let result = play(&bot_a, &make_empty_bot(), &make_round_params(100_000)).unwrap();
+        // Both programs have ended after the third step, which makes the round a draw.
+        assert_eq!((result.status(), result.nr_steps), (RoundStatus::Draw, 3));
+    }
+
+    #[test]
+    fn snapshot_insideLoop_reportsBothBots() {
+        let bot_a = make_enemy_flag_clearing_bot();
+        let bot_b = make_bot_idle_three_turns();
+        let mut arena = Arena::new(&bot_a, &bot_b, &make_round_params(100_000)).unwrap();
+        for _ in 0..11 {
+            arena.step();
+        }
+        let snapshot = arena.snapshot();
+        assert_eq!(snapshot.step_nr, 11);
+        assert_eq!(snapshot.tape, &arena.get_tape()[..]);
+        assert_eq!((snapshot.start_bot.position, snapshot.start_bot.loop_depth), (9, 1));
+        assert_eq!((snapshot.end_bot.code_pointer, snapshot.end_bot.loop_depth), (3, 0));
+    }
+
+    #[test]
+    fn reset_afterFinishedRound_playsLikeNewArena() {
+        let bot_a = make_bot_decrementing_enemy_flag(vec![Instruction::SkipExecution]);
+        let bot_b = make_empty_bot();
+        let suicidal_bot = make_suicidal_bot();
+        let round_params = RoundParams { tape_length: 12, ..make_round_params(100_000) };
+        let mut arena = Arena::new(&suicidal_bot, &bot_b, &make_round_params(500)).unwrap();
+        arena.finish();
+        arena.reset(&bot_a, &bot_b, &round_params).unwrap();
+        assert_eq!(arena.get_step_nr(), 0);
+        assert_eq!(arena.get_tape(), &round_params.initial_tape().unwrap());
+        assert_eq!(arena.finish(),
+                   Arena::new(&bot_a, &bot_b, &round_params).unwrap().run_to_completion());
+    }
+
+    #[test]
+    fn reset_recorderAttached_startsNewRecording() {
+        let bot = make_bot_idle_three_turns();
+        let mut arena = Arena::new(&bot, &bot, &make_round_params(100_000)).unwrap();
+        arena.attach_recorder();
+        arena.step();
+        arena.reset(&bot, &bot, &make_round_params(100_000)).unwrap();
+        assert!(arena.take_replay().unwrap().steps.is_empty());
+    }
+
+    #[test]
+    fn play_suicidalBot_lostByLeavingTheTapeAtFirstStep() {
+        let result =
+            play(&make_suicidal_bot(), &make_empty_bot(), &make_round_params(100_000)).unwrap();
+        assert_eq!(result.bot_a_loss, Some(LossReason::OffTape));
+        assert_eq!(result.bot_b_loss, None);
+        assert_eq!(result.nr_steps, 1);
+    }
+
+    #[test]
+    fn play_flagCleared_lostByFlagZeroedAndTapeHashed() {
+        let bot_a = make_enemy_flag_clearing_bot();
+        let bot_b = make_empty_bot();
+        let result = play(&bot_a, &bot_b, &make_round_params(100_000)).unwrap();
+        assert_eq!(result.bot_b_loss, Some(LossReason::FlagZeroed));
+        // Nine moves and the first [, then 128 times -], the last ] in the second cycle that the
+        // flag is zero.
+        assert_eq!(result.nr_steps, 9 + 1 + 2 * 128);
+        assert_ne!(result.tape_hash, 0);
+    }
+
+    #[test]
+    fn play_bothBotsLooping_timeout() {
+        let bot_a = make_idle_looping_bot();
+        let result = play(&bot_a, &bot_a, &make_round_params_with_early_termination()).unwrap();
+        assert_eq!(result.bot_a_loss, Some(LossReason::Timeout));
+        assert_eq!(result.bot_b_loss, Some(LossReason::Timeout));
+    }
+
+    /// Its program, in BrainFuck: >>>>>>>>>[+]
+    fn make_incrementing_enemy_flag_clearing_bot() -> Bot {
+        let mut program = vec![Instruction::MoveForward; 9];
+        program.extend(vec![Instruction::JumpIfZero(11),
+                            Instruction::Increment,
+                            Instruction::JumpIfNonZero(9)]);
+        Bot::new(program)
+    }
+
+    #[test]
+    fn play_incrementingClearerWithI8Cells_winsByWrappingAround() {
+        let bot_a = make_incrementing_enemy_flag_clearing_bot();
+        let result = play(&bot_a, &make_empty_bot(), &make_round_params(100_000)).unwrap();
+        assert_eq!(result.status(), RoundStatus::StartBotWon);
+        assert_eq!(result.nr_steps, 9 + 1 + 2 * 128);
+    }
+
+    #[test]
+    fn play_incrementingClearerWithI16Cells_cannotClearFlagInTime() {
+        let bot_a = make_incrementing_enemy_flag_clearing_bot();
+        let round_params = RoundParams { cell_width: CellWidth::I16, ..make_round_params(100_000) };
+        let result = play(&bot_a, &make_empty_bot(), &round_params).unwrap();
+        assert_eq!(result.status(), RoundStatus::Draw);
+    }
+
+    #[test]
+    fn play_decrementingClearerWithU8Cells_sameResultAsWithI8Cells() {
+        let bot_a = make_enemy_flag_clearing_bot();
+        let round_params = RoundParams { cell_width: CellWidth::U8, ..make_round_params(100_000) };
+        assert_eq!(play(&bot_a, &make_empty_bot(), &round_params).unwrap().nr_steps,
+                   play(&bot_a, &make_empty_bot(), &make_round_params(100_000)).unwrap().nr_steps);
+    }
+
+    #[test]
+    fn play_customFlagValue_clearingTakesThatManyDecrements() {
+        let bot_a = make_enemy_flag_clearing_bot();
+        let round_params = RoundParams {
+            flag_init: FlagInit::Custom(5),
+            ..make_round_params(100_000)
+        };
+        let result = play(&bot_a, &make_empty_bot(), &round_params).unwrap();
+        assert_eq!(result.nr_steps, 9 + 1 + 2 * 5);
+    }
+
+    #[test]
+    fn play_customFlagValueAndReversedPolarity_endBotNeedsAsManyIncrements() {
+        let mut program = vec![Instruction::MoveForward; 9];
+        program.extend(vec![Instruction::JumpIfZero(11),
+                            Instruction::Increment,
+                            Instruction::JumpIfNonZero(9)]);
+        let bot_b = Bot::new(program);
+        let round_params = RoundParams {
+            flag_init: FlagInit::Custom(5),
+            ..RoundParams::new(10, true, 100_000)
+        };
+        let result = play(&make_empty_bot(), &bot_b, &round_params).unwrap();
+        assert_eq!(result.status(), RoundStatus::EndBotWon);
+        assert_eq!(result.nr_steps, 9 + 1 + 2 * 5);
+    }
+
+    #[test]
+    fn play_startBotInverted_incrementingBotBehavesAsDecrementingBotDoesNormally() {
+        let round_params = RoundParams {
+            flag_init: FlagInit::Custom(5),
+            ..make_round_params(100_000)
+        };
+        let inverted_round_params = RoundParams {
+            invert_polarity: true,
+            polarity_mode: PolarityMode::StartBot,
+            ..round_params.clone()
+        };
+        let incrementing = play(&make_incrementing_enemy_flag_clearing_bot(),
+                                &make_empty_bot(),
+                                &inverted_round_params).unwrap();
+        let decrementing =
+            play(&make_enemy_flag_clearing_bot(), &make_empty_bot(), &round_params).unwrap();
+        assert_eq!(incrementing.status(), RoundStatus::StartBotWon);
+        assert_eq!(incrementing.nr_steps, decrementing.nr_steps);
+    }
+
+    #[test]
+    fn play_bothBotsInverted_incrementingBotNeedsToWrapAround() {
+        let round_params = RoundParams {
+            invert_polarity: true,
+            polarity_mode: PolarityMode::Both,
+            flag_init: FlagInit::Custom(5),
+            ..make_round_params(100_000)
+        };
+        let result = play(&make_incrementing_enemy_flag_clearing_bot(),
+                          &make_empty_bot(),
+                          &round_params).unwrap();
+        // The start bot sees the enemy flag at 5 and increments it, so it has to wrap around.
+        assert_eq!(result.nr_steps, 9 + 1 + 2 * (256 - 5));
+    }
+
+    /// Plays six cycles in which both bots walk to the cell at index 4 of a tape of length 10 and
+    /// change it in the sixth cycle, in opposite directions. Returns the value of the cell.
+    fn play_collision(collision_rule: CollisionRule) -> i32 {
+        let mut program_a = vec![Instruction::SkipExecution];
+        program_a.extend(vec![Instruction::MoveForward; 4]);
+        program_a.push(Instruction::Increment);
+        let mut program_b = vec![Instruction::MoveForward; 5];
+        program_b.push(Instruction::Increment);
+        let (bot_a, bot_b) = (Bot::new(program_a), Bot::new(program_b));
+        let round_params = RoundParams { collision_rule, ..RoundParams::new(10, true, 100) };
+        let mut arena = Arena::new(&bot_a, &bot_b, &round_params).unwrap();
+        for _ in 0..6 {
+            arena.step();
+        }
+        arena.get_tape()[4]
+    }
+
+    #[test]
+    fn play_collisionByDefault_changesAreSummed() {
+        assert_eq!(play_collision(CollisionRule::default()), 0);
+    }
+
+    #[test]
+    fn play_collisionWithPrecedence_onlyThatChangeApplied() {
+        assert_eq!(play_collision(CollisionRule::StartBotFirst), 1);
+        assert_eq!(play_collision(CollisionRule::EndBotFirst), -1);
+    }
+
+    #[test]
+    fn play_collisionRandom_onlyOneChangeApplied() {
+        assert_eq!(play_collision(CollisionRule::Random { seed: 1 }).abs(), 1);
+    }
+
+    #[test]
+    fn play_earlyTerminationWithRandomCollisions_sameResultAsWithout() {
+        // `>>>+[+]>>>>>>[-]>[-]` against `>>>>>>[-]`: both bots meet at the cell at index 3, where
+        // the sum of their changes would cancel out forever. With random collisions, the state of
+        // the tape and the bots repeats itself, but the start bot eventually gets to clear the
+        // cell and moves on.
+        let mut program_a = vec![Instruction::MoveForward; 3];
+        program_a.extend(vec![Instruction::Increment,
+                              Instruction::JumpIfZero(6),
+                              Instruction::Increment,
+                              Instruction::JumpIfNonZero(4)]);
+        program_a.extend(vec![Instruction::MoveForward; 6]);
+        program_a.extend(vec![Instruction::JumpIfZero(15),
+                              Instruction::Decrement,
+                              Instruction::JumpIfNonZero(13),
+                              Instruction::MoveForward,
+                              Instruction::JumpIfZero(19),
+                              Instruction::Decrement,
+                              Instruction::JumpIfNonZero(17)]);
+        let mut program_b = vec![Instruction::MoveForward; 6];
+        program_b.extend(vec![Instruction::JumpIfZero(8),
+                              Instruction::Decrement,
+                              Instruction::JumpIfNonZero(6)]);
+        let (bot_a, bot_b) = (Bot::new(program_a), Bot::new(program_b));
+        let round_params = RoundParams {
+            collision_rule: CollisionRule::Random { seed: 0 },
+            ..make_round_params(100_000)
+        };
+        let result = play(&bot_a, &bot_b, &round_params).unwrap();
+        assert_eq!(result.status(), RoundStatus::StartBotWon);
+        let early_round_params = RoundParams { early_termination: true, ..round_params };
+        assert!(!early_round_params.detects_loops());
+        assert_eq!(play(&bot_a, &bot_b, &early_round_params).unwrap(), result);
+    }
+
+    fn make_round_params_with_budgets(start_bot: u32, end_bot: u32) -> RoundParams {
+        RoundParams {
+            instruction_budgets: Some(InstructionBudgets { start_bot, end_bot }),
+            ..make_round_params(100_000)
+        }
+    }
+
+    #[test]
+    fn play_startBotBudgetRunsOut_drawWhenItIsUsedUp() {
+        let bot = make_idle_looping_bot();
+        let result = play(&bot, &bot, &make_round_params_with_budgets(50, 1000)).unwrap();
+        assert_eq!(result.status(), RoundStatus::Draw);
+        assert_eq!(result.nr_steps, 50);
+    }
+
+    #[test]
+    fn play_opponentBudgetRunsOutBeforeFlagIsCleared_draw() {
+        let bot_a = make_enemy_flag_clearing_bot();
+        let bot_b = make_idle_looping_bot();
+        let result = play(&bot_a, &bot_b, &make_round_params_with_budgets(1000, 10)).unwrap();
+        assert_eq!(result.status(), RoundStatus::Draw);
+        let result = play(&bot_a, &bot_b, &make_round_params_with_budgets(1000, 1000)).unwrap();
+        assert_eq!(result.status(), RoundStatus::StartBotWon);
+    }
+
+    #[test]
+    fn play_endedProgram_doesNotUseUpBudget() {
+        let bot_a = make_enemy_flag_clearing_bot();
+        let bot_b = make_bot_idle_three_turns();
+        let result = play(&bot_a, &bot_b, &make_round_params_with_budgets(1000, 3)).unwrap();
+        assert_eq!(result.status(), RoundStatus::StartBotWon);
+    }
+
+    #[test]
+    fn play_mirrorMatchWithDelay_delayedBotLoses() {
+        let bot = make_enemy_flag_clearing_bot();
+        assert_eq!(play(&bot, &bot, &make_round_params(100_000)).unwrap().status(),
+                   RoundStatus::Draw);
+        let round_params = RoundParams {
+            start_delays: StartDelays {
+                start_bot: 0,
+                end_bot: 1,
+            },
+            ..make_round_params(100_000)
+        };
+        assert_eq!(play(&bot, &bot, &round_params).unwrap().status(),
+                   RoundStatus::StartBotWon);
+    }
+
+    #[test]
+    fn solo_flagClearingBot_replayEndsWithWin() {
+        let bot = make_enemy_flag_clearing_bot();
+        let replay = Arena::solo(&bot, &make_round_params(100_000)).unwrap();
+        let result = replay.result.clone().expect("The round ended.");
+        assert_eq!(result.status(), RoundStatus::StartBotWon);
+        assert_eq!(replay.tape_at(replay.steps.len())[9], 0);
+    }
+
+    #[test]
+    fn solo_variousBots_sameReplayAsRoundAgainstEmptyBot() {
+        let idle_bot = make_empty_bot();
+        let delayed_params = RoundParams {
+            start_delays: StartDelays {
+                start_bot: 3,
+                end_bot: 0,
+            },
+            ..make_round_params(100_000)
+        };
+        for bot in &[make_enemy_flag_clearing_bot(),
+                     make_suicidal_bot(),
+                     make_idle_looping_bot(),
+                     make_bot_idle_three_turns()] {
+            for round_params in &[make_round_params(50),
+                                  make_round_params_with_early_termination(),
+                                  delayed_params.clone()] {
+                let mut arena = Arena::new(bot, &idle_bot, round_params).unwrap();
+                arena.attach_recorder();
+                arena.finish();
+                assert_eq!(Arena::solo(bot, round_params).unwrap(),
+                           arena.take_replay().unwrap());
+            }
+        }
+    }
+
+    #[cfg(feature = "tracing")]
+    thread_local! {
+        /// The messages that were logged on the thread of a test, so that tests that run at the
+        /// same time don't see each other's messages.
+        static MESSAGES: ::std::cell::RefCell<Vec<String>> =
+            const { ::std::cell::RefCell::new(vec![]) };
+    }
+
+    #[cfg(feature = "tracing")]
+    struct MemoryLogger;
+
+    #[cfg(feature = "tracing")]
+    impl ::log::Log for MemoryLogger {
+        fn enabled(&self, _: &::log::Metadata) -> bool {
+            true
+        }
+
+        fn log(&self, record: &::log::Record) {
+            MESSAGES.with(|messages| messages.borrow_mut().push(record.args().to_string()));
+        }
+
+        fn flush(&self) {}
+    }
+
+    #[cfg(feature = "tracing")]
+    #[test]
+    fn play_tracing_everyInstructionLogged() {
+        ::log::set_logger(&MemoryLogger).unwrap();
+        ::log::set_max_level(::log::LevelFilter::Trace);
+        play(&make_bot_idle_three_turns(), &make_empty_bot(), &make_round_params(10)).unwrap();
+        MESSAGES.with(|messages| {
+            let messages = messages.borrow();
+            assert_eq!(messages.len(), 3);
+            assert_eq!(messages[2],
+                       "step 2, start bot: instruction 2 SkipExecution, tape position 0, cell \
+                        value -128");
+        });
+    }
 }
@@ -0,0 +1,9 @@
+use simul_round::RoundResult;
+
+/// The final result of a round that was played to completion, together with its length.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RoundOutcome {
+    pub result: RoundResult,
+    /// The number of cycles that were executed before the round ended.
+    pub nr_steps: u32,
+}
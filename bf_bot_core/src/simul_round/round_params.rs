@@ -1,8 +1,152 @@
+use alloc::vec::Vec;
+
+use error::BfError;
+use simul_round::{CellWidth, CollisionRule, ExecutionEngine, FlagInit, Noise, PolarityMode,
+                  Rules};
+
 /// Specifies the conditions of a single round of Brainfuck Jousting.
-#[derive(Debug)]
-#[derive(PartialEq)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct RoundParams {
     pub tape_length: u32,
+    /// If true, the bots selected by `polarity_mode` play with reversed polarity.
     pub invert_polarity: bool,
     pub max_steps: u32,
+    /// If true, the round is ended as a draw as soon as the arena detects that it has entered a
+    /// loop, because the state of the tape and both bots repeats itself. Such a round would
+    /// otherwise only end once `max_steps` is reached, so this doesn't change the result, but it
+    /// does cost a bit of time on every step.
+    pub early_termination: bool,
+    /// The type of the cells on the tape.
+    pub cell_width: CellWidth,
+    /// The values that the flags start with.
+    pub flag_init: FlagInit,
+    /// Which bots play with reversed polarity if `invert_polarity` is set.
+    pub polarity_mode: PolarityMode,
+    /// If set, each bot may only execute this many instructions. The round ends as a draw as soon
+    /// as either bot has used up its budget, even if `max_steps` hasn't been reached yet. A bot
+    /// whose program has ended doesn't use up any more of its budget.
+    pub instruction_budgets: Option<InstructionBudgets>,
+    /// What happens when both bots change the same cell in the same cycle. Early termination is
+    /// not applied to a round with random collisions, because the outcome of a collision depends
+    /// on the cycle it happens in.
+    pub collision_rule: CollisionRule,
+    /// How the programs of the bots are executed. This never changes the result of the round.
+    pub engine: ExecutionEngine,
+    /// The variant of the game: where the flags are, and when a bot loses.
+    pub rules: Rules,
+    /// If set, a bot only senses whether the cell it stands on is zero, with `[` and `]`, if it
+    /// also stood on that cell during one of the given number of preceding steps. Otherwise the
+    /// cell seems non-zero to it. Early termination is not applied to such a round, because its
+    /// state then includes what each bot remembers.
+    pub fog_of_war: Option<u32>,
+    /// If set, random cells change during the round, independent of the bots, so that bots that
+    /// only win on an undisturbed tape can be told apart from robust ones. Early termination is
+    /// not applied to such a round, because it can't repeat itself exactly.
+    pub noise: Option<Noise>,
+    /// The number of cycles that each bot idles at the start of the round, while its opponent
+    /// already moves. A handicap like this shows how dominant a bot is. Early termination is not
+    /// applied to a round with delays, because the bots don't play by the same rules throughout.
+    pub start_delays: StartDelays,
+}
+
+/// The number of instructions that each bot may execute during a round.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct InstructionBudgets {
+    pub start_bot: u32,
+    pub end_bot: u32,
+}
+
+/// The number of cycles that each bot waits before it executes its first instruction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct StartDelays {
+    pub start_bot: u32,
+    pub end_bot: u32,
+}
+
+impl RoundParams {
+    /// Constructs the parameters for a round with the given tape length, polarity and maximum
+    /// number of steps. All other options are disabled, the tape has the standard `i8` cells and
+    /// flags, colliding changes are summed, the default engine executes the bots, and the standard
+    /// rules apply without fog of war, noise or delays. If the polarity is inverted, it is inverted
+    /// for the end bot.
+    pub fn new(tape_length: u32, invert_polarity: bool, max_steps: u32) -> RoundParams {
+        RoundParams {
+            tape_length,
+            invert_polarity,
+            max_steps,
+            early_termination: false,
+            cell_width: CellWidth::default(),
+            flag_init: FlagInit::default(),
+            polarity_mode: PolarityMode::default(),
+            instruction_budgets: None,
+            collision_rule: CollisionRule::default(),
+            engine: ExecutionEngine::default(),
+            rules: Rules::default(),
+            fog_of_war: None,
+            noise: None,
+            start_delays: StartDelays::default(),
+        }
+    }
+
+    /// Returns an error if a round can't be played with these parameters: the tape has to hold
+    /// every flag, with at least one other cell in between under rules with several flags.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bf_bot_core::simul_round::{RoundParams, Rules};
+    /// assert!(RoundParams::new(10, false, 1000).validate().is_ok());
+    /// assert!(RoundParams::new(1, false, 1000).validate().is_err());
+    /// let round_params = RoundParams {
+    ///     rules: Rules::MultipleFlags { nr_flags: 5 },
+    ///     ..RoundParams::new(10, false, 1000)
+    /// };
+    /// assert!(round_params.validate().is_err());
+    /// ```
+    pub fn validate(&self) -> Result<(), BfError> {
+        if self.tape_length < 2 {
+            return Err(BfError::InvalidParams(format!("A tape of {} cells has no room for both \
+                                                       flags.",
+                                                      self.tape_length)));
+        }
+        if let Rules::MultipleFlags { nr_flags } = self.rules {
+            if nr_flags == 0 || self.tape_length <= 2 * nr_flags {
+                return Err(BfError::InvalidParams(format!("A tape of {} cells has no room for \
+                                                           {} flags per bot.",
+                                                          self.tape_length,
+                                                          nr_flags)));
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns whether the arena ends the round as soon as it detects a loop: early termination
+    /// has to be enabled, and the round mustn't have fog of war, noise, delays or random
+    /// collisions. Random collisions depend on the step number, so a round whose state repeats
+    /// itself can still take a different turn.
+    pub fn detects_loops(&self) -> bool {
+        self.early_termination && self.fog_of_war.is_none() && self.noise.is_none() &&
+        self.start_delays == StartDelays::default() &&
+        !matches!(self.collision_rule, CollisionRule::Random { .. })
+    }
+
+    /// Returns the tape at the start of the round, as the rules lay it out. Under the standard
+    /// rules, all cells are zero, except for the flags. Fails if the parameters are not valid.
+    pub fn initial_tape(&self) -> Result<Vec<i32>, BfError> {
+        let mut tape = vec![];
+        self.write_initial_tape(&mut tape)?;
+        Ok(tape)
+    }
+
+    /// Overwrites the given tape with the tape at the start of the round. This reuses the memory
+    /// of the given tape where possible. Fails, leaving the tape as it was, if the parameters are
+    /// not valid.
+    pub fn write_initial_tape(&self, tape: &mut Vec<i32>) -> Result<(), BfError> {
+        self.validate()?;
+        self.rules.rule_set().write_initial_tape(self, tape);
+        Ok(())
+    }
 }
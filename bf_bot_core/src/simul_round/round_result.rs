@@ -1,31 +1,115 @@
-#[derive(PartialEq, Debug)]
+/// Why a bot lost a round.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum LossReason {
+    /// Its flag was zero at the end of two consecutive cycles.
+    FlagZeroed,
+    /// It moved its tape pointer off the tape, or otherwise left the playing field of the rules.
+    OffTape,
+    /// Neither bot could win any more before the maximum number of steps, so both lost and the
+    /// round is a draw. This is also the reason if the arena ended the round early, because it
+    /// detected that the round would otherwise run until the maximum number of steps.
+    Timeout,
+    /// The match that the round was part of used up its time budget before the round ended, so
+    /// both lost and the round is a draw.
+    OutOfTime,
+}
+
+/// The state of a round, without any details about how it came about.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum RoundStatus {
+    Ongoing,
+    StartBotWon,
+    EndBotWon,
+    Draw,
+}
+
+/// The result of a round after a step. Besides which bots lost, if any, it records why they lost
+/// and when, so that a narrow win can be told apart from a dominant one.
+#[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct RoundResult {
-    pub bot_a_lost: bool,
-    pub bot_b_lost: bool,
+    /// Why the start bot lost, or `None` if it did not lose.
+    pub bot_a_loss: Option<LossReason>,
+    /// Why the end bot lost, or `None` if it did not lose.
+    pub bot_b_loss: Option<LossReason>,
+    /// The number of cycles that had been executed when this result was decided.
+    pub nr_steps: u32,
+    /// A hash of the tape at the end of the round. Zero while the round is ongoing, because
+    /// computing it costs time on every step.
+    pub tape_hash: u64,
 }
 
 impl RoundResult {
-    pub fn new(bot_a_lost: bool, bot_b_lost: bool) -> Self {
+    /// Constructs a result that is not tied to a particular step or tape.
+    pub fn new(bot_a_loss: Option<LossReason>, bot_b_loss: Option<LossReason>) -> Self {
         RoundResult {
-            bot_a_lost: bot_a_lost,
-            bot_b_lost: bot_b_lost,
+            bot_a_loss,
+            bot_b_loss,
+            nr_steps: 0,
+            tape_hash: 0,
+        }
+    }
+
+    /// Returns this result, tied to the given step and the given hash of the tape.
+    pub fn at_step(self, nr_steps: u32, tape_hash: u64) -> Self {
+        RoundResult {
+            nr_steps,
+            tape_hash,
+            ..self
         }
     }
 
     pub fn round_ongoing() -> Self {
-        RoundResult::new(false, false)
+        RoundResult::new(None, None)
     }
 
+    /// Constructs a result in which the flag of the end bot was zeroed.
     pub fn start_bot_wins() -> Self {
-        RoundResult::new(false, true)
+        RoundResult::new(None, Some(LossReason::FlagZeroed))
     }
 
+    /// Constructs a result in which the flag of the start bot was zeroed.
     pub fn end_bot_wins() -> Self {
-        RoundResult::new(true, false)
+        RoundResult::new(Some(LossReason::FlagZeroed), None)
     }
 
+    /// Constructs a result in which the round timed out.
     pub fn draw() -> Self {
-        RoundResult::new(true, true)
+        RoundResult::new(Some(LossReason::Timeout), Some(LossReason::Timeout))
+    }
+
+    /// Constructs a result in which the round was cut short, because its match ran out of time.
+    pub fn out_of_time() -> Self {
+        RoundResult::new(Some(LossReason::OutOfTime), Some(LossReason::OutOfTime))
+    }
+
+    pub fn bot_a_lost(&self) -> bool {
+        self.bot_a_loss.is_some()
+    }
+
+    pub fn bot_b_lost(&self) -> bool {
+        self.bot_b_loss.is_some()
+    }
+
+    /// Returns whether the round is ongoing, was won by either bot, or is a draw.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bf_bot_core::simul_round::{LossReason, RoundResult, RoundStatus};
+    /// let result = RoundResult::new(Some(LossReason::OffTape), Some(LossReason::FlagZeroed));
+    /// assert_eq!(result.status(), RoundStatus::Draw);
+    /// assert_eq!(RoundResult::start_bot_wins().status(), RoundStatus::StartBotWon);
+    /// ```
+    pub fn status(&self) -> RoundStatus {
+        match (self.bot_a_lost(), self.bot_b_lost()) {
+            (false, false) => RoundStatus::Ongoing,
+            (false, true) => RoundStatus::StartBotWon,
+            (true, false) => RoundStatus::EndBotWon,
+            (true, true) => RoundStatus::Draw,
+        }
     }
 
     /// Returns true if this round has a winner. A round has a winner if and only if exactly one
@@ -42,7 +126,7 @@ impl RoundResult {
     /// assert!(!RoundResult::draw().has_winner());
     /// ```
     pub fn has_winner(&self) -> bool {
-        self.bot_a_lost ^ self.bot_b_lost
+        self.bot_a_lost() ^ self.bot_b_lost()
     }
 
     /// Returns true if this round is finished, false if it is still ongoing. Rounds are finished
@@ -58,6 +142,6 @@ impl RoundResult {
     /// assert!(RoundResult::draw().round_is_finished());
     /// ```
     pub fn round_is_finished(&self) -> bool {
-        self.bot_a_lost || self.bot_b_lost
+        self.bot_a_lost() || self.bot_b_lost()
     }
 }
@@ -0,0 +1,26 @@
+use alloc::boxed::Box;
+
+use engine::{BfJoustRules, MultipleFlagRules, RuleSet};
+
+/// The variant of the game that a round is played by: where the flags are, and when a bot loses.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum Rules {
+    /// The standard rules: each bot has one flag, the cell at its own end of the tape.
+    #[default]
+    Standard,
+    /// Each bot defends the given number of flags, the cells at its own end of the tape, and only
+    /// loses once all of them have been zero at the end of two consecutive cycles. The tape must be
+    /// more than twice this number of cells long.
+    MultipleFlags { nr_flags: u32 },
+}
+
+impl Rules {
+    /// Returns the rule set that the arena plays these rules with.
+    pub fn rule_set(self) -> Box<dyn RuleSet> {
+        match self {
+            Rules::Standard => Box::new(BfJoustRules),
+            Rules::MultipleFlags { nr_flags } => Box::new(MultipleFlagRules::new(nr_flags)),
+        }
+    }
+}
@@ -0,0 +1,9 @@
+/// The finalizer of SplitMix64, which turns similar inputs into unrelated outputs. Seeded random
+/// choices during a round mix the seed with the number of the cycle, so that every choice only
+/// depends on those two.
+pub fn mix(value: u64) -> u64 {
+    let mut z = value.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
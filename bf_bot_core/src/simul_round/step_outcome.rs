@@ -0,0 +1,53 @@
+use simul_round::{RoundResult, RoundStatus};
+
+/// What happened in a single step of a round: either the round goes on, or it has just ended with
+/// the given result.
+#[derive(Debug, Clone, PartialEq)]
+pub enum StepOutcome {
+    Continue,
+    Finished(RoundResult),
+}
+
+impl StepOutcome {
+    pub fn round_is_finished(&self) -> bool {
+        match *self {
+            StepOutcome::Continue => false,
+            StepOutcome::Finished(_) => true,
+        }
+    }
+
+    /// Returns the status of the round after this step.
+    pub fn status(&self) -> RoundStatus {
+        match *self {
+            StepOutcome::Continue => RoundStatus::Ongoing,
+            StepOutcome::Finished(ref result) => result.status(),
+        }
+    }
+
+    /// Returns the final result of the round, or `None` if it is still ongoing.
+    pub fn result(self) -> Option<RoundResult> {
+        match self {
+            StepOutcome::Continue => None,
+            StepOutcome::Finished(result) => Some(result),
+        }
+    }
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn status_continue_isOngoing() {
+        assert_eq!(StepOutcome::Continue.status(), RoundStatus::Ongoing);
+        assert!(!StepOutcome::Continue.round_is_finished());
+    }
+
+    #[test]
+    fn result_finished_returnsTheResult() {
+        let outcome = StepOutcome::Finished(RoundResult::end_bot_wins());
+        assert_eq!(outcome.status(), RoundStatus::EndBotWon);
+        assert_eq!(outcome.result(), Some(RoundResult::end_bot_wins()));
+    }
+}
@@ -0,0 +1,299 @@
+use std::collections::HashMap;
+
+use bf::{Bot, NamedBot};
+use match_runner::{Match, ScoringScheme};
+use simul_game::AllRounds;
+
+/// A bot on a hill, together with its current standing.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HillEntry {
+    pub name: String,
+    pub author: Option<String>,
+    pub bot: Bot,
+    /// The sum of the points this bot earned in its matches against all other bots on the hill.
+    pub points: f64,
+}
+
+/// The outcome of submitting a bot to a hill.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Submission {
+    /// The bot is now on the hill, at the given rank; zero being the top of the hill. If the hill
+    /// was full, the lowest ranked bot was evicted to make room.
+    Accepted {
+        rank: usize,
+        evicted: Option<HillEntry>,
+    },
+    /// The hill was full and the bot ranked lowest of all, so it was not added.
+    Rejected,
+}
+
+/// A king-of-the-hill tournament: a ranked set of at most `capacity` bots. Every new bot plays a
+/// complete match against every bot on the hill, after which all bots are ranked by the sum of
+/// their points. Bots with equal points keep the order in which they were submitted.
+///
+/// The results of all matches are kept, so that every pair of bots plays only once.
+#[derive(Debug, Clone)]
+pub struct Hill {
+    capacity: usize,
+    scoring_scheme: ScoringScheme,
+    /// Ordered by rank.
+    entries: Vec<HillEntry>,
+    /// The points that the first bot earned against the second, keyed by their names. Every pair
+    /// is stored only once, in the order in which they played.
+    results: HashMap<(String, String), f64>,
+}
+
+impl Hill {
+    /// Constructs an empty hill with room for `capacity` bots, that uses the default scoring
+    /// scheme.
+    pub fn new(capacity: usize) -> Hill {
+        Hill {
+            capacity,
+            scoring_scheme: ScoringScheme::default(),
+            entries: vec![],
+            results: HashMap::new(),
+        }
+    }
+
+    /// Selects the scoring scheme used to rank the bots. Must be called before any bot is
+    /// submitted.
+    pub fn with_scoring_scheme(self, scoring_scheme: ScoringScheme) -> Hill {
+        Hill { scoring_scheme, ..self }
+    }
+
+    pub fn get_capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Returns the bots on the hill, from the highest to the lowest ranked.
+    pub fn get_entries(&self) -> &[HillEntry] {
+        &self.entries
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Returns the points of every pair of bots that played each other, as the name of the first
+    /// bot, the name of the second and the points that the first earned against the second. Every
+    /// pair is returned once, in no particular order.
+    pub fn get_results(&self) -> impl Iterator<Item = (&str, &str, f64)> + '_ {
+        self.results
+            .iter()
+            .map(|((name, opponent), &points)| (name.as_str(), opponent.as_str(), points))
+    }
+
+    /// Records the points that the first named bot earned against the second, for example in an
+    /// earlier session. When both bots are submitted, they don't play each other again. The caller
+    /// is responsible for the result belonging to the bots that are submitted under these names.
+    pub fn add_result(&mut self, name: &str, opponent: &str, points: f64) {
+        self.results.insert((name.to_string(), opponent.to_string()), points);
+    }
+
+    /// Plays the given bot against every bot on the hill and ranks it. If a bot with the same name
+    /// is already on the hill, it is replaced, as is customary on BF Joust hills.
+    pub fn submit(&mut self, name: &str, bot: Bot) -> Submission {
+        self.submit_named(NamedBot::new(name, bot))
+    }
+
+    /// Submits the given bot like `submit` does, keeping track of its author.
+    pub fn submit_named(&mut self, named_bot: NamedBot) -> Submission {
+        let NamedBot { name, author, bot } = named_bot;
+        let name = name.as_str();
+        if let Some(index) = self.entries.iter().position(|entry| entry.name == name) {
+            let previous = self.entries.remove(index);
+            self.forget_results_of(&previous.name);
+        }
+        for incumbent in &self.entries {
+            if self.has_result(name, &incumbent.name) {
+                continue;
+            }
+            let points = Hill::play(self.scoring_scheme, &bot, &incumbent.bot);
+            self.results.insert((name.to_string(), incumbent.name.clone()), points);
+        }
+        self.entries.push(HillEntry {
+            name: name.to_string(),
+            author,
+            bot,
+            points: 0.0,
+        });
+        self.rank();
+
+        let evicted = if self.entries.len() > self.capacity {
+            let evicted = self.entries.pop().expect("The hill is over capacity.");
+            self.forget_results_of(&evicted.name);
+            self.rank();
+            if evicted.name == name {
+                return Submission::Rejected;
+            }
+            Some(evicted)
+        } else {
+            None
+        };
+        let rank = self.entries
+            .iter()
+            .position(|entry| entry.name == name)
+            .expect("The submitted bot is on the hill.");
+        Submission::Accepted { rank, evicted }
+    }
+
+    fn has_result(&self, name: &str, opponent: &str) -> bool {
+        let key = |first: &str, second: &str| (first.to_string(), second.to_string());
+        self.results.contains_key(&key(name, opponent)) ||
+        self.results.contains_key(&key(opponent, name))
+    }
+
+    /// Returns the points that the first named bot earned against the second.
+    fn points_between(&self, name: &str, opponent: &str) -> f64 {
+        let key = |first: &str, second: &str| (first.to_string(), second.to_string());
+        match self.results.get(&key(name, opponent)) {
+            Some(points) => *points,
+            // Every scoring scheme is symmetric: what one bot earns, the other loses.
+            None => -self.results[&key(opponent, name)],
+        }
+    }
+
+    /// Recomputes the points of every bot and sorts the bots by them.
+    fn rank(&mut self) {
+        let points = self.entries
+            .iter()
+            .map(|entry| {
+                self.entries
+                    .iter()
+                    .filter(|opponent| opponent.name != entry.name)
+                    .map(|opponent| self.points_between(&entry.name, &opponent.name))
+                    .sum()
+            })
+            .collect::<Vec<f64>>();
+        for (entry, points) in self.entries.iter_mut().zip(points) {
+            entry.points = points;
+        }
+        // The sort is stable, so bots with equal points keep their order of submission.
+        self.entries.sort_by(|a, b| b.points.total_cmp(&a.points));
+    }
+
+    fn forget_results_of(&mut self, name: &str) {
+        self.results.retain(|(first, second), _| first != name && second != name);
+    }
+
+    fn play(scoring_scheme: ScoringScheme, bot: &Bot, opponent: &Bot) -> f64 {
+        Match::new(bot, opponent)
+            .with_scoring_scheme(scoring_scheme)
+            .run_rounds(AllRounds::new().with_early_termination())
+            .expect("The standard rounds are valid.")
+            .points()
+    }
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod tests {
+    use super::*;
+    use bf::Instruction;
+
+    /// Its program, in BrainFuck: <
+    fn make_suicidal_bot() -> Bot {
+        Bot::new(vec![Instruction::MoveBack])
+    }
+
+    /// Its program, in BrainFuck: (>)*9[-]
+    /// Reaches the enemy flag on the shortest tape and clears it, but stops short on longer tapes.
+    fn make_short_tape_bot() -> Bot {
+        let mut program = vec![Instruction::MoveForward; 9];
+        program.extend(vec![Instruction::JumpIfZero(11),
+                            Instruction::Decrement,
+                            Instruction::JumpIfNonZero(9)]);
+        Bot::new(program)
+    }
+
+    fn names(hill: &Hill) -> Vec<&str> {
+        hill.get_entries().iter().map(|entry| entry.name.as_str()).collect()
+    }
+
+    #[test]
+    fn submit_emptyHill_acceptedAtTop() {
+        let mut hill = Hill::new(2);
+        assert_eq!(hill.submit("idle", Bot::new(vec![])),
+                   Submission::Accepted {
+                       rank: 0,
+                       evicted: None,
+                   });
+    }
+
+    #[test]
+    fn submit_strongerBot_ranksAboveWeakerBots() {
+        let mut hill = Hill::new(3);
+        hill.submit("suicidal", make_suicidal_bot());
+        hill.submit("idle", Bot::new(vec![]));
+        assert_eq!(names(&hill), vec!["idle", "suicidal"]);
+        assert_eq!(hill.get_entries()[0].points, 42.0);
+        assert_eq!(hill.get_entries()[1].points, -42.0);
+    }
+
+    #[test]
+    fn submitNamed_keepsAuthor() {
+        let mut hill = Hill::new(3);
+        hill.submit_named(NamedBot::new("idle", Bot::new(vec![])).with_author("Arjan"));
+        hill.submit("suicidal", make_suicidal_bot());
+        assert_eq!(hill.get_entries()[0].author, Some("Arjan".to_string()));
+        assert_eq!(hill.get_entries()[1].author, None);
+    }
+
+    #[test]
+    fn submit_fullHill_evictsLowestRanked() {
+        let mut hill = Hill::new(2);
+        hill.submit("suicidal", make_suicidal_bot());
+        hill.submit("idle", Bot::new(vec![]));
+        let submission = hill.submit("clearer", make_short_tape_bot());
+        match submission {
+            Submission::Accepted { evicted: Some(evicted), .. } => {
+                assert_eq!(evicted.name, "suicidal")
+            }
+            other => panic!("Expected an eviction, got {:?}", other),
+        }
+        assert_eq!(hill.len(), 2);
+        assert!(!names(&hill).contains(&"suicidal"));
+    }
+
+    #[test]
+    fn submit_fullHillAndWeakestBot_rejected() {
+        let mut hill = Hill::new(1);
+        hill.submit("idle", Bot::new(vec![]));
+        assert_eq!(hill.submit("suicidal", make_suicidal_bot()), Submission::Rejected);
+        assert_eq!(names(&hill), vec!["idle"]);
+        assert_eq!(hill.get_entries()[0].points, 0.0);
+    }
+
+    #[test]
+    fn submit_existingName_replacesBot() {
+        let mut hill = Hill::new(2);
+        hill.submit("idle", Bot::new(vec![]));
+        hill.submit("changing", make_suicidal_bot());
+        hill.submit("changing", Bot::new(vec![]));
+        assert_eq!(hill.len(), 2);
+        assert!(hill.get_entries().iter().all(|entry| entry.points == 0.0));
+    }
+
+    #[test]
+    fn submit_addedResult_notReplayed() {
+        let mut hill = Hill::new(2);
+        // The real result is a draw; the recorded one shows that it was used.
+        hill.add_result("idle", "other idle", 5.0);
+        hill.submit("idle", Bot::new(vec![]));
+        hill.submit("other idle", Bot::new(vec![]));
+        assert_eq!(names(&hill), vec!["idle", "other idle"]);
+        assert_eq!(hill.get_entries()[1].points, -5.0);
+    }
+
+    #[test]
+    fn getResults_onePerPair() {
+        let mut hill = Hill::new(3);
+        hill.submit("suicidal", make_suicidal_bot());
+        hill.submit("idle", Bot::new(vec![]));
+        assert_eq!(hill.get_results().collect::<Vec<_>>(), vec![("idle", "suicidal", 42.0)]);
+    }
+}
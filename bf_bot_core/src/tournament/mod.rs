@@ -0,0 +1,10 @@
+//! Tournaments between more than two bots, built on top of complete matches.
+
+pub use self::hill::{Hill, HillEntry, Submission};
+mod hill;
+
+pub use self::pairings::{PairingOutcome, Pairings};
+mod pairings;
+
+pub use self::round_robin::{RoundRobin, Standings, StandingsRow};
+mod round_robin;
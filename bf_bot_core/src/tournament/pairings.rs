@@ -0,0 +1,77 @@
+use match_runner::MatchResult;
+use tournament::RoundRobin;
+
+/// The outcome of a single pairing of a round-robin tournament, reported as soon as its match has
+/// been played.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PairingOutcome {
+    /// The index of the first bot, in the order in which the bots entered the tournament.
+    pub bot_a: usize,
+    /// The index of the second bot. It is always greater than that of the first bot.
+    pub bot_b: usize,
+    pub result: MatchResult,
+    /// The number of pairings that have been played so far, including this one.
+    pub nr_played: usize,
+    /// The number of pairings in the complete tournament.
+    pub nr_pairings: usize,
+}
+
+impl PairingOutcome {
+    /// Returns the percentage of the tournament that has been played, from 0 to 100.
+    pub fn progress(&self) -> f64 {
+        100.0 * self.nr_played as f64 / self.nr_pairings as f64
+    }
+}
+
+/// An iterator over the pairings of a round-robin tournament, that plays the match of every
+/// pairing as it is reached. It ends early if the tournament is cancelled; the match that was
+/// being played at that moment is yielded with its unfinished rounds ongoing. Created by
+/// `RoundRobin::pairings`.
+#[derive(Debug)]
+pub struct Pairings<'a> {
+    tournament: &'a RoundRobin,
+    bot_a: usize,
+    bot_b: usize,
+    nr_played: usize,
+}
+
+impl<'a> Pairings<'a> {
+    pub fn new(tournament: &'a RoundRobin) -> Pairings<'a> {
+        Pairings {
+            tournament,
+            bot_a: 0,
+            bot_b: 1,
+            nr_played: 0,
+        }
+    }
+}
+
+impl<'a> Iterator for Pairings<'a> {
+    type Item = PairingOutcome;
+
+    fn next(&mut self) -> Option<PairingOutcome> {
+        if self.bot_b >= self.tournament.nr_bots() || self.tournament.is_cancelled() {
+            return None;
+        }
+        let (bot_a, bot_b) = (self.bot_a, self.bot_b);
+        self.bot_b += 1;
+        if self.bot_b == self.tournament.nr_bots() {
+            self.bot_a += 1;
+            self.bot_b = self.bot_a + 1;
+        }
+        self.nr_played += 1;
+        Some(PairingOutcome {
+            bot_a,
+            bot_b,
+            result: self.tournament.play(bot_a, bot_b),
+            nr_played: self.nr_played,
+            nr_pairings: self.tournament.nr_pairings(),
+        })
+    }
+
+    /// The tournament can be cancelled at any time, so all pairings that are left are only an
+    /// upper bound.
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, Some(self.tournament.nr_pairings() - self.nr_played))
+    }
+}
@@ -0,0 +1,304 @@
+use std::fmt;
+use std::time::Duration;
+
+use bf::NamedBot;
+use cancellation::CancellationToken;
+use match_runner::{Match, MatchResult, ScoringScheme};
+use simul_game::AllRounds;
+use tournament::{PairingOutcome, Pairings};
+
+/// The record of a single bot in a round-robin tournament. Wins, losses and draws count complete
+/// matches: a match is won by the bot that earned more than zero points in it.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct StandingsRow {
+    pub name: String,
+    pub author: Option<String>,
+    pub wins: u32,
+    pub losses: u32,
+    pub draws: u32,
+    /// The sum of the points this bot earned in all of its matches.
+    pub points: f64,
+}
+
+impl StandingsRow {
+    fn new(bot: &NamedBot) -> StandingsRow {
+        StandingsRow {
+            name: bot.name.clone(),
+            author: bot.author.clone(),
+            wins: 0,
+            losses: 0,
+            draws: 0,
+            points: 0.0,
+        }
+    }
+
+    fn add_match(&mut self, points: f64) {
+        if points > 0.0 {
+            self.wins += 1;
+        } else if points < 0.0 {
+            self.losses += 1;
+        } else {
+            self.draws += 1;
+        }
+        self.points += points;
+    }
+}
+
+/// The final standings of a round-robin tournament, ordered from the highest to the lowest number
+/// of points. Bots with equal points keep the order in which they entered the tournament.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Standings {
+    pub rows: Vec<StandingsRow>,
+}
+
+impl fmt::Display for Standings {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name_width = self.rows.iter().map(|row| row.name.len()).max().unwrap_or(0).max(4);
+        writeln!(f,
+                 "{:>4}  {:<name_width$}  {:>4}  {:>6}  {:>5}  {:>8}",
+                 "Rank",
+                 "Name",
+                 "Wins",
+                 "Losses",
+                 "Draws",
+                 "Points",
+                 name_width = name_width)?;
+        for (rank, row) in self.rows.iter().enumerate() {
+            writeln!(f,
+                     "{:>4}  {:<name_width$}  {:>4}  {:>6}  {:>5}  {:>8.2}",
+                     rank + 1,
+                     row.name,
+                     row.wins,
+                     row.losses,
+                     row.draws,
+                     row.points,
+                     name_width = name_width)?;
+        }
+        Ok(())
+    }
+}
+
+/// A tournament in which every bot plays a complete match against every other bot.
+#[derive(Debug, Clone)]
+pub struct RoundRobin {
+    bots: Vec<NamedBot>,
+    scoring_scheme: ScoringScheme,
+    cancellation: Option<CancellationToken>,
+    time_budget: Option<Duration>,
+}
+
+impl RoundRobin {
+    /// Constructs a tournament between the given named bots, that uses the default scoring
+    /// scheme.
+    pub fn new(bots: Vec<NamedBot>) -> RoundRobin {
+        RoundRobin {
+            bots,
+            scoring_scheme: ScoringScheme::default(),
+            cancellation: None,
+            time_budget: None,
+        }
+    }
+
+    /// Selects the scoring scheme used to award points for each match.
+    pub fn with_scoring_scheme(self, scoring_scheme: ScoringScheme) -> RoundRobin {
+        RoundRobin { scoring_scheme, ..self }
+    }
+
+    /// Stops the tournament as soon as the given token is cancelled. No more pairings are played
+    /// then, and the match that was being played is cut short.
+    pub fn with_cancellation(self, cancellation: CancellationToken) -> RoundRobin {
+        RoundRobin { cancellation: Some(cancellation), ..self }
+    }
+
+    /// Limits the wall-clock time of every match, as `Match::with_time_budget` does.
+    pub fn with_time_budget(self, time_budget: Duration) -> RoundRobin {
+        RoundRobin { time_budget: Some(time_budget), ..self }
+    }
+
+    /// Returns true if the tournament has been cancelled.
+    pub fn is_cancelled(&self) -> bool {
+        self.cancellation.as_ref().is_some_and(|cancellation| cancellation.is_cancelled())
+    }
+
+    pub fn get_bots(&self) -> &[NamedBot] {
+        &self.bots
+    }
+
+    pub fn nr_bots(&self) -> usize {
+        self.bots.len()
+    }
+
+    /// Returns the number of matches in the tournament: one for every pair of bots.
+    pub fn nr_pairings(&self) -> usize {
+        self.bots.len() * self.bots.len().saturating_sub(1) / 2
+    }
+
+    /// Returns an iterator that plays the pairings one by one, and yields the outcome of each as
+    /// soon as it is known. Pairings are played in order: the first bot against every later bot,
+    /// then the second bot against every later bot, and so on.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bf_bot_core::bf::{Bot, NamedBot};
+    /// use bf_bot_core::tournament::RoundRobin;
+    /// let bots = ["a", "b", "c"].iter().map(|name| NamedBot::new(name, Bot::new(vec![])));
+    /// let tournament = RoundRobin::new(bots.collect());
+    /// let progress = tournament.pairings().map(|outcome| outcome.progress()).collect::<Vec<_>>();
+    /// assert_eq!(progress.last(), Some(&100.0));
+    /// ```
+    pub fn pairings(&self) -> Pairings<'_> {
+        Pairings::new(self)
+    }
+
+    /// Plays every pairing once and returns the standings. If the tournament is cancelled, the
+    /// standings only count the matches that were completed.
+    pub fn run(&self) -> Standings {
+        self.run_with_progress(|_| {})
+    }
+
+    /// Like `run`, but calls the given function with the outcome of every pairing as soon as it
+    /// has been played, for example to show progress.
+    pub fn run_with_progress<F>(&self, mut on_pairing: F) -> Standings
+        where F: FnMut(&PairingOutcome)
+    {
+        let mut rows = self.bots
+            .iter()
+            .map(StandingsRow::new)
+            .collect::<Vec<_>>();
+        for outcome in self.pairings() {
+            on_pairing(&outcome);
+            if !outcome.result.is_complete() {
+                continue;
+            }
+            let points = outcome.result.points();
+            rows[outcome.bot_a].add_match(points);
+            // Every scoring scheme is symmetric: what one bot earns, the other loses.
+            rows[outcome.bot_b].add_match(-points);
+        }
+        // The sort is stable, so bots with equal points keep their order.
+        rows.sort_by(|a, b| b.points.total_cmp(&a.points));
+        Standings { rows }
+    }
+
+    /// Plays the match between the bots with the given indices.
+    pub fn play(&self, bot_a: usize, bot_b: usize) -> MatchResult {
+        let pairing = Match::between(&self.bots[bot_a], &self.bots[bot_b])
+            .with_scoring_scheme(self.scoring_scheme);
+        let pairing = match self.cancellation {
+            Some(ref cancellation) => pairing.with_cancellation(cancellation.clone()),
+            None => pairing,
+        };
+        let pairing = match self.time_budget {
+            Some(time_budget) => pairing.with_time_budget(time_budget),
+            None => pairing,
+        };
+        pairing.run_rounds(AllRounds::new().with_early_termination())
+            .expect("The standard rounds are valid.")
+    }
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod tests {
+    use super::*;
+    use bf::{Bot, Instruction};
+
+    /// Its program, in BrainFuck: <
+    fn make_suicidal_bot() -> Bot {
+        Bot::new(vec![Instruction::MoveBack])
+    }
+
+    fn make_tournament() -> RoundRobin {
+        RoundRobin::new(vec![NamedBot::new("suicidal", make_suicidal_bot()).with_author("Arjan"),
+                             NamedBot::new("idle", Bot::new(vec![])),
+                             NamedBot::new("also idle", Bot::new(vec![]))])
+    }
+
+    #[test]
+    fn run_threeBots_countsEveryMatchTwice() {
+        let standings = make_tournament().run();
+        let row = |name: &str| standings.rows.iter().find(|row| row.name == name).unwrap().clone();
+        assert_eq!(row("suicidal").losses, 2);
+        assert_eq!((row("idle").wins, row("idle").draws), (1, 1));
+        assert_eq!(row("also idle").points, 42.0);
+        assert_eq!(row("suicidal").author, Some("Arjan".to_string()));
+    }
+
+    #[test]
+    fn run_threeBots_ordersByPoints() {
+        let names = make_tournament()
+            .run()
+            .rows
+            .into_iter()
+            .map(|row| row.name)
+            .collect::<Vec<_>>();
+        assert_eq!(names, vec!["idle", "also idle", "suicidal"]);
+    }
+
+    #[test]
+    fn pairings_threeBots_everyPairOnceInOrder() {
+        let pairs = make_tournament()
+            .pairings()
+            .map(|outcome| (outcome.bot_a, outcome.bot_b, outcome.nr_played))
+            .collect::<Vec<_>>();
+        assert_eq!(pairs, vec![(0, 1, 1), (0, 2, 2), (1, 2, 3)]);
+    }
+
+    #[test]
+    fn pairings_sizeHintCountsDown() {
+        let tournament = make_tournament();
+        let mut pairings = tournament.pairings();
+        assert_eq!(pairings.size_hint(), (0, Some(3)));
+        pairings.next();
+        assert_eq!(pairings.size_hint(), (0, Some(2)));
+    }
+
+    #[test]
+    fn runWithProgress_reportsEveryPairing() {
+        let mut progress = vec![];
+        let standings = make_tournament().run_with_progress(|outcome| {
+            progress.push(outcome.progress().round())
+        });
+        assert_eq!(progress, vec![33.0, 67.0, 100.0]);
+        assert_eq!(standings, make_tournament().run());
+    }
+
+    #[test]
+    fn runWithProgress_cancelledAfterFirstPairing_countsOnlyThatOne() {
+        let cancellation = CancellationToken::new();
+        let tournament = make_tournament().with_cancellation(cancellation.clone());
+        let mut nr_played = 0;
+        let standings = tournament.run_with_progress(|_| {
+            nr_played += 1;
+            cancellation.cancel();
+        });
+        assert_eq!(nr_played, 1);
+        let nr_matches = standings.rows.iter().map(|row| row.wins + row.losses + row.draws);
+        assert_eq!(nr_matches.sum::<u32>(), 2);
+    }
+
+    #[test]
+    fn run_noTimeBudget_everyMatchDrawn() {
+        let standings = make_tournament().with_time_budget(Duration::from_secs(0)).run();
+        assert!(standings.rows.iter().all(|row| row.draws == 2 && row.points == 0.0));
+    }
+
+    #[test]
+    fn pairings_singleBot_none() {
+        let tournament = RoundRobin::new(vec![NamedBot::new("alone", make_suicidal_bot())]);
+        assert_eq!(tournament.nr_pairings(), 0);
+        assert_eq!(tournament.pairings().count(), 0);
+    }
+
+    #[test]
+    fn display_printsHeaderAndOneLinePerBot() {
+        let text = make_tournament().run().to_string();
+        let lines = text.lines().collect::<Vec<_>>();
+        assert_eq!(lines.len(), 4);
+        assert_eq!(lines[1], "   1  idle          1       0      1     42.00");
+        assert_eq!(lines[3], "   3  suicidal      0       2      0    -84.00");
+    }
+}
@@ -0,0 +1,325 @@
+use std::mem;
+
+use evolution::{seeded_rng, Breeder, EvolutionParams, EvolutionRng};
+use fitness::FitnessFunction;
+use operators::{ranking, CrossoverOperator, MutationOperator};
+use population::{Individual, Population};
+
+/// How the maximum age grows from layer to layer, in multiples of the age gap.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AgingScheme {
+    /// 1, 2, 3, 4, 5, ...
+    Linear,
+    /// 1, 2, 4, 9, 16, 25, ...
+    Polynomial,
+    /// 1, 2, 4, 8, 16, ...
+    Exponential,
+}
+
+impl AgingScheme {
+    fn multiple(&self, layer: usize) -> u32 {
+        let layer = layer as u32;
+        match *self {
+            AgingScheme::Linear => layer + 1,
+            AgingScheme::Polynomial if layer < 2 => layer + 1,
+            AgingScheme::Polynomial => layer * layer,
+            AgingScheme::Exponential => 1 << layer,
+        }
+    }
+}
+
+/// Specifies the layers of an `AlpsRun`. The other parameters of the run are `EvolutionParams`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AlpsParams {
+    pub nr_layers: usize,
+    /// The number of generations between two replacements of the bottom layer by fresh random
+    /// genomes, which is also the maximum age of the bottom layer. Must be positive.
+    pub age_gap: u32,
+    pub aging: AgingScheme,
+    /// The number of `Op` genes of the fresh random genomes.
+    pub genome_length: usize,
+}
+
+impl AlpsParams {
+    /// Returns the maximum age of the individuals in the given layer, or `None` for the top layer,
+    /// in which individuals can grow as old as they like.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bf_bot_evolve::alps::{AgingScheme, AlpsParams};
+    /// let params = AlpsParams {
+    ///     nr_layers: 4,
+    ///     age_gap: 10,
+    ///     aging: AgingScheme::Polynomial,
+    ///     genome_length: 20,
+    /// };
+    /// let max_ages = (0..4).map(|layer| params.max_age(layer)).collect::<Vec<_>>();
+    /// assert_eq!(max_ages, vec![Some(10), Some(20), Some(40), None]);
+    /// ```
+    pub fn max_age(&self, layer: usize) -> Option<u32> {
+        if layer + 1 >= self.nr_layers {
+            None
+        } else {
+            Some(self.age_gap.max(1) * self.aging.multiple(layer))
+        }
+    }
+}
+
+impl Default for AlpsParams {
+    fn default() -> AlpsParams {
+        AlpsParams {
+            nr_layers: 5,
+            age_gap: 10,
+            aging: AgingScheme::Polynomial,
+            genome_length: 20,
+        }
+    }
+}
+
+/// The individuals of a single age layer, together with their ages.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Layer {
+    pub population: Population,
+    /// The age of every individual, in the order of the population. Fresh random genomes have age
+    /// zero, and an offspring is one generation older than its oldest parent.
+    pub ages: Vec<u32>,
+}
+
+impl Layer {
+    fn push(&mut self, individual: Individual, age: u32) {
+        self.population.individuals.push(individual);
+        self.ages.push(age);
+    }
+
+    /// Adds an evaluated individual that has grown too old for the layer below, if the layer has
+    /// room for it or the individual is fitter than the weakest individual of the layer, which it
+    /// then replaces.
+    fn promote(&mut self, individual: Individual, age: u32, capacity: usize) {
+        if self.population.len() < capacity {
+            self.push(individual, age);
+            return;
+        }
+        let fitnesses = self.population.fitnesses();
+        if let Some(&weakest) = ranking(&fitnesses).last() {
+            if individual.fitness.unwrap_or(f64::NEG_INFINITY) > fitnesses[weakest] {
+                self.population.individuals[weakest] = individual;
+                self.ages[weakest] = age;
+            }
+        }
+    }
+}
+
+/// Evolves a population in age layers, with ALPS. Every generation, each layer is bred from the
+/// individuals of its own layer and the layer below that aren't too old for it, so that good
+/// young individuals can move up. Individuals that have grown too old for their layer move up to
+/// the next layer if they are fitter than its weakest individual, and die otherwise. Every
+/// `age_gap` generations, the bottom layer is replaced by fresh random genomes.
+///
+/// The population size, elitism, selection method and other breeding parameters of the
+/// `EvolutionParams` apply to every layer separately. Self-adaptive mutation rates are inherited
+/// as usual, but the one-fifth rule isn't applied.
+pub struct AlpsRun {
+    params: EvolutionParams,
+    alps_params: AlpsParams,
+    layers: Vec<Layer>,
+    generation: u32,
+    rng: EvolutionRng,
+    breeder: Breeder,
+    fitness_function: Box<dyn FitnessFunction>,
+}
+
+impl AlpsRun {
+    /// Starts a run with fresh random genomes in the bottom layer, which are evaluated right away.
+    /// All other layers start out empty.
+    pub fn new(params: EvolutionParams,
+               alps_params: AlpsParams,
+               mutation: Box<dyn MutationOperator>,
+               crossover: Box<dyn CrossoverOperator>,
+               fitness_function: Box<dyn FitnessFunction>)
+               -> AlpsRun {
+        let mut run = AlpsRun {
+            rng: seeded_rng(params.seed),
+            breeder: Breeder::new(mutation, crossover, Box::new(params.selection)),
+            layers: vec![Layer::default(); alps_params.nr_layers.max(1)],
+            params,
+            alps_params,
+            generation: 0,
+            fitness_function,
+        };
+        run.layers[0] = run.fresh_layer();
+        run
+    }
+
+    /// Breeds every layer from the top down, so that individuals that move up compete with the
+    /// new generation of the layer above, and replaces the bottom layer if that is due.
+    pub fn run_generation(&mut self) {
+        let old_layers = mem::take(&mut self.layers);
+        let nr_layers = old_layers.len();
+        let mut new_layers = vec![Layer::default(); nr_layers];
+        for index in (0..nr_layers).rev() {
+            let max_age = self.alps_params.max_age(index);
+            let is_young = |age: u32| max_age.is_none_or(|max_age| age < max_age);
+            if index + 1 < nr_layers {
+                let old_layer = &old_layers[index];
+                let individuals = old_layer.population.individuals.iter();
+                for (individual, &age) in individuals.zip(&old_layer.ages) {
+                    if !is_young(age) {
+                        new_layers[index + 1].promote(individual.clone(),
+                                                      age + 1,
+                                                      self.params.population_size);
+                    }
+                }
+            }
+            let mut pool = Layer::default();
+            for layer in &old_layers[index.saturating_sub(1)..=index] {
+                for (individual, &age) in layer.population.individuals.iter().zip(&layer.ages) {
+                    if is_young(age) {
+                        pool.push(individual.clone(), age);
+                    }
+                }
+            }
+            if !pool.population.is_empty() {
+                new_layers[index] = self.breed_layer(&pool);
+            }
+        }
+        self.layers = new_layers;
+        self.generation += 1;
+        if self.generation.is_multiple_of(self.alps_params.age_gap.max(1)) {
+            self.layers[0] = self.fresh_layer();
+        }
+    }
+
+    /// Runs the given number of generations.
+    pub fn run_generations(&mut self, nr_generations: u32) {
+        for _ in 0..nr_generations {
+            self.run_generation();
+        }
+    }
+
+    /// Breeds and evaluates a new layer from the given parents.
+    fn breed_layer(&mut self, parents: &Layer) -> Layer {
+        let (mut population, parentages) =
+            self.breeder.breed_generation_with_parentage(&parents.population,
+                                                         &self.params,
+                                                         &mut self.rng);
+        population.evaluate(&*self.fitness_function);
+        let ages = parentages.iter()
+            .map(|parentage| {
+                parentage.parents().into_iter().map(|index| parents.ages[index]).max().unwrap() + 1
+            })
+            .collect();
+        Layer { population, ages }
+    }
+
+    fn fresh_layer(&mut self) -> Layer {
+        let mut population = Population::random(self.params.population_size,
+                                                self.alps_params.genome_length,
+                                                &mut self.rng);
+        population.evaluate(&*self.fitness_function);
+        Layer {
+            ages: vec![0; population.len()],
+            population,
+        }
+    }
+
+    /// Returns the layers, from the youngest to the oldest.
+    pub fn get_layers(&self) -> &[Layer] {
+        &self.layers
+    }
+
+    /// Returns the number of generations that have been run so far.
+    pub fn get_generation(&self) -> u32 {
+        self.generation
+    }
+
+    /// Returns the fittest individual of any layer.
+    pub fn best(&self) -> Option<&Individual> {
+        self.layers
+            .iter()
+            .filter_map(|layer| layer.population.best())
+            .fold(None, |best: Option<&Individual>, individual| match best {
+                Some(best) if best.fitness >= individual.fitness => Some(best),
+                _ => Some(individual),
+            })
+    }
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod tests {
+    use super::*;
+    use bf_bot_core::bf::{Bot, Instruction};
+    use operators::{PointMutation, SinglePointCrossover};
+
+    /// Rewards bots for every increment in their program.
+    fn count_increments(bot: &Bot) -> f64 {
+        bot.get_program().iter().filter(|&i| *i == Instruction::Increment).count() as f64
+    }
+
+    fn make_run(aging: AgingScheme) -> AlpsRun {
+        let params = EvolutionParams {
+            population_size: 8,
+            elitism: 1,
+            seed: 3,
+            ..EvolutionParams::default()
+        };
+        let alps_params = AlpsParams {
+            nr_layers: 3,
+            age_gap: 3,
+            aging,
+            genome_length: 10,
+        };
+        AlpsRun::new(params,
+                     alps_params,
+                     Box::new(PointMutation),
+                     Box::new(SinglePointCrossover),
+                     Box::new(count_increments))
+    }
+
+    #[test]
+    fn new_onlyBottomLayerFilled() {
+        let run = make_run(AgingScheme::Linear);
+        let sizes = run.get_layers().iter().map(|layer| layer.population.len()).collect::<Vec<_>>();
+        assert_eq!(sizes, vec![8, 0, 0]);
+        assert!(run.get_layers()[0].ages.iter().all(|&age| age == 0));
+    }
+
+    #[test]
+    fn runGenerations_agesStayWithinTheirLayer() {
+        for &aging in &[AgingScheme::Linear, AgingScheme::Polynomial, AgingScheme::Exponential] {
+            let mut run = make_run(aging);
+            for _ in 0..20 {
+                run.run_generation();
+                for (index, layer) in run.get_layers().iter().enumerate() {
+                    let max_age = run.alps_params.max_age(index).unwrap_or(u32::MAX);
+                    assert!(layer.ages.iter().all(|&age| age <= max_age));
+                    assert_eq!(layer.ages.len(), layer.population.len());
+                    assert!(layer.population.len() <= 8);
+                }
+            }
+            assert!(run.get_layers().iter().all(|layer| !layer.population.is_empty()));
+        }
+    }
+
+    #[test]
+    fn runGenerations_ageGapReached_bottomLayerReplaced() {
+        let mut run = make_run(AgingScheme::Linear);
+        run.run_generations(2);
+        assert!(run.get_layers()[0].ages.iter().all(|&age| age > 0));
+        run.run_generation();
+        assert!(run.get_layers()[0].ages.iter().all(|&age| age == 0));
+        assert!(run.get_layers()[0].population.individuals.iter().all(|ind| ind.fitness.is_some()));
+    }
+
+    #[test]
+    fn best_isBestOfAllLayers() {
+        let mut run = make_run(AgingScheme::Polynomial);
+        run.run_generations(10);
+        let best_fitness = run.best().unwrap().fitness;
+        assert!(run.get_layers()
+            .iter()
+            .filter_map(|layer| layer.population.best())
+            .all(|best| best.fitness <= best_fitness));
+    }
+}
@@ -0,0 +1,8 @@
+//! The age-layered population structure (ALPS) of Hornby. The population is divided into layers
+//! by age: the number of generations that the oldest part of an individual's genetic material has
+//! been evolving. Individuals only compete with others of about the same age, and the bottom layer
+//! is regularly replaced by fresh random genomes. New lineages thus get the time to mature before
+//! they have to compete with the old, highly optimized ones, so that a long run keeps exploring
+//! instead of converging on a single lineage.
+pub use self::alps_run::{AgingScheme, AlpsParams, AlpsRun, Layer};
+mod alps_run;
@@ -0,0 +1,255 @@
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+
+use evolution::{seeded_rng, Breeder, EvolutionParams, EvolutionRng};
+use fitness::HillFitness;
+use operators::{CrossoverOperator, MutationOperator};
+use population::{Individual, Population};
+
+/// Specifies how the populations of a coevolution run are pitted against each other.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CoevolutionParams {
+    /// The number of fittest individuals of every population that the individuals of all other
+    /// populations play against. These are the parasites; the individuals being evaluated are the
+    /// hosts.
+    pub nr_parasites: usize,
+    /// Whether to use competitive fitness sharing. Without it, the fitness of a host is its
+    /// average normalized match score against all parasites. With it, beating a parasite is
+    /// worth 1 divided by the number of hosts in the same population that beat it, so that hosts
+    /// are rewarded for beating parasites that few others can beat.
+    pub fitness_sharing: bool,
+}
+
+impl Default for CoevolutionParams {
+    fn default() -> CoevolutionParams {
+        CoevolutionParams {
+            nr_parasites: 5,
+            fitness_sharing: true,
+        }
+    }
+}
+
+/// Evolves several populations, each of which is evaluated against the fittest individuals of all
+/// other populations of the previous generation. At least two populations are needed; a single
+/// population has no opponents, and all of its individuals get a fitness of zero.
+///
+/// All populations are bred with the same parameters and operators.
+pub struct CoevolutionRun {
+    params: EvolutionParams,
+    coevolution_params: CoevolutionParams,
+    populations: Vec<Population>,
+    generation: u32,
+    rng: EvolutionRng,
+    breeder: Breeder,
+}
+
+impl CoevolutionRun {
+    /// Starts a run from the given initial populations, which are evaluated right away. Because
+    /// nothing is known about the initial individuals yet, the first individuals of every
+    /// population serve as the initial parasites.
+    pub fn new(params: EvolutionParams,
+               coevolution_params: CoevolutionParams,
+               populations: Vec<Population>,
+               mutation: Box<dyn MutationOperator>,
+               crossover: Box<dyn CrossoverOperator>)
+               -> CoevolutionRun {
+        let mut run = CoevolutionRun {
+            rng: seeded_rng(params.seed),
+            breeder: Breeder::new(mutation, crossover, Box::new(params.selection)),
+            params,
+            coevolution_params,
+            populations,
+            generation: 0,
+        };
+        let parasites = run.pick_parasites();
+        run.evaluate(&parasites);
+        run
+    }
+
+    /// Breeds the next generation of every population, and evaluates it against the parasites
+    /// picked from the current generation.
+    pub fn run_generation(&mut self) {
+        let parasites = self.pick_parasites();
+        let mut offspring = Vec::with_capacity(self.populations.len());
+        for population in &self.populations {
+            offspring.push(self.breeder.breed_generation(population, &self.params, &mut self.rng));
+        }
+        self.populations = offspring;
+        self.evaluate(&parasites);
+        self.generation += 1;
+    }
+
+    /// Runs the given number of generations.
+    pub fn run_generations(&mut self, nr_generations: u32) {
+        for _ in 0..nr_generations {
+            self.run_generation();
+        }
+    }
+
+    /// Returns the parasites of every population.
+    fn pick_parasites(&self) -> Vec<Vec<Individual>> {
+        self.populations
+            .iter()
+            .map(|population| population.fittest(self.coevolution_params.nr_parasites))
+            .collect()
+    }
+
+    /// Evaluates every population against the parasites of all other populations. The fitness of
+    /// the elites is evaluated again, since their opponents have changed.
+    fn evaluate(&mut self, parasites: &[Vec<Individual>]) {
+        for index in 0..self.populations.len() {
+            let opponents = parasites.iter()
+                .enumerate()
+                .filter(|&(other, _)| other != index)
+                .flat_map(|(_, parasites)| parasites.iter().cloned())
+                .collect::<Vec<_>>();
+            let scores = score_matrix(&self.populations[index], &opponents);
+            let fitnesses = if self.coevolution_params.fitness_sharing {
+                shared_fitnesses(&scores, opponents.len())
+            } else {
+                average_scores(&scores, opponents.len())
+            };
+            let individuals = self.populations[index].individuals.iter_mut();
+            for (individual, fitness) in individuals.zip(fitnesses) {
+                individual.fitness = Some(fitness);
+            }
+        }
+    }
+
+    pub fn get_populations(&self) -> &[Population] {
+        &self.populations
+    }
+
+    /// Returns the number of generations that have been run so far.
+    pub fn get_generation(&self) -> u32 {
+        self.generation
+    }
+}
+
+/// Returns, for every host, its normalized match score against every parasite.
+#[cfg(not(feature = "rayon"))]
+fn score_matrix(hosts: &Population, parasites: &[Individual]) -> Vec<Vec<f64>> {
+    hosts.individuals
+        .iter()
+        .map(|host| {
+            parasites.iter()
+                .map(|parasite| HillFitness::normalized_score(&host.bot, &parasite.bot))
+                .collect()
+        })
+        .collect()
+}
+
+/// Returns, for every host, its normalized match score against every parasite. The hosts are
+/// scored in parallel.
+#[cfg(feature = "rayon")]
+fn score_matrix(hosts: &Population, parasites: &[Individual]) -> Vec<Vec<f64>> {
+    hosts.individuals
+        .par_iter()
+        .map(|host| {
+            parasites.iter()
+                .map(|parasite| HillFitness::normalized_score(&host.bot, &parasite.bot))
+                .collect()
+        })
+        .collect()
+}
+
+fn average_scores(scores: &[Vec<f64>], nr_parasites: usize) -> Vec<f64> {
+    scores.iter()
+        .map(|row| if nr_parasites == 0 {
+            0.0
+        } else {
+            row.iter().sum::<f64>() / nr_parasites as f64
+        })
+        .collect()
+}
+
+/// Computes competitive fitness sharing: every parasite is worth one point in total, which is
+/// shared equally among the hosts that beat it.
+fn shared_fitnesses(scores: &[Vec<f64>], nr_parasites: usize) -> Vec<f64> {
+    let nr_winners = (0..nr_parasites)
+        .map(|parasite| scores.iter().filter(|row| row[parasite] > 0.0).count())
+        .collect::<Vec<_>>();
+    scores.iter()
+        .map(|row| {
+            (0..nr_parasites)
+                .filter(|&parasite| row[parasite] > 0.0)
+                .map(|parasite| 1.0 / nr_winners[parasite] as f64)
+                .sum()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod tests {
+    use super::*;
+    use bf_bot_core::bf::{Bot, Instruction};
+    use evolution::seeded_rng;
+    use operators::{PointMutation, SinglePointCrossover};
+
+    #[test]
+    fn sharedFitnesses_rareVictoriesCountMore() {
+        let scores = vec![vec![1.0, 1.0], vec![1.0, -1.0], vec![0.0, -1.0]];
+        assert_eq!(shared_fitnesses(&scores, 2), vec![1.5, 0.5, 0.0]);
+    }
+
+    #[test]
+    fn averageScores_noParasites_zero() {
+        assert_eq!(average_scores(&[vec![], vec![]], 0), vec![0.0, 0.0]);
+    }
+
+    fn make_run(fitness_sharing: bool) -> CoevolutionRun {
+        let params = EvolutionParams {
+            population_size: 4,
+            ..EvolutionParams::default()
+        };
+        let coevolution_params = CoevolutionParams {
+            nr_parasites: 2,
+            fitness_sharing,
+        };
+        let populations = vec![Population::random(4, 5, &mut seeded_rng(1)),
+                               Population::random(4, 5, &mut seeded_rng(2))];
+        CoevolutionRun::new(params,
+                            coevolution_params,
+                            populations,
+                            Box::new(PointMutation),
+                            Box::new(SinglePointCrossover))
+    }
+
+    #[test]
+    fn new_evaluatesEveryIndividual() {
+        let run = make_run(true);
+        assert!(run.get_populations()
+            .iter()
+            .flat_map(|population| &population.individuals)
+            .all(|individual| individual.fitness.is_some()));
+    }
+
+    #[test]
+    fn runGenerations_keepsPopulationsEvaluated() {
+        let mut run = make_run(false);
+        run.run_generations(2);
+        assert_eq!(run.get_generation(), 2);
+        for population in run.get_populations() {
+            assert_eq!(population.len(), 4);
+            assert!(population.fitnesses().iter().all(|fitness| (-1.0..=1.0).contains(fitness)));
+        }
+    }
+
+    #[test]
+    fn evaluate_hostsPlayOtherPopulation() {
+        // The idle bot beats the suicidal bot in every round, and draws against the other idle bot.
+        let idle = Population::from_bots(&[Bot::new(vec![]), Bot::new(vec![])]);
+        let suicidal = Population::from_bots(&[Bot::new(vec![Instruction::MoveBack])]);
+        let run = CoevolutionRun::new(EvolutionParams::default(),
+                                      CoevolutionParams {
+                                          nr_parasites: 1,
+                                          fitness_sharing: false,
+                                      },
+                                      vec![idle, suicidal],
+                                      Box::new(PointMutation),
+                                      Box::new(SinglePointCrossover));
+        assert_eq!(run.get_populations()[0].fitnesses(), vec![1.0, 1.0]);
+        assert_eq!(run.get_populations()[1].fitnesses(), vec![-1.0]);
+    }
+}
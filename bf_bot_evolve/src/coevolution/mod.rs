@@ -0,0 +1,5 @@
+//! Coevolution: several populations evolve side by side and serve as each other's opponents, in
+//! place of a fixed hill. When one population finds a way to beat the other, the other is pushed to
+//! find a counter, so the arms race keeps producing stronger and more robust bots.
+pub use self::coevolution_run::{CoevolutionParams, CoevolutionRun};
+mod coevolution_run;
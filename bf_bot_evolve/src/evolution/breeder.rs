@@ -0,0 +1,134 @@
+use rand::{Rng, RngCore};
+
+use evolution::EvolutionParams;
+use genome::{Genome, MAX_EXPANDED_LENGTH};
+use operators::{ranking, CrossoverOperator, MutationOperator, SelectionStrategy};
+use population::{Individual, Population};
+
+/// The genetic operators that together turn one generation into the next.
+pub struct Breeder {
+    pub mutation: Box<dyn MutationOperator>,
+    pub crossover: Box<dyn CrossoverOperator>,
+    pub selection: Box<dyn SelectionStrategy>,
+}
+
+impl Breeder {
+    pub fn new(mutation: Box<dyn MutationOperator>,
+               crossover: Box<dyn CrossoverOperator>,
+               selection: Box<dyn SelectionStrategy>)
+               -> Breeder {
+        Breeder {
+            mutation,
+            crossover,
+            selection,
+        }
+    }
+
+    /// Breeds the next generation of an evaluated population. The elites are copied along with
+    /// their fitness; all other offspring still need to be evaluated.
+    pub fn breed_generation(&self,
+                            population: &Population,
+                            params: &EvolutionParams,
+                            rng: &mut dyn RngCore)
+                            -> Population {
+        self.breed_generation_with_parentage(population, params, rng).0
+    }
+
+    /// Breeds the next generation like `breed_generation`, and also returns the parentage of every
+    /// individual of the next generation, in the same order.
+    pub fn breed_generation_with_parentage(&self,
+                                           population: &Population,
+                                           params: &EvolutionParams,
+                                           rng: &mut dyn RngCore)
+                                           -> (Population, Vec<Parentage>) {
+        let nr_elites = params.elitism.min(params.population_size);
+        let fitnesses = population.fitnesses();
+        let elites = ranking(&fitnesses).into_iter().take(nr_elites).collect::<Vec<_>>();
+        let mut offspring = elites.iter()
+            .map(|&index| population.individuals[index].clone())
+            .collect::<Vec<_>>();
+        let mut parentages = elites.into_iter().map(Parentage::Elite).collect::<Vec<_>>();
+        for _ in nr_elites..params.population_size {
+            let (genome, parentage) = self.breed(population, &fitnesses, params, rng);
+            offspring.push(Individual::new(genome));
+            parentages.push(parentage);
+        }
+        (Population { individuals: offspring }, parentages)
+    }
+
+    /// Produces a single offspring of the given population.
+    fn breed(&self,
+             population: &Population,
+             fitnesses: &[f64],
+             params: &EvolutionParams,
+             rng: &mut dyn RngCore)
+             -> (Genome, Parentage) {
+        let first_parent = self.selection.select(fitnesses, rng);
+        let parent_a = &population.individuals[first_parent].genome;
+        let (child, second_parent) = if rng.gen_bool(params.crossover_rate) {
+            let second_parent = self.selection.select(fitnesses, rng);
+            let parent_b = &population.individuals[second_parent].genome;
+            (self.crossover.crossover(parent_a, parent_b, rng), Some(second_parent))
+        } else {
+            (parent_a.clone(), None)
+        };
+        let own_rate = params.mutation_adaptation.and_then(|adaptation| {
+            adaptation.offspring_rate(parent_a.mutation_rate.unwrap_or(params.mutation_rate), rng)
+        });
+        let (child, mutation) = if rng.gen_bool(own_rate.unwrap_or(params.mutation_rate)) {
+            let (mutant, operator) = self.mutation.mutate_named(&child, rng);
+            (mutant, Some(operator.to_string()))
+        } else {
+            (child, None)
+        };
+        let too_long = params.max_genome_length.is_some_and(|max_length| {
+            child.nr_ops() > max_length
+        }) || child.expanded_length() > MAX_EXPANDED_LENGTH;
+        let (mut child, parentage) = if too_long {
+            (parent_a.clone(),
+             Parentage::Offspring {
+                 first_parent,
+                 second_parent: None,
+                 mutation: None,
+             })
+        } else {
+            (child,
+             Parentage::Offspring {
+                 first_parent,
+                 second_parent,
+                 mutation,
+             })
+        };
+        child.mutation_rate = own_rate;
+        (child, parentage)
+    }
+}
+
+/// Where an individual of a freshly bred generation came from, in terms of the indices of the
+/// individuals in the generation that it was bred from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Parentage {
+    /// An unchanged copy of one of the fittest individuals.
+    Elite(usize),
+    /// A new individual. Its second parent is only set if it was produced by crossover, and its
+    /// mutation is the name of the mutation operator that altered it, if it was mutated.
+    Offspring {
+        first_parent: usize,
+        second_parent: Option<usize>,
+        mutation: Option<String>,
+    },
+}
+
+impl Parentage {
+    /// Returns the indices of the one or two parents.
+    pub fn parents(&self) -> Vec<usize> {
+        match *self {
+            Parentage::Elite(index) => vec![index],
+            Parentage::Offspring { first_parent, second_parent, .. } => {
+                let mut parents = vec![first_parent];
+                parents.extend(second_parent);
+                parents
+            }
+        }
+    }
+}
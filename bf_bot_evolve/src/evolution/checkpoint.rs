@@ -0,0 +1,411 @@
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, BufWriter, Write};
+use std::path::Path;
+
+use evolution::{EvolutionParams, HallOfFame, Lineage, LineageRecord, MutationImpact,
+                OperatorImpact};
+use genome::Genome;
+use population::{Individual, Population};
+use speciation::{Speciation, Species};
+
+/// The first line of every checkpoint file, which identifies the format.
+const HEADER: &str = "bf_bot_evolve checkpoint 1";
+
+/// Precedes the mutation rate of a self-adaptive genome, between the fitness and the source code
+/// of an individual. Source code never starts with it.
+const MUTATION_RATE_PREFIX: &str = "mutation_rate=";
+
+/// The state of an evolution run that doesn't use any of the options that keep state of their
+/// own, apart from its operators and fitness function, which can't be stored. Resuming from a
+/// checkpoint with the same operators and fitness function continues the run exactly as if it had
+/// never been interrupted.
+///
+/// Checkpoints are stored as text, one value per line. Genomes are stored as source code.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Checkpoint {
+    pub params: EvolutionParams,
+    pub generation: u32,
+    /// The number of words that the random number generator has produced since it was seeded.
+    pub rng_word_pos: u128,
+    pub population: Population,
+    pub hall_of_fame: Option<HallOfFame>,
+    pub speciation: Option<Speciation>,
+    /// The stage of the curriculum that the population was evaluated in, if any.
+    pub curriculum_stage: Option<usize>,
+    /// How the mutation operators fared in the current generation, and in all generations of the
+    /// run so far.
+    pub mutation_impact: MutationImpact,
+    pub total_mutation_impact: MutationImpact,
+    pub lineage: Lineage,
+}
+
+impl Checkpoint {
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        self.write(&mut writer)?;
+        writer.flush()
+    }
+
+    pub fn load<P: AsRef<Path>>(path: P) -> io::Result<Checkpoint> {
+        Checkpoint::read(BufReader::new(File::open(path)?))
+    }
+
+    pub fn write<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        writeln!(writer, "{}", HEADER)?;
+        writeln!(writer, "population_size {}", self.params.population_size)?;
+        writeln!(writer, "crossover_rate {}", self.params.crossover_rate)?;
+        writeln!(writer, "mutation_rate {}", self.params.mutation_rate)?;
+        if let Some(mutation_adaptation) = self.params.mutation_adaptation {
+            writeln!(writer, "mutation_adaptation {}", mutation_adaptation)?;
+        }
+        writeln!(writer, "selection {}", self.params.selection)?;
+        writeln!(writer, "elitism {}", self.params.elitism)?;
+        if let Some(max_genome_length) = self.params.max_genome_length {
+            writeln!(writer, "max_genome_length {}", max_genome_length)?;
+        }
+        writeln!(writer, "seed {}", self.params.seed)?;
+        writeln!(writer, "generation {}", self.generation)?;
+        writeln!(writer, "rng_word_pos {}", self.rng_word_pos)?;
+        if let Some(ref hall_of_fame) = self.hall_of_fame {
+            writeln!(writer,
+                     "hall_of_fame {} {} {}",
+                     hall_of_fame.get_capacity(),
+                     hall_of_fame.get_interval(),
+                     hall_of_fame.get_weight())?;
+            for member in hall_of_fame.get_members() {
+                write!(writer, "hall_of_fame_member ")?;
+                write_individual(writer, member)?;
+            }
+        }
+        if let Some(ref speciation) = self.speciation {
+            writeln!(writer, "speciation {}", speciation.get_threshold())?;
+            // Representatives are only compared by their genes, so their mutation rates are left
+            // out.
+            for species in speciation.get_species() {
+                let members = species.members.iter().map(|index| index.to_string());
+                writeln!(writer,
+                         "species {} {}",
+                         join_or_dash(members),
+                         species.representative.to_source())?;
+            }
+        }
+        if let Some(curriculum_stage) = self.curriculum_stage {
+            writeln!(writer, "curriculum_stage {}", curriculum_stage)?;
+        }
+        // Operator names can contain spaces, so they come last.
+        for (key, impact) in &[("mutation_impact", &self.mutation_impact),
+                               ("total_mutation_impact", &self.total_mutation_impact)] {
+            for (operator, counts) in impact.iter() {
+                writeln!(writer,
+                         "{} {} {} {} {}",
+                         key,
+                         counts.nr_offspring,
+                         counts.nr_improved,
+                         counts.nr_worsened,
+                         operator)?;
+            }
+        }
+        writeln!(writer, "lineage_next_id {}", self.lineage.get_next_id())?;
+        let current_ids = self.lineage
+            .get_current_ids()
+            .iter()
+            .map(|id| id.to_string())
+            .collect::<Vec<_>>();
+        writeln!(writer, "lineage_current_ids {}", current_ids.join(" "))?;
+        for record in self.lineage.get_records() {
+            writeln!(writer,
+                     "ancestor {} {} {} {} {}",
+                     record.id,
+                     record.generation,
+                     format_fitness(record.fitness),
+                     join_or_dash(record.parent_ids.iter().map(|id| id.to_string())),
+                     record.source)?;
+        }
+        for individual in &self.population.individuals {
+            write!(writer, "individual ")?;
+            write_individual(writer, individual)?;
+        }
+        Ok(())
+    }
+
+    pub fn read<R: BufRead>(reader: R) -> io::Result<Checkpoint> {
+        let mut lines = reader.lines();
+        if lines.next().transpose()?.as_deref() != Some(HEADER) {
+            return Err(invalid_data("Not a checkpoint file".to_string()));
+        }
+        let mut values = vec![];
+        let mut individuals = vec![];
+        let mut mutation_impact = MutationImpact::new();
+        let mut total_mutation_impact = MutationImpact::new();
+        let mut records = vec![];
+        let mut hall_of_fame_members = vec![];
+        let mut species = vec![];
+        for line in lines {
+            let line = line?;
+            let (key, value) = line.split_once(' ').unwrap_or((&line, ""));
+            match key {
+                "individual" => individuals.push(parse_individual(value)?),
+                "mutation_impact" => parse_impact(value, &mut mutation_impact)?,
+                "total_mutation_impact" => parse_impact(value, &mut total_mutation_impact)?,
+                "ancestor" => records.push(parse_record(value)?),
+                "hall_of_fame_member" => hall_of_fame_members.push(parse_individual(value)?),
+                "species" => species.push(parse_species(value)?),
+                _ => values.push((key.to_string(), value.to_string())),
+            }
+        }
+        let optional_value = |key: &str| {
+            values.iter().find(|(k, _)| k == key).map(|(_, value)| value.as_str())
+        };
+        let value = |key: &str| {
+            optional_value(key).ok_or_else(|| invalid_data(format!("Missing {}", key)))
+        };
+        let generation = parse(value("generation")?)?;
+        let population = Population { individuals };
+        let current_ids = value("lineage_current_ids")?
+            .split_whitespace()
+            .map(parse)
+            .collect::<io::Result<_>>()?;
+        let lineage = Lineage::restore(records, current_ids, parse(value("lineage_next_id")?)?);
+        let hall_of_fame = optional_value("hall_of_fame")
+            .map(|value| -> io::Result<HallOfFame> {
+                let fields = split_fields(value, 3)?;
+                Ok(HallOfFame::new(parse(fields[0])?)
+                    .with_interval(parse(fields[1])?)
+                    .with_weight(parse(fields[2])?)
+                    .with_members(hall_of_fame_members))
+            })
+            .transpose()?;
+        let speciation = optional_value("speciation")
+            .map(|value| {
+                parse(value).map(|threshold| Speciation::new(threshold).with_species(species))
+            })
+            .transpose()?;
+        let params = EvolutionParams {
+            population_size: parse(value("population_size")?)?,
+            crossover_rate: parse(value("crossover_rate")?)?,
+            mutation_rate: parse(value("mutation_rate")?)?,
+            mutation_adaptation: optional_value("mutation_adaptation")
+                .map(|value| value.parse().map_err(invalid_data))
+                .transpose()?,
+            selection: value("selection")?.parse().map_err(invalid_data)?,
+            elitism: parse(value("elitism")?)?,
+            // Checkpoints without a maximum length were written before it existed.
+            max_genome_length: optional_value("max_genome_length").map(parse).transpose()?,
+            seed: parse(value("seed")?)?,
+        };
+        params.validate().map_err(|error| invalid_data(error.to_string()))?;
+        Ok(Checkpoint {
+            params,
+            generation,
+            rng_word_pos: parse(value("rng_word_pos")?)?,
+            population,
+            hall_of_fame,
+            speciation,
+            curriculum_stage: optional_value("curriculum_stage").map(parse).transpose()?,
+            mutation_impact,
+            total_mutation_impact,
+            lineage,
+        })
+    }
+}
+
+fn invalid_data(message: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+fn parse<T: std::str::FromStr>(value: &str) -> io::Result<T> {
+    value.parse().map_err(|_| invalid_data(format!("Invalid value: {}", value)))
+}
+
+/// Splits a value into the given number of fields, separated by spaces. The last field is the rest
+/// of the value, spaces and all.
+fn split_fields(value: &str, nr_fields: usize) -> io::Result<Vec<&str>> {
+    let fields = value.splitn(nr_fields, ' ').collect::<Vec<_>>();
+    if fields.len() < nr_fields {
+        return Err(invalid_data(format!("Invalid value: {}", value)));
+    }
+    Ok(fields)
+}
+
+/// Joins the given values with commas, or returns `-` if there are none.
+fn join_or_dash<I: Iterator<Item = String>>(values: I) -> String {
+    let values = values.collect::<Vec<_>>();
+    if values.is_empty() {
+        "-".to_string()
+    } else {
+        values.join(",")
+    }
+}
+
+/// Parses a list of values that was joined by `join_or_dash`.
+fn parse_list<T: std::str::FromStr>(value: &str) -> io::Result<Vec<T>> {
+    match value {
+        "-" => Ok(vec![]),
+        values => values.split(',').map(parse).collect(),
+    }
+}
+
+/// Writes an individual as its fitness, the mutation rate of its genome if it has one, and its
+/// genome, followed by a newline.
+fn write_individual<W: Write>(writer: &mut W, individual: &Individual) -> io::Result<()> {
+    write!(writer, "{} ", format_fitness(individual.fitness))?;
+    if let Some(mutation_rate) = individual.genome.mutation_rate {
+        write!(writer, "{}{} ", MUTATION_RATE_PREFIX, mutation_rate)?;
+    }
+    writeln!(writer, "{}", individual.genome.to_source())
+}
+
+/// Formats an optional fitness as `-` if it is missing. Floats are written with as many digits as
+/// it takes to read back the same value.
+fn format_fitness(fitness: Option<f64>) -> String {
+    fitness.map_or("-".to_string(), |fitness| fitness.to_string())
+}
+
+fn parse_fitness(value: &str) -> io::Result<Option<f64>> {
+    if value == "-" {
+        Ok(None)
+    } else {
+        parse(value).map(Some)
+    }
+}
+
+/// Parses the number of offspring, improvements and deteriorations of a mutation operator,
+/// followed by its name, and adds them to the given impact.
+fn parse_impact(value: &str, impact: &mut MutationImpact) -> io::Result<()> {
+    let fields = split_fields(value, 4)?;
+    let counts = OperatorImpact {
+        nr_offspring: parse(fields[0])?,
+        nr_improved: parse(fields[1])?,
+        nr_worsened: parse(fields[2])?,
+    };
+    impact.insert(fields[3].to_string(), counts);
+    Ok(())
+}
+
+/// Parses the record of an individual in the lineage from its id, generation, fitness, the ids of
+/// its parents separated by commas, or `-` if it has none, and its source code.
+fn parse_record(value: &str) -> io::Result<LineageRecord> {
+    let fields = split_fields(value, 5)?;
+    Ok(LineageRecord {
+        id: parse(fields[0])?,
+        parent_ids: parse_list(fields[3])?,
+        generation: parse(fields[1])?,
+        fitness: parse_fitness(fields[2])?,
+        source: fields[4].to_string(),
+    })
+}
+
+/// Parses a species from the indices of its members, as parsed by `parse_list`, and the source code
+/// of its representative.
+fn parse_species(value: &str) -> io::Result<Species> {
+    let fields = split_fields(value, 2)?;
+    Ok(Species {
+        representative: parse_genome(fields[1])?,
+        members: parse_list(fields[0])?,
+    })
+}
+
+fn parse_genome(source: &str) -> io::Result<Genome> {
+    Genome::from_source(source).map_err(|error| invalid_data(error.to_string()))
+}
+
+/// Parses an individual from its fitness, or `-` if it has none, followed by the mutation rate of
+/// its genome if it has one, and its genome.
+fn parse_individual(value: &str) -> io::Result<Individual> {
+    let (fitness, rest) = value.split_once(' ').unwrap_or((value, ""));
+    let (mutation_rate, source) = match rest.strip_prefix(MUTATION_RATE_PREFIX) {
+        Some(rest) => {
+            let (mutation_rate, source) = rest.split_once(' ').unwrap_or((rest, ""));
+            (Some(parse(mutation_rate)?), source)
+        }
+        None => (None, rest),
+    };
+    let mut genome = parse_genome(source)?;
+    genome.mutation_rate = mutation_rate;
+    let mut individual = Individual::new(genome);
+    individual.fitness = parse_fitness(fitness)?;
+    Ok(individual)
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod tests {
+    use super::*;
+    use evolution::{MutationAdaptation, Parentage};
+    use operators::SelectionMethod;
+
+    fn make_checkpoint() -> Checkpoint {
+        let mut population = Population::new(vec![Genome::from_source("(+[-])*3").unwrap(),
+                                                   Genome::default()]);
+        let mut speciation = Speciation::new(0.3);
+        speciation.apply(&mut population.clone());
+        population.individuals[0].fitness = Some(0.1 + 0.2);
+        population.individuals[1].genome.mutation_rate = Some(0.15);
+        let mut lineage = Lineage::new(&population, 16);
+        lineage.record_generation(&population,
+                                  &[Parentage::Elite(0),
+                                    Parentage::Offspring {
+                                        first_parent: 0,
+                                        second_parent: Some(1),
+                                        mutation: None,
+                                    }],
+                                  17);
+        let mut mutation_impact = MutationImpact::new();
+        mutation_impact.insert("loop wrap".to_string(),
+                               OperatorImpact {
+                                   nr_offspring: 3,
+                                   nr_improved: 1,
+                                   nr_worsened: 2,
+                               });
+        Checkpoint {
+            params: EvolutionParams {
+                mutation_adaptation: Some(MutationAdaptation::SelfAdaptive { learning_rate: 0.2 }),
+                selection: SelectionMethod::Rank,
+                elitism: 2,
+                max_genome_length: Some(40),
+                seed: u64::MAX,
+                ..EvolutionParams::default()
+            },
+            generation: 17,
+            rng_word_pos: 1 << 70,
+            hall_of_fame: Some(HallOfFame::new(4)
+                .with_interval(3)
+                .with_weight(0.25)
+                .with_members(vec![population.individuals[0].clone()])),
+            speciation: Some(speciation),
+            population,
+            curriculum_stage: Some(2),
+            total_mutation_impact: mutation_impact.clone(),
+            mutation_impact,
+            lineage,
+        }
+    }
+
+    #[test]
+    fn writeThenRead_restoresCheckpoint() {
+        let checkpoint = make_checkpoint();
+        let mut bytes = vec![];
+        checkpoint.write(&mut bytes).unwrap();
+        assert_eq!(Checkpoint::read(&bytes[..]).unwrap(), checkpoint);
+    }
+
+    #[test]
+    fn read_wrongHeader_fails() {
+        let error = Checkpoint::read(&b"something else\n"[..]).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn read_noMaxGenomeLength_unlimited() {
+        let mut bytes = vec![];
+        make_checkpoint().write(&mut bytes).unwrap();
+        let text = String::from_utf8(bytes).unwrap().replace("max_genome_length 40\n", "");
+        assert_eq!(Checkpoint::read(text.as_bytes()).unwrap().params.max_genome_length, None);
+    }
+
+    #[test]
+    fn read_missingValue_fails() {
+        let text = format!("{}\nseed 3\n", HEADER);
+        assert!(Checkpoint::read(text.as_bytes()).is_err());
+    }
+}
@@ -0,0 +1,74 @@
+use bf_bot_core::error::BfError;
+use evolution::MutationAdaptation;
+use operators::SelectionMethod;
+
+/// Specifies how the next generation is bred from the current one.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EvolutionParams {
+    /// The number of individuals in every generation.
+    pub population_size: usize,
+    /// The probability that an offspring is produced by crossover of two parents, rather than
+    /// being a copy of a single parent.
+    pub crossover_rate: f64,
+    /// The probability that an offspring is mutated. With the one-fifth rule, the run adjusts this
+    /// rate after every generation; with self-adaptation, it is the rate of the initial genomes.
+    pub mutation_rate: f64,
+    /// If set, how the mutation rate adapts over the run. Otherwise it stays fixed.
+    pub mutation_adaptation: Option<MutationAdaptation>,
+    /// How parents are picked from the current generation.
+    pub selection: SelectionMethod,
+    /// The number of fittest individuals that are copied unchanged into the next generation.
+    pub elitism: usize,
+    /// If set, offspring with more `Op` genes than this are discarded and replaced by a copy of
+    /// their first parent, so that genomes can never grow beyond this length. Offspring that
+    /// compile into more than `genome::MAX_EXPANDED_LENGTH` instructions are always discarded.
+    pub max_genome_length: Option<usize>,
+    /// The seed of the random number generator. Two runs with the same parameters, operators and
+    /// initial population produce exactly the same result.
+    pub seed: u64,
+}
+
+impl Default for EvolutionParams {
+    fn default() -> EvolutionParams {
+        EvolutionParams {
+            population_size: 100,
+            crossover_rate: 0.7,
+            mutation_rate: 0.3,
+            mutation_adaptation: None,
+            selection: SelectionMethod::default(),
+            elitism: 0,
+            max_genome_length: None,
+            seed: 0,
+        }
+    }
+}
+
+impl EvolutionParams {
+    /// Checks that a run can be bred with these parameters: the population must not be empty,
+    /// and the crossover and mutation rates are probabilities, so they must lie between 0 and 1.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// extern crate bf_bot_evolve;
+    /// use bf_bot_evolve::evolution::EvolutionParams;
+    /// assert!(EvolutionParams::default().validate().is_ok());
+    /// let params = EvolutionParams { crossover_rate: 1.5, ..EvolutionParams::default() };
+    /// assert!(params.validate().is_err());
+    /// ```
+    pub fn validate(&self) -> Result<(), BfError> {
+        if self.population_size == 0 {
+            return Err(BfError::InvalidParams("The population must not be empty.".to_string()));
+        }
+        let rates = [("crossover", self.crossover_rate), ("mutation", self.mutation_rate)];
+        for &(name, rate) in &rates {
+            if !(0.0..=1.0).contains(&rate) {
+                return Err(BfError::InvalidParams(format!("The {} rate {} is not between 0 and \
+                                                           1.",
+                                                          name,
+                                                          rate)));
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,979 @@
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use bf_bot_core::cancellation::CancellationToken;
+use bf_bot_core::error::BfError;
+use evolution::{mutation_impact, seeded_rng, Breeder, Checkpoint, EvolutionParams, EvolutionRng,
+                GenerationStats, HallOfFame, Lineage, MutationAdaptation, MutationImpact,
+                Parentage};
+use fitness::{Curriculum, FitnessFunction, FitnessScreening, OpponentSampling, SamplingReport,
+              TestCases};
+use novelty::NoveltySearch;
+use operators::{ranking, CrossoverOperator, LexicaseSelection, MutationOperator,
+                SelectionStrategy};
+use population::{Individual, Population};
+use speciation::Speciation;
+
+/// Describes a run, so that it can be stored alongside its results and reproduced later.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RunMetadata {
+    /// The seed that the run was started with.
+    pub seed: u64,
+    /// The number of generations that had been run when the metadata was taken.
+    pub generation: u32,
+    pub population_size: usize,
+}
+
+/// Receives the statistics of every generation of a run.
+pub type StatsCallback = Box<dyn FnMut(&GenerationStats) + Send>;
+
+/// Evolves a population, one generation at a time.
+pub struct EvolutionRun {
+    params: EvolutionParams,
+    population: Population,
+    generation: u32,
+    rng: EvolutionRng,
+    breeder: Breeder,
+    fitness_function: Box<dyn FitnessFunction>,
+    novelty_search: Option<NoveltySearch>,
+    hall_of_fame: Option<HallOfFame>,
+    speciation: Option<Speciation>,
+    /// The test cases that parents are selected on by lexicase selection, if it is used.
+    lexicase_cases: Option<TestCases>,
+    opponent_sampling: Option<OpponentSampling>,
+    fitness_screening: Option<FitnessScreening>,
+    curriculum: Option<Curriculum>,
+    /// The stage of the curriculum that the current population was evaluated in.
+    curriculum_stage: Option<usize>,
+    /// What evaluating the current population with opponent sampling cost, if it was used.
+    sampling_report: Option<SamplingReport>,
+    /// How the mutation operators fared in the current generation, and in all generations of the
+    /// run so far.
+    mutation_impact: MutationImpact,
+    total_mutation_impact: MutationImpact,
+    lineage: Lineage,
+    stats_callback: Option<StatsCallback>,
+    cancellation: Option<CancellationToken>,
+}
+
+impl EvolutionRun {
+    /// Starts a run from the given initial population, which is evaluated right away. Parents are
+    /// picked with the selection method from the parameters. Fails if the parameters are not
+    /// valid, or if the initial population is empty.
+    pub fn new(params: EvolutionParams,
+               population: Population,
+               mutation: Box<dyn MutationOperator>,
+               crossover: Box<dyn CrossoverOperator>,
+               fitness_function: Box<dyn FitnessFunction>)
+               -> Result<EvolutionRun, BfError> {
+        params.validate()?;
+        EvolutionRun::check_population(&population)?;
+        let mut run = EvolutionRun {
+            rng: seeded_rng(params.seed),
+            breeder: Breeder::new(mutation, crossover, Box::new(params.selection)),
+            params,
+            population,
+            generation: 0,
+            fitness_function,
+            novelty_search: None,
+            hall_of_fame: None,
+            speciation: None,
+            lexicase_cases: None,
+            opponent_sampling: None,
+            fitness_screening: None,
+            curriculum: None,
+            curriculum_stage: None,
+            sampling_report: None,
+            mutation_impact: MutationImpact::new(),
+            total_mutation_impact: MutationImpact::new(),
+            lineage: Lineage::default(),
+            stats_callback: None,
+            cancellation: None,
+        };
+        run.population.evaluate(&*run.fitness_function);
+        run.lineage = Lineage::new(&run.population, 0);
+        Ok(run)
+    }
+
+    /// Continues a run from a checkpoint that was saved with `save_checkpoint`. The operators and
+    /// fitness function aren't part of the checkpoint, so they have to be supplied again, as do
+    /// any custom selection strategy, lexicase selection and curriculum. The hall of fame and the
+    /// speciation are restored along with the population. With the same operators, fitness
+    /// function and options, the resumed run continues exactly as the original run would have,
+    /// including its lineage and the totals of the mutation impact. Fails with `InvalidData` if
+    /// the checkpoint holds no individuals, just as `new` fails for an empty population.
+    pub fn resume<P: AsRef<Path>>(path: P,
+                                  mutation: Box<dyn MutationOperator>,
+                                  crossover: Box<dyn CrossoverOperator>,
+                                  fitness_function: Box<dyn FitnessFunction>)
+                                  -> io::Result<EvolutionRun> {
+        let checkpoint = Checkpoint::load(path)?;
+        EvolutionRun::check_population(&checkpoint.population)
+            .map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error.to_string()))?;
+        let mut rng = seeded_rng(checkpoint.params.seed);
+        rng.set_word_pos(checkpoint.rng_word_pos);
+        Ok(EvolutionRun {
+            rng,
+            breeder: Breeder::new(mutation, crossover, Box::new(checkpoint.params.selection)),
+            params: checkpoint.params,
+            population: checkpoint.population,
+            generation: checkpoint.generation,
+            fitness_function,
+            novelty_search: None,
+            hall_of_fame: checkpoint.hall_of_fame,
+            speciation: checkpoint.speciation,
+            lexicase_cases: None,
+            opponent_sampling: None,
+            fitness_screening: None,
+            curriculum: None,
+            curriculum_stage: checkpoint.curriculum_stage,
+            sampling_report: None,
+            mutation_impact: checkpoint.mutation_impact,
+            total_mutation_impact: checkpoint.total_mutation_impact,
+            lineage: checkpoint.lineage,
+            stats_callback: None,
+            cancellation: None,
+        })
+    }
+
+    /// Returns an error if there is nobody in the given population to start a run from.
+    fn check_population(population: &Population) -> Result<(), BfError> {
+        if population.individuals.is_empty() {
+            return Err(BfError::InvalidParams("The initial population is empty.".to_string()));
+        }
+        Ok(())
+    }
+
+    /// Saves the parameters, the generation, the position of the random number generator, the
+    /// population, the hall of fame, the speciation, the stage of the curriculum, the mutation
+    /// impact and the lineage of the run, so that it can be resumed later. Novelty search,
+    /// opponent sampling and fitness screening keep state of their own, which can't be saved, so
+    /// a run that uses any of them can't be checkpointed: it returns an error of the kind
+    /// `Unsupported`.
+    pub fn save_checkpoint<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        let unsaved_state = [(self.novelty_search.is_some(), "novelty search"),
+                             (self.opponent_sampling.is_some(), "opponent sampling"),
+                             (self.fitness_screening.is_some(), "fitness screening")];
+        if let Some(&(_, feature)) = unsaved_state.iter().find(|&&(is_used, _)| is_used) {
+            return Err(io::Error::new(io::ErrorKind::Unsupported,
+                                      format!("A run with {} can't be checkpointed", feature)));
+        }
+        Checkpoint {
+                params: self.params.clone(),
+                generation: self.generation,
+                rng_word_pos: self.rng.get_word_pos(),
+                population: self.population.clone(),
+                hall_of_fame: self.hall_of_fame.clone(),
+                speciation: self.speciation.clone(),
+                curriculum_stage: self.curriculum_stage,
+                mutation_impact: self.mutation_impact.clone(),
+                total_mutation_impact: self.total_mutation_impact.clone(),
+                lineage: self.lineage.clone(),
+            }
+            .save(path)
+    }
+
+    /// Calls the given function with the statistics of every generation that is run from now on.
+    /// To process the statistics on another thread, send them through a channel from within the
+    /// callback.
+    pub fn with_stats_callback<F>(self, callback: F) -> EvolutionRun
+        where F: FnMut(&GenerationStats) + Send + 'static
+    {
+        EvolutionRun { stats_callback: Some(Box::new(callback)), ..self }
+    }
+
+    /// Returns the statistics of the current generation.
+    pub fn stats(&self) -> GenerationStats {
+        GenerationStats {
+            mutation_impact: self.mutation_impact.clone(),
+            ..GenerationStats::of(self.generation, &self.population)
+        }
+    }
+
+    /// Returns how the offspring of every mutation operator have fared against their parents over
+    /// all generations that this run has bred, to help tune the weights of the operators.
+    pub fn get_mutation_impact(&self) -> &MutationImpact {
+        &self.total_mutation_impact
+    }
+
+    /// Blends novelty into the fitness of every individual, starting with the current population.
+    /// Since novelty is relative to the rest of the population, the elites are then evaluated
+    /// again every generation.
+    pub fn with_novelty_search(mut self, mut novelty_search: NoveltySearch) -> EvolutionRun {
+        novelty_search.apply(&mut self.population);
+        self.novelty_search = Some(novelty_search);
+        self
+    }
+
+    /// Picks parents with a custom strategy, rather than the selection method from the parameters.
+    pub fn with_selection_strategy(mut self,
+                                   selection: Box<dyn SelectionStrategy>)
+                                   -> EvolutionRun {
+        self.breeder.selection = selection;
+        self
+    }
+
+    /// Picks parents with `LexicaseSelection` on the given test cases, instead of by fitness. The
+    /// outcomes of every generation on the cases are played after it has been evaluated, so this
+    /// takes as many rounds again as a `HillFitness` against the same opponents. Fitness still
+    /// decides the elites and the best individual.
+    pub fn with_lexicase_selection(mut self, test_cases: TestCases) -> EvolutionRun {
+        self.breeder.selection =
+            Box::new(LexicaseSelection::new(test_cases.outcomes_of(&self.population)));
+        self.lexicase_cases = Some(test_cases);
+        self
+    }
+
+    /// Keeps a hall of fame of the fittest individuals of the run, starting with the current
+    /// population, and makes every generation for which it is due fight its members. Like novelty,
+    /// this makes fitness depend on more than the individual itself, so the elites are then
+    /// evaluated again every generation.
+    pub fn with_hall_of_fame(mut self, mut hall_of_fame: HallOfFame) -> EvolutionRun {
+        hall_of_fame.update(&self.population);
+        self.hall_of_fame = Some(hall_of_fame);
+        self
+    }
+
+    /// Divides every generation into species, starting with the current population, and shares
+    /// fitness within each species. Since the shared fitness depends on the rest of the
+    /// population, the elites are then evaluated again every generation.
+    pub fn with_speciation(mut self, mut speciation: Speciation) -> EvolutionRun {
+        speciation.apply(&mut self.population);
+        self.speciation = Some(speciation);
+        self
+    }
+
+    pub fn get_speciation(&self) -> Option<&Speciation> {
+        self.speciation.as_ref()
+    }
+
+    /// Evaluates every generation on a sample of the rounds against the opponents of the given
+    /// sampling, instead of with the fitness function of the run, starting with the current
+    /// population. The sample changes every generation, so the elites are then evaluated again
+    /// every generation.
+    pub fn with_opponent_sampling(mut self, opponent_sampling: OpponentSampling) -> EvolutionRun {
+        self.sampling_report = Some(opponent_sampling.evaluate(&mut self.population,
+                                                               &mut self.rng));
+        self.opponent_sampling = Some(opponent_sampling);
+        self
+    }
+
+    /// Returns how many rounds evaluating the current population took, and how precise the
+    /// fitness of every individual is, if the run uses opponent sampling.
+    pub fn get_sampling_report(&self) -> Option<&SamplingReport> {
+        self.sampling_report.as_ref()
+    }
+
+    /// Consults the predictor of the given screening before evaluating offspring, starting with the
+    /// next generation, and skips the fitness function for those that it deems obviously bad. Has
+    /// no effect if the run uses opponent sampling.
+    pub fn with_fitness_screening(mut self, fitness_screening: FitnessScreening) -> EvolutionRun {
+        self.fitness_screening = Some(fitness_screening);
+        self
+    }
+
+    pub fn get_fitness_screening(&self) -> Option<&FitnessScreening> {
+        self.fitness_screening.as_ref()
+    }
+
+    /// Evaluates every generation with the fitness function of the stage of the given curriculum
+    /// that it is in, starting with the current population, and with the fitness function of the
+    /// run before the first stage. When the next stage starts, the elites are evaluated again. Has
+    /// no effect if the run uses opponent sampling.
+    pub fn with_curriculum(mut self, curriculum: Curriculum) -> EvolutionRun {
+        self.curriculum_stage = curriculum.stage_at(self.generation);
+        if let Some(fitness) = curriculum.fitness_at(self.generation) {
+            for individual in &mut self.population.individuals {
+                individual.fitness = None;
+            }
+            self.population.evaluate(fitness);
+        }
+        self.curriculum = Some(curriculum);
+        self
+    }
+
+    pub fn get_curriculum(&self) -> Option<&Curriculum> {
+        self.curriculum.as_ref()
+    }
+
+    /// Returns the index of the stage of the curriculum that the current population was evaluated
+    /// in, if the run has a curriculum and its first stage has started.
+    pub fn get_curriculum_stage(&self) -> Option<usize> {
+        self.curriculum_stage
+    }
+
+    /// Replaces the current population by its offspring, and evaluates the new population.
+    pub fn run_generation(&mut self) {
+        #[cfg(feature = "tracing")]
+        let span = ::tracing::info_span!("generation",
+                                         generation = self.generation + 1,
+                                         best_fitness = ::tracing::field::Empty)
+            .entered();
+        let parent_fitnesses = self.population.fitnesses();
+        let (population, parentages) =
+            self.breeder.breed_generation_with_parentage(&self.population,
+                                                         &self.params,
+                                                         &mut self.rng);
+        self.population = population;
+        self.generation += 1;
+        self.evaluate();
+        self.record_mutation_impact(&parent_fitnesses, &parentages);
+        self.lineage.record_generation(&self.population, &parentages, self.generation);
+        self.adapt_mutation_rate(&parent_fitnesses, &parentages);
+        #[cfg(feature = "tracing")]
+        if let Some(best_fitness) = self.best().and_then(|best| best.fitness) {
+            span.record("best_fitness", best_fitness);
+        }
+        if self.stats_callback.is_some() {
+            let stats = self.stats();
+            if let Some(ref mut callback) = self.stats_callback {
+                callback(&stats);
+            }
+        }
+    }
+
+    /// Evaluates the freshly bred population, with the fitness function of the current stage of the
+    /// curriculum if the run has one, blending in novelty and the matches against the hall of fame
+    /// if the run has them, and plays the test cases of lexicase selection.
+    fn evaluate(&mut self) {
+        let curriculum_stage = self.curriculum
+            .as_ref()
+            .and_then(|curriculum| curriculum.stage_at(self.generation));
+        let is_new_stage = curriculum_stage != self.curriculum_stage;
+        self.curriculum_stage = curriculum_stage;
+        let fitness_function: &dyn FitnessFunction =
+            match self.curriculum.as_ref().and_then(|c| c.fitness_at(self.generation)) {
+                Some(fitness) => fitness,
+                None => &*self.fitness_function,
+            };
+        if self.novelty_search.is_some() || self.hall_of_fame.is_some() ||
+           self.speciation.is_some() || is_new_stage {
+            for individual in &mut self.population.individuals {
+                individual.fitness = None;
+            }
+        }
+        match (&self.opponent_sampling, &mut self.fitness_screening) {
+            (Some(opponent_sampling), _) => {
+                self.sampling_report = Some(opponent_sampling.evaluate(&mut self.population,
+                                                                       &mut self.rng));
+            }
+            (None, Some(fitness_screening)) => {
+                fitness_screening.evaluate(&mut self.population,
+                                           fitness_function,
+                                           self.generation);
+            }
+            (None, None) => self.population.evaluate(fitness_function),
+        }
+        if let Some(ref mut novelty_search) = self.novelty_search {
+            novelty_search.apply(&mut self.population);
+        }
+        if let Some(ref mut hall_of_fame) = self.hall_of_fame {
+            hall_of_fame.update(&self.population);
+            if hall_of_fame.is_due(self.generation) {
+                hall_of_fame.apply(&mut self.population);
+            }
+        }
+        if let Some(ref mut speciation) = self.speciation {
+            speciation.apply(&mut self.population);
+        }
+        if let Some(ref test_cases) = self.lexicase_cases {
+            self.breeder.selection =
+                Box::new(LexicaseSelection::new(test_cases.outcomes_of(&self.population)));
+        }
+    }
+
+    fn record_mutation_impact(&mut self, parent_fitnesses: &[f64], parentages: &[Parentage]) {
+        self.mutation_impact = mutation_impact(parent_fitnesses, &self.population, parentages);
+        for (operator, impact) in &self.mutation_impact {
+            self.total_mutation_impact.entry(operator.clone()).or_default().add(impact);
+        }
+    }
+
+    /// Applies the one-fifth rule, if the run uses it, to the freshly evaluated population, given
+    /// the fitnesses of the previous generation and the parentage of every individual.
+    fn adapt_mutation_rate(&mut self, parent_fitnesses: &[f64], parentages: &[Parentage]) {
+        let adaptation = match self.params.mutation_adaptation {
+            Some(adaptation) => adaptation,
+            None => return,
+        };
+        let outcomes = self.population
+            .individuals
+            .iter()
+            .zip(parentages)
+            .filter_map(|(individual, parentage)| match *parentage {
+                Parentage::Offspring { first_parent, mutation: Some(_), .. } => {
+                    Some(individual.fitness > Some(parent_fitnesses[first_parent]))
+                }
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        if !outcomes.is_empty() {
+            let nr_successes = outcomes.iter().filter(|&&success| success).count();
+            let success_ratio = nr_successes as f64 / outcomes.len() as f64;
+            self.params.mutation_rate = adaptation.next_rate(self.params.mutation_rate,
+                                                             success_ratio);
+        }
+    }
+
+    /// Returns the current mutation rate: the rate of the parameters, which the one-fifth rule
+    /// adjusts, or the mean rate of the genomes if the rates are self-adaptive.
+    pub fn mutation_rate(&self) -> f64 {
+        let rates = self.population
+            .individuals
+            .iter()
+            .filter_map(|individual| individual.genome.mutation_rate)
+            .collect::<Vec<_>>();
+        match self.params.mutation_adaptation {
+            Some(MutationAdaptation::SelfAdaptive { .. }) if !rates.is_empty() => {
+                rates.iter().sum::<f64>() / rates.len() as f64
+            }
+            _ => self.params.mutation_rate,
+        }
+    }
+
+    /// Stops `run_generations` as soon as the given token is cancelled. A generation that has
+    /// started is always completed, so the population is never left half bred or half evaluated.
+    pub fn with_cancellation(self, cancellation: CancellationToken) -> EvolutionRun {
+        EvolutionRun { cancellation: Some(cancellation), ..self }
+    }
+
+    /// Returns true if the run has been cancelled.
+    pub fn is_cancelled(&self) -> bool {
+        self.cancellation.as_ref().is_some_and(|cancellation| cancellation.is_cancelled())
+    }
+
+    /// Runs the given number of generations, or fewer if the run is cancelled.
+    pub fn run_generations(&mut self, nr_generations: u32) {
+        for _ in 0..nr_generations {
+            if self.is_cancelled() {
+                return;
+            }
+            self.run_generation();
+        }
+    }
+
+    pub fn get_novelty_search(&self) -> Option<&NoveltySearch> {
+        self.novelty_search.as_ref()
+    }
+
+    pub fn get_hall_of_fame(&self) -> Option<&HallOfFame> {
+        self.hall_of_fame.as_ref()
+    }
+
+    pub fn get_population(&self) -> &Population {
+        &self.population
+    }
+
+    /// Lets the given individuals, which must have been evaluated, replace the weakest individuals
+    /// of the current population.
+    pub fn immigrate(&mut self, immigrants: Vec<Individual>) {
+        let replaced = ranking(&self.population.fitnesses())
+            .into_iter()
+            .rev()
+            .take(immigrants.len())
+            .collect::<Vec<_>>();
+        self.lineage.record_replacements(&replaced, &immigrants, self.generation);
+        self.population.replace_weakest(immigrants);
+    }
+
+    /// Returns the number of generations that have been run so far.
+    pub fn get_generation(&self) -> u32 {
+        self.generation
+    }
+
+    pub fn metadata(&self) -> RunMetadata {
+        RunMetadata {
+            seed: self.params.seed,
+            generation: self.generation,
+            population_size: self.params.population_size,
+        }
+    }
+
+    /// Returns the fittest individual of the current generation.
+    pub fn best(&self) -> Option<&Individual> {
+        self.population.best()
+    }
+
+    /// Returns the ancestry of every individual of the current generation.
+    pub fn get_lineage(&self) -> &Lineage {
+        &self.lineage
+    }
+
+    /// Returns the id that the lineage knows the fittest individual of the current generation by.
+    pub fn best_id(&self) -> Option<u64> {
+        let best = self.best()?;
+        let index = self.population
+            .individuals
+            .iter()
+            .position(|individual| std::ptr::eq(individual, best))?;
+        self.lineage.id_of(index)
+    }
+
+    /// Writes the ancestry of the fittest individual of the current generation to the given file,
+    /// as a Graphviz graph annotated with the fitness of every ancestor.
+    pub fn export_lineage_dot<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        let best_id = self.best_id().ok_or_else(|| {
+                io::Error::new(io::ErrorKind::InvalidInput, "No individual has been evaluated")
+            })?;
+        fs::write(path, self.lineage.to_dot(best_id))
+    }
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod tests {
+    use super::*;
+    use bf_bot_core::bf::{Bot, Instruction};
+    use bf_bot_core::simul_game::TapeLengths;
+    use fitness::SamplingMode;
+    use novelty::MatchBehavior;
+    use genome::MAX_EXPANDED_LENGTH;
+    use operators::{DuplicationMutation, PointMutation, RepeatWrapMutation, SelectionMethod,
+                    SinglePointCrossover};
+    use evolution::{seeded_rng, MIN_MUTATION_RATE};
+    use std::sync::mpsc;
+    use std::{env, fs};
+
+    /// Rewards bots for every increment in their program.
+    fn count_increments(bot: &Bot) -> f64 {
+        bot.get_program().iter().filter(|&i| *i == Instruction::Increment).count() as f64
+    }
+
+    fn make_run(population: Population) -> EvolutionRun {
+        make_seeded_run(population, 0)
+    }
+
+    fn make_seeded_run(population: Population, seed: u64) -> EvolutionRun {
+        let params = EvolutionParams {
+            population_size: 20,
+            seed,
+            ..EvolutionParams::default()
+        };
+        EvolutionRun::new(params,
+                          population,
+                          Box::new(PointMutation),
+                          Box::new(SinglePointCrossover),
+                          Box::new(count_increments))
+            .unwrap()
+    }
+
+    #[test]
+    fn new_invalidParamsOrEmptyPopulation_invalidParams() {
+        let make = |params: EvolutionParams, population: Population| {
+            EvolutionRun::new(params,
+                              population,
+                              Box::new(PointMutation),
+                              Box::new(SinglePointCrossover),
+                              Box::new(count_increments))
+        };
+        let params = EvolutionParams { mutation_rate: -0.1, ..EvolutionParams::default() };
+        let population = Population::random(5, 10, &mut seeded_rng(0));
+        assert!(matches!(make(params, population), Err(BfError::InvalidParams(_))));
+        let params = EvolutionParams { population_size: 5, ..EvolutionParams::default() };
+        assert!(matches!(make(params, Population::default()), Err(BfError::InvalidParams(_))));
+    }
+
+    #[test]
+    fn new_evaluatesInitialPopulation() {
+        let mut rng = seeded_rng(0);
+        let run = make_run(Population::random(5, 10, &mut rng));
+        assert!(run.get_population().individuals.iter().all(|ind| ind.fitness.is_some()));
+    }
+
+    #[test]
+    fn runGenerations_countsGenerationsAndKeepsPopulationSize() {
+        let mut rng = seeded_rng(1);
+        let mut run = make_run(Population::random(5, 10, &mut rng));
+        run.run_generations(3);
+        assert_eq!(run.get_generation(), 3);
+        assert_eq!(run.get_population().len(), 20);
+    }
+
+    #[test]
+    fn runGenerations_cancelledByCallback_stopsAfterThatGeneration() {
+        let mut rng = seeded_rng(2);
+        let cancellation = CancellationToken::new();
+        let handle = cancellation.clone();
+        let mut run = make_run(Population::random(5, 10, &mut rng))
+            .with_cancellation(cancellation)
+            .with_stats_callback(move |stats| if stats.generation == 2 {
+                handle.cancel();
+            });
+        run.run_generations(10);
+        assert_eq!(run.get_generation(), 2);
+        assert!(run.is_cancelled());
+    }
+
+    fn mean_fitness(run: &EvolutionRun) -> f64 {
+        let fitnesses = run.get_population().fitnesses();
+        fitnesses.iter().sum::<f64>() / fitnesses.len() as f64
+    }
+
+    #[test]
+    fn runGenerations_improvesMeanFitness() {
+        let mut run = make_run(Population::random(20, 20, &mut seeded_rng(7)));
+        let initial_mean = mean_fitness(&run);
+        run.run_generations(30);
+        assert!(mean_fitness(&run) > initial_mean);
+    }
+
+    #[test]
+    fn runGenerations_sameSeed_samePopulation() {
+        let population = Population::random(20, 20, &mut seeded_rng(3));
+        let mut run_a = make_seeded_run(population.clone(), 11);
+        let mut run_b = make_seeded_run(population, 11);
+        run_a.run_generations(5);
+        run_b.run_generations(5);
+        assert_eq!(run_a.get_population(), run_b.get_population());
+    }
+
+    #[test]
+    fn runGenerations_differentSeed_differentPopulation() {
+        let population = Population::random(20, 20, &mut seeded_rng(3));
+        let mut run_a = make_seeded_run(population.clone(), 11);
+        let mut run_b = make_seeded_run(population, 12);
+        run_a.run_generations(5);
+        run_b.run_generations(5);
+        assert!(run_a.get_population() != run_b.get_population());
+    }
+
+    fn best_fitness(run: &EvolutionRun) -> f64 {
+        run.best().unwrap().fitness.unwrap()
+    }
+
+    #[test]
+    fn runGeneration_withElitism_neverLosesBest() {
+        let params = EvolutionParams {
+            population_size: 10,
+            mutation_rate: 1.0,
+            selection: SelectionMethod::Rank,
+            elitism: 2,
+            ..EvolutionParams::default()
+        };
+        let mut run = EvolutionRun::new(params,
+                                        Population::random(10, 10, &mut seeded_rng(5)),
+                                        Box::new(PointMutation),
+                                        Box::new(SinglePointCrossover),
+                                        Box::new(count_increments))
+            .unwrap();
+        for _ in 0..10 {
+            let previous_best = run.best().unwrap().clone();
+            run.run_generation();
+            assert!(best_fitness(&run) >= previous_best.fitness.unwrap());
+            assert!(run.get_population().individuals.contains(&previous_best));
+        }
+    }
+
+    #[test]
+    fn runGeneration_maxGenomeLength_genomesNeverGrowBeyondIt() {
+        let params = EvolutionParams {
+            population_size: 10,
+            mutation_rate: 1.0,
+            max_genome_length: Some(12),
+            ..EvolutionParams::default()
+        };
+        let mut run = EvolutionRun::new(params,
+                                        Population::random(10, 10, &mut seeded_rng(5)),
+                                        Box::new(DuplicationMutation),
+                                        Box::new(SinglePointCrossover),
+                                        Box::new(count_increments))
+            .unwrap();
+        run.run_generations(5);
+        assert!(run.get_population().individuals.iter().all(|ind| ind.genome.nr_ops() <= 12));
+    }
+
+    #[test]
+    fn runGeneration_nestedRepetitions_offspringNeverExpandBeyondLimit() {
+        let params = EvolutionParams {
+            population_size: 10,
+            mutation_rate: 1.0,
+            ..EvolutionParams::default()
+        };
+        let mut run = EvolutionRun::new(params,
+                                        Population::random(10, 10, &mut seeded_rng(5)),
+                                        Box::new(RepeatWrapMutation { max_count: 1000 }),
+                                        Box::new(SinglePointCrossover),
+                                        Box::new(count_increments))
+            .unwrap();
+        run.run_generations(5);
+        assert!(run.get_population()
+            .individuals
+            .iter()
+            .all(|ind| ind.genome.expanded_length() <= MAX_EXPANDED_LENGTH));
+    }
+
+    #[test]
+    fn withNoveltySearch_scoresEveryGeneration() {
+        let novelty_search = NoveltySearch::new(Box::new(MatchBehavior::new(vec![])));
+        let mut run = make_run(Population::random(5, 10, &mut seeded_rng(1)))
+            .with_novelty_search(novelty_search);
+        run.run_generations(2);
+        // Without opponents, every bot behaves the same, so nothing is novel.
+        assert!(run.get_population().fitnesses().iter().all(|&fitness| fitness == 0.0));
+        assert!(run.get_novelty_search().unwrap().get_archive().is_empty());
+    }
+
+    #[test]
+    fn resume_continuesExactlyWhereSaved() {
+        let path = env::temp_dir().join(format!("bf_resume_{}.checkpoint", std::process::id()));
+        let mut run = make_seeded_run(Population::random(20, 20, &mut seeded_rng(3)), 9);
+        run.run_generations(2);
+        run.save_checkpoint(&path).unwrap();
+        let mut resumed = EvolutionRun::resume(&path,
+                                               Box::new(PointMutation),
+                                               Box::new(SinglePointCrossover),
+                                               Box::new(count_increments))
+            .unwrap();
+        fs::remove_file(&path).unwrap();
+        assert_eq!(resumed.metadata(), run.metadata());
+        run.run_generations(3);
+        resumed.run_generations(3);
+        assert_eq!(resumed.get_population(), run.get_population());
+        assert_eq!(resumed.get_lineage(), run.get_lineage());
+        assert_eq!(resumed.get_mutation_impact(), run.get_mutation_impact());
+    }
+
+    #[test]
+    fn resume_hallOfFameAndSpeciation_restored() {
+        let path = env::temp_dir().join(format!("bf_hof_{}.checkpoint", std::process::id()));
+        let mut run = make_seeded_run(Population::random(20, 20, &mut seeded_rng(3)), 9)
+            .with_hall_of_fame(HallOfFame::new(3).with_interval(2))
+            .with_speciation(Speciation::new(0.3));
+        run.run_generations(3);
+        run.save_checkpoint(&path).unwrap();
+        let mut resumed = EvolutionRun::resume(&path,
+                                               Box::new(PointMutation),
+                                               Box::new(SinglePointCrossover),
+                                               Box::new(count_increments))
+            .unwrap();
+        fs::remove_file(&path).unwrap();
+        run.run_generations(3);
+        resumed.run_generations(3);
+        assert_eq!(resumed.get_population(), run.get_population());
+        assert_eq!(resumed.get_hall_of_fame(), run.get_hall_of_fame());
+    }
+
+    #[test]
+    fn resume_emptyPopulation_invalidData() {
+        let path = env::temp_dir().join(format!("bf_empty_{}.checkpoint", std::process::id()));
+        make_run(Population::random(5, 10, &mut seeded_rng(1))).save_checkpoint(&path).unwrap();
+        let mut checkpoint = Checkpoint::load(&path).unwrap();
+        checkpoint.population = Population::default();
+        checkpoint.save(&path).unwrap();
+        let result = EvolutionRun::resume(&path,
+                                          Box::new(PointMutation),
+                                          Box::new(SinglePointCrossover),
+                                          Box::new(count_increments));
+        fs::remove_file(&path).unwrap();
+        let error = result.err().expect("An empty population can't be resumed.");
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+        assert!(error.to_string().contains("The initial population is empty."));
+    }
+
+    #[test]
+    fn saveCheckpoint_noveltySearch_unsupported() {
+        let path = env::temp_dir().join(format!("bf_novelty_{}.checkpoint", std::process::id()));
+        let novelty_search = NoveltySearch::new(Box::new(MatchBehavior::new(vec![])));
+        let run = make_run(Population::random(5, 10, &mut seeded_rng(1)))
+            .with_novelty_search(novelty_search);
+        assert_eq!(run.save_checkpoint(&path).unwrap_err().kind(),
+                   io::ErrorKind::Unsupported);
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn withStatsCallback_reportsEveryGeneration() {
+        let (sender, receiver) = mpsc::channel();
+        let mut run = make_run(Population::random(5, 10, &mut seeded_rng(1)))
+            .with_stats_callback(move |stats| sender.send(stats.clone()).unwrap());
+        run.run_generations(3);
+        let generations = receiver.try_iter().map(|stats| stats.generation).collect::<Vec<_>>();
+        assert_eq!(generations, vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn withHallOfFame_keepsBestEverSeen() {
+        let mut run = make_run(Population::random(10, 10, &mut seeded_rng(2)))
+            .with_hall_of_fame(HallOfFame::new(3).with_interval(0));
+        let initial_best = run.best().unwrap().fitness;
+        run.run_generations(5);
+        let members = run.get_hall_of_fame().unwrap().get_members();
+        assert_eq!(members.len(), 3);
+        assert!(members[0].fitness >= initial_best);
+        assert!(members[0].fitness >= run.best().unwrap().fitness);
+    }
+
+    #[test]
+    fn withOpponentSampling_reportsEveryGeneration() {
+        let sampling = OpponentSampling::new(vec![Bot::new(vec![])],
+                                             SamplingMode::RandomTapeLengths {
+                                                 nr_tape_lengths: 2,
+                                             });
+        let mut run = make_run(Population::random(5, 10, &mut seeded_rng(4)))
+            .with_opponent_sampling(sampling);
+        assert_eq!(run.get_sampling_report().unwrap().nr_rounds_played, 5 * 4);
+        run.run_generations(1);
+        let report = run.get_sampling_report().unwrap();
+        assert_eq!(report.nr_rounds_played, 20 * 4);
+        assert_eq!(report.estimates.len(), 20);
+    }
+
+    #[test]
+    fn withSpeciation_everyIndividualInASpecies() {
+        let mut run = make_run(Population::random(10, 10, &mut seeded_rng(5)))
+            .with_speciation(Speciation::new(0.3));
+        run.run_generations(2);
+        let nr_members = run.get_speciation()
+            .unwrap()
+            .get_species()
+            .iter()
+            .map(|species| species.members.len())
+            .sum::<usize>();
+        assert_eq!(nr_members, 20);
+        assert!(run.get_population().fitnesses().iter().all(|&fitness| fitness >= 0.0));
+    }
+
+    #[test]
+    fn withLexicaseSelection_evolvesAgainstTheCases() {
+        let test_cases = TestCases::new(vec![Bot::new(vec![]),
+                                             Bot::new(vec![Instruction::MoveBack])]);
+        let mut run = make_run(Population::random(10, 10, &mut seeded_rng(8)))
+            .with_lexicase_selection(test_cases.clone());
+        let best_total = |run: &EvolutionRun| {
+            test_cases.outcomes_of(run.get_population())
+                .iter()
+                .map(|outcomes| outcomes.iter().sum::<f64>())
+                .fold(f64::NEG_INFINITY, f64::max)
+        };
+        let initial_best = best_total(&run);
+        run.run_generations(5);
+        assert_eq!(run.get_population().len(), 20);
+        assert!(best_total(&run) >= initial_best);
+    }
+
+    #[test]
+    fn withFitnessScreening_skipsPredictedFailures() {
+        // Predicts that no bot has any increments, so that all offspring would be skipped, but the
+        // predictions are checked every other generation.
+        let screening = FitnessScreening::new(Box::new(|_: &Bot| -1.0), 0.0)
+            .with_calibration_interval(2);
+        let mut run = make_run(Population::random(20, 10, &mut seeded_rng(9)))
+            .with_fitness_screening(screening);
+        run.run_generation();
+        assert!(run.get_population().fitnesses().iter().all(|&fitness| fitness == -1.0));
+        run.run_generation();
+        let screening = run.get_fitness_screening().unwrap();
+        assert_eq!(screening.get_nr_skipped(), 20);
+        assert_eq!(screening.get_calibration().unwrap().generation, 2);
+        assert!(run.get_population().fitnesses().iter().all(|&fitness| fitness >= 0.0));
+    }
+
+    fn make_adaptive_run(adaptation: MutationAdaptation) -> EvolutionRun {
+        let params = EvolutionParams {
+            population_size: 20,
+            mutation_adaptation: Some(adaptation),
+            ..EvolutionParams::default()
+        };
+        EvolutionRun::new(params,
+                          Population::random(20, 10, &mut seeded_rng(6)),
+                          Box::new(PointMutation),
+                          Box::new(SinglePointCrossover),
+                          Box::new(count_increments))
+            .unwrap()
+    }
+
+    #[test]
+    fn runGeneration_recordsMutationImpactPerOperator() {
+        let mut run = make_run(Population::random(20, 10, &mut seeded_rng(3)));
+        assert!(run.stats().mutation_impact.is_empty());
+        run.run_generation();
+        let impact = run.stats().mutation_impact;
+        assert_eq!(impact.keys().collect::<Vec<_>>(), vec!["point"]);
+        assert!(impact["point"].nr_offspring > 0);
+        run.run_generation();
+        let latest = run.stats().mutation_impact["point"].nr_offspring;
+        assert_eq!(run.get_mutation_impact()["point"].nr_offspring,
+                   impact["point"].nr_offspring + latest);
+    }
+
+    #[test]
+    fn exportLineageDot_championTracedBackToInitialPopulation() {
+        let path = env::temp_dir().join("bf_bot_evolve_lineage_test.dot");
+        let mut run = make_run(Population::random(20, 10, &mut seeded_rng(5)));
+        run.run_generations(3);
+        let best_id = run.best_id().unwrap();
+        let ancestry = run.get_lineage().ancestry(best_id);
+        assert_eq!(ancestry.last().unwrap().id, best_id);
+        assert_eq!(ancestry[0].generation, 0);
+        run.export_lineage_dot(&path).unwrap();
+        let dot = fs::read_to_string(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+        assert!(dot.starts_with("digraph lineage {"));
+        assert!(dot.contains(&format!("    n{} [label=\"#{},", best_id, best_id)));
+    }
+
+    #[test]
+    fn withCurriculum_nextStage_everyoneEvaluatedByIt() {
+        let suicidal = Bot::new(vec![Instruction::MoveBack]);
+        let curriculum = Curriculum::new().with_stage(2, vec![suicidal], TapeLengths::Single(10));
+        let mut run = make_run(Population::random(10, 10, &mut seeded_rng(2)))
+            .with_curriculum(curriculum);
+        run.run_generation();
+        assert_eq!(run.get_curriculum_stage(), None);
+        assert!(run.get_population()
+            .individuals
+            .iter()
+            .all(|ind| ind.fitness == Some(count_increments(&ind.bot))));
+        run.run_generation();
+        assert_eq!(run.get_curriculum_stage(), Some(0));
+        let fitness = run.get_curriculum().unwrap().fitness_at(2).unwrap();
+        assert!(run.get_population()
+            .individuals
+            .iter()
+            .all(|ind| ind.fitness == Some(fitness.evaluate(&ind.bot))));
+    }
+
+    #[test]
+    fn runGeneration_oneFifthRule_adjustsRateByFactor() {
+        let mut run = make_adaptive_run(MutationAdaptation::OneFifthRule { factor: 1.2 });
+        let mut ratios = vec![];
+        for _ in 0..5 {
+            let previous_rate = run.mutation_rate();
+            run.run_generation();
+            ratios.push(run.mutation_rate() / previous_rate);
+        }
+        let is_close = |ratio: f64, factor: f64| (ratio - factor).abs() < 1e-9;
+        assert!(ratios.iter().all(|&ratio| {
+            [1.2, 1.0, 1.0 / 1.2].iter().any(|&factor| is_close(ratio, factor))
+        }));
+        assert!(ratios.iter().any(|&ratio| !is_close(ratio, 1.0)));
+    }
+
+    #[test]
+    fn runGeneration_selfAdaptive_everyOffspringHasItsOwnRate() {
+        let mut run = make_adaptive_run(MutationAdaptation::SelfAdaptive { learning_rate: 0.5 });
+        assert_eq!(run.mutation_rate(), 0.3);
+        run.run_generations(3);
+        let rates = run.get_population()
+            .individuals
+            .iter()
+            .map(|individual| individual.genome.mutation_rate.unwrap())
+            .collect::<Vec<_>>();
+        assert!(rates.iter().all(|&rate| (MIN_MUTATION_RATE..=1.0).contains(&rate)));
+        assert!(rates.iter().any(|&rate| rate != rates[0]));
+        assert!(run.mutation_rate() != 0.3);
+    }
+
+    #[test]
+    fn metadata_recordsSeedAndGeneration() {
+        let mut run = make_seeded_run(Population::random(20, 20, &mut seeded_rng(3)), 42);
+        run.run_generations(2);
+        assert_eq!(run.metadata(),
+                   RunMetadata {
+                       seed: 42,
+                       generation: 2,
+                       population_size: 20,
+                   });
+    }
+}
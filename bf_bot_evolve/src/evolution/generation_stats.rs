@@ -0,0 +1,123 @@
+use std::collections::HashSet;
+
+use evolution::MutationImpact;
+use population::Population;
+
+/// A summary of a single generation, for following the progress of a run.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GenerationStats {
+    /// The number of generations that had been run when the statistics were taken.
+    pub generation: u32,
+    pub best_fitness: f64,
+    pub mean_fitness: f64,
+    pub median_fitness: f64,
+    pub fitness_std_dev: f64,
+    /// The shortest, mean and longest program length of the compiled bots, in instructions.
+    pub min_length: usize,
+    pub mean_length: f64,
+    pub max_length: usize,
+    /// The fraction of individuals with a genome that no other individual has: 1 if all genomes
+    /// differ, and close to 0 if the population has converged on a single genome.
+    pub diversity: f64,
+    /// How the offspring of every mutation operator fared against their parents in this
+    /// generation. Only a run knows the parents, so this is empty in statistics of a bare
+    /// population and of the initial generation.
+    pub mutation_impact: MutationImpact,
+}
+
+impl GenerationStats {
+    /// Computes the statistics of an evaluated population. Individuals without a fitness are left
+    /// out of the fitness statistics. For an empty population, all statistics are zero.
+    pub fn of(generation: u32, population: &Population) -> GenerationStats {
+        let mut fitnesses = population.individuals
+            .iter()
+            .filter_map(|individual| individual.fitness)
+            .collect::<Vec<_>>();
+        fitnesses.sort_by(|a, b| a.total_cmp(b));
+        let lengths = population.individuals
+            .iter()
+            .map(|individual| individual.bot.get_program().len())
+            .collect::<Vec<_>>();
+        let distinct = population.individuals
+            .iter()
+            .map(|individual| individual.genome.to_source())
+            .collect::<HashSet<_>>();
+        let mean_fitness = mean(&fitnesses);
+        let variance = mean(&fitnesses.iter()
+            .map(|fitness| (fitness - mean_fitness).powi(2))
+            .collect::<Vec<_>>());
+        GenerationStats {
+            generation,
+            best_fitness: fitnesses.last().cloned().unwrap_or(0.0),
+            mean_fitness,
+            median_fitness: median(&fitnesses),
+            fitness_std_dev: variance.sqrt(),
+            min_length: lengths.iter().cloned().min().unwrap_or(0),
+            mean_length: mean(&lengths.iter().map(|&length| length as f64).collect::<Vec<_>>()),
+            max_length: lengths.iter().cloned().max().unwrap_or(0),
+            diversity: if lengths.is_empty() {
+                0.0
+            } else {
+                distinct.len() as f64 / lengths.len() as f64
+            },
+            mutation_impact: MutationImpact::new(),
+        }
+    }
+}
+
+fn mean(values: &[f64]) -> f64 {
+    if values.is_empty() {
+        0.0
+    } else {
+        values.iter().sum::<f64>() / values.len() as f64
+    }
+}
+
+/// Returns the median of sorted values.
+fn median(values: &[f64]) -> f64 {
+    match values.len() {
+        0 => 0.0,
+        len if len % 2 == 0 => (values[len / 2 - 1] + values[len / 2]) / 2.0,
+        len => values[len / 2],
+    }
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod tests {
+    use super::*;
+    use genome::{Gene, Genome, Op};
+
+    fn make_population() -> Population {
+        let mut population = Population::new(vec![Genome::new(vec![Gene::Op(Op::Increment)]),
+                                                  Genome::new(vec![Gene::Op(Op::Increment)]),
+                                                  Genome::new(vec![Gene::Op(Op::Skip); 4]),
+                                                  Genome::default()]);
+        for (individual, &fitness) in population.individuals.iter_mut().zip(&[1.0, 3.0, 5.0, 7.0]) {
+            individual.fitness = Some(fitness);
+        }
+        population
+    }
+
+    #[test]
+    fn of_computesFitnessStatistics() {
+        let stats = GenerationStats::of(3, &make_population());
+        assert_eq!(stats.generation, 3);
+        assert_eq!((stats.best_fitness, stats.mean_fitness, stats.median_fitness),
+                   (7.0, 4.0, 4.0));
+        assert_eq!(stats.fitness_std_dev, 5.0f64.sqrt());
+    }
+
+    #[test]
+    fn of_computesLengthsAndDiversity() {
+        let stats = GenerationStats::of(0, &make_population());
+        assert_eq!((stats.min_length, stats.mean_length, stats.max_length), (0, 1.5, 4));
+        assert_eq!(stats.diversity, 0.75);
+    }
+
+    #[test]
+    fn of_emptyPopulation_zeroes() {
+        let stats = GenerationStats::of(0, &Population::default());
+        assert_eq!((stats.best_fitness, stats.diversity, stats.max_length), (0.0, 0.0, 0));
+    }
+}
@@ -0,0 +1,172 @@
+use fitness::{FitnessFunction, HillFitness};
+use population::{Individual, Population};
+
+/// An archive of the fittest distinct individuals that have been seen during a run. Every few
+/// generations, the population also has to fight the members of the hall of fame, so that it
+/// doesn't forget how to beat strategies that it has moved away from.
+///
+/// Individuals are distinct if they compile into different bots.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HallOfFame {
+    capacity: usize,
+    members: Vec<Individual>,
+    interval: u32,
+    weight: f64,
+}
+
+impl HallOfFame {
+    /// Constructs an empty hall of fame with room for the given number of members, that the
+    /// population fights every 5 generations, for half of its fitness.
+    pub fn new(capacity: usize) -> HallOfFame {
+        HallOfFame {
+            capacity,
+            members: vec![],
+            interval: 5,
+            weight: 0.5,
+        }
+    }
+
+    /// Selects the number of generations between two generations that fight the hall of fame. An
+    /// interval of zero means never.
+    pub fn with_interval(self, interval: u32) -> HallOfFame {
+        HallOfFame { interval, ..self }
+    }
+
+    /// Selects how much the average normalized match score against the members counts, from 0
+    /// for not at all to 1 for replacing the fitness entirely.
+    pub fn with_weight(self, weight: f64) -> HallOfFame {
+        HallOfFame { weight, ..self }
+    }
+
+    /// Replaces the members by the given evaluated individuals, which are expected to be sorted
+    /// from the fittest down, as they were saved from another hall of fame.
+    pub fn with_members(self, members: Vec<Individual>) -> HallOfFame {
+        HallOfFame { members, ..self }
+    }
+
+    /// Returns the members, from the fittest down.
+    pub fn get_members(&self) -> &[Individual] {
+        &self.members
+    }
+
+    pub fn get_capacity(&self) -> usize {
+        self.capacity
+    }
+
+    pub fn get_interval(&self) -> u32 {
+        self.interval
+    }
+
+    pub fn get_weight(&self) -> f64 {
+        self.weight
+    }
+
+    /// Admits the fittest individuals of an evaluated population, as far as they are fitter than
+    /// the current members and there is room for them.
+    pub fn update(&mut self, population: &Population) {
+        for candidate in population.fittest(self.capacity) {
+            if candidate.fitness.is_none() {
+                continue;
+            }
+            match self.members.iter_mut().find(|member| member.bot == candidate.bot) {
+                Some(member) => {
+                    if candidate.fitness > member.fitness {
+                        *member = candidate;
+                    }
+                }
+                None => self.members.push(candidate),
+            }
+        }
+        let fitness = |member: &Individual| {
+            member.fitness.expect("Only evaluated individuals are admitted.")
+        };
+        self.members.sort_by(|a, b| fitness(b).total_cmp(&fitness(a)));
+        self.members.truncate(self.capacity);
+    }
+
+    /// Returns whether the population of the given generation has to fight the hall of fame.
+    pub fn is_due(&self, generation: u32) -> bool {
+        self.interval > 0 && generation.is_multiple_of(self.interval)
+    }
+
+    /// Blends the average normalized match score of every individual against the members into
+    /// its fitness. Does nothing while the hall of fame is empty.
+    pub fn apply(&self, population: &mut Population) {
+        if self.members.is_empty() {
+            return;
+        }
+        let hill = HillFitness::new(self.members.iter().map(|member| member.bot.clone()).collect());
+        for individual in &mut population.individuals {
+            let score = hill.evaluate(&individual.bot);
+            let fitness = individual.fitness.unwrap_or(0.0);
+            individual.fitness = Some((1.0 - self.weight) * fitness + self.weight * score);
+        }
+    }
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod tests {
+    use super::*;
+    use bf_bot_core::bf::Bot;
+    use genome::{Gene, Genome, Op};
+
+    fn make_population(fitnesses: &[f64]) -> Population {
+        let mut population = Population::new((0..fitnesses.len())
+            .map(|length| Genome::new(vec![Gene::Op(Op::Increment); length]))
+            .collect());
+        for (individual, &fitness) in population.individuals.iter_mut().zip(fitnesses) {
+            individual.fitness = Some(fitness);
+        }
+        population
+    }
+
+    fn member_fitnesses(hall_of_fame: &HallOfFame) -> Vec<f64> {
+        hall_of_fame.get_members().iter().map(|member| member.fitness.unwrap()).collect()
+    }
+
+    #[test]
+    fn update_keepsFittestUpToCapacity() {
+        let mut hall_of_fame = HallOfFame::new(2);
+        hall_of_fame.update(&make_population(&[1.0, 5.0, 3.0]));
+        assert_eq!(member_fitnesses(&hall_of_fame), vec![5.0, 3.0]);
+        hall_of_fame.update(&make_population(&[4.0, 0.0]));
+        assert_eq!(member_fitnesses(&hall_of_fame), vec![5.0, 4.0]);
+    }
+
+    #[test]
+    fn update_sameBot_admittedOnce() {
+        let mut hall_of_fame = HallOfFame::new(3);
+        hall_of_fame.update(&make_population(&[2.0]));
+        hall_of_fame.update(&make_population(&[1.0]));
+        hall_of_fame.update(&make_population(&[6.0]));
+        assert_eq!(member_fitnesses(&hall_of_fame), vec![6.0]);
+    }
+
+    #[test]
+    fn isDue_everyInterval() {
+        let hall_of_fame = HallOfFame::new(1).with_interval(3);
+        assert_eq!((1..7).filter(|&generation| hall_of_fame.is_due(generation)).count(), 2);
+        assert!(!HallOfFame::new(1).with_interval(0).is_due(0));
+    }
+
+    #[test]
+    fn update_notEvaluated_notAdmitted() {
+        let mut hall_of_fame = HallOfFame::new(1);
+        hall_of_fame.update(&Population::from_bots(&[Bot::new(vec![])]));
+        assert!(hall_of_fame.get_members().is_empty());
+    }
+
+    #[test]
+    fn apply_blendsScoreAgainstMembers() {
+        let mut members = Population::from_bots(&[Bot::new(vec![])]);
+        members.individuals[0].fitness = Some(1.0);
+        let mut hall_of_fame = HallOfFame::new(1).with_weight(0.5);
+        hall_of_fame.update(&members);
+        // Neither of these bots touches the tape of the idle member, so every round is a draw and
+        // they score 0 against it.
+        let mut population = make_population(&[4.0, 2.0]);
+        hall_of_fame.apply(&mut population);
+        assert_eq!(population.fitnesses(), vec![2.0, 1.0]);
+    }
+}
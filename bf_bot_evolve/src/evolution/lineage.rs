@@ -0,0 +1,244 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::Write;
+
+use evolution::Parentage;
+use population::{Individual, Population};
+
+/// What is known about one individual of a run: where it came from and how fit it was.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LineageRecord {
+    /// The id of the individual, unique within the run.
+    pub id: u64,
+    /// The ids of its one or two parents, or none if it was part of the initial population or an
+    /// immigrant.
+    pub parent_ids: Vec<u64>,
+    /// The generation that it was born in.
+    pub generation: u32,
+    /// Its fitness in the generation that it was born in.
+    pub fitness: Option<f64>,
+    /// The source code of its genome.
+    pub source: String,
+}
+
+/// Keeps track of the ancestry of the individuals of a run. Every individual is given an id when
+/// it is born, which elites keep when they are copied into the next generation. To keep the memory
+/// use in check, only the ancestors of the current population are remembered.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Lineage {
+    records: BTreeMap<u64, LineageRecord>,
+    /// The id of every individual of the current population, in the same order.
+    current_ids: Vec<u64>,
+    next_id: u64,
+}
+
+impl Lineage {
+    /// Starts tracking the given population, whose individuals have no parents.
+    pub fn new(population: &Population, generation: u32) -> Lineage {
+        let mut lineage = Lineage::default();
+        let ids = population.individuals
+            .iter()
+            .map(|individual| lineage.add(individual, vec![], generation))
+            .collect();
+        lineage.current_ids = ids;
+        lineage
+    }
+
+    /// Restores a lineage from the records of the individuals that it remembers, the ids of the
+    /// current population in order, and the id that the next individual will get, as they were
+    /// saved from another lineage.
+    pub fn restore(records: Vec<LineageRecord>, current_ids: Vec<u64>, next_id: u64) -> Lineage {
+        Lineage {
+            records: records.into_iter().map(|record| (record.id, record)).collect(),
+            current_ids,
+            next_id,
+        }
+    }
+
+    /// Records a freshly bred and evaluated generation, given the parentage of every individual in
+    /// terms of the previous generation, and forgets the individuals that are no longer ancestors.
+    pub fn record_generation(&mut self,
+                             population: &Population,
+                             parentages: &[Parentage],
+                             generation: u32) {
+        let previous_ids = std::mem::take(&mut self.current_ids);
+        self.current_ids = population.individuals
+            .iter()
+            .zip(parentages)
+            .map(|(individual, parentage)| match *parentage {
+                Parentage::Elite(index) => previous_ids[index],
+                Parentage::Offspring { .. } => {
+                    let parent_ids =
+                        parentage.parents().into_iter().map(|index| previous_ids[index]).collect();
+                    self.add(individual, parent_ids, generation)
+                }
+            })
+            .collect();
+        self.prune();
+    }
+
+    /// Records that the individuals at the given indices of the current population have been
+    /// replaced by the given newcomers, whose ancestry is unknown.
+    pub fn record_replacements(&mut self,
+                               indices: &[usize],
+                               newcomers: &[Individual],
+                               generation: u32) {
+        for (&index, newcomer) in indices.iter().zip(newcomers) {
+            self.current_ids[index] = self.add(newcomer, vec![], generation);
+        }
+        self.prune();
+    }
+
+    /// Returns the id of the individual at the given index of the current population.
+    pub fn id_of(&self, index: usize) -> Option<u64> {
+        self.current_ids.get(index).cloned()
+    }
+
+    pub fn get(&self, id: u64) -> Option<&LineageRecord> {
+        self.records.get(&id)
+    }
+
+    /// Returns the records of all remembered individuals, by increasing id.
+    pub fn get_records(&self) -> Vec<&LineageRecord> {
+        self.records.values().collect()
+    }
+
+    /// Returns the id of every individual of the current population, in the same order.
+    pub fn get_current_ids(&self) -> &[u64] {
+        &self.current_ids
+    }
+
+    /// Returns the id that the next individual will get.
+    pub fn get_next_id(&self) -> u64 {
+        self.next_id
+    }
+
+    /// Returns the record of the given individual and of all its known ancestors, from the oldest
+    /// to the youngest.
+    pub fn ancestry(&self, id: u64) -> Vec<&LineageRecord> {
+        let mut ancestry = self.ancestor_ids(&[id])
+            .into_iter()
+            .filter_map(|id| self.records.get(&id))
+            .collect::<Vec<_>>();
+        ancestry.sort_by_key(|record| (record.generation, record.id));
+        ancestry
+    }
+
+    /// Describes the ancestry of the given individual as a Graphviz graph, in which every
+    /// individual is annotated with its generation and fitness, and points to its offspring.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bf_bot_evolve::evolution::Lineage;
+    /// use bf_bot_evolve::population::Population;
+    /// let lineage = Lineage::new(&Population::default(), 0);
+    /// assert_eq!(lineage.to_dot(0), "digraph lineage {\n    node [shape=box];\n}\n");
+    /// ```
+    pub fn to_dot(&self, id: u64) -> String {
+        let ancestry = self.ancestry(id);
+        let mut dot = String::from("digraph lineage {\n    node [shape=box];\n");
+        for record in &ancestry {
+            let fitness = record.fitness.map_or("unknown".to_string(), |f| format!("{:.4}", f));
+            writeln!(dot,
+                     "    n{} [label=\"#{}, generation {}\\nfitness {}\\n{}\"];",
+                     record.id,
+                     record.id,
+                     record.generation,
+                     fitness,
+                     escape(&record.source))
+                .expect("Writing to a string never fails.");
+        }
+        for record in &ancestry {
+            for parent_id in &record.parent_ids {
+                writeln!(dot, "    n{} -> n{};", parent_id, record.id)
+                    .expect("Writing to a string never fails.");
+            }
+        }
+        dot.push_str("}\n");
+        dot
+    }
+
+    fn add(&mut self, individual: &Individual, parent_ids: Vec<u64>, generation: u32) -> u64 {
+        let id = self.next_id;
+        self.next_id += 1;
+        self.records.insert(id,
+                            LineageRecord {
+                                id,
+                                parent_ids,
+                                generation,
+                                fitness: individual.fitness,
+                                source: individual.genome.to_source(),
+                            });
+        id
+    }
+
+    /// Returns the given ids and the ids of all their ancestors.
+    fn ancestor_ids(&self, ids: &[u64]) -> BTreeSet<u64> {
+        let mut found = BTreeSet::new();
+        let mut to_visit = ids.to_vec();
+        while let Some(id) = to_visit.pop() {
+            if found.insert(id) {
+                if let Some(record) = self.records.get(&id) {
+                    to_visit.extend(&record.parent_ids);
+                }
+            }
+        }
+        found
+    }
+
+    fn prune(&mut self) {
+        let ancestors = self.ancestor_ids(&self.current_ids);
+        self.records.retain(|id, _| ancestors.contains(id));
+    }
+}
+
+/// Escapes the characters that have a special meaning in a quoted Graphviz string.
+fn escape(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod tests {
+    use super::*;
+    use genome::{Gene, Genome, Op};
+
+    fn make_population(nr_ops: &[usize]) -> Population {
+        Population::new(nr_ops.iter()
+            .map(|&nr_ops| Genome::new(vec![Gene::Op(Op::Increment); nr_ops]))
+            .collect())
+    }
+
+    fn offspring(first_parent: usize, second_parent: Option<usize>) -> Parentage {
+        Parentage::Offspring {
+            first_parent,
+            second_parent,
+            mutation: None,
+        }
+    }
+
+    #[test]
+    fn recordGeneration_elitesKeepTheirIdAndOffspringGetNewOnes() {
+        let mut lineage = Lineage::new(&make_population(&[1, 2, 3]), 0);
+        lineage.record_generation(&make_population(&[2, 4, 5]),
+                                  &[Parentage::Elite(1), offspring(0, Some(1)), offspring(1, None)],
+                                  1);
+        assert_eq!(lineage.id_of(0), Some(1));
+        assert_eq!(lineage.get(3).unwrap().parent_ids, vec![0, 1]);
+        assert_eq!(lineage.get(4).unwrap().parent_ids, vec![1]);
+        // The third individual of the initial population has no descendants left.
+        assert_eq!(lineage.get(2), None);
+    }
+
+    #[test]
+    fn ancestry_oldestFirst() {
+        let mut lineage = Lineage::new(&make_population(&[1, 2]), 0);
+        lineage.record_generation(&make_population(&[3, 4]),
+                                  &[offspring(1, None), offspring(0, Some(1))],
+                                  1);
+        lineage.record_generation(&make_population(&[5]), &[offspring(1, None)], 2);
+        let ids = lineage.ancestry(4).iter().map(|record| record.id).collect::<Vec<_>>();
+        assert_eq!(ids, vec![0, 1, 3, 4]);
+        assert!(lineage.to_dot(4).contains("    n0 -> n3;\n    n1 -> n3;\n    n3 -> n4;\n"));
+    }
+}
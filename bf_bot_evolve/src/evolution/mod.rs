@@ -0,0 +1,41 @@
+//! The generational loop that drives the evolution of a population.
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+
+/// The random number generator that is used throughout the evolution. Unlike `rand::StdRng`, it is
+/// guaranteed to produce the same numbers for the same seed on every platform and version, which
+/// makes runs reproducible.
+pub type EvolutionRng = ChaCha8Rng;
+
+/// Creates the random number generator for the given seed. Use this to generate the initial
+/// population, so that the complete run can be reproduced from the seed.
+pub fn seeded_rng(seed: u64) -> EvolutionRng {
+    EvolutionRng::seed_from_u64(seed)
+}
+
+pub use self::breeder::{Breeder, Parentage};
+mod breeder;
+
+pub use self::checkpoint::Checkpoint;
+mod checkpoint;
+
+pub use self::evolution_params::EvolutionParams;
+mod evolution_params;
+
+pub use self::evolution_run::{EvolutionRun, RunMetadata, StatsCallback};
+mod evolution_run;
+
+pub use self::generation_stats::GenerationStats;
+mod generation_stats;
+
+pub use self::hall_of_fame::HallOfFame;
+mod hall_of_fame;
+
+pub use self::lineage::{Lineage, LineageRecord};
+mod lineage;
+
+pub use self::mutation_impact::{mutation_impact, MutationImpact, OperatorImpact};
+mod mutation_impact;
+
+pub use self::mutation_adaptation::{MutationAdaptation, MIN_MUTATION_RATE};
+mod mutation_adaptation;
@@ -0,0 +1,151 @@
+use std::f64::consts::PI;
+use std::fmt;
+use std::str::FromStr;
+
+use rand::{Rng, RngCore};
+
+/// The lowest mutation rate that adaptation can lead to, so that mutation never stops entirely.
+pub const MIN_MUTATION_RATE: f64 = 0.01;
+
+/// How the mutation rate changes over the course of a run, so that a run that has reached a
+/// plateau can escape it without being restarted with other parameters.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MutationAdaptation {
+    /// Rechenberg's one-fifth rule: after every generation, the mutation rate of the parameters
+    /// is multiplied by `factor` if more than a fifth of the mutated offspring are fitter than
+    /// their first parent, and divided by it if fewer are. Many successes mean that the population
+    /// is far from an optimum and can take bigger steps; few mean that it should search closer to
+    /// home.
+    OneFifthRule { factor: f64 },
+    /// Self-adaptation: every genome carries its own mutation rate, which starts at the rate of
+    /// the parameters. An offspring inherits the rate of its first parent, multiplied by
+    /// `exp(learning_rate * N(0, 1))`, and is then mutated with that probability. Rates that lead
+    /// to fit offspring thus spread along with them.
+    SelfAdaptive { learning_rate: f64 },
+}
+
+impl MutationAdaptation {
+    /// For self-adaptation, returns the mutation rate of an offspring whose first parent had the
+    /// given rate. Returns `None` for the one-fifth rule, under which genomes have no rate.
+    pub fn offspring_rate(&self, parent_rate: f64, rng: &mut dyn RngCore) -> Option<f64> {
+        match *self {
+            MutationAdaptation::OneFifthRule { .. } => None,
+            MutationAdaptation::SelfAdaptive { learning_rate } => {
+                let rate = parent_rate * (learning_rate * standard_normal(rng)).exp();
+                Some(rate.clamp(MIN_MUTATION_RATE, 1.0))
+            }
+        }
+    }
+
+    /// For the one-fifth rule, returns the mutation rate for the next generation, given the
+    /// current rate and the fraction of the mutated offspring that were fitter than their first
+    /// parent. Self-adaptation leaves the rate unchanged.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bf_bot_evolve::evolution::MutationAdaptation;
+    /// let adaptation = MutationAdaptation::OneFifthRule { factor: 2.0 };
+    /// assert_eq!(adaptation.next_rate(0.2, 0.5), 0.4);
+    /// assert_eq!(adaptation.next_rate(0.2, 0.2), 0.2);
+    /// assert_eq!(adaptation.next_rate(0.2, 0.0), 0.1);
+    /// ```
+    pub fn next_rate(&self, rate: f64, success_ratio: f64) -> f64 {
+        match *self {
+            MutationAdaptation::OneFifthRule { factor } => {
+                let rate = if success_ratio > 0.2 {
+                    rate * factor
+                } else if success_ratio < 0.2 {
+                    rate / factor
+                } else {
+                    rate
+                };
+                rate.clamp(MIN_MUTATION_RATE, 1.0)
+            }
+            MutationAdaptation::SelfAdaptive { .. } => rate,
+        }
+    }
+}
+
+/// Draws a number from the standard normal distribution, with the Box-Muller transform.
+fn standard_normal(rng: &mut dyn RngCore) -> f64 {
+    // The first number must not be zero, since its logarithm is taken.
+    let u1: f64 = 1.0 - rng.gen::<f64>();
+    let u2: f64 = rng.gen();
+    (-2.0 * u1.ln()).sqrt() * (2.0 * PI * u2).cos()
+}
+
+/// Formats the adaptation as `one-fifth <factor>` or `self-adaptive <learning rate>`, which is how
+/// config files and checkpoints specify it.
+impl fmt::Display for MutationAdaptation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            MutationAdaptation::OneFifthRule { factor } => write!(f, "one-fifth {}", factor),
+            MutationAdaptation::SelfAdaptive { learning_rate } => {
+                write!(f, "self-adaptive {}", learning_rate)
+            }
+        }
+    }
+}
+
+/// Parses the format of `Display`.
+impl FromStr for MutationAdaptation {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<MutationAdaptation, String> {
+        let words = s.split_whitespace().collect::<Vec<_>>();
+        match words.as_slice() {
+            ["one-fifth", factor] => {
+                factor.parse()
+                    .ok()
+                    .filter(|&factor: &f64| factor >= 1.0)
+                    .map(|factor| MutationAdaptation::OneFifthRule { factor })
+                    .ok_or_else(|| format!("Invalid one-fifth rule factor: {}", factor))
+            }
+            ["self-adaptive", learning_rate] => {
+                learning_rate.parse()
+                    .ok()
+                    .filter(|&learning_rate: &f64| learning_rate >= 0.0)
+                    .map(|learning_rate| MutationAdaptation::SelfAdaptive { learning_rate })
+                    .ok_or_else(|| format!("Invalid learning rate: {}", learning_rate))
+            }
+            _ => Err(format!("Unknown mutation adaptation: {}", s)),
+        }
+    }
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod tests {
+    use super::*;
+    use evolution::seeded_rng;
+
+    #[test]
+    fn toStringThenParse_sameAdaptation() {
+        for &adaptation in &[MutationAdaptation::OneFifthRule { factor: 1.5 },
+                             MutationAdaptation::SelfAdaptive { learning_rate: 0.2 }] {
+            assert_eq!(adaptation.to_string().parse(), Ok(adaptation));
+        }
+        assert!("one-fifth 0.5".parse::<MutationAdaptation>().is_err());
+        assert!("self-adaptive".parse::<MutationAdaptation>().is_err());
+    }
+
+    #[test]
+    fn nextRate_oneFifthRule_staysWithinBounds() {
+        let adaptation = MutationAdaptation::OneFifthRule { factor: 10.0 };
+        assert_eq!(adaptation.next_rate(0.5, 1.0), 1.0);
+        assert_eq!(adaptation.next_rate(0.05, 0.0), MIN_MUTATION_RATE);
+    }
+
+    #[test]
+    fn offspringRate_selfAdaptive_variesAroundParentRate() {
+        let adaptation = MutationAdaptation::SelfAdaptive { learning_rate: 0.2 };
+        let mut rng = seeded_rng(1);
+        let rates = (0..1000)
+            .map(|_| adaptation.offspring_rate(0.3, &mut rng).unwrap())
+            .collect::<Vec<_>>();
+        assert!(rates.iter().any(|&rate| rate < 0.3) && rates.iter().any(|&rate| rate > 0.3));
+        let mean_log = rates.iter().map(|rate| (rate / 0.3).ln()).sum::<f64>() / 1000.0;
+        assert!(mean_log.abs() < 0.05);
+    }
+}
@@ -0,0 +1,128 @@
+use std::collections::BTreeMap;
+
+use evolution::Parentage;
+use population::Population;
+
+/// How the offspring of a single mutation operator fared, compared to their parents.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct OperatorImpact {
+    /// The number of evaluated offspring that the operator mutated.
+    pub nr_offspring: usize,
+    /// The number of them that were fitter than the fittest of their parents.
+    pub nr_improved: usize,
+    /// The number of them that were less fit than the least fit of their parents.
+    pub nr_worsened: usize,
+}
+
+impl OperatorImpact {
+    /// Returns the fraction of the offspring that improved on their parents, or 0 if the operator
+    /// mutated none.
+    pub fn improvement_ratio(&self) -> f64 {
+        if self.nr_offspring == 0 {
+            0.0
+        } else {
+            self.nr_improved as f64 / self.nr_offspring as f64
+        }
+    }
+
+    /// Adds the counts of another impact to these.
+    pub fn add(&mut self, other: &OperatorImpact) {
+        self.nr_offspring += other.nr_offspring;
+        self.nr_improved += other.nr_improved;
+        self.nr_worsened += other.nr_worsened;
+    }
+}
+
+/// The impact of every mutation operator that was applied, by the name of the operator. Ordered, so
+/// that the operators are always listed in the same order.
+pub type MutationImpact = BTreeMap<String, OperatorImpact>;
+
+/// Compares every mutated individual of an evaluated generation to its parents, given the fitness
+/// of the generation it was bred from, and counts the outcomes per mutation operator. Individuals
+/// that haven't been evaluated are left out.
+///
+/// Offspring that were also produced by crossover count towards the mutation operator as well;
+/// the comparison with both parents tells how the combination fared.
+pub fn mutation_impact(parent_fitnesses: &[f64],
+                       population: &Population,
+                       parentages: &[Parentage])
+                       -> MutationImpact {
+    let mut impact = MutationImpact::new();
+    for (individual, parentage) in population.individuals.iter().zip(parentages) {
+        let operator = match *parentage {
+            Parentage::Offspring { mutation: Some(ref operator), .. } => operator,
+            _ => continue,
+        };
+        let fitness = match individual.fitness {
+            Some(fitness) => fitness,
+            None => continue,
+        };
+        let parents = parentage.parents()
+            .into_iter()
+            .map(|parent| parent_fitnesses[parent])
+            .collect::<Vec<_>>();
+        let best_parent = parents.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+        let worst_parent = parents.iter().cloned().fold(f64::INFINITY, f64::min);
+        let counts = impact.entry(operator.clone()).or_default();
+        counts.nr_offspring += 1;
+        if fitness > best_parent {
+            counts.nr_improved += 1;
+        } else if fitness < worst_parent {
+            counts.nr_worsened += 1;
+        }
+    }
+    impact
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod tests {
+    use super::*;
+    use genome::Genome;
+
+    fn make_population(fitnesses: &[f64]) -> Population {
+        let mut population = Population::new(vec![Genome::default(); fitnesses.len()]);
+        for (individual, &fitness) in population.individuals.iter_mut().zip(fitnesses) {
+            individual.fitness = Some(fitness);
+        }
+        population
+    }
+
+    fn offspring(first_parent: usize, second_parent: Option<usize>, mutation: &str) -> Parentage {
+        Parentage::Offspring {
+            first_parent,
+            second_parent,
+            mutation: Some(mutation.to_string()),
+        }
+    }
+
+    #[test]
+    fn mutationImpact_countsPerOperator() {
+        let parentages = vec![Parentage::Elite(1),
+                              offspring(0, None, "point"),
+                              offspring(1, None, "point"),
+                              offspring(0, Some(1), "deletion"),
+                              Parentage::Offspring {
+                                  first_parent: 0,
+                                  second_parent: None,
+                                  mutation: None,
+                              }];
+        let impact = mutation_impact(&[1.0, 3.0],
+                                     &make_population(&[3.0, 2.0, 2.0, 2.0, 9.0]),
+                                     &parentages);
+        assert_eq!(impact.len(), 2);
+        assert_eq!(impact["point"],
+                   OperatorImpact {
+                       nr_offspring: 2,
+                       nr_improved: 1,
+                       nr_worsened: 1,
+                   });
+        assert_eq!(impact["deletion"],
+                   OperatorImpact {
+                       nr_offspring: 1,
+                       nr_improved: 0,
+                       nr_worsened: 0,
+                   });
+        assert_eq!(impact["point"].improvement_ratio(), 0.5);
+    }
+}
@@ -0,0 +1,92 @@
+use bf_bot_core::bf::Bot;
+use fitness::{FitnessFunction, HillFitness};
+
+/// Scores a bot on how well it beats one specific target, without letting it regress against a
+/// hill: the way humans attack the top of a hill. The fitness is a weighted average of the
+/// normalized match score against the target and the fitness on the hill, in which the target
+/// weighs heavily by default.
+///
+/// If a floor is set for the fitness on the hill, every point that a bot falls below it costs as
+/// much as a point against the target earns, so that a bot can't buy its win against the target by
+/// giving up on the rest of the hill.
+#[derive(Debug, Clone)]
+pub struct CounterFitness {
+    target: Bot,
+    hill: HillFitness,
+    target_weight: f64,
+    hill_floor: Option<f64>,
+}
+
+impl CounterFitness {
+    /// Creates a fitness function in which the target weighs 0.8, and the hill 0.2, without a
+    /// floor.
+    pub fn new(target: Bot, hill: Vec<Bot>) -> CounterFitness {
+        CounterFitness {
+            target,
+            hill: HillFitness::new(hill),
+            target_weight: 0.8,
+            hill_floor: None,
+        }
+    }
+
+    /// Selects the weight of the target, between 0 and 1. The hill gets the rest.
+    pub fn with_target_weight(self, target_weight: f64) -> CounterFitness {
+        CounterFitness { target_weight: target_weight.clamp(0.0, 1.0), ..self }
+    }
+
+    /// Penalizes bots with a fitness on the hill below the given floor, such as the fitness of the
+    /// target itself.
+    pub fn with_hill_floor(self, hill_floor: f64) -> CounterFitness {
+        CounterFitness { hill_floor: Some(hill_floor), ..self }
+    }
+
+    pub fn get_target(&self) -> &Bot {
+        &self.target
+    }
+
+    pub fn get_hill(&self) -> &HillFitness {
+        &self.hill
+    }
+}
+
+impl FitnessFunction for CounterFitness {
+    fn evaluate(&self, bot: &Bot) -> f64 {
+        let against_target = HillFitness::normalized_score(bot, &self.target);
+        let on_hill = self.hill.evaluate(bot);
+        let fitness = self.target_weight * against_target + (1.0 - self.target_weight) * on_hill;
+        match self.hill_floor {
+            Some(floor) if on_hill < floor => fitness - self.target_weight * (floor - on_hill),
+            _ => fitness,
+        }
+    }
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod tests {
+    use super::*;
+    use bf_bot_core::bf::Instruction;
+
+    fn idle() -> Bot {
+        Bot::new(vec![])
+    }
+
+    fn suicidal() -> Bot {
+        Bot::new(vec![Instruction::MoveBack])
+    }
+
+    #[test]
+    fn evaluate_weightsTargetAndHill() {
+        // The idle bot draws against the idle target and beats the suicidal hill.
+        let fitness = CounterFitness::new(idle(), vec![suicidal()]).with_target_weight(0.75);
+        assert_eq!(fitness.evaluate(&idle()), 0.25);
+    }
+
+    #[test]
+    fn evaluate_belowHillFloor_penalized() {
+        // The idle bot beats the suicidal target, but draws against the idle hill.
+        let fitness = CounterFitness::new(suicidal(), vec![idle()]).with_target_weight(0.5);
+        assert_eq!(fitness.evaluate(&idle()), 0.5);
+        assert_eq!(fitness.with_hill_floor(0.5).evaluate(&idle()), 0.25);
+    }
+}
@@ -0,0 +1,132 @@
+use bf_bot_core::bf::Bot;
+use bf_bot_core::reference_bots;
+use bf_bot_core::simul_game::TapeLengths;
+use fitness::HillFitness;
+
+/// A stage of a curriculum: from which generation on the bots play against which opponents, and
+/// on which tape lengths.
+#[derive(Debug, Clone)]
+pub struct CurriculumStage {
+    pub from_generation: u32,
+    pub fitness: HillFitness,
+}
+
+/// Schedules the difficulty of the evaluation over the course of a run. Random programs rarely
+/// score at all against strong bots on long tapes, which leaves the evolution nothing to select
+/// on. A curriculum lets the first generations play against easy opponents on short tapes, and
+/// moves on to harder opponents and longer tapes at the given generations.
+///
+/// # Examples
+///
+/// ```
+/// extern crate bf_bot_core;
+/// extern crate bf_bot_evolve;
+/// use bf_bot_core::bf::Bot;
+/// use bf_bot_core::reference_bots;
+/// use bf_bot_core::simul_game::TapeLengths;
+/// use bf_bot_evolve::fitness::Curriculum;
+/// let curriculum = Curriculum::new()
+///     .with_stage(0, vec![reference_bots::suicide()], TapeLengths::Single(10))
+///     .with_stage(20, vec![reference_bots::fast_rush()], TapeLengths::Range { min: 10, max: 30 });
+/// assert_eq!(curriculum.stage_at(19), Some(0));
+/// assert_eq!(curriculum.stage_at(20), Some(1));
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Curriculum {
+    /// Ordered by the generation they start from.
+    stages: Vec<CurriculumStage>,
+}
+
+impl Curriculum {
+    /// Creates a curriculum without stages.
+    pub fn new() -> Curriculum {
+        Curriculum::default()
+    }
+
+    /// A curriculum on the reference bots, of which every stage lasts the given number of
+    /// generations: first the bots that never attack on short tapes, then the starter hill on
+    /// short tapes, and finally the starter hill on all tape lengths.
+    pub fn starter(stage_length: u32) -> Curriculum {
+        let starter_hill = reference_bots::starter_hill()
+            .into_iter()
+            .map(|warrior| warrior.bot)
+            .collect::<Vec<_>>();
+        let short_tapes = TapeLengths::Range { min: 10, max: 15 };
+        Curriculum::new()
+            .with_stage(0,
+                        vec![reference_bots::suicide(), reference_bots::sit_and_wait()],
+                        short_tapes.clone())
+            .with_stage(stage_length, starter_hill.clone(), short_tapes)
+            .with_stage(2 * stage_length, starter_hill, TapeLengths::default())
+    }
+
+    /// Adds a stage in which the bots play against the given opponents on the given tape lengths,
+    /// from the given generation on, until the next stage starts.
+    pub fn with_stage(self,
+                      from_generation: u32,
+                      opponents: Vec<Bot>,
+                      tape_lengths: TapeLengths)
+                      -> Curriculum {
+        let fitness = HillFitness::new(opponents).with_tape_lengths(tape_lengths);
+        self.with_stage_fitness(from_generation, fitness)
+    }
+
+    /// Adds a stage with a fitness function of its own, for instance one with weights or shaping.
+    /// A stage that starts from the same generation as an existing stage replaces it.
+    pub fn with_stage_fitness(mut self, from_generation: u32, fitness: HillFitness) -> Curriculum {
+        self.stages.retain(|stage| stage.from_generation != from_generation);
+        let index = self.stages
+            .iter()
+            .position(|stage| stage.from_generation > from_generation)
+            .unwrap_or(self.stages.len());
+        self.stages.insert(index,
+                           CurriculumStage {
+                               from_generation,
+                               fitness,
+                           });
+        self
+    }
+
+    pub fn get_stages(&self) -> &[CurriculumStage] {
+        &self.stages
+    }
+
+    /// Returns the index of the stage that the given generation is in, or `None` if the generation
+    /// comes before the first stage.
+    pub fn stage_at(&self, generation: u32) -> Option<usize> {
+        self.stages.iter().rposition(|stage| stage.from_generation <= generation)
+    }
+
+    /// Returns the fitness function of the stage that the given generation is in.
+    pub fn fitness_at(&self, generation: u32) -> Option<&HillFitness> {
+        self.stage_at(generation).map(|index| &self.stages[index].fitness)
+    }
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod tests {
+    use super::*;
+    use fitness::FitnessFunction;
+
+    #[test]
+    fn stageAt_stagesAddedOutOfOrder_sortedByGeneration() {
+        let curriculum = Curriculum::new()
+            .with_stage(10, vec![], TapeLengths::default())
+            .with_stage(5, vec![], TapeLengths::Single(10));
+        assert_eq!(curriculum.stage_at(4), None);
+        assert_eq!(curriculum.stage_at(5), Some(0));
+        assert_eq!(curriculum.stage_at(9), Some(0));
+        assert_eq!(curriculum.stage_at(100), Some(1));
+    }
+
+    #[test]
+    fn starter_firstStageEasierThanLast() {
+        let curriculum = Curriculum::starter(10);
+        assert_eq!(curriculum.get_stages().len(), 3);
+        let idle = Bot::new(vec![]);
+        let first = curriculum.fitness_at(0).unwrap().evaluate(&idle);
+        let last = curriculum.fitness_at(20).unwrap().evaluate(&idle);
+        assert!(first > last);
+    }
+}
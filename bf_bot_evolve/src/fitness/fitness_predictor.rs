@@ -0,0 +1,215 @@
+use bf_bot_core::bf::Bot;
+use fitness::FitnessFunction;
+use population::Population;
+
+/// Predicts the fitness of a bot without playing any rounds, for instance with a model that was
+/// trained on features of the program. A prediction only has to be good enough to tell the
+/// obviously bad bots apart, but it has to be much cheaper than the fitness function.
+pub trait FitnessPredictor: Send + Sync {
+    fn predict(&self, bot: &Bot) -> f64;
+
+    /// Receives bots of which the fitness was both predicted and computed, so that the model can
+    /// correct itself. By default, they are ignored.
+    fn calibrate(&mut self, _samples: &[CalibrationSample]) {}
+}
+
+/// Any function or closure that takes a bot and returns a number can be used as a predictor that
+/// never calibrates.
+impl<F> FitnessPredictor for F
+    where F: Fn(&Bot) -> f64 + Send + Sync
+{
+    fn predict(&self, bot: &Bot) -> f64 {
+        self(bot)
+    }
+}
+
+/// A bot with both its predicted and its actual fitness.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CalibrationSample {
+    pub bot: Bot,
+    pub predicted: f64,
+    pub actual: f64,
+}
+
+/// How well the predictions matched the actual fitness the last time they were checked.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Calibration {
+    /// The generation in which the predictions were checked.
+    pub generation: u32,
+    pub nr_samples: usize,
+    pub mean_absolute_error: f64,
+    /// The number of bots that would have been skipped, although their actual fitness was at least
+    /// the threshold.
+    pub nr_wrongly_skipped: usize,
+}
+
+/// Skips the fitness function for offspring that a `FitnessPredictor` deems obviously bad: those
+/// with a predicted fitness below the threshold get the predicted fitness instead. Every so many
+/// generations, all offspring are evaluated anyway, to check the predictions and let the predictor
+/// calibrate itself.
+pub struct FitnessScreening {
+    predictor: Box<dyn FitnessPredictor>,
+    threshold: f64,
+    calibration_interval: u32,
+    nr_skipped: usize,
+    calibration: Option<Calibration>,
+}
+
+impl FitnessScreening {
+    /// Creates a screening that skips the offspring with a predicted fitness below the threshold,
+    /// and checks the predictions every 10 generations.
+    pub fn new(predictor: Box<dyn FitnessPredictor>, threshold: f64) -> FitnessScreening {
+        FitnessScreening {
+            predictor,
+            threshold,
+            calibration_interval: 10,
+            nr_skipped: 0,
+            calibration: None,
+        }
+    }
+
+    /// Selects the number of generations between two checks of the predictions. With an interval
+    /// of 0, the predictions are never checked.
+    pub fn with_calibration_interval(self, calibration_interval: u32) -> FitnessScreening {
+        FitnessScreening { calibration_interval, ..self }
+    }
+
+    /// Returns the number of evaluations that have been skipped so far.
+    pub fn get_nr_skipped(&self) -> usize {
+        self.nr_skipped
+    }
+
+    /// Returns the result of the latest check of the predictions, if there has been one.
+    pub fn get_calibration(&self) -> Option<Calibration> {
+        self.calibration
+    }
+
+    /// Evaluates all individuals of the population whose fitness isn't known yet, of the given
+    /// generation, with either the predictor or the fitness function.
+    pub fn evaluate(&mut self,
+                    population: &mut Population,
+                    fitness_function: &dyn FitnessFunction,
+                    generation: u32) {
+        let predictions = population.individuals
+            .iter()
+            .map(|individual| match individual.fitness {
+                Some(_) => None,
+                None => Some(self.predictor.predict(&individual.bot)),
+            })
+            .collect::<Vec<_>>();
+        let is_due = self.calibration_interval > 0 &&
+                     generation.is_multiple_of(self.calibration_interval);
+        if is_due {
+            population.evaluate(fitness_function);
+            self.calibrate(population, &predictions, generation);
+            return;
+        }
+        for (individual, prediction) in population.individuals.iter_mut().zip(predictions) {
+            if let Some(prediction) = prediction.filter(|&prediction| prediction < self.threshold) {
+                individual.fitness = Some(prediction);
+                self.nr_skipped += 1;
+            }
+        }
+        population.evaluate(fitness_function);
+    }
+
+    fn calibrate(&mut self, population: &Population, predictions: &[Option<f64>], generation: u32) {
+        let samples = population.individuals
+            .iter()
+            .zip(predictions)
+            .filter_map(|(individual, prediction)| {
+                prediction.map(|predicted| {
+                    CalibrationSample {
+                        bot: individual.bot.clone(),
+                        predicted,
+                        actual: individual.fitness.expect("The population has been evaluated."),
+                    }
+                })
+            })
+            .collect::<Vec<_>>();
+        if samples.is_empty() {
+            return;
+        }
+        let total_error = samples.iter()
+            .map(|sample| (sample.predicted - sample.actual).abs())
+            .sum::<f64>();
+        let nr_wrongly_skipped = samples.iter()
+            .filter(|sample| sample.predicted < self.threshold && sample.actual >= self.threshold)
+            .count();
+        self.calibration = Some(Calibration {
+            generation,
+            nr_samples: samples.len(),
+            mean_absolute_error: total_error / samples.len() as f64,
+            nr_wrongly_skipped,
+        });
+        self.predictor.calibrate(&samples);
+    }
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod tests {
+    use super::*;
+    use bf_bot_core::bf::Instruction;
+
+    /// Predicts the fitness from the length of the program, and corrects its offset whenever it
+    /// is calibrated.
+    struct LengthModel {
+        offset: f64,
+    }
+
+    impl FitnessPredictor for LengthModel {
+        fn predict(&self, bot: &Bot) -> f64 {
+            bot.get_program().len() as f64 + self.offset
+        }
+
+        fn calibrate(&mut self, samples: &[CalibrationSample]) {
+            let error = samples.iter().map(|sample| sample.actual - sample.predicted).sum::<f64>();
+            self.offset += error / samples.len() as f64;
+        }
+    }
+
+    fn program_length(bot: &Bot) -> f64 {
+        bot.get_program().len() as f64
+    }
+
+    fn make_population() -> Population {
+        let bots = [1, 5, 9].iter()
+            .map(|&length| Bot::new(vec![Instruction::Increment; length]))
+            .collect::<Vec<_>>();
+        Population::from_bots(&bots)
+    }
+
+    #[test]
+    fn evaluate_belowThreshold_predictionKept() {
+        let predictor = |bot: &Bot| -(bot.get_program().len() as f64);
+        let mut screening = FitnessScreening::new(Box::new(predictor), -4.0)
+            .with_calibration_interval(0);
+        let mut population = make_population();
+        screening.evaluate(&mut population, &program_length, 1);
+        assert_eq!(population.fitnesses(), vec![1.0, -5.0, -9.0]);
+        assert_eq!(screening.get_nr_skipped(), 2);
+        assert_eq!(screening.get_calibration(), None);
+    }
+
+    #[test]
+    fn evaluate_calibrationDue_everythingEvaluatedAndPredictorCorrected() {
+        let mut screening = FitnessScreening::new(Box::new(LengthModel { offset: -10.0 }), 0.0)
+            .with_calibration_interval(2);
+        let mut population = make_population();
+        screening.evaluate(&mut population, &program_length, 2);
+        assert_eq!(population.fitnesses(), vec![1.0, 5.0, 9.0]);
+        assert_eq!(screening.get_calibration(),
+                   Some(Calibration {
+                       generation: 2,
+                       nr_samples: 3,
+                       mean_absolute_error: 10.0,
+                       nr_wrongly_skipped: 3,
+                   }));
+        // Now that the offset is corrected, nothing is skipped anymore.
+        let mut population = make_population();
+        screening.evaluate(&mut population, &program_length, 3);
+        assert_eq!(screening.get_nr_skipped(), 0);
+        assert_eq!(population.fitnesses(), vec![1.0, 5.0, 9.0]);
+    }
+}
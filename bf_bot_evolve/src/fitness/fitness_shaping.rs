@@ -0,0 +1,176 @@
+use bf_bot_core::bf::Bot;
+use bf_bot_core::engine::Arena;
+use bf_bot_core::match_runner::{MatchResult, RoundRecord};
+use bf_bot_core::simul_round::{CellWidth, RoundStatus, StepOutcome};
+
+/// Gives lost rounds partial credit, so that evolution can tell a bot that nearly held out, or
+/// nearly zeroed the enemy flag, from one that lost straight away. With a plain win or loss per
+/// round, a population in which every bot loses every round has nothing to climb.
+///
+/// A won round counts as 1 and a draw as 0, as usual. A lost round counts as
+/// `-1 + survival * steps_survived / max_steps + flag_damage * damage`, where `damage` is the
+/// largest part of the way to zero that the bot moved the enemy flag at any point in the round,
+/// between 0 and 1. The weights should add up to at most 1, so that a lost round never counts for
+/// more than a draw. The default gives no credit at all, which scores like
+/// `ScoringScheme::Chainbf`.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct FitnessShaping {
+    /// The credit for surviving until the maximum number of steps.
+    pub survival: f64,
+    /// The credit for zeroing the enemy flag all the way. Finding out how much damage was done
+    /// means playing each lost round again, so this makes evaluation slower.
+    pub flag_damage: f64,
+}
+
+impl FitnessShaping {
+    /// Returns the average value of the rounds of the given match for bot A, which lies between -1
+    /// and 1. The bots are needed to work out the damage to the enemy flag.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// extern crate bf_bot_core;
+    /// extern crate bf_bot_evolve;
+    /// use bf_bot_core::bf::{Bot, Instruction};
+    /// use bf_bot_core::match_runner::Match;
+    /// use bf_bot_evolve::fitness::FitnessShaping;
+    /// let suicidal = Bot::new(vec![Instruction::MoveBack]);
+    /// let idle = Bot::new(vec![]);
+    /// let result = Match::new(&suicidal, &idle).run().unwrap();
+    /// assert_eq!(FitnessShaping::default().points(&suicidal, &idle, &result), -1.0);
+    /// ```
+    pub fn points(&self, bot_a: &Bot, bot_b: &Bot, match_result: &MatchResult) -> f64 {
+        if match_result.rounds.is_empty() {
+            return 0.0;
+        }
+        let total: f64 = match_result.rounds
+            .iter()
+            .map(|round| match round.result.status() {
+                RoundStatus::StartBotWon => 1.0,
+                RoundStatus::EndBotWon => -1.0 + self.loss_credit(bot_a, bot_b, round),
+                RoundStatus::Draw | RoundStatus::Ongoing => 0.0,
+            })
+            .sum();
+        total / match_result.rounds.len() as f64
+    }
+
+    fn loss_credit(&self, bot_a: &Bot, bot_b: &Bot, round: &RoundRecord) -> f64 {
+        let max_steps = round.params.max_steps;
+        let survival = if max_steps == 0 {
+            0.0
+        } else {
+            f64::from(round.result.nr_steps.min(max_steps)) / f64::from(max_steps)
+        };
+        let flag_damage = if self.flag_damage == 0.0 {
+            0.0
+        } else {
+            FitnessShaping::flag_damage(bot_a, bot_b, round)
+        };
+        self.survival * survival + self.flag_damage * flag_damage
+    }
+
+    /// Plays the round again to find out how close bot A got to zeroing the flag at the far end of
+    /// the tape, as a fraction of the distance of the flag from zero at the start of the round. The
+    /// closest the flag came to zero at the end of any step counts, even if it was raised again
+    /// later.
+    fn flag_damage(bot_a: &Bot, bot_b: &Bot, round: &RoundRecord) -> f64 {
+        let cell_width = round.params.cell_width;
+        let mut arena = Arena::new(bot_a, bot_b, &round.params).expect("The round was played.");
+        let flag = |tape: &[i32]| distance_from_zero(cell_width, tape[tape.len() - 1]);
+        let initial_distance = flag(arena.get_tape());
+        if initial_distance == 0 {
+            return 0.0;
+        }
+        let mut closest_distance = initial_distance;
+        loop {
+            let outcome = arena.step();
+            closest_distance = closest_distance.min(flag(arena.get_tape()));
+            if let StepOutcome::Finished(_) = outcome {
+                break;
+            }
+        }
+        1.0 - f64::from(closest_distance) / f64::from(initial_distance)
+    }
+}
+
+/// The number of increments or decrements it takes to zero a cell with the given value.
+fn distance_from_zero(cell_width: CellWidth, value: i32) -> u32 {
+    match cell_width {
+        CellWidth::U8 => (value as u32).min(256 - value as u32),
+        _ => value.unsigned_abs(),
+    }
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod tests {
+    use super::*;
+    use bf_bot_core::bf::Instruction;
+    use bf_bot_core::match_runner::{Match, ScoringScheme};
+    use bf_bot_core::simul_round::RoundParams;
+
+    /// Its program, in BrainFuck: (>)*9(-)*64<<<<<<<<<<
+    /// It halves the enemy flag on the shortest tape, then walks off its own end of the tape.
+    fn make_half_clearing_bot() -> Bot {
+        let mut program = vec![Instruction::MoveForward; 9];
+        program.extend(vec![Instruction::Decrement; 64]);
+        program.extend(vec![Instruction::MoveBack; 10]);
+        Bot::new(program)
+    }
+
+    #[test]
+    fn points_noShaping_sameAsChainbf() {
+        let bot_a = make_half_clearing_bot();
+        let bot_b = Bot::new(vec![]);
+        let result = Match::new(&bot_a, &bot_b).run().unwrap();
+        assert_eq!(FitnessShaping::default().points(&bot_a, &bot_b, &result),
+                   ScoringScheme::Chainbf.points(&result));
+    }
+
+    #[test]
+    fn points_survival_creditForStepsSurvived() {
+        let bot_a = make_half_clearing_bot();
+        let bot_b = Bot::new(vec![]);
+        let result = Match::new(&bot_a, &bot_b)
+            .run_rounds(vec![RoundParams::new(10, false, 1000)].into_iter())
+            .unwrap();
+        let shaping = FitnessShaping {
+            survival: 1.0,
+            flag_damage: 0.0,
+        };
+        assert_eq!(result.rounds[0].result.nr_steps, 83);
+        assert_eq!(shaping.points(&bot_a, &bot_b, &result), -1.0 + 0.083);
+    }
+
+    #[test]
+    fn points_flagDamage_creditForHalvedFlagInBothPolarities() {
+        let bot_a = make_half_clearing_bot();
+        let bot_b = Bot::new(vec![]);
+        let rounds = vec![RoundParams::new(10, false, 1000), RoundParams::new(10, true, 1000)];
+        let result = Match::new(&bot_a, &bot_b).run_rounds(rounds.into_iter()).unwrap();
+        let shaping = FitnessShaping {
+            survival: 0.0,
+            flag_damage: 1.0,
+        };
+        assert_eq!(shaping.points(&bot_a, &bot_b, &result), -0.5);
+    }
+
+    #[test]
+    fn points_flagDamage_creditForClosestApproachEvenIfRestored() {
+        // (>)*9(-)*64(+)*64(<)*10
+        let mut program = vec![Instruction::MoveForward; 9];
+        program.extend(vec![Instruction::Decrement; 64]);
+        program.extend(vec![Instruction::Increment; 64]);
+        program.extend(vec![Instruction::MoveBack; 10]);
+        let bot_a = Bot::new(program);
+        let bot_b = Bot::new(vec![]);
+        let result = Match::new(&bot_a, &bot_b)
+            .run_rounds(vec![RoundParams::new(10, false, 1000)].into_iter())
+            .unwrap();
+        let shaping = FitnessShaping {
+            survival: 0.0,
+            flag_damage: 1.0,
+        };
+        assert_eq!(shaping.points(&bot_a, &bot_b, &result), -0.5);
+    }
+}
@@ -0,0 +1,206 @@
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+
+use std::sync::Arc;
+
+use bf_bot_core::bf::Bot;
+use bf_bot_core::match_runner::{Match, ScoringScheme};
+use bf_bot_core::simul_game::{AllRounds, TapeLengths};
+use fitness::{FitnessFunction, FitnessShaping, MatchCache};
+
+/// Scores a bot by letting it play a complete match against each bot on a fixed hill of reference
+/// bots. Every opponent has a weight, so that beating some opponents can be made to count for more
+/// than beating others.
+///
+/// The fitness is the weighted average of the normalized match scores, where a normalized score of
+/// 1 means that every round was won and -1 means that every round was lost. The fitness thus also
+/// lies between -1 and 1. With `FitnessShaping`, lost rounds earn partial credit.
+#[derive(Debug, Clone)]
+pub struct HillFitness {
+    opponents: Vec<(Bot, f64)>,
+    cache: Option<Arc<MatchCache>>,
+    shaping: Option<FitnessShaping>,
+    /// The tape lengths of the matches, if they aren't those of a complete match.
+    tape_lengths: Option<TapeLengths>,
+}
+
+impl HillFitness {
+    /// Creates a fitness function in which all bots on the hill count equally.
+    pub fn new(hill: Vec<Bot>) -> HillFitness {
+        HillFitness::weighted(hill.into_iter().map(|bot| (bot, 1.0)).collect())
+    }
+
+    /// Creates a fitness function from bots on the hill and their respective weights.
+    pub fn weighted(opponents: Vec<(Bot, f64)>) -> HillFitness {
+        HillFitness {
+            opponents,
+            cache: None,
+            shaping: None,
+            tape_lengths: None,
+        }
+    }
+
+    /// Looks up the matches in the given cache before playing them, and stores them there after.
+    /// The cache can be shared with other fitness functions.
+    pub fn with_match_cache(self, cache: Arc<MatchCache>) -> HillFitness {
+        HillFitness {
+            cache: Some(cache),
+            ..self
+        }
+    }
+
+    /// Gives lost rounds partial credit as the given shaping prescribes, instead of counting each
+    /// of them as -1.
+    pub fn with_shaping(self, shaping: FitnessShaping) -> HillFitness {
+        HillFitness {
+            shaping: Some(shaping),
+            ..self
+        }
+    }
+
+    /// Plays the matches on the given tape lengths only, instead of on all tape lengths of a
+    /// complete match.
+    ///
+    /// # Panics
+    ///
+    /// Evaluating a bot panics if any of the tape lengths is too short to play on.
+    pub fn with_tape_lengths(self, tape_lengths: TapeLengths) -> HillFitness {
+        HillFitness {
+            tape_lengths: Some(tape_lengths),
+            ..self
+        }
+    }
+
+    pub fn get_opponents(&self) -> &[(Bot, f64)] {
+        &self.opponents
+    }
+
+    /// Plays a complete match against the given opponent and returns the normalized score.
+    pub fn normalized_score(bot: &Bot, opponent: &Bot) -> f64 {
+        let rounds = AllRounds::new().with_early_termination();
+        Match::new(bot, opponent)
+            .with_scoring_scheme(ScoringScheme::Chainbf)
+            .run_rounds(rounds)
+            .expect("The standard rounds are valid.")
+            .points()
+    }
+
+    fn score_against(&self, bot: &Bot, opponent: &Bot) -> f64 {
+        match (&self.cache, self.shaping, &self.tape_lengths) {
+            (&None, None, &None) => HillFitness::normalized_score(bot, opponent),
+            (cache, shaping, tape_lengths) => {
+                let rounds = match *tape_lengths {
+                    Some(ref tape_lengths) => AllRounds::new().with_tape_lengths(tape_lengths),
+                    None => AllRounds::new(),
+                };
+                let rounds = rounds.with_early_termination();
+                let result = match *cache {
+                    Some(ref cache) => cache.play(bot, opponent, &rounds.collect::<Vec<_>>()),
+                    None => Match::new(bot, opponent).run_rounds(rounds),
+                };
+                let result = result.expect("The tape lengths can be played on.");
+                match shaping {
+                    Some(shaping) => shaping.points(bot, opponent, &result),
+                    None => ScoringScheme::Chainbf.points(&result),
+                }
+            }
+        }
+    }
+
+    #[cfg(not(feature = "rayon"))]
+    fn weighted_scores(&self, bot: &Bot) -> Vec<f64> {
+        self.opponents
+            .iter()
+            .map(|&(ref opponent, weight)| weight * self.score_against(bot, opponent))
+            .collect()
+    }
+
+    #[cfg(feature = "rayon")]
+    fn weighted_scores(&self, bot: &Bot) -> Vec<f64> {
+        self.opponents
+            .par_iter()
+            .map(|&(ref opponent, weight)| weight * self.score_against(bot, opponent))
+            .collect()
+    }
+}
+
+impl FitnessFunction for HillFitness {
+    fn evaluate(&self, bot: &Bot) -> f64 {
+        let total_weight: f64 = self.opponents.iter().map(|&(_, weight)| weight).sum();
+        if total_weight == 0.0 {
+            return 0.0;
+        }
+        // The scores are collected before adding them up, so that they are always added in the
+        // same order, even if they were computed in parallel.
+        self.weighted_scores(bot).iter().sum::<f64>() / total_weight
+    }
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod tests {
+    use super::*;
+    use bf_bot_core::bf::Instruction;
+
+    /// Its program, in BrainFuck: <
+    fn make_suicidal_bot() -> Bot {
+        Bot::new(vec![Instruction::MoveBack])
+    }
+
+    #[test]
+    fn evaluate_emptyHill_zero() {
+        assert_eq!(HillFitness::new(vec![]).evaluate(&Bot::new(vec![])), 0.0);
+    }
+
+    #[test]
+    fn evaluate_beatsEveryOpponentInEveryRound_one() {
+        let fitness = HillFitness::new(vec![make_suicidal_bot(), make_suicidal_bot()]);
+        assert_eq!(fitness.evaluate(&Bot::new(vec![])), 1.0);
+    }
+
+    #[test]
+    fn evaluate_losesEveryRound_minusOne() {
+        let fitness = HillFitness::new(vec![Bot::new(vec![])]);
+        assert_eq!(fitness.evaluate(&make_suicidal_bot()), -1.0);
+    }
+
+    #[test]
+    fn evaluate_weightedOpponents_weightedAverage() {
+        // Against the suicidal bot, an empty bot scores 1. Against another empty bot it scores 0.
+        let fitness = HillFitness::weighted(vec![(make_suicidal_bot(), 3.0),
+                                                 (Bot::new(vec![]), 1.0)]);
+        assert_eq!(fitness.evaluate(&Bot::new(vec![])), 0.75);
+    }
+
+    #[test]
+    fn evaluate_withShaping_lossesEarnCredit() {
+        let fitness = HillFitness::new(vec![Bot::new(vec![])]).with_shaping(FitnessShaping {
+            survival: 0.5,
+            flag_damage: 0.0,
+        });
+        let fitness = fitness.evaluate(&make_suicidal_bot());
+        assert!(fitness > -1.0 && fitness < 0.0);
+    }
+
+    #[test]
+    fn evaluate_withMatchCache_sameFitnessAndCached() {
+        let cache = Arc::new(MatchCache::default());
+        let hill = vec![make_suicidal_bot(), Bot::new(vec![])];
+        let fitness = HillFitness::new(hill.clone()).with_match_cache(cache.clone());
+        let bot = Bot::new(vec![Instruction::MoveForward]);
+        assert_eq!(fitness.evaluate(&bot), HillFitness::new(hill).evaluate(&bot));
+        fitness.evaluate(&bot);
+        assert_eq!((cache.nr_hits(), cache.nr_misses()), (2, 2));
+    }
+
+    #[test]
+    fn evaluate_withTapeLengths_onlyThoseLengthsPlayed() {
+        // Twelve steps forward run off a tape of 10 cells, but not off a tape of 30 cells.
+        let bot = Bot::new(vec![Instruction::MoveForward; 12]);
+        let fitness = |tape_lengths| {
+            HillFitness::new(vec![Bot::new(vec![])]).with_tape_lengths(tape_lengths).evaluate(&bot)
+        };
+        assert_eq!(fitness(TapeLengths::Single(10)), -1.0);
+        assert_eq!(fitness(TapeLengths::Single(30)), 0.0);
+    }
+}
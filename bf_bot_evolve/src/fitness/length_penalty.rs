@@ -0,0 +1,42 @@
+use bf_bot_core::bf::Bot;
+use fitness::FitnessFunction;
+
+/// Counters bloat by subtracting a fixed penalty for every instruction of the program from the
+/// fitness computed by another fitness function. Of two bots that are equally strong, the shorter
+/// one thus has the higher fitness. The length is that of the compiled program, with every
+/// repetition expanded, since that is what gets submitted to a hill.
+pub struct LengthPenalty {
+    fitness_function: Box<dyn FitnessFunction>,
+    penalty_per_instruction: f64,
+}
+
+impl LengthPenalty {
+    pub fn new(fitness_function: Box<dyn FitnessFunction>,
+               penalty_per_instruction: f64)
+               -> LengthPenalty {
+        LengthPenalty {
+            fitness_function,
+            penalty_per_instruction,
+        }
+    }
+}
+
+impl FitnessFunction for LengthPenalty {
+    fn evaluate(&self, bot: &Bot) -> f64 {
+        self.fitness_function.evaluate(bot) -
+        self.penalty_per_instruction * bot.get_program().len() as f64
+    }
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod tests {
+    use super::*;
+    use bf_bot_core::bf::Instruction;
+
+    #[test]
+    fn evaluate_subtractsPenaltyPerInstruction() {
+        let fitness = LengthPenalty::new(Box::new(|_: &Bot| 1.0), 0.25);
+        assert_eq!(fitness.evaluate(&Bot::new(vec![Instruction::Increment; 3])), 0.25);
+    }
+}
@@ -0,0 +1,163 @@
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+
+use bf_bot_core::bf::Bot;
+use bf_bot_core::error::BfError;
+use bf_bot_core::match_runner::{Match, MatchResult};
+use bf_bot_core::simul_round::RoundParams;
+
+/// The number of matches that a cache holds by default.
+pub const DEFAULT_MATCH_CACHE_CAPACITY: usize = 100_000;
+
+/// Identifies a match by the fingerprints of both bots and a hash of the parameters of its rounds.
+type MatchKey = (u64, u64, u64);
+
+/// A cache of match results, addressed by their content: the programs of both bots and the
+/// parameters of every round. During evolution the same pairings recur all the time, such as the
+/// elites that play the hill again every generation, or offspring that are a copy of their parent.
+/// Those matches are only simulated once.
+///
+/// The cache can be shared between threads. When it is full, it is emptied and starts over, which
+/// loses little, because the pairings that recur do so within a few generations.
+///
+/// # Examples
+///
+/// ```
+/// extern crate bf_bot_core;
+/// extern crate bf_bot_evolve;
+/// use bf_bot_core::bf::Bot;
+/// use bf_bot_core::simul_game::AllRounds;
+/// use bf_bot_evolve::fitness::MatchCache;
+/// let cache = MatchCache::default();
+/// let rounds = AllRounds::new().collect::<Vec<_>>();
+/// let first = cache.play(&Bot::new(vec![]), &Bot::new(vec![]), &rounds).unwrap();
+/// let second = cache.play(&Bot::new(vec![]), &Bot::new(vec![]), &rounds).unwrap();
+/// assert_eq!(first, second);
+/// assert_eq!((cache.nr_hits(), cache.nr_misses()), (1, 1));
+/// ```
+#[derive(Debug)]
+pub struct MatchCache {
+    capacity: usize,
+    results: Mutex<HashMap<MatchKey, MatchResult>>,
+    nr_hits: AtomicUsize,
+    nr_misses: AtomicUsize,
+}
+
+impl MatchCache {
+    /// Creates an empty cache that holds at most `capacity` matches.
+    pub fn new(capacity: usize) -> MatchCache {
+        MatchCache {
+            capacity,
+            results: Mutex::new(HashMap::new()),
+            nr_hits: AtomicUsize::new(0),
+            nr_misses: AtomicUsize::new(0),
+        }
+    }
+
+    /// Returns the result of a match between the given bots, in which one round is played for
+    /// each of the given round parameters. The match is only played if it is not in the cache yet.
+    /// The result uses the default scoring scheme. Fails if any of the round parameters are not
+    /// valid.
+    pub fn play(&self,
+                bot_a: &Bot,
+                bot_b: &Bot,
+                rounds: &[RoundParams])
+                -> Result<MatchResult, BfError> {
+        let mut hasher = DefaultHasher::new();
+        rounds.hash(&mut hasher);
+        let key = (bot_a.fingerprint(), bot_b.fingerprint(), hasher.finish());
+        if let Some(result) = self.lock().get(&key) {
+            self.nr_hits.fetch_add(1, Ordering::Relaxed);
+            return Ok(result.clone());
+        }
+        self.nr_misses.fetch_add(1, Ordering::Relaxed);
+        // The lock is not held while playing, so that other threads can play their matches.
+        let result = Match::new(bot_a, bot_b).run_rounds(rounds.iter().cloned())?;
+        let mut results = self.lock();
+        if results.len() >= self.capacity {
+            results.clear();
+        }
+        if self.capacity > 0 {
+            results.insert(key, result.clone());
+        }
+        Ok(result)
+    }
+
+    /// Returns the number of matches in the cache.
+    pub fn len(&self) -> usize {
+        self.lock().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the number of matches that were found in the cache.
+    pub fn nr_hits(&self) -> usize {
+        self.nr_hits.load(Ordering::Relaxed)
+    }
+
+    /// Returns the number of matches that had to be played.
+    pub fn nr_misses(&self) -> usize {
+        self.nr_misses.load(Ordering::Relaxed)
+    }
+
+    fn lock(&self) -> ::std::sync::MutexGuard<'_, HashMap<MatchKey, MatchResult>> {
+        // A thread that panicked while holding the lock can't have left a result half inserted.
+        self.results.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+impl Default for MatchCache {
+    fn default() -> MatchCache {
+        MatchCache::new(DEFAULT_MATCH_CACHE_CAPACITY)
+    }
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod tests {
+    use super::*;
+    use bf_bot_core::bf::Instruction;
+    use bf_bot_core::simul_game::AllRounds;
+
+    fn rounds() -> Vec<RoundParams> {
+        AllRounds::new().with_early_termination().collect()
+    }
+
+    #[test]
+    fn play_sameBotsAgain_hit() {
+        let cache = MatchCache::new(10);
+        let suicidal = Bot::new(vec![Instruction::MoveBack]);
+        let idle = Bot::new(vec![]);
+        let played = cache.play(&suicidal, &idle, &rounds()).unwrap();
+        assert_eq!(cache.play(&suicidal, &idle, &rounds()).unwrap(), played);
+        assert_eq!(played, Match::new(&suicidal, &idle).run_rounds(rounds().into_iter()).unwrap());
+        assert_eq!((cache.nr_hits(), cache.nr_misses()), (1, 1));
+    }
+
+    #[test]
+    fn play_swappedBotsOrOtherRounds_miss() {
+        let cache = MatchCache::new(10);
+        let suicidal = Bot::new(vec![Instruction::MoveBack]);
+        let idle = Bot::new(vec![]);
+        cache.play(&suicidal, &idle, &rounds()).unwrap();
+        cache.play(&idle, &suicidal, &rounds()).unwrap();
+        cache.play(&suicidal, &idle, &rounds()[..2]).unwrap();
+        assert_eq!((cache.nr_hits(), cache.nr_misses(), cache.len()), (0, 3, 3));
+    }
+
+    #[test]
+    fn play_full_startsOver() {
+        let cache = MatchCache::new(1);
+        let idle = Bot::new(vec![]);
+        cache.play(&idle, &idle, &rounds()).unwrap();
+        cache.play(&idle, &Bot::new(vec![Instruction::MoveBack]), &rounds()).unwrap();
+        assert_eq!(cache.len(), 1);
+        cache.play(&idle, &idle, &rounds()).unwrap();
+        assert_eq!(cache.nr_hits(), 0);
+    }
+}
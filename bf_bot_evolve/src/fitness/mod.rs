@@ -0,0 +1,65 @@
+//! Fitness functions determine how good a bot is. The evolution engine tries to breed bots with a
+//! fitness that is as high as possible.
+use bf_bot_core::bf::Bot;
+
+/// Assigns a fitness to a bot. A higher fitness means a better bot.
+///
+/// Fitness functions must be `Sync`, so that a population can be evaluated in parallel, and
+/// `Send`, so that whole runs can be moved to other threads, as islands are.
+pub trait FitnessFunction: Send + Sync {
+    fn evaluate(&self, bot: &Bot) -> f64;
+}
+
+/// Any function or closure that takes a bot and returns a number can be used as fitness function.
+///
+/// # Examples
+///
+/// ```
+/// extern crate bf_bot_core;
+/// extern crate bf_bot_evolve;
+/// use bf_bot_core::bf::Bot;
+/// use bf_bot_core::match_runner::Match;
+/// use bf_bot_evolve::fitness::FitnessFunction;
+/// let opponent = Bot::new(vec![]);
+/// let fitness = |bot: &Bot| Match::new(bot, &opponent).run().unwrap().score() as f64;
+/// assert_eq!(fitness.evaluate(&Bot::new(vec![])), 0.0);
+/// ```
+impl<F> FitnessFunction for F
+    where F: Fn(&Bot) -> f64 + Send + Sync
+{
+    fn evaluate(&self, bot: &Bot) -> f64 {
+        self(bot)
+    }
+}
+
+pub use self::hill_fitness::HillFitness;
+mod hill_fitness;
+
+pub use self::counter_fitness::CounterFitness;
+mod counter_fitness;
+
+pub use self::curriculum::{Curriculum, CurriculumStage};
+mod curriculum;
+
+pub use self::survival_fitness::SurvivalFitness;
+mod survival_fitness;
+
+pub use self::fitness_shaping::FitnessShaping;
+mod fitness_shaping;
+
+pub use self::opponent_sampling::{FitnessEstimate, OpponentSampling, SamplingMode,
+                                  SamplingReport};
+mod opponent_sampling;
+
+pub use self::fitness_predictor::{Calibration, CalibrationSample, FitnessPredictor,
+                                  FitnessScreening};
+mod fitness_predictor;
+
+pub use self::test_cases::TestCases;
+mod test_cases;
+
+pub use self::length_penalty::LengthPenalty;
+mod length_penalty;
+
+pub use self::match_cache::{MatchCache, DEFAULT_MATCH_CACHE_CAPACITY};
+mod match_cache;
@@ -0,0 +1,358 @@
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+
+use rand::seq::SliceRandom;
+use rand::RngCore;
+
+use bf_bot_core::bf::Bot;
+use bf_bot_core::match_runner::Match;
+use bf_bot_core::simul_game::{AllRounds, TapeLengths};
+use bf_bot_core::simul_round::RoundStatus;
+use population::Population;
+
+/// How `OpponentSampling` decides which rounds each individual plays.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SamplingMode {
+    /// Every individual plays the same random subset of the tape lengths, with both polarities,
+    /// drawn anew every generation.
+    RandomTapeLengths { nr_tape_lengths: usize },
+    /// Successive halving: every individual first plays a random subset of the tape lengths. Then
+    /// the better half plays twice as many tape lengths, the better half of those twice as many
+    /// again, and so on, until the best individual has been found or the survivors play every tape
+    /// length. Only the promising individuals are thus evaluated precisely.
+    ///
+    /// Individuals are ranked by the number of halvings they survived first, and only then by their
+    /// estimate, since the estimates of individuals that played different rounds can't be compared.
+    SuccessiveHalving { initial_nr_tape_lengths: usize },
+}
+
+/// How precisely the fitness of a single individual is known, after playing a sample of rounds.
+/// Every round counts as 1 if it was won, -1 if it was lost and 0 if it was a draw.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FitnessEstimate {
+    /// The average value of the rounds played.
+    pub mean: f64,
+    /// The standard error of the mean. Zero if fewer than two rounds were played.
+    pub standard_error: f64,
+    pub nr_rounds: usize,
+}
+
+impl FitnessEstimate {
+    /// Returns the bounds of the 95% confidence interval of the fitness, under the normal
+    /// approximation.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bf_bot_evolve::fitness::FitnessEstimate;
+    /// let estimate = FitnessEstimate {
+    ///     mean: 0.5,
+    ///     standard_error: 0.1,
+    ///     nr_rounds: 40,
+    /// };
+    /// let (low, high) = estimate.confidence_interval();
+    /// assert!((low - 0.304).abs() < 1e-9 && (high - 0.696).abs() < 1e-9);
+    /// ```
+    pub fn confidence_interval(&self) -> (f64, f64) {
+        let margin = 1.96 * self.standard_error;
+        (self.mean - margin, self.mean + margin)
+    }
+}
+
+/// What a sampled evaluation of a population cost, and how precise it was.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SamplingReport {
+    /// The estimate of every individual, in the order of the population.
+    pub estimates: Vec<FitnessEstimate>,
+    /// The number of rounds that were played in total.
+    pub nr_rounds_played: usize,
+    /// The number of rounds that evaluating every individual against every opponent in every
+    /// configuration would have taken.
+    pub nr_rounds_full: usize,
+}
+
+impl SamplingReport {
+    /// Returns the fraction of the rounds of a full evaluation that was played.
+    pub fn cost(&self) -> f64 {
+        if self.nr_rounds_full == 0 {
+            0.0
+        } else {
+            self.nr_rounds_played as f64 / self.nr_rounds_full as f64
+        }
+    }
+
+    /// Returns the average standard error of the estimates.
+    pub fn mean_standard_error(&self) -> f64 {
+        if self.estimates.is_empty() {
+            return 0.0;
+        }
+        self.estimates.iter().map(|estimate| estimate.standard_error).sum::<f64>() /
+        self.estimates.len() as f64
+    }
+}
+
+/// Estimates fitness against a fixed hill of opponents from a sample of the rounds, rather than
+/// from every tape length and polarity, which is what makes evaluating a population expensive.
+/// Without sampling, the fitness would be the average value of all rounds, like an unweighted
+/// `HillFitness`.
+///
+/// Since the sample differs from generation to generation, fitnesses from different generations
+/// can't be compared, so every individual is evaluated again every generation.
+#[derive(Debug, Clone)]
+pub struct OpponentSampling {
+    opponents: Vec<Bot>,
+    mode: SamplingMode,
+}
+
+/// The sum and the sum of squares of the values of the rounds that an individual played so far.
+#[derive(Debug, Clone, Copy, Default)]
+struct Tally {
+    sum: f64,
+    sum_of_squares: f64,
+    nr_rounds: usize,
+}
+
+impl Tally {
+    fn add(&mut self, other: Tally) {
+        self.sum += other.sum;
+        self.sum_of_squares += other.sum_of_squares;
+        self.nr_rounds += other.nr_rounds;
+    }
+
+    fn mean(&self) -> f64 {
+        if self.nr_rounds == 0 {
+            0.0
+        } else {
+            self.sum / self.nr_rounds as f64
+        }
+    }
+
+    fn estimate(&self) -> FitnessEstimate {
+        let n = self.nr_rounds as f64;
+        let mean = self.mean();
+        let standard_error = if self.nr_rounds < 2 {
+            0.0
+        } else {
+            let variance = (self.sum_of_squares - n * mean * mean).max(0.0) / (n - 1.0);
+            (variance / n).sqrt()
+        };
+        FitnessEstimate {
+            mean,
+            standard_error,
+            nr_rounds: self.nr_rounds,
+        }
+    }
+}
+
+impl OpponentSampling {
+    pub fn new(opponents: Vec<Bot>, mode: SamplingMode) -> OpponentSampling {
+        OpponentSampling { opponents, mode }
+    }
+
+    pub fn get_mode(&self) -> SamplingMode {
+        self.mode
+    }
+
+    /// Evaluates every individual of the population on a sample of the rounds, drawn with the
+    /// given random number generator. With `RandomTapeLengths`, the fitness becomes the estimated
+    /// mean. With `SuccessiveHalving`, it becomes three times the number of halvings survived plus
+    /// the estimated mean, so that an individual that survived longer always ranks higher.
+    pub fn evaluate(&self, population: &mut Population, rng: &mut dyn RngCore) -> SamplingReport {
+        let mut tape_lengths = TapeLengths::default().lengths();
+        tape_lengths.shuffle(rng);
+        let nr_individuals = population.individuals.len();
+        let mut tallies = vec![Tally::default(); nr_individuals];
+        let mut nr_halvings_survived = vec![0; nr_individuals];
+        let mut nr_rounds_played = 0;
+        match self.mode {
+            SamplingMode::RandomTapeLengths { nr_tape_lengths } => {
+                let sample = &tape_lengths[..nr_tape_lengths.min(tape_lengths.len())];
+                let everyone = (0..nr_individuals).collect::<Vec<_>>();
+                nr_rounds_played += self.play(population, &everyone, sample, &mut tallies);
+            }
+            SamplingMode::SuccessiveHalving { initial_nr_tape_lengths } => {
+                let mut survivors = (0..nr_individuals).collect::<Vec<_>>();
+                let mut nr_played = 0;
+                let mut nr_to_play = initial_nr_tape_lengths.clamp(1, tape_lengths.len());
+                loop {
+                    let sample = &tape_lengths[nr_played..nr_to_play];
+                    nr_rounds_played += self.play(population, &survivors, sample, &mut tallies);
+                    nr_played = nr_to_play;
+                    if survivors.len() <= 1 || nr_played == tape_lengths.len() {
+                        break;
+                    }
+                    // The sort is stable, so ties are broken by the order of the population.
+                    survivors.sort_by(|&a, &b| tallies[b].mean().total_cmp(&tallies[a].mean()));
+                    survivors.truncate(survivors.len().div_ceil(2));
+                    survivors.sort_unstable();
+                    for &survivor in &survivors {
+                        nr_halvings_survived[survivor] += 1;
+                    }
+                    nr_to_play = (nr_to_play * 2).min(tape_lengths.len());
+                }
+            }
+        }
+        let estimates = tallies.iter().map(Tally::estimate).collect::<Vec<_>>();
+        for (index, individual) in population.individuals.iter_mut().enumerate() {
+            // Means lie within [-1, 1], so they can't make up for a halving that wasn't survived.
+            individual.fitness = Some(3.0 * nr_halvings_survived[index] as f64 +
+                                      estimates[index].mean);
+        }
+        SamplingReport {
+            estimates,
+            nr_rounds_played,
+            nr_rounds_full: nr_individuals * self.opponents.len() * 2 * tape_lengths.len(),
+        }
+    }
+
+    /// Lets the individuals with the given indices play every opponent on the given tape lengths,
+    /// with both polarities, and adds the results to their tallies. Returns the number of rounds
+    /// played.
+    fn play(&self,
+            population: &Population,
+            indices: &[usize],
+            tape_lengths: &[u32],
+            tallies: &mut [Tally])
+            -> usize {
+        if tape_lengths.is_empty() {
+            return 0;
+        }
+        let tape_lengths = TapeLengths::List(tape_lengths.to_vec());
+        let results = self.tally_all(population, indices, &tape_lengths);
+        let mut nr_rounds = 0;
+        for (&index, tally) in indices.iter().zip(results) {
+            nr_rounds += tally.nr_rounds;
+            tallies[index].add(tally);
+        }
+        nr_rounds
+    }
+
+    #[cfg(not(feature = "rayon"))]
+    fn tally_all(&self,
+                 population: &Population,
+                 indices: &[usize],
+                 tape_lengths: &TapeLengths)
+                 -> Vec<Tally> {
+        indices.iter()
+            .map(|&index| self.tally(&population.individuals[index].bot, tape_lengths))
+            .collect()
+    }
+
+    #[cfg(feature = "rayon")]
+    fn tally_all(&self,
+                 population: &Population,
+                 indices: &[usize],
+                 tape_lengths: &TapeLengths)
+                 -> Vec<Tally> {
+        indices.par_iter()
+            .map(|&index| self.tally(&population.individuals[index].bot, tape_lengths))
+            .collect()
+    }
+
+    fn tally(&self, bot: &Bot, tape_lengths: &TapeLengths) -> Tally {
+        let mut tally = Tally::default();
+        for opponent in &self.opponents {
+            let rounds = AllRounds::new().with_tape_lengths(tape_lengths).with_early_termination();
+            let result = Match::new(bot, opponent)
+                .run_rounds(rounds)
+                .expect("The sample consists of standard tape lengths.");
+            for round in result.rounds {
+                let value = match round.result.status() {
+                    RoundStatus::StartBotWon => 1.0,
+                    RoundStatus::EndBotWon => -1.0,
+                    RoundStatus::Draw | RoundStatus::Ongoing => 0.0,
+                };
+                tally.add(Tally {
+                    sum: value,
+                    sum_of_squares: value * value,
+                    nr_rounds: 1,
+                });
+            }
+        }
+        tally
+    }
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod tests {
+    use super::*;
+    use bf_bot_core::bf::Instruction;
+    use evolution::seeded_rng;
+    use fitness::{FitnessFunction, HillFitness};
+    use genome::Genome;
+
+    fn make_population() -> Population {
+        let sources = ["<", "", ">>>>>>>>>[-]", "(>)*9([-]>)*21"];
+        Population::new(sources.iter()
+            .map(|source| Genome::from_source(source).unwrap())
+            .collect())
+    }
+
+    fn make_hill() -> Vec<Bot> {
+        vec![Bot::new(vec![]), Bot::new(vec![Instruction::MoveBack])]
+    }
+
+    #[test]
+    fn evaluate_allTapeLengths_fullFitness() {
+        let mut population = make_population();
+        let sampling = OpponentSampling::new(make_hill(),
+                                             SamplingMode::RandomTapeLengths {
+                                                 nr_tape_lengths: 21,
+                                             });
+        let report = sampling.evaluate(&mut population, &mut seeded_rng(1));
+        assert_eq!(report.cost(), 1.0);
+        let hill_fitness = HillFitness::new(make_hill());
+        for individual in &population.individuals {
+            assert_eq!(individual.fitness, Some(hill_fitness.evaluate(&individual.bot)));
+        }
+    }
+
+    #[test]
+    fn evaluate_randomTapeLengths_everyonePlaysTheSample() {
+        let mut population = make_population();
+        let sampling = OpponentSampling::new(make_hill(),
+                                             SamplingMode::RandomTapeLengths {
+                                                 nr_tape_lengths: 3,
+                                             });
+        let report = sampling.evaluate(&mut population, &mut seeded_rng(1));
+        assert_eq!(report.nr_rounds_played, 4 * 2 * 2 * 3);
+        assert!(report.estimates.iter().all(|estimate| estimate.nr_rounds == 12));
+        // The suicidal bot loses every round against the idle bot and draws against the other
+        // suicidal bot.
+        let (low, high) = report.estimates[0].confidence_interval();
+        assert_eq!(report.estimates[0].mean, -0.5);
+        assert!(low < -0.5 && high > -0.5);
+    }
+
+    #[test]
+    fn evaluate_successiveHalving_bestPlaysMostRounds() {
+        let mut population = make_population();
+        let sampling = OpponentSampling::new(make_hill(),
+                                             SamplingMode::SuccessiveHalving {
+                                                 initial_nr_tape_lengths: 2,
+                                             });
+        let report = sampling.evaluate(&mut population, &mut seeded_rng(1));
+        assert!(report.cost() < 1.0);
+        assert_eq!(report.estimates[0].nr_rounds, 2 * 2 * 2);
+        let most_rounds = report.estimates.iter().map(|estimate| estimate.nr_rounds).max();
+        assert_eq!(most_rounds, Some(2 * 2 * 8));
+    }
+
+    #[test]
+    fn evaluate_successiveHalving_survivorsRankAboveTheEliminated() {
+        let mut population = make_population();
+        let sampling = OpponentSampling::new(make_hill(),
+                                             SamplingMode::SuccessiveHalving {
+                                                 initial_nr_tape_lengths: 2,
+                                             });
+        let report = sampling.evaluate(&mut population, &mut seeded_rng(1));
+        for (a, b) in population.individuals.iter().zip(&report.estimates) {
+            for (c, d) in population.individuals.iter().zip(&report.estimates) {
+                if b.nr_rounds > d.nr_rounds {
+                    assert!(a.fitness > c.fitness);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,75 @@
+use std::sync::Arc;
+
+use bf_bot_core::bf::Bot;
+use bf_bot_core::match_runner::{Match, MatchResult};
+use bf_bot_core::simul_game::AllRounds;
+use fitness::{FitnessFunction, MatchCache};
+
+/// Scores a bot by the fraction of rounds it survives against a fixed hill of reference bots: the
+/// rounds that it wins or draws. Unlike `HillFitness`, a draw counts as much as a win, so this
+/// rewards bots that are hard to beat, even if they rarely win.
+#[derive(Debug, Clone)]
+pub struct SurvivalFitness {
+    hill: Vec<Bot>,
+    cache: Option<Arc<MatchCache>>,
+}
+
+impl SurvivalFitness {
+    pub fn new(hill: Vec<Bot>) -> SurvivalFitness {
+        SurvivalFitness { hill, cache: None }
+    }
+
+    /// Looks up the matches in the given cache before playing them, and stores them there after.
+    /// The cache can be shared with other fitness functions.
+    pub fn with_match_cache(self, cache: Arc<MatchCache>) -> SurvivalFitness {
+        SurvivalFitness {
+            cache: Some(cache),
+            ..self
+        }
+    }
+
+    fn play(&self, bot: &Bot, opponent: &Bot) -> MatchResult {
+        let rounds = AllRounds::new().with_early_termination();
+        let result = match self.cache {
+            Some(ref cache) => cache.play(bot, opponent, &rounds.collect::<Vec<_>>()),
+            None => Match::new(bot, opponent).run_rounds(rounds),
+        };
+        result.expect("The standard rounds are valid.")
+    }
+}
+
+impl FitnessFunction for SurvivalFitness {
+    fn evaluate(&self, bot: &Bot) -> f64 {
+        let mut nr_rounds = 0;
+        let mut nr_survived = 0;
+        for opponent in &self.hill {
+            let result = self.play(bot, opponent);
+            nr_rounds += result.rounds.len();
+            nr_survived += result.rounds.len() - result.nr_bot_b_wins();
+        }
+        if nr_rounds == 0 {
+            0.0
+        } else {
+            nr_survived as f64 / nr_rounds as f64
+        }
+    }
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod tests {
+    use super::*;
+    use bf_bot_core::bf::Instruction;
+
+    #[test]
+    fn evaluate_drawsEveryRound_one() {
+        let fitness = SurvivalFitness::new(vec![Bot::new(vec![])]);
+        assert_eq!(fitness.evaluate(&Bot::new(vec![])), 1.0);
+    }
+
+    #[test]
+    fn evaluate_losesEveryRound_zero() {
+        let fitness = SurvivalFitness::new(vec![Bot::new(vec![])]);
+        assert_eq!(fitness.evaluate(&Bot::new(vec![Instruction::MoveBack])), 0.0);
+    }
+}
@@ -0,0 +1,96 @@
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+
+use bf_bot_core::bf::Bot;
+use bf_bot_core::match_runner::Match;
+use bf_bot_core::simul_game::AllRounds;
+use bf_bot_core::simul_round::RoundStatus;
+use population::Population;
+
+/// Splits the performance against a hill into separate test cases: one for every combination of
+/// opponent, tape length and polarity, so 42 per opponent. Where a fitness function sums the
+/// outcomes up into a single number, test cases keep them apart, for `LexicaseSelection`.
+#[derive(Debug, Clone)]
+pub struct TestCases {
+    opponents: Vec<Bot>,
+}
+
+impl TestCases {
+    pub fn new(opponents: Vec<Bot>) -> TestCases {
+        TestCases { opponents }
+    }
+
+    pub fn get_opponents(&self) -> &[Bot] {
+        &self.opponents
+    }
+
+    /// Returns the number of test cases.
+    pub fn len(&self) -> usize {
+        self.opponents.len() * AllRounds::new().count()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Plays every test case and returns the outcome of each, in the order of the opponents and
+    /// then of the rounds of a match: 1 for a win, 0 for a draw and -1 for a loss.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// extern crate bf_bot_core;
+    /// extern crate bf_bot_evolve;
+    /// use bf_bot_core::bf::Bot;
+    /// use bf_bot_evolve::fitness::TestCases;
+    /// let test_cases = TestCases::new(vec![Bot::new(vec![])]);
+    /// let outcomes = test_cases.outcomes(&Bot::new(vec![]));
+    /// assert_eq!(outcomes, vec![0.0; 42]);
+    /// ```
+    pub fn outcomes(&self, bot: &Bot) -> Vec<f64> {
+        let mut outcomes = Vec::with_capacity(self.len());
+        for opponent in &self.opponents {
+            let rounds = AllRounds::new().with_early_termination();
+            outcomes.extend(Match::new(bot, opponent)
+                .run_rounds(rounds)
+                .expect("The standard rounds are valid.")
+                .rounds
+                .iter()
+                .map(|round| match round.result.status() {
+                    RoundStatus::StartBotWon => 1.0,
+                    RoundStatus::EndBotWon => -1.0,
+                    RoundStatus::Draw | RoundStatus::Ongoing => 0.0,
+                }));
+        }
+        outcomes
+    }
+
+    /// Returns the outcomes of every individual of the population, in the order of the
+    /// population.
+    #[cfg(not(feature = "rayon"))]
+    pub fn outcomes_of(&self, population: &Population) -> Vec<Vec<f64>> {
+        population.individuals.iter().map(|individual| self.outcomes(&individual.bot)).collect()
+    }
+
+    /// Returns the outcomes of every individual of the population, in the order of the
+    /// population.
+    #[cfg(feature = "rayon")]
+    pub fn outcomes_of(&self, population: &Population) -> Vec<Vec<f64>> {
+        population.individuals.par_iter().map(|individual| self.outcomes(&individual.bot)).collect()
+    }
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod tests {
+    use super::*;
+    use bf_bot_core::bf::Instruction;
+
+    #[test]
+    fn outcomes_suicidalBot_losesEveryCase() {
+        let test_cases = TestCases::new(vec![Bot::new(vec![]), Bot::new(vec![])]);
+        let outcomes = test_cases.outcomes(&Bot::new(vec![Instruction::MoveBack]));
+        assert_eq!(outcomes.len(), test_cases.len());
+        assert!(outcomes.iter().all(|&outcome| outcome == -1.0));
+    }
+}
@@ -0,0 +1,120 @@
+use genome::{Gene, Genome, Op};
+
+/// A token of the source code of a genome. Repeat counts are part of the token that ends the
+/// repetition, so that changing a count is a single edit.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Token {
+    Op(Op),
+    LoopStart,
+    LoopEnd,
+    RepetitionStart,
+    RepeatEnd(u32),
+    InnerStart,
+    InnerEnd,
+    NestEnd(u32),
+}
+
+/// Returns how different two genomes are: the edit distance between their token streams, divided
+/// by the length of the longer stream. Each op, bracket and repetition count is a token. The
+/// distance lies between 0 for identical genomes and 1 for genomes that have nothing in common.
+///
+/// # Examples
+///
+/// ```
+/// extern crate bf_bot_evolve;
+/// use bf_bot_evolve::genome::{self, Genome};
+/// let rush = Genome::from_source("(>)*9([-]>)*21").unwrap();
+/// let slower_rush = Genome::from_source("(>)*8([-]>)*21").unwrap();
+/// assert_eq!(genome::distance(&rush, &rush), 0.0);
+/// // Of the 9 tokens, only the first count differs.
+/// assert_eq!(genome::distance(&rush, &slower_rush), 1.0 / 9.0);
+/// ```
+pub fn distance(genome_a: &Genome, genome_b: &Genome) -> f64 {
+    let tokens_a = tokens(genome_a);
+    let tokens_b = tokens(genome_b);
+    let longest = tokens_a.len().max(tokens_b.len());
+    if longest == 0 {
+        return 0.0;
+    }
+    edit_distance(&tokens_a, &tokens_b) as f64 / longest as f64
+}
+
+fn tokens(genome: &Genome) -> Vec<Token> {
+    fn push_tokens(genes: &[Gene], tokens: &mut Vec<Token>) {
+        for gene in genes {
+            match *gene {
+                Gene::Op(op) => tokens.push(Token::Op(op)),
+                Gene::Loop(ref body) => {
+                    tokens.push(Token::LoopStart);
+                    push_tokens(body, tokens);
+                    tokens.push(Token::LoopEnd);
+                }
+                Gene::Repeat { ref body, count } => {
+                    tokens.push(Token::RepetitionStart);
+                    push_tokens(body, tokens);
+                    tokens.push(Token::RepeatEnd(count));
+                }
+                Gene::Nest { ref pre, ref inner, ref post, count } => {
+                    tokens.push(Token::RepetitionStart);
+                    push_tokens(pre, tokens);
+                    tokens.push(Token::InnerStart);
+                    push_tokens(inner, tokens);
+                    tokens.push(Token::InnerEnd);
+                    push_tokens(post, tokens);
+                    tokens.push(Token::NestEnd(count));
+                }
+            }
+        }
+    }
+    let mut tokens = vec![];
+    push_tokens(&genome.genes, &mut tokens);
+    tokens
+}
+
+/// The Levenshtein distance: the number of insertions, deletions and substitutions of tokens it
+/// takes to turn one stream into the other.
+fn edit_distance(tokens_a: &[Token], tokens_b: &[Token]) -> usize {
+    // Only the previous row of the table is kept.
+    let mut previous = (0..=tokens_b.len()).collect::<Vec<_>>();
+    for (i, token_a) in tokens_a.iter().enumerate() {
+        let mut current = vec![i + 1; tokens_b.len() + 1];
+        for (j, token_b) in tokens_b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(token_a != token_b);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        previous = current;
+    }
+    previous[tokens_b.len()]
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod tests {
+    use super::*;
+
+    fn genome(source: &str) -> Genome {
+        Genome::from_source(source).unwrap()
+    }
+
+    #[test]
+    fn distance_bothEmpty_zero() {
+        assert_eq!(distance(&Genome::default(), &Genome::default()), 0.0);
+    }
+
+    #[test]
+    fn distance_nothingInCommon_one() {
+        assert_eq!(distance(&genome("+++"), &genome("<<")), 1.0);
+    }
+
+    #[test]
+    fn distance_insertedOp_oneEdit() {
+        // `>[-]>` has 5 tokens, `>[-.]>` has 6.
+        assert_eq!(distance(&genome(">[-]>"), &genome(">[-.]>")), 1.0 / 6.0);
+    }
+
+    #[test]
+    fn distance_isSymmetric() {
+        let (a, b) = (genome("(>)*9([-]>)*21"), genome(">(+{.}-)%3<"));
+        assert_eq!(distance(&a, &b), distance(&b, &a));
+    }
+}
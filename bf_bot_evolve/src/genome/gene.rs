@@ -0,0 +1,74 @@
+use rand::{Rng, RngCore};
+
+use bf_bot_core::bf::Instruction;
+
+/// An instruction that doesn't affect the flow of the program. Loops are represented by
+/// `Gene::Loop` instead, so that they are always balanced.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum Op {
+    MoveBack,
+    MoveForward,
+    Increment,
+    Decrement,
+    Skip,
+}
+
+/// Every kind of `Op`, for picking one at random.
+pub const ALL_OPS: [Op; 5] = [Op::MoveBack,
+                              Op::MoveForward,
+                              Op::Increment,
+                              Op::Decrement,
+                              Op::Skip];
+
+impl Op {
+    /// Picks an op at random, each kind being equally likely.
+    pub fn random(rng: &mut dyn RngCore) -> Op {
+        ALL_OPS[rng.gen_range(0..ALL_OPS.len())]
+    }
+
+    pub fn to_instruction(self) -> Instruction {
+        match self {
+            Op::MoveBack => Instruction::MoveBack,
+            Op::MoveForward => Instruction::MoveForward,
+            Op::Increment => Instruction::Increment,
+            Op::Decrement => Instruction::Decrement,
+            Op::Skip => Instruction::SkipExecution,
+        }
+    }
+
+    /// Returns the op that the given character of source code stands for, if any.
+    pub fn from_char(character: char) -> Option<Op> {
+        ALL_OPS.iter().cloned().find(|op| op.to_char() == character)
+    }
+
+    pub fn to_char(self) -> char {
+        match self {
+            Op::MoveBack => '<',
+            Op::MoveForward => '>',
+            Op::Increment => '+',
+            Op::Decrement => '-',
+            Op::Skip => '.',
+        }
+    }
+}
+
+/// An element of a genome. Genes mirror the constructs of BF Joust source code, so that a
+/// mutation can for example change a repeat count without touching the repeated code.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum Gene {
+    Op(Op),
+    /// `[body]`: Executes the body for as long as the current cell is not zero.
+    Loop(Vec<Gene>),
+    /// `(body)*count`: The body, repeated `count` times.
+    Repeat { body: Vec<Gene>, count: u32 },
+    /// `(pre{inner}post)%count`: `pre` repeated `count` times, then `inner` once, then `post`
+    /// repeated `count` times.
+    Nest {
+        pre: Vec<Gene>,
+        inner: Vec<Gene>,
+        post: Vec<Gene>,
+        count: u32,
+    },
+}
@@ -0,0 +1,90 @@
+use fitness::FitnessFunction;
+use genome::Genome;
+
+/// Shrinks a genome for as long as that doesn't lower its fitness, in the manner of delta
+/// debugging. Every sequence of genes, such as the body of a loop, is cut into chunks: first
+/// halves, then quarters, and so on down to single genes. A chunk is deleted for good if the
+/// genome without it is at least as fit as the given genome. This repeats until no single gene can
+/// be deleted anymore, so the result is as small as deletions alone can make it.
+///
+/// # Examples
+///
+/// ```
+/// extern crate bf_bot_core;
+/// extern crate bf_bot_evolve;
+/// use bf_bot_core::bf::{Bot, Instruction};
+/// use bf_bot_evolve::genome::{self, Genome};
+/// // The fitter a bot, the more increments it has and the fewer other instructions.
+/// let fitness = |bot: &Bot| {
+///     bot.get_program()
+///         .iter()
+///         .map(|&instruction| if instruction == Instruction::Increment { 1.0 } else { -0.1 })
+///         .sum::<f64>()
+/// };
+/// let genome = Genome::from_source(">+<[+>-]+").unwrap();
+/// assert_eq!(genome::minimize(&genome, &fitness).to_source(), "+[+]+");
+/// ```
+pub fn minimize(genome: &Genome, fitness_function: &dyn FitnessFunction) -> Genome {
+    let target = fitness_function.evaluate(&genome.compile());
+    let mut minimized = genome.clone();
+    let mut deleted_any = true;
+    while deleted_any {
+        deleted_any = false;
+        // Deleting a gene can delete sequences with it, so the number is checked every time.
+        let mut sequence_index = 0;
+        while sequence_index < minimized.nr_sequences() {
+            let mut chunk_length = sequence_length(&mut minimized, sequence_index).div_ceil(2);
+            while chunk_length > 0 {
+                let mut start = 0;
+                while start < sequence_length(&mut minimized, sequence_index) {
+                    let mut candidate = minimized.clone();
+                    {
+                        let sequence = candidate.sequence_mut(sequence_index)
+                            .expect("The sequence exists.");
+                        let end = (start + chunk_length).min(sequence.len());
+                        sequence.drain(start..end);
+                    }
+                    if fitness_function.evaluate(&candidate.compile()) >= target {
+                        minimized = candidate;
+                        deleted_any = true;
+                    } else {
+                        start += chunk_length;
+                    }
+                }
+                chunk_length /= 2;
+            }
+            sequence_index += 1;
+        }
+    }
+    minimized
+}
+
+fn sequence_length(genome: &mut Genome, index: usize) -> usize {
+    genome.sequence_mut(index).map_or(0, |sequence| sequence.len())
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod tests {
+    use super::*;
+    use bf_bot_core::bf::Bot;
+    use fitness::HillFitness;
+
+    #[test]
+    fn minimize_paddedRush_keepsHillScore() {
+        let hill = vec![Bot::new(vec![]),
+                        Genome::from_source(">(+)*20>(-)*20<<[.]").unwrap().compile()];
+        let fitness = HillFitness::new(hill);
+        let genome = Genome::from_source("+-.>(>)*8.([-]>)*21<<.").unwrap();
+        let minimized = minimize(&genome, &fitness);
+        assert!(fitness.evaluate(&minimized.compile()) >= fitness.evaluate(&genome.compile()));
+        assert!(minimized.nr_ops() < genome.nr_ops(), "{}", minimized.to_source());
+    }
+
+    #[test]
+    fn minimize_everyGeneNeeded_unchanged() {
+        let fitness = |bot: &Bot| bot.get_program().len() as f64;
+        let genome = Genome::from_source("+[-](>)*3").unwrap();
+        assert_eq!(minimize(&genome, &fitness), genome);
+    }
+}
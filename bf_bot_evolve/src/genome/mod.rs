@@ -0,0 +1,445 @@
+//! The representation of a bot that evolution operates on. A `Genome` keeps the structure of BF
+//! Joust source code, such as loops and repetitions, which a compiled `Bot` has lost. This lets
+//! the genetic operators make meaningful changes, like adjusting how often something is repeated,
+//! and guarantees that every genome compiles into a valid program.
+
+pub use self::gene::{Gene, Op, ALL_OPS};
+mod gene;
+
+pub use self::distance::distance;
+mod distance;
+
+pub use self::minimize::minimize;
+mod minimize;
+
+pub use self::parser::{GenomeParseError, GenomeParseErrorKind};
+mod parser;
+
+pub use self::random::{random, TemplateConfig};
+mod random;
+
+pub use self::simplify::simplify;
+mod simplify;
+
+use bf_bot_core::bf::{Bot, Instruction};
+
+/// The maximum number of instructions that offspring may compile into, the same limit that the
+/// compiler puts on source code. Repetitions can nest, so without it a few mutations could make a
+/// genome expand into more instructions than fit in memory.
+pub const MAX_EXPANDED_LENGTH: usize = 1_000_000;
+
+/// The genetic material of a bot: a sequence of genes that compiles into a program.
+#[derive(Debug, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Genome {
+    pub genes: Vec<Gene>,
+    /// The probability that offspring of this genome are mutated, if the run adapts it per genome
+    /// with `MutationAdaptation::SelfAdaptive`. It evolves along with the genes, but isn't part of
+    /// the source code.
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none"))]
+    pub mutation_rate: Option<f64>,
+}
+
+impl Genome {
+    pub fn new(genes: Vec<Gene>) -> Genome {
+        Genome {
+            genes,
+            mutation_rate: None,
+        }
+    }
+
+    /// Constructs a genome that compiles into the same program as the given bot. Since a bot has
+    /// no repetitions left, neither has the genome.
+    pub fn from_bot(bot: &Bot) -> Genome {
+        let mut stack = vec![vec![]];
+        for instruction in bot.get_program() {
+            let gene = match *instruction {
+                Instruction::MoveBack => Gene::Op(Op::MoveBack),
+                Instruction::MoveForward => Gene::Op(Op::MoveForward),
+                Instruction::Increment => Gene::Op(Op::Increment),
+                Instruction::Decrement => Gene::Op(Op::Decrement),
+                Instruction::SkipExecution => Gene::Op(Op::Skip),
+                Instruction::JumpIfZero(_) => {
+                    stack.push(vec![]);
+                    continue;
+                }
+                Instruction::JumpIfNonZero(_) => {
+                    Gene::Loop(stack.pop().expect("Loops in a bot are balanced."))
+                }
+            };
+            stack.last_mut().expect("Loops in a bot are balanced.").push(gene);
+        }
+        Genome::new(stack.pop().expect("Loops in a bot are balanced."))
+    }
+
+    /// Compiles the genome into a bot, expanding all repetitions. The program can get very long,
+    /// so `expanded_length` should be checked first for genomes that don't come from a run.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// extern crate bf_bot_evolve;
+    /// use bf_bot_evolve::genome::{Gene, Genome, Op};
+    /// let genome = Genome::new(vec![Gene::Repeat {
+    ///                                   body: vec![Gene::Op(Op::MoveForward)],
+    ///                                   count: 3,
+    ///                               },
+    ///                               Gene::Loop(vec![Gene::Op(Op::Decrement)])]);
+    /// assert_eq!(genome.compile().to_source(), ">>>[-]");
+    /// ```
+    pub fn compile(&self) -> Bot {
+        let mut program = vec![];
+        expand(&self.genes, &mut program);
+        Bot::new(program)
+    }
+
+    /// Returns the number of instructions that the genome compiles into, without compiling it. The
+    /// length saturates at `usize::MAX`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// extern crate bf_bot_evolve;
+    /// use bf_bot_evolve::genome::{Gene, Genome, Op};
+    /// let genome = Genome::new(vec![Gene::Repeat {
+    ///                                   body: vec![Gene::Loop(vec![Gene::Op(Op::Decrement)])],
+    ///                                   count: 3,
+    ///                               }]);
+    /// assert_eq!(genome.expanded_length(), 9);
+    /// ```
+    pub fn expanded_length(&self) -> usize {
+        length_of(&self.genes)
+    }
+
+    /// Returns the genome as BF Joust source code, keeping the repetitions.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// extern crate bf_bot_evolve;
+    /// use bf_bot_evolve::genome::{Gene, Genome, Op};
+    /// let genome = Genome::new(vec![Gene::Nest {
+    ///                                   pre: vec![Gene::Op(Op::Increment)],
+    ///                                   inner: vec![Gene::Op(Op::Skip)],
+    ///                                   post: vec![Gene::Op(Op::Decrement)],
+    ///                                   count: 4,
+    ///                               }]);
+    /// assert_eq!(genome.to_source(), "(+{.}-)%4");
+    /// ```
+    pub fn to_source(&self) -> String {
+        let mut source = String::new();
+        write_source(&self.genes, &mut source);
+        source
+    }
+
+    /// Returns the number of `Op` genes in the genome, including those inside other genes.
+    pub fn nr_ops(&self) -> usize {
+        let mut nr_ops = 0;
+        for_each_op(&self.genes, &mut |_| nr_ops += 1);
+        nr_ops
+    }
+
+    /// Returns a mutable reference to the `Op` gene with the given index, counting in the order in
+    /// which they appear in the source code.
+    pub fn op_mut(&mut self, index: usize) -> Option<&mut Op> {
+        nth_op_mut(&mut self.genes, &mut { index })
+    }
+
+    /// Returns the number of sequences of genes in the genome: the genome itself, and every
+    /// sequence contained in a gene, such as the body of a loop.
+    pub fn nr_sequences(&self) -> usize {
+        fn count(genes: &[Gene]) -> usize {
+            1 + genes.iter().flat_map(children).map(|child| count(child)).sum::<usize>()
+        }
+        count(&self.genes)
+    }
+
+    /// Returns a mutable reference to the sequence of genes with the given index, counting in the
+    /// order in which they start in the source code. Index zero is the genome itself.
+    pub fn sequence_mut(&mut self, index: usize) -> Option<&mut Vec<Gene>> {
+        nth_sequence_mut(&mut self.genes, &mut { index })
+    }
+
+    /// Returns the number of repeat counts in the genome, of both `Repeat` and `Nest` genes.
+    pub fn nr_counts(&self) -> usize {
+        fn count(genes: &[Gene]) -> usize {
+            genes.iter()
+                .map(|gene| {
+                    let own = match *gene {
+                        Gene::Repeat { .. } | Gene::Nest { .. } => 1,
+                        _ => 0,
+                    };
+                    own + children(gene).into_iter().map(|child| count(child)).sum::<usize>()
+                })
+                .sum()
+        }
+        count(&self.genes)
+    }
+
+    /// Returns a mutable reference to the repeat count with the given index, counting in the
+    /// order in which they appear in the source code.
+    pub fn count_mut(&mut self, index: usize) -> Option<&mut u32> {
+        nth_count_mut(&mut self.genes, &mut { index })
+    }
+}
+
+/// Appends the instructions of the given genes to the program.
+fn expand(genes: &[Gene], program: &mut Vec<Instruction>) {
+    for gene in genes {
+        match *gene {
+            Gene::Op(op) => program.push(op.to_instruction()),
+            Gene::Loop(ref body) => {
+                let start = program.len();
+                program.push(Instruction::JumpIfZero(0));
+                expand(body, program);
+                program[start] = Instruction::JumpIfZero(program.len());
+                program.push(Instruction::JumpIfNonZero(start));
+            }
+            Gene::Repeat { ref body, count } => {
+                for _ in 0..count {
+                    expand(body, program);
+                }
+            }
+            Gene::Nest { ref pre, ref inner, ref post, count } => {
+                for _ in 0..count {
+                    expand(pre, program);
+                }
+                expand(inner, program);
+                for _ in 0..count {
+                    expand(post, program);
+                }
+            }
+        }
+    }
+}
+
+/// Returns the number of instructions that the given genes expand into, saturating on overflow.
+fn length_of(genes: &[Gene]) -> usize {
+    genes.iter().fold(0, |length: usize, gene| {
+        let gene_length = match *gene {
+            Gene::Op(_) => 1,
+            Gene::Loop(ref body) => length_of(body).saturating_add(2),
+            Gene::Repeat { ref body, count } => length_of(body).saturating_mul(count as usize),
+            Gene::Nest { ref pre, ref inner, ref post, count } => {
+                length_of(pre)
+                    .saturating_add(length_of(post))
+                    .saturating_mul(count as usize)
+                    .saturating_add(length_of(inner))
+            }
+        };
+        length.saturating_add(gene_length)
+    })
+}
+
+fn write_source(genes: &[Gene], source: &mut String) {
+    for gene in genes {
+        match *gene {
+            Gene::Op(op) => source.push(op.to_char()),
+            Gene::Loop(ref body) => {
+                source.push('[');
+                write_source(body, source);
+                source.push(']');
+            }
+            Gene::Repeat { ref body, count } => {
+                source.push('(');
+                write_source(body, source);
+                source.push_str(&format!(")*{}", count));
+            }
+            Gene::Nest { ref pre, ref inner, ref post, count } => {
+                source.push('(');
+                write_source(pre, source);
+                source.push('{');
+                write_source(inner, source);
+                source.push('}');
+                write_source(post, source);
+                source.push_str(&format!(")%{}", count));
+            }
+        }
+    }
+}
+
+/// Returns the genes that are directly contained in the given gene.
+fn children(gene: &Gene) -> Vec<&Vec<Gene>> {
+    match *gene {
+        Gene::Op(_) => vec![],
+        Gene::Loop(ref body) | Gene::Repeat { ref body, .. } => vec![body],
+        Gene::Nest { ref pre, ref inner, ref post, .. } => vec![pre, inner, post],
+    }
+}
+
+fn children_mut(gene: &mut Gene) -> Vec<&mut Vec<Gene>> {
+    match *gene {
+        Gene::Op(_) => vec![],
+        Gene::Loop(ref mut body) | Gene::Repeat { ref mut body, .. } => vec![body],
+        Gene::Nest { ref mut pre, ref mut inner, ref mut post, .. } => vec![pre, inner, post],
+    }
+}
+
+fn for_each_op<F>(genes: &[Gene], f: &mut F)
+    where F: FnMut(Op)
+{
+    for gene in genes {
+        match *gene {
+            Gene::Op(op) => f(op),
+            _ => {
+                for child in children(gene) {
+                    for_each_op(child, f);
+                }
+            }
+        }
+    }
+}
+
+/// Finds the `Op` gene with the given index, decrementing the index for every `Op` gene passed.
+fn nth_op_mut<'a>(genes: &'a mut [Gene], index: &mut usize) -> Option<&'a mut Op> {
+    for gene in genes {
+        if let Gene::Op(ref mut op) = *gene {
+            if *index == 0 {
+                return Some(op);
+            }
+            *index -= 1;
+            continue;
+        }
+        for child in children_mut(gene) {
+            if let Some(op) = nth_op_mut(child, index) {
+                return Some(op);
+            }
+        }
+    }
+    None
+}
+
+/// Finds the sequence with the given index, decrementing the index for every sequence passed.
+fn nth_sequence_mut<'a>(genes: &'a mut Vec<Gene>, index: &mut usize) -> Option<&'a mut Vec<Gene>> {
+    if *index == 0 {
+        return Some(genes);
+    }
+    *index -= 1;
+    for gene in genes.iter_mut() {
+        for child in children_mut(gene) {
+            if let Some(sequence) = nth_sequence_mut(child, index) {
+                return Some(sequence);
+            }
+        }
+    }
+    None
+}
+
+/// Finds the repeat count with the given index, decrementing the index for every count passed.
+fn nth_count_mut<'a>(genes: &'a mut [Gene], index: &mut usize) -> Option<&'a mut u32> {
+    for gene in genes {
+        let (count, children) = match *gene {
+            Gene::Op(_) | Gene::Loop(_) => (None, children_mut(gene)),
+            Gene::Repeat { ref mut body, ref mut count } => (Some(count), vec![body]),
+            Gene::Nest { ref mut pre, ref mut inner, ref mut post, ref mut count } => {
+                (Some(count), vec![pre, inner, post])
+            }
+        };
+        if let Some(count) = count {
+            if *index == 0 {
+                return Some(count);
+            }
+            *index -= 1;
+        }
+        for child in children {
+            if let Some(count) = nth_count_mut(child, index) {
+                return Some(count);
+            }
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod tests {
+    use super::*;
+
+    fn op(op: Op) -> Gene {
+        Gene::Op(op)
+    }
+
+    /// Its source code: >(+[-])*2(<{.}>)%3
+    fn make_genome() -> Genome {
+        Genome::new(vec![op(Op::MoveForward),
+                         Gene::Repeat {
+                             body: vec![op(Op::Increment), Gene::Loop(vec![op(Op::Decrement)])],
+                             count: 2,
+                         },
+                         Gene::Nest {
+                             pre: vec![op(Op::MoveBack)],
+                             inner: vec![op(Op::Skip)],
+                             post: vec![op(Op::MoveForward)],
+                             count: 3,
+                         }])
+    }
+
+    #[test]
+    fn compile_expandsRepetitionsAndLinksLoops() {
+        let bot = make_genome().compile();
+        assert_eq!(bot.to_source(), ">+[-]+[-]<<<.>>>");
+        assert_eq!(bot.get_program()[2], Instruction::JumpIfZero(4));
+        assert_eq!(bot.get_program()[8], Instruction::JumpIfNonZero(6));
+    }
+
+    #[test]
+    fn expandedLength_sameAsCompiledLength() {
+        assert_eq!(make_genome().expanded_length(),
+                   make_genome().compile().get_program().len());
+    }
+
+    #[test]
+    fn expandedLength_nestedRepetitions_saturates() {
+        let mut genes = vec![op(Op::Increment)];
+        for _ in 0..8 {
+            genes = vec![Gene::Repeat {
+                             body: genes,
+                             count: u32::MAX,
+                         }];
+        }
+        assert_eq!(Genome::new(genes).expanded_length(), usize::MAX);
+    }
+
+    #[test]
+    fn toSource_keepsRepetitions() {
+        assert_eq!(make_genome().to_source(), ">(+[-])*2(<{.}>)%3");
+    }
+
+    #[test]
+    fn fromBot_compilesIntoSameProgram() {
+        let bot = make_genome().compile();
+        assert_eq!(Genome::from_bot(&bot).compile(), bot);
+    }
+
+    #[test]
+    fn nrOps_countsOpsInsideOtherGenes() {
+        assert_eq!(make_genome().nr_ops(), 6);
+    }
+
+    #[test]
+    fn sequenceMut_findsSequencesInSourceOrder() {
+        let mut genome = make_genome();
+        assert_eq!(genome.nr_sequences(), 6);
+        genome.sequence_mut(2).unwrap().push(op(Op::Skip));
+        genome.sequence_mut(5).unwrap().clear();
+        assert_eq!(genome.to_source(), ">(+[-.])*2(<{.})%3");
+        assert_eq!(genome.sequence_mut(6), None);
+    }
+
+    #[test]
+    fn countMut_findsCountsInSourceOrder() {
+        let mut genome = make_genome();
+        assert_eq!(genome.nr_counts(), 2);
+        *genome.count_mut(1).unwrap() = 7;
+        assert_eq!(genome.to_source(), ">(+[-])*2(<{.}>)%7");
+        assert_eq!(genome.count_mut(2), None);
+    }
+
+    #[test]
+    fn opMut_findsOpsInSourceOrder() {
+        let mut genome = make_genome();
+        *genome.op_mut(2).unwrap() = Op::Skip;
+        assert_eq!(genome.to_source(), ">(+[.])*2(<{.}>)%3");
+        assert_eq!(genome.op_mut(6), None);
+    }
+}
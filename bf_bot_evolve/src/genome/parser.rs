@@ -0,0 +1,223 @@
+use std::error::Error;
+use std::fmt;
+
+use genome::{Gene, Genome, Op, MAX_EXPANDED_LENGTH};
+
+/// The different reasons why source code can fail to parse into a genome.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum GenomeParseErrorKind {
+    /// A character that isn't allowed where it appears, or the end of the source code.
+    UnexpectedCharacter,
+    /// The genome expands into more than `MAX_EXPANDED_LENGTH` instructions.
+    ProgramTooLong,
+}
+
+/// Describes why and where source code could not be parsed into a genome.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GenomeParseError {
+    pub kind: GenomeParseErrorKind,
+    /// The offending character, or `None` if the source code ended too soon or is too long as a
+    /// whole.
+    pub character: Option<char>,
+    /// The index of the offending character, counted in characters.
+    pub position: usize,
+}
+
+impl fmt::Display for GenomeParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match (self.kind, self.character) {
+            (GenomeParseErrorKind::ProgramTooLong, _) => {
+                write!(f,
+                       "The program expands into more than {} instructions.",
+                       MAX_EXPANDED_LENGTH)
+            }
+            (_, Some(character)) => {
+                write!(f, "Unexpected '{}' at position {}.", character, self.position)
+            }
+            (_, None) => write!(f, "Unexpected end of source code at position {}.", self.position),
+        }
+    }
+}
+
+impl Error for GenomeParseError {}
+
+impl Genome {
+    /// Parses BF Joust source code into a genome, keeping its loops and repetitions. This is the
+    /// counterpart of `to_source`. Characters that are not part of the language are comments.
+    /// Like the compiler, this refuses source code that expands into more than
+    /// `MAX_EXPANDED_LENGTH` instructions.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// extern crate bf_bot_evolve;
+    /// use bf_bot_evolve::genome::Genome;
+    /// let genome = Genome::from_source(">(-)*3 clear: [-]").unwrap();
+    /// assert_eq!(genome.to_source(), ">(-)*3[-]");
+    /// assert!(Genome::from_source("[+").is_err());
+    /// ```
+    pub fn from_source(source_code: &str) -> Result<Genome, GenomeParseError> {
+        let mut parser = Parser {
+            characters: source_code.chars().collect(),
+            position: 0,
+        };
+        let genes = parser.parse_sequence()?;
+        if parser.peek().is_some() {
+            return Err(parser.error());
+        }
+        let genome = Genome::new(genes);
+        if genome.expanded_length() > MAX_EXPANDED_LENGTH {
+            return Err(GenomeParseError {
+                kind: GenomeParseErrorKind::ProgramTooLong,
+                character: None,
+                position: 0,
+            });
+        }
+        Ok(genome)
+    }
+}
+
+struct Parser {
+    characters: Vec<char>,
+    position: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<char> {
+        self.characters.get(self.position).cloned()
+    }
+
+    fn error(&self) -> GenomeParseError {
+        GenomeParseError {
+            kind: GenomeParseErrorKind::UnexpectedCharacter,
+            character: self.peek(),
+            position: self.position,
+        }
+    }
+
+    fn expect(&mut self, character: char) -> Result<(), GenomeParseError> {
+        if self.peek() == Some(character) {
+            self.position += 1;
+            Ok(())
+        } else {
+            Err(self.error())
+        }
+    }
+
+    /// Parses genes until the end of the source code, a closing bracket or a brace, which is left
+    /// for the caller to check.
+    fn parse_sequence(&mut self) -> Result<Vec<Gene>, GenomeParseError> {
+        let mut genes = vec![];
+        while let Some(character) = self.peek() {
+            match character {
+                ']' | ')' | '{' | '}' => break,
+                '[' => {
+                    self.position += 1;
+                    let body = self.parse_sequence()?;
+                    self.expect(']')?;
+                    genes.push(Gene::Loop(body));
+                }
+                '(' => {
+                    self.position += 1;
+                    genes.push(self.parse_repetition()?);
+                }
+                _ => {
+                    self.position += 1;
+                    if let Some(op) = Op::from_char(character) {
+                        genes.push(Gene::Op(op));
+                    }
+                }
+            }
+        }
+        Ok(genes)
+    }
+
+    /// Parses the rest of a repetition, after its opening bracket.
+    fn parse_repetition(&mut self) -> Result<Gene, GenomeParseError> {
+        let pre = self.parse_sequence()?;
+        if self.peek() == Some('{') {
+            self.position += 1;
+            let inner = self.parse_sequence()?;
+            self.expect('}')?;
+            let post = self.parse_sequence()?;
+            self.expect(')')?;
+            self.expect('%')?;
+            let count = self.parse_count()?;
+            return Ok(Gene::Nest {
+                pre,
+                inner,
+                post,
+                count,
+            });
+        }
+        self.expect(')')?;
+        match self.peek() {
+            Some('*') | Some('%') => self.position += 1,
+            _ => return Err(self.error()),
+        }
+        let count = self.parse_count()?;
+        Ok(Gene::Repeat { body: pre, count })
+    }
+
+    fn parse_count(&mut self) -> Result<u32, GenomeParseError> {
+        let start = self.position;
+        while self.peek().is_some_and(|character| character.is_ascii_digit()) {
+            self.position += 1;
+        }
+        let digits = self.characters[start..self.position].iter().collect::<String>();
+        digits.parse().map_err(|_| {
+            GenomeParseError {
+                kind: GenomeParseErrorKind::UnexpectedCharacter,
+                character: self.characters.get(start).cloned(),
+                position: start,
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fromSource_toSource_roundTrips() {
+        let source = ">(+[-.])*2(<{.}>)%3[]";
+        assert_eq!(Genome::from_source(source).unwrap().to_source(), source);
+    }
+
+    #[test]
+    fn fromSource_ignoresComments() {
+        let genome = Genome::from_source("wait: (.)*4\nattack! >").unwrap();
+        assert_eq!(genome.to_source(), "(.)*4>");
+    }
+
+    #[test]
+    fn fromSource_percentWithoutInnerBlock_isRepeat() {
+        assert_eq!(Genome::from_source("(+)%2").unwrap().to_source(), "(+)*2");
+    }
+
+    #[test]
+    fn fromSource_unbalanced_reportsPosition() {
+        assert_eq!(Genome::from_source("+]"),
+                   Err(GenomeParseError {
+                       kind: GenomeParseErrorKind::UnexpectedCharacter,
+                       character: Some(']'),
+                       position: 1,
+                   }));
+        assert_eq!(Genome::from_source("[+").unwrap_err().character, None);
+    }
+
+    #[test]
+    fn fromSource_missingCount_fails() {
+        assert_eq!(Genome::from_source("(+)*x").unwrap_err().position, 4);
+        assert!(Genome::from_source("(+{-}+)*2").is_err());
+    }
+
+    #[test]
+    fn fromSource_expandsTooFar_fails() {
+        assert_eq!(Genome::from_source("(+)*4000000000").unwrap_err().kind,
+                   GenomeParseErrorKind::ProgramTooLong);
+        assert!(Genome::from_source("((+)*1000)*1000").is_ok());
+    }
+}
@@ -0,0 +1,135 @@
+use rand::{Rng, RngCore};
+
+use bf_bot_core::simul_game::{MAX_TAPE_LENGTH, MIN_TAPE_LENGTH};
+use genome::{Gene, Genome, Op};
+
+/// Bounds on the random choices that `random` makes.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TemplateConfig {
+    /// The greatest number of decoys that a bot lays in front of its flag.
+    pub max_decoys: u32,
+    /// The greatest number of times a decoy is incremented or decremented.
+    pub max_decoy_size: u32,
+    /// The greatest number of cells that a bot skips after its decoys, before it starts
+    /// attacking.
+    pub max_advance: u32,
+    /// The probability that a bot scans for non-zero cells, rather than clearing every cell.
+    pub scan_probability: f64,
+    /// The greatest number of times a clearing bot changes each cell before its clear loop.
+    pub max_offset: u32,
+}
+
+impl Default for TemplateConfig {
+    fn default() -> TemplateConfig {
+        TemplateConfig {
+            max_decoys: 6,
+            max_decoy_size: 64,
+            max_advance: 8,
+            scan_probability: 0.5,
+            max_offset: 64,
+        }
+    }
+}
+
+/// Generates a random bot that follows the plan of most working warriors, which uniformly random
+/// instructions almost never do. The bot lays decoys in front of its flag, skips ahead, and then
+/// attacks every cell up to the end of the longest tape in one of two ways:
+///
+/// * by scanning, `(>[-])*29`: only cells that are not zero are cleared;
+/// * by clearing, `(>(-)*16[-])*29`: every cell is changed a number of times before it is
+///   cleared, to get through decoys quickly.
+///
+/// The decoys and the cells that are skipped never reach past the shortest tape, so the attack
+/// always starts in time to find the enemy flag. The only ops the bot uses are moves, increments
+/// and decrements, so every genome compiles into a valid program.
+///
+/// # Examples
+///
+/// ```
+/// extern crate bf_bot_evolve;
+/// use bf_bot_evolve::evolution;
+/// use bf_bot_evolve::genome::{self, TemplateConfig};
+/// let genome = genome::random(&mut evolution::seeded_rng(0), &TemplateConfig::default());
+/// assert!(genome.to_source().ends_with(")*29"));
+/// ```
+pub fn random(rng: &mut dyn RngCore, config: &TemplateConfig) -> Genome {
+    let mut genes = vec![];
+    // The first cell that can hold the enemy flag is never passed before the attack.
+    let max_preparation = MIN_TAPE_LENGTH - 2;
+    let nr_decoys = rng.gen_range(0..=config.max_decoys.min(max_preparation));
+    for _ in 0..nr_decoys {
+        genes.push(Gene::Op(Op::MoveForward));
+        genes.push(Gene::Repeat {
+            body: vec![Gene::Op(random_change(rng))],
+            count: rng.gen_range(1..=config.max_decoy_size.max(1)),
+        });
+    }
+    let nr_skipped = rng.gen_range(0..=config.max_advance.min(max_preparation - nr_decoys));
+    if nr_skipped > 0 {
+        genes.push(Gene::Repeat {
+            body: vec![Gene::Op(Op::MoveForward)],
+            count: nr_skipped,
+        });
+    }
+    let clear = random_change(rng);
+    let mut attack = vec![Gene::Op(Op::MoveForward)];
+    if !rng.gen_bool(config.scan_probability) {
+        attack.push(Gene::Repeat {
+            body: vec![Gene::Op(clear)],
+            count: rng.gen_range(1..=config.max_offset.max(1)),
+        });
+    }
+    attack.push(Gene::Loop(vec![Gene::Op(clear)]));
+    genes.push(Gene::Repeat {
+        body: attack,
+        count: MAX_TAPE_LENGTH - 1,
+    });
+    Genome::new(genes)
+}
+
+/// Picks an increment or a decrement, each being equally likely.
+fn random_change(rng: &mut dyn RngCore) -> Op {
+    if rng.gen_bool(0.5) {
+        Op::Increment
+    } else {
+        Op::Decrement
+    }
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod tests {
+    use super::*;
+    use bf_bot_core::bf::Bot;
+    use bf_bot_core::match_runner::Match;
+    use bf_bot_core::simul_game::AllRounds;
+    use evolution::seeded_rng;
+
+    #[test]
+    fn random_everyBot_beatsIdleOpponentInEveryRound() {
+        let mut rng = seeded_rng(3);
+        let idle = Bot::new(vec![]);
+        for _ in 0..50 {
+            let genome = random(&mut rng, &TemplateConfig::default());
+            let bot = genome.compile();
+            let match_result = Match::new(&bot, &idle).run_rounds(AllRounds::new()).unwrap();
+            assert_eq!(match_result.nr_bot_a_wins(), 42, "{}", genome.to_source());
+        }
+    }
+
+    #[test]
+    fn random_sourceParsesBackIntoTheSameGenome() {
+        let mut rng = seeded_rng(4);
+        for _ in 0..20 {
+            let genome = random(&mut rng, &TemplateConfig::default());
+            assert_eq!(Genome::from_source(&genome.to_source()), Ok(genome));
+        }
+    }
+
+    #[test]
+    fn random_scanProbabilityOne_onlyScans() {
+        let config = TemplateConfig { scan_probability: 1.0, ..TemplateConfig::default() };
+        let source = random(&mut seeded_rng(5), &config).to_source();
+        assert!(source.ends_with("(>[-])*29") || source.ends_with("(>[+])*29"), "{}", source);
+    }
+}
@@ -0,0 +1,194 @@
+use bf_bot_core::analysis;
+use bf_bot_core::simul_round::RoundParams;
+use genome::{Gene, Genome, Op};
+
+/// The number of extra probe opponents, besides the reference bots, that a simplified bot must
+/// play the same against.
+const NR_EQUIVALENCE_PROBES: usize = 8;
+
+/// The maximum number of steps of every round of the equivalence check: that of a complete match.
+const EQUIVALENCE_MAX_STEPS: u32 = 100_000;
+
+/// Returns a shorter, more readable genome that plays the same way as the given one. Three kinds
+/// of code are removed:
+///
+/// * repetitions that change nothing about the program, like `(+)*1`, `(-)*0` and `()*5`;
+/// * code at the end of the program that neither moves nor changes a cell, such as `[.]`, since a
+///   bot that has run out of instructions does nothing either;
+/// * pairs of ops that undo each other, like `+-` and `<>`.
+///
+/// Unlike the first two, removing a pair makes the rest of the program run two cycles earlier,
+/// and `<>` may even save a bot from stepping off the tape. So every pair is only removed if
+/// the bot still wins, loses and draws exactly the same rounds against the probe opponents of
+/// `analysis::equivalent`.
+///
+/// # Examples
+///
+/// ```
+/// extern crate bf_bot_evolve;
+/// use bf_bot_evolve::genome::{self, Genome};
+/// let genome = Genome::from_source("(>)*9(+)*1([-]>)*21(.)*3[..]").unwrap();
+/// assert_eq!(genome::simplify(&genome).to_source(), "(>)*9+([-]>)*21");
+/// ```
+pub fn simplify(genome: &Genome) -> Genome {
+    let mut simplified = Genome::new(normalize(&genome.genes));
+    strip_inert_tail(&mut simplified.genes);
+    let original = genome.compile();
+    let params = equivalence_params();
+    for sequence_index in 0..simplified.nr_sequences() {
+        let mut index = 0;
+        loop {
+            let cancels = {
+                let sequence = simplified.sequence_mut(sequence_index)
+                    .expect("Removing ops leaves the sequences in place.");
+                if index + 1 >= sequence.len() {
+                    break;
+                }
+                cancel_out(&sequence[index], &sequence[index + 1])
+            };
+            if !cancels {
+                index += 1;
+                continue;
+            }
+            let mut candidate = simplified.clone();
+            candidate.sequence_mut(sequence_index)
+                .expect("Removing ops leaves the sequences in place.")
+                .drain(index..index + 2);
+            let equivalence = analysis::equivalent(&original,
+                                                   &candidate.compile(),
+                                                   &params,
+                                                   NR_EQUIVALENCE_PROBES)
+                .expect("The standard rules are valid on every tape length.");
+            if equivalence.is_equivalent() {
+                simplified = candidate;
+                // The ops around the removed pair may cancel out as well, as in `+<>-`.
+                index = index.saturating_sub(1);
+            } else {
+                index += 1;
+            }
+        }
+    }
+    Genome::new(normalize(&simplified.genes))
+}
+
+/// Rewrites repetitions that are empty, repeated zero times or repeated once, without changing
+/// the program that the genes compile into.
+fn normalize(genes: &[Gene]) -> Vec<Gene> {
+    let mut normalized = vec![];
+    for gene in genes {
+        match *gene {
+            Gene::Op(op) => normalized.push(Gene::Op(op)),
+            Gene::Loop(ref body) => normalized.push(Gene::Loop(normalize(body))),
+            Gene::Repeat { ref body, count } => {
+                let body = normalize(body);
+                if body.is_empty() || count == 0 {
+                    continue;
+                }
+                if count == 1 {
+                    normalized.extend(body);
+                } else {
+                    normalized.push(Gene::Repeat { body, count });
+                }
+            }
+            Gene::Nest { ref pre, ref inner, ref post, count } => {
+                let (pre, inner, post) = (normalize(pre), normalize(inner), normalize(post));
+                if count == 0 || (pre.is_empty() && post.is_empty()) {
+                    normalized.extend(inner);
+                } else if count == 1 {
+                    normalized.extend(pre);
+                    normalized.extend(inner);
+                    normalized.extend(post);
+                } else if post.is_empty() {
+                    normalized.push(Gene::Repeat { body: pre, count });
+                    normalized.extend(inner);
+                } else if pre.is_empty() {
+                    normalized.extend(inner);
+                    normalized.push(Gene::Repeat { body: post, count });
+                } else {
+                    normalized.push(Gene::Nest {
+                        pre,
+                        inner,
+                        post,
+                        count,
+                    });
+                }
+            }
+        }
+    }
+    normalized
+}
+
+/// Removes the genes at the end that neither move the bot nor change a cell.
+fn strip_inert_tail(genes: &mut Vec<Gene>) {
+    while genes.last().is_some_and(is_inert) {
+        genes.pop();
+    }
+}
+
+fn is_inert(gene: &Gene) -> bool {
+    match *gene {
+        Gene::Op(op) => op == Op::Skip,
+        Gene::Loop(ref body) | Gene::Repeat { ref body, .. } => body.iter().all(is_inert),
+        Gene::Nest { ref pre, ref inner, ref post, .. } => {
+            pre.iter().chain(inner).chain(post).all(is_inert)
+        }
+    }
+}
+
+fn cancel_out(first: &Gene, second: &Gene) -> bool {
+    matches!((first, second),
+             (&Gene::Op(Op::Increment), &Gene::Op(Op::Decrement)) |
+             (&Gene::Op(Op::Decrement), &Gene::Op(Op::Increment)) |
+             (&Gene::Op(Op::MoveForward), &Gene::Op(Op::MoveBack)) |
+             (&Gene::Op(Op::MoveBack), &Gene::Op(Op::MoveForward)))
+}
+
+/// The rules of the rounds of the equivalence check. The tape length and polarity are varied by
+/// the check itself.
+fn equivalence_params() -> RoundParams {
+    RoundParams { early_termination: true, ..RoundParams::new(0, false, EQUIVALENCE_MAX_STEPS) }
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod tests {
+    use super::*;
+
+    fn simplified_source(source: &str) -> String {
+        simplify(&Genome::from_source(source).unwrap()).to_source()
+    }
+
+    #[test]
+    fn simplify_trivialRepetitions_unrolled() {
+        assert_eq!(simplified_source("(>)*1(+{}-)%0(+{>}-)%1(+{>})%3()*4"), ">+>-(+)*3>");
+    }
+
+    #[test]
+    fn simplify_inertTail_removed() {
+        assert_eq!(simplified_source(">(+)*5[.](.[.])*2."), ">(+)*5");
+        assert_eq!(simplified_source(">[.]+"), ">[.]+");
+    }
+
+    #[test]
+    fn simplify_harmlessPair_removed() {
+        assert_eq!(simplified_source(">+<>-(+)*5>-+[-]"), ">(+)*5>[-]");
+    }
+
+    #[test]
+    fn simplify_pairThatSavesTheBot_kept() {
+        // Without it, the bot would step off the tape right away.
+        assert_eq!(simplified_source("<>"), "<>");
+    }
+
+    #[test]
+    fn simplify_anyPairRemoved_sameOutcomes() {
+        let genome = Genome::from_source("(>)*9+-([-]>)*21").unwrap();
+        let simplified = simplify(&genome);
+        assert!(analysis::equivalent(&simplified.compile(),
+                                     &genome.compile(),
+                                     &equivalence_params(),
+                                     NR_EQUIVALENCE_PROBES)
+            .unwrap()
+            .is_equivalent());
+    }
+}
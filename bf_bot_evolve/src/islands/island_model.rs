@@ -0,0 +1,172 @@
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+
+use evolution::EvolutionRun;
+use population::Individual;
+
+/// Specifies when and how individuals move between islands.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Migration {
+    /// The number of generations between two migrations.
+    pub interval: u32,
+    /// The number of fittest individuals that every island sends to the next island.
+    pub nr_migrants: usize,
+}
+
+impl Default for Migration {
+    fn default() -> Migration {
+        Migration {
+            interval: 10,
+            nr_migrants: 2,
+        }
+    }
+}
+
+/// Several evolution runs, the islands, that are arranged in a ring. Every migration, each island
+/// sends copies of its fittest individuals to the next island in the ring, where they replace the
+/// weakest individuals.
+///
+/// Give the islands different seeds, or they evolve in exactly the same way.
+pub struct IslandModel {
+    islands: Vec<EvolutionRun>,
+    migration: Migration,
+    generation: u32,
+}
+
+impl IslandModel {
+    pub fn new(islands: Vec<EvolutionRun>, migration: Migration) -> IslandModel {
+        IslandModel {
+            islands,
+            migration,
+            generation: 0,
+        }
+    }
+
+    /// Runs a single generation on every island, followed by a migration if one is due.
+    pub fn run_generation(&mut self) {
+        self.evolve_islands();
+        self.generation += 1;
+        if self.migration.interval > 0 && self.generation.is_multiple_of(self.migration.interval) {
+            self.migrate();
+        }
+    }
+
+    /// Runs the given number of generations.
+    pub fn run_generations(&mut self, nr_generations: u32) {
+        for _ in 0..nr_generations {
+            self.run_generation();
+        }
+    }
+
+    #[cfg(not(feature = "rayon"))]
+    fn evolve_islands(&mut self) {
+        for island in &mut self.islands {
+            island.run_generation();
+        }
+    }
+
+    /// Evolves all islands in parallel. Every island has its own random number generator, so the
+    /// result is the same as that of evolving them one by one.
+    #[cfg(feature = "rayon")]
+    fn evolve_islands(&mut self) {
+        self.islands.par_iter_mut().for_each(|island| island.run_generation());
+    }
+
+    /// Sends the fittest individuals of every island to the next island in the ring.
+    fn migrate(&mut self) {
+        // All emigrants are picked before any arrive, so that no individual migrates twice.
+        let emigrants = self.islands
+            .iter()
+            .map(|island| island.get_population().fittest(self.migration.nr_migrants))
+            .collect::<Vec<Vec<Individual>>>();
+        let nr_islands = self.islands.len();
+        for (index, emigrants) in emigrants.into_iter().enumerate() {
+            self.islands[(index + 1) % nr_islands].immigrate(emigrants);
+        }
+    }
+
+    pub fn get_islands(&self) -> &[EvolutionRun] {
+        &self.islands
+    }
+
+    /// Returns the number of generations that have been run so far.
+    pub fn get_generation(&self) -> u32 {
+        self.generation
+    }
+
+    /// Returns the fittest individual on any island.
+    pub fn best(&self) -> Option<&Individual> {
+        self.islands
+            .iter()
+            .filter_map(|island| island.best())
+            .fold(None, |best: Option<&Individual>, individual| match best {
+                Some(best) if best.fitness >= individual.fitness => Some(best),
+                _ => Some(individual),
+            })
+    }
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod tests {
+    use super::*;
+    use bf_bot_core::bf::{Bot, Instruction};
+    use evolution::{seeded_rng, EvolutionParams};
+    use operators::{PointMutation, SinglePointCrossover};
+    use population::Population;
+
+    /// Rewards bots for every increment in their program.
+    fn count_increments(bot: &Bot) -> f64 {
+        bot.get_program().iter().filter(|&i| *i == Instruction::Increment).count() as f64
+    }
+
+    fn make_island(seed: u64) -> EvolutionRun {
+        let params = EvolutionParams {
+            population_size: 10,
+            seed,
+            ..EvolutionParams::default()
+        };
+        EvolutionRun::new(params,
+                          Population::random(10, 10, &mut seeded_rng(seed)),
+                          Box::new(PointMutation),
+                          Box::new(SinglePointCrossover),
+                          Box::new(count_increments))
+            .unwrap()
+    }
+
+    fn make_model(nr_migrants: usize) -> IslandModel {
+        let migration = Migration {
+            interval: 2,
+            nr_migrants,
+        };
+        IslandModel::new((0..3).map(make_island).collect(), migration)
+    }
+
+    #[test]
+    fn runGenerations_runsEveryIsland() {
+        let mut model = make_model(1);
+        model.run_generations(3);
+        assert_eq!(model.get_generation(), 3);
+        assert!(model.get_islands().iter().all(|island| island.get_generation() == 3));
+    }
+
+    #[test]
+    fn runGenerations_migrationDue_fittestMoveToNextIsland() {
+        let mut model = make_model(1);
+        model.run_generations(2);
+        // Without migrants, the islands evolve exactly as they did before the migration.
+        let mut reference = make_model(0);
+        reference.run_generations(2);
+        let migrant = reference.get_islands()[2].best().unwrap();
+        assert!(model.get_islands()[0].get_population().individuals.contains(migrant));
+    }
+
+    #[test]
+    fn best_isBestOfAllIslands() {
+        let model = make_model(1);
+        let best_fitness = model.best().unwrap().fitness;
+        assert!(model.get_islands()
+            .iter()
+            .all(|island| island.best().unwrap().fitness <= best_fitness));
+    }
+}
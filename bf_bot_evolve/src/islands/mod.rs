@@ -0,0 +1,5 @@
+//! The island model: several populations evolve independently and only occasionally exchange
+//! their best individuals. Because the islands drift apart between migrations, the population as
+//! a whole stays diverse for longer, which counters premature convergence on a single strategy.
+pub use self::island_model::{IslandModel, Migration};
+mod island_model;
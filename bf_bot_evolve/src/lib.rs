@@ -0,0 +1,74 @@
+//! Evolves `BrainFuck` Jousting bots using a genetic algorithm.
+//!
+//! A `Population` of bots is improved generation by generation by an `EvolutionRun`. Every
+//! generation, each bot is assigned a fitness by a `FitnessFunction`, typically by letting it
+//! fight other bots in the arena. Parents are then picked by a `SelectionStrategy` and combined
+//! and altered by a `CrossoverOperator` and a `MutationOperator` to form the next generation.
+//! All of these are traits, so that users can plug in their own implementations.
+//!
+//! The operators don't work on bots directly, but on their `Genome`: a representation that keeps
+//! the loops and repetitions of BF Joust source code, and which is compiled into a bot to evaluate
+//! it.
+//!
+//! To keep the population diverse, a run can reward novel behavior through `NoveltySearch`, select
+//! parents that excel on particular opponents and tape lengths through `LexicaseSelection`, and
+//! several runs can evolve side by side as the islands of an `IslandModel`, exchanging their best
+//! individuals every few generations.
+//!
+//! Random programs rarely score against strong bots, so a run can follow a `Curriculum`: the first
+//! generations fight easy opponents on short tapes, and later generations the real hill.
+//!
+//! Instead of fighting a fixed set of opponents, populations can also be evaluated against each
+//! other in a `CoevolutionRun`.
+//!
+//! For long runs, an `AlpsRun` divides the population into age layers and keeps injecting fresh
+//! random genomes at the bottom, so that the run never converges on a single lineage.
+//!
+//! Rather than a single champion, a `MapElitesRun` finds the best bot for every kind of behavior,
+//! such as every combination of program length and aggression.
+//!
+//! To protect new lineages that are still weak, a `Speciation` divides the population into species
+//! of similar genomes, whose members share their fitness.
+//!
+//! To trade off several objectives, such as the score on a hill and the length of the program, an
+//! `Nsga2Run` evolves a population towards a Pareto front instead of a single champion.
+//!
+//! Evolution is deterministic: all randomness is drawn from a random number generator that is
+//! seeded from `EvolutionParams::seed`, so a run can be reproduced exactly from its seed.
+//!
+//! Evaluating the fitness of a population is by far the most expensive part of evolution. With the
+//! `rayon` feature enabled, the individuals of a population are evaluated in parallel on all
+//! available cores. The results are identical to those of a sequential evaluation.
+//!
+//! With the `sqlite` feature enabled, the `storage` module records runs, their generations and
+//! champions, and matches in a SQLite database.
+//!
+//! With the `tracing` feature enabled, every generation, match and round is a span of the
+//! `tracing` crate, so that runs can be profiled and inspected with any `tracing` subscriber.
+extern crate bf_bot_core;
+extern crate rand;
+extern crate rand_chacha;
+#[cfg(feature = "rayon")]
+extern crate rayon;
+#[cfg(feature = "sqlite")]
+extern crate rusqlite;
+#[cfg(feature = "serde")]
+#[macro_use]
+extern crate serde;
+#[cfg(feature = "tracing")]
+extern crate tracing;
+
+pub mod alps;
+pub mod coevolution;
+pub mod evolution;
+pub mod fitness;
+pub mod genome;
+pub mod islands;
+pub mod map_elites;
+pub mod multi_objective;
+pub mod novelty;
+pub mod operators;
+pub mod population;
+pub mod speciation;
+#[cfg(feature = "sqlite")]
+pub mod storage;
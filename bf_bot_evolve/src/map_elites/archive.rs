@@ -0,0 +1,173 @@
+use std::collections::BTreeMap;
+
+use novelty::Behavior;
+use population::Individual;
+
+/// One axis of the grid: a feature of the behavior, divided into bins of equal width.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GridDimension {
+    pub min: f64,
+    pub max: f64,
+    pub nr_bins: usize,
+}
+
+impl GridDimension {
+    pub fn new(min: f64, max: f64, nr_bins: usize) -> GridDimension {
+        GridDimension { min, max, nr_bins }
+    }
+
+    /// Returns the bin that the given value falls in. Values outside the range of the dimension
+    /// fall in the first or the last bin.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bf_bot_evolve::map_elites::GridDimension;
+    /// let dimension = GridDimension::new(0.0, 100.0, 4);
+    /// assert_eq!(dimension.bin(30.0), 1);
+    /// assert_eq!(dimension.bin(-5.0), 0);
+    /// assert_eq!(dimension.bin(100.0), 3);
+    /// ```
+    pub fn bin(&self, value: f64) -> usize {
+        let nr_bins = self.nr_bins.max(1);
+        if self.max <= self.min {
+            return 0;
+        }
+        let fraction = (value - self.min) / (self.max - self.min);
+        ((fraction * nr_bins as f64).floor().max(0.0) as usize).min(nr_bins - 1)
+    }
+}
+
+/// The occupant of a cell of the grid.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Elite {
+    pub individual: Individual,
+    pub behavior: Behavior,
+}
+
+/// A grid over behavior, in which every cell holds the fittest individual that was found with a
+/// behavior in that cell. The features of a behavior are matched to the dimensions of the grid in
+/// order; features beyond the last dimension are ignored.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MapElitesArchive {
+    dimensions: Vec<GridDimension>,
+    /// The occupied cells, by the bin of every dimension. Ordered, so that the elites are always
+    /// listed in the same order.
+    cells: BTreeMap<Vec<usize>, Elite>,
+}
+
+impl MapElitesArchive {
+    pub fn new(dimensions: Vec<GridDimension>) -> MapElitesArchive {
+        MapElitesArchive {
+            dimensions,
+            cells: BTreeMap::new(),
+        }
+    }
+
+    pub fn get_dimensions(&self) -> &[GridDimension] {
+        &self.dimensions
+    }
+
+    /// Returns the cell that the given behavior falls in. Missing features count as zero.
+    pub fn cell_of(&self, behavior: &Behavior) -> Vec<usize> {
+        self.dimensions
+            .iter()
+            .enumerate()
+            .map(|(index, dimension)| {
+                dimension.bin(behavior.features.get(index).cloned().unwrap_or(0.0))
+            })
+            .collect()
+    }
+
+    /// Puts the evaluated individual in the cell of its behavior if that cell is empty, or if the
+    /// individual is fitter than the elite that occupies it. Returns whether it was put in.
+    pub fn insert(&mut self, individual: Individual, behavior: Behavior) -> bool {
+        let cell = self.cell_of(&behavior);
+        let fitness = individual.fitness.unwrap_or(f64::NEG_INFINITY);
+        let is_better = self.cells
+            .get(&cell)
+            .is_none_or(|elite| fitness > elite.individual.fitness.unwrap_or(f64::NEG_INFINITY));
+        if is_better {
+            self.cells.insert(cell,
+                              Elite {
+                                  individual,
+                                  behavior,
+                              });
+        }
+        is_better
+    }
+
+    /// Returns the elite in the given cell, if it is occupied.
+    pub fn get(&self, cell: &[usize]) -> Option<&Elite> {
+        self.cells.get(cell)
+    }
+
+    /// Returns every elite, in the order of their cells.
+    pub fn elites(&self) -> Vec<&Elite> {
+        self.cells.values().collect()
+    }
+
+    /// Returns the number of occupied cells.
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    /// Returns the number of cells of the grid.
+    pub fn nr_cells(&self) -> usize {
+        self.dimensions.iter().map(|dimension| dimension.nr_bins.max(1)).product()
+    }
+
+    /// Returns the fraction of the cells that is occupied.
+    pub fn coverage(&self) -> f64 {
+        self.len() as f64 / self.nr_cells() as f64
+    }
+
+    /// Returns the fittest elite of all cells.
+    pub fn best(&self) -> Option<&Elite> {
+        self.cells.values().fold(None, |best: Option<&Elite>, elite| match best {
+            Some(best) if best.individual.fitness >= elite.individual.fitness => Some(best),
+            _ => Some(elite),
+        })
+    }
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod tests {
+    use super::*;
+    use genome::Genome;
+
+    fn make_individual(fitness: f64) -> Individual {
+        let mut individual = Individual::new(Genome::default());
+        individual.fitness = Some(fitness);
+        individual
+    }
+
+    fn make_archive() -> MapElitesArchive {
+        MapElitesArchive::new(vec![GridDimension::new(0.0, 10.0, 5),
+                                   GridDimension::new(0.0, 1.0, 2)])
+    }
+
+    #[test]
+    fn insert_emptyCell_occupiesIt() {
+        let mut archive = make_archive();
+        assert!(archive.insert(make_individual(1.0), Behavior::new(vec![3.0, 0.7])));
+        assert_eq!(archive.get(&[1, 1]).unwrap().individual.fitness, Some(1.0));
+        assert_eq!(archive.nr_cells(), 10);
+        assert_eq!(archive.coverage(), 0.1);
+    }
+
+    #[test]
+    fn insert_occupiedCell_onlyFitterReplaces() {
+        let mut archive = make_archive();
+        archive.insert(make_individual(1.0), Behavior::new(vec![3.0, 0.7]));
+        assert!(!archive.insert(make_individual(0.5), Behavior::new(vec![2.5, 0.9])));
+        assert!(archive.insert(make_individual(2.0), Behavior::new(vec![2.5, 0.9])));
+        assert_eq!(archive.len(), 1);
+        assert_eq!(archive.best().unwrap().behavior, Behavior::new(vec![2.5, 0.9]));
+    }
+}
@@ -0,0 +1,156 @@
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+
+use evolution::{seeded_rng, Breeder, EvolutionParams, EvolutionRng};
+use fitness::FitnessFunction;
+use map_elites::{GridDimension, MapElitesArchive};
+use novelty::{Behavior, BehaviorDescriptor};
+use operators::{CrossoverOperator, MutationOperator, TournamentSelection};
+use population::Population;
+
+/// Fills a `MapElitesArchive`. Every generation, parents are picked uniformly at random from the
+/// elites in the archive, and the offspring are evaluated and described, and put in the archive
+/// if they are the fittest of their cell so far.
+///
+/// The parameters specify the number of offspring per generation as the population size, and how
+/// they are bred. Their selection method and elitism are not used: every elite is equally likely
+/// to be a parent, and the archive keeps the elites anyway.
+pub struct MapElitesRun {
+    params: EvolutionParams,
+    archive: MapElitesArchive,
+    generation: u32,
+    rng: EvolutionRng,
+    breeder: Breeder,
+    fitness_function: Box<dyn FitnessFunction>,
+    descriptor: Box<dyn BehaviorDescriptor>,
+}
+
+impl MapElitesRun {
+    /// Starts a run with a grid of the given dimensions, which is filled with the initial
+    /// population right away.
+    pub fn new(params: EvolutionParams,
+               dimensions: Vec<GridDimension>,
+               population: Population,
+               mutation: Box<dyn MutationOperator>,
+               crossover: Box<dyn CrossoverOperator>,
+               fitness_function: Box<dyn FitnessFunction>,
+               descriptor: Box<dyn BehaviorDescriptor>)
+               -> MapElitesRun {
+        // A tournament of one picks every elite with the same probability.
+        let selection = Box::new(TournamentSelection::new(1));
+        let mut run = MapElitesRun {
+            params: EvolutionParams { elitism: 0, ..params },
+            archive: MapElitesArchive::new(dimensions),
+            generation: 0,
+            rng: seeded_rng(params.seed),
+            breeder: Breeder::new(mutation, crossover, selection),
+            fitness_function,
+            descriptor,
+        };
+        run.add_to_archive(population);
+        run
+    }
+
+    /// Breeds a batch of offspring from the elites and puts them in the archive. Does nothing
+    /// but count the generation if the archive is empty.
+    pub fn run_generation(&mut self) {
+        if !self.archive.is_empty() {
+            let parents = Population {
+                individuals: self.archive
+                    .elites()
+                    .into_iter()
+                    .map(|elite| elite.individual.clone())
+                    .collect(),
+            };
+            let offspring = self.breeder.breed_generation(&parents, &self.params, &mut self.rng);
+            self.add_to_archive(offspring);
+        }
+        self.generation += 1;
+    }
+
+    /// Runs the given number of generations.
+    pub fn run_generations(&mut self, nr_generations: u32) {
+        for _ in 0..nr_generations {
+            self.run_generation();
+        }
+    }
+
+    fn add_to_archive(&mut self, mut population: Population) {
+        population.evaluate(&*self.fitness_function);
+        let behaviors = self.describe_all(&population);
+        for (individual, behavior) in population.individuals.into_iter().zip(behaviors) {
+            self.archive.insert(individual, behavior);
+        }
+    }
+
+    #[cfg(not(feature = "rayon"))]
+    fn describe_all(&self, population: &Population) -> Vec<Behavior> {
+        population.individuals.iter().map(|ind| self.descriptor.describe(&ind.bot)).collect()
+    }
+
+    #[cfg(feature = "rayon")]
+    fn describe_all(&self, population: &Population) -> Vec<Behavior> {
+        // Only the descriptor is shared between the threads; the breeder isn't Sync.
+        let descriptor = &*self.descriptor;
+        population.individuals.par_iter().map(|ind| descriptor.describe(&ind.bot)).collect()
+    }
+
+    pub fn get_archive(&self) -> &MapElitesArchive {
+        &self.archive
+    }
+
+    /// Returns the number of generations that have been run so far.
+    pub fn get_generation(&self) -> u32 {
+        self.generation
+    }
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod tests {
+    use super::*;
+    use bf_bot_core::bf::{Bot, Instruction};
+    use novelty::LengthAndAggression;
+    use operators::{SinglePointCrossover, WeightedMutation};
+
+    /// Rewards bots for every increment in their program.
+    fn count_increments(bot: &Bot) -> f64 {
+        bot.get_program().iter().filter(|&i| *i == Instruction::Increment).count() as f64
+    }
+
+    fn make_run() -> MapElitesRun {
+        let params = EvolutionParams {
+            population_size: 20,
+            mutation_rate: 1.0,
+            seed: 4,
+            ..EvolutionParams::default()
+        };
+        let dimensions = vec![GridDimension::new(0.0, 40.0, 8), GridDimension::new(0.0, 1.0, 3)];
+        MapElitesRun::new(params,
+                          dimensions,
+                          Population::random(10, 10, &mut seeded_rng(4)),
+                          Box::new(WeightedMutation::default()),
+                          Box::new(SinglePointCrossover),
+                          Box::new(count_increments),
+                          Box::new(LengthAndAggression::new(vec![Bot::new(vec![])])))
+    }
+
+    #[test]
+    fn new_initialPopulationInArchive() {
+        let run = make_run();
+        assert!(!run.get_archive().is_empty());
+        // All initial bots are 10 instructions long, so only one column can be occupied.
+        assert!(run.get_archive().elites().iter().all(|elite| elite.behavior.features[0] == 10.0));
+    }
+
+    #[test]
+    fn runGenerations_coverageGrows() {
+        let mut run = make_run();
+        let initial_len = run.get_archive().len();
+        let initial_best = run.get_archive().best().unwrap().individual.fitness;
+        run.run_generations(10);
+        assert_eq!(run.get_generation(), 10);
+        assert!(run.get_archive().len() > initial_len);
+        assert!(run.get_archive().best().unwrap().individual.fitness >= initial_best);
+    }
+}
@@ -0,0 +1,9 @@
+//! MAP-Elites: instead of evolving a single champion, fill a grid over a few behavioral dimensions
+//! with the best bot for every kind of behavior, such as every combination of program length and
+//! aggression. The result is a diverse portfolio of strategies, and stepping stones in one cell
+//! often lead to better bots in another.
+pub use self::archive::{Elite, GridDimension, MapElitesArchive};
+mod archive;
+
+pub use self::map_elites_run::MapElitesRun;
+mod map_elites_run;
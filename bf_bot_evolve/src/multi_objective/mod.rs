@@ -0,0 +1,8 @@
+//! Evolution towards several objectives at once, such as a high score on the hill and a short
+//! program. Rather than a single champion, it yields a Pareto front: the individuals that no other
+//! individual beats on every objective. Which trade-off is best is then up to the user.
+pub use self::pareto::{crowding_distances, dominates, non_dominated_fronts};
+mod pareto;
+
+pub use self::nsga2::{standard_objectives, Nsga2Run, ParetoIndividual};
+mod nsga2;
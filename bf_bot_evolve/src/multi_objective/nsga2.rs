@@ -0,0 +1,291 @@
+use std::cmp::Ordering;
+
+use rand::Rng;
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+
+use bf_bot_core::bf::Bot;
+use evolution::{seeded_rng, EvolutionParams, EvolutionRng};
+use fitness::{FitnessFunction, HillFitness, SurvivalFitness};
+use genome::{Genome, MAX_EXPANDED_LENGTH};
+use multi_objective::{crowding_distances, non_dominated_fronts};
+use operators::{CrossoverOperator, MutationOperator};
+use population::Population;
+
+/// A member of a multi-objective population: a genome and the bot it compiles into, together with
+/// its value for every objective.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParetoIndividual {
+    pub genome: Genome,
+    pub bot: Bot,
+    /// One value per objective, in the order of the objectives of the run. Higher is better.
+    pub objectives: Vec<f64>,
+}
+
+/// Returns the objectives that make a bot worth submitting to a hill: its fitness on the given
+/// hill, the length of its program, negated so that shorter is better, and the fraction of rounds
+/// it survives on the hill.
+pub fn standard_objectives(hill: Vec<Bot>) -> Vec<Box<dyn FitnessFunction>> {
+    let program_length = |bot: &Bot| -(bot.get_program().len() as f64);
+    vec![Box::new(HillFitness::new(hill.clone())),
+         Box::new(program_length),
+         Box::new(SurvivalFitness::new(hill))]
+}
+
+/// Evolves a population towards several objectives at once, with NSGA-II. Every generation, the
+/// offspring and their parents are sorted into non-dominated fronts, and the best fronts survive.
+/// Within a front, individuals in sparsely populated parts of the front are preferred, so that the
+/// front stays spread out.
+///
+/// Parents are picked by binary tournaments on front and crowding distance. The selection method
+/// and elitism of the parameters are not used: every generation keeps the best of parents and
+/// offspring anyway.
+pub struct Nsga2Run {
+    params: EvolutionParams,
+    individuals: Vec<ParetoIndividual>,
+    /// The front that every individual belongs to, starting at 0 for the Pareto front.
+    ranks: Vec<usize>,
+    crowding: Vec<f64>,
+    generation: u32,
+    rng: EvolutionRng,
+    mutation: Box<dyn MutationOperator>,
+    crossover: Box<dyn CrossoverOperator>,
+    objectives: Vec<Box<dyn FitnessFunction>>,
+}
+
+impl Nsga2Run {
+    /// Starts a run from the given initial population, which is evaluated right away.
+    pub fn new(params: EvolutionParams,
+               population: Population,
+               mutation: Box<dyn MutationOperator>,
+               crossover: Box<dyn CrossoverOperator>,
+               objectives: Vec<Box<dyn FitnessFunction>>)
+               -> Nsga2Run {
+        let genomes = population.individuals.into_iter().map(|individual| individual.genome);
+        let mut run = Nsga2Run {
+            rng: seeded_rng(params.seed),
+            params,
+            individuals: vec![],
+            ranks: vec![],
+            crowding: vec![],
+            generation: 0,
+            mutation,
+            crossover,
+            objectives,
+        };
+        run.individuals = run.evaluate(genomes.collect());
+        run.rank();
+        run
+    }
+
+    /// Breeds as many offspring as there are individuals, and keeps the best of both.
+    pub fn run_generation(&mut self) {
+        let offspring = (0..self.params.population_size)
+            .map(|_| self.breed())
+            .collect::<Vec<_>>();
+        let mut offspring = self.evaluate(offspring);
+        self.individuals.append(&mut offspring);
+        self.rank();
+        self.survive();
+        self.generation += 1;
+    }
+
+    /// Runs the given number of generations.
+    pub fn run_generations(&mut self, nr_generations: u32) {
+        for _ in 0..nr_generations {
+            self.run_generation();
+        }
+    }
+
+    /// Returns the individuals that no other individual dominates.
+    pub fn pareto_front(&self) -> Vec<&ParetoIndividual> {
+        self.individuals
+            .iter()
+            .zip(&self.ranks)
+            .filter(|&(_, &rank)| rank == 0)
+            .map(|(individual, _)| individual)
+            .collect()
+    }
+
+    pub fn get_individuals(&self) -> &[ParetoIndividual] {
+        &self.individuals
+    }
+
+    /// Returns the number of generations that have been run so far.
+    pub fn get_generation(&self) -> u32 {
+        self.generation
+    }
+
+    #[cfg(not(feature = "rayon"))]
+    fn evaluate(&self, genomes: Vec<Genome>) -> Vec<ParetoIndividual> {
+        let objectives = &self.objectives;
+        genomes.into_iter().map(|genome| Nsga2Run::evaluate_one(objectives, genome)).collect()
+    }
+
+    #[cfg(feature = "rayon")]
+    fn evaluate(&self, genomes: Vec<Genome>) -> Vec<ParetoIndividual> {
+        let objectives = &self.objectives;
+        genomes.into_par_iter().map(|genome| Nsga2Run::evaluate_one(objectives, genome)).collect()
+    }
+
+    fn evaluate_one(objectives: &[Box<dyn FitnessFunction>], genome: Genome) -> ParetoIndividual {
+        let bot = genome.compile();
+        ParetoIndividual {
+            objectives: objectives.iter().map(|objective| objective.evaluate(&bot)).collect(),
+            genome,
+            bot,
+        }
+    }
+
+    /// Determines the front and crowding distance of every individual.
+    fn rank(&mut self) {
+        let values = self.individuals
+            .iter()
+            .map(|individual| individual.objectives.clone())
+            .collect::<Vec<_>>();
+        self.ranks = vec![0; values.len()];
+        self.crowding = vec![0.0; values.len()];
+        for (rank, front) in non_dominated_fronts(&values).into_iter().enumerate() {
+            let distances = crowding_distances(&values, &front);
+            for (&index, distance) in front.iter().zip(distances) {
+                self.ranks[index] = rank;
+                self.crowding[index] = distance;
+            }
+        }
+    }
+
+    /// Keeps the population size best individuals: those of the lowest fronts, and of the last
+    /// front that fits partially, those with the largest crowding distance.
+    fn survive(&mut self) {
+        let mut order = (0..self.individuals.len()).collect::<Vec<_>>();
+        order.sort_by(|&a, &b| self.compare(a, b));
+        order.truncate(self.params.population_size);
+        let mut individuals = vec![];
+        let mut ranks = vec![];
+        let mut crowding = vec![];
+        for &index in &order {
+            individuals.push(self.individuals[index].clone());
+            ranks.push(self.ranks[index]);
+            crowding.push(self.crowding[index]);
+        }
+        self.individuals = individuals;
+        self.ranks = ranks;
+        self.crowding = crowding;
+    }
+
+    /// Orders the better of two individuals first: the one in the lower front or, within a front,
+    /// the one with the larger crowding distance.
+    fn compare(&self, a: usize, b: usize) -> Ordering {
+        self.ranks[a]
+            .cmp(&self.ranks[b])
+            .then_with(|| self.crowding[b].total_cmp(&self.crowding[a]))
+    }
+
+    /// Picks the better of two random individuals.
+    fn select(&mut self) -> usize {
+        let a = self.rng.gen_range(0..self.individuals.len());
+        let b = self.rng.gen_range(0..self.individuals.len());
+        if self.compare(b, a) == Ordering::Less {
+            b
+        } else {
+            a
+        }
+    }
+
+    fn breed(&mut self) -> Genome {
+        let parent_a = self.select();
+        let child = if self.rng.gen_bool(self.params.crossover_rate) {
+            let parent_b = self.select();
+            self.crossover.crossover(&self.individuals[parent_a].genome,
+                                     &self.individuals[parent_b].genome,
+                                     &mut self.rng)
+        } else {
+            self.individuals[parent_a].genome.clone()
+        };
+        let child = if self.rng.gen_bool(self.params.mutation_rate) {
+            self.mutation.mutate(&child, &mut self.rng)
+        } else {
+            child
+        };
+        if child.expanded_length() > MAX_EXPANDED_LENGTH {
+            self.individuals[parent_a].genome.clone()
+        } else {
+            child
+        }
+    }
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod tests {
+    use super::*;
+    use bf_bot_core::bf::Instruction;
+    use genome::{Gene, Op};
+    use multi_objective::dominates;
+    use operators::{PointMutation, SinglePointCrossover};
+
+    /// Two conflicting objectives: the number of increments, and the shortness of the program.
+    fn make_objectives() -> Vec<Box<dyn FitnessFunction>> {
+        let increments = |bot: &Bot| {
+            bot.get_program().iter().filter(|&i| *i == Instruction::Increment).count() as f64
+        };
+        let shortness = |bot: &Bot| -(bot.get_program().len() as f64);
+        vec![Box::new(increments), Box::new(shortness)]
+    }
+
+    fn make_run() -> Nsga2Run {
+        let params = EvolutionParams {
+            population_size: 20,
+            seed: 7,
+            ..EvolutionParams::default()
+        };
+        let population = Population::random(20, 10, &mut seeded_rng(3));
+        Nsga2Run::new(params,
+                      population,
+                      Box::new(PointMutation),
+                      Box::new(SinglePointCrossover),
+                      make_objectives())
+    }
+
+    #[test]
+    fn paretoFront_noMemberDominatesAnother() {
+        let mut run = make_run();
+        run.run_generations(5);
+        let front = run.pareto_front();
+        assert!(!front.is_empty());
+        for a in &front {
+            assert!(front.iter().all(|b| !dominates(&a.objectives, &b.objectives)));
+        }
+    }
+
+    #[test]
+    fn runGeneration_keepsPopulationSize() {
+        let mut run = make_run();
+        run.run_generation();
+        assert_eq!(run.get_individuals().len(), 20);
+        assert_eq!(run.get_generation(), 1);
+    }
+
+    #[test]
+    fn runGeneration_sameSeed_sameFront() {
+        let objectives = |run: &Nsga2Run| {
+            run.pareto_front().iter().map(|ind| ind.objectives.clone()).collect::<Vec<_>>()
+        };
+        let mut run_a = make_run();
+        let mut run_b = make_run();
+        run_a.run_generations(3);
+        run_b.run_generations(3);
+        assert_eq!(objectives(&run_a), objectives(&run_b));
+    }
+
+    #[test]
+    fn new_evaluatesEveryObjective() {
+        let population = Population::new(vec![Genome::new(vec![Gene::Op(Op::Increment); 3])]);
+        let run = Nsga2Run::new(EvolutionParams::default(),
+                                population,
+                                Box::new(PointMutation),
+                                Box::new(SinglePointCrossover),
+                                make_objectives());
+        assert_eq!(run.get_individuals()[0].objectives, vec![3.0, -3.0]);
+    }
+}
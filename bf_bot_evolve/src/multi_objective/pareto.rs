@@ -0,0 +1,110 @@
+use std::f64;
+
+/// Returns true if the objective values `a` are at least as high as `b` on every objective, and
+/// higher on at least one.
+///
+/// # Examples
+///
+/// ```
+/// use bf_bot_evolve::multi_objective::dominates;
+/// assert!(dominates(&[2.0, 1.0], &[1.0, 1.0]));
+/// assert!(!dominates(&[2.0, 0.0], &[1.0, 1.0]));
+/// assert!(!dominates(&[1.0, 1.0], &[1.0, 1.0]));
+/// ```
+pub fn dominates(a: &[f64], b: &[f64]) -> bool {
+    a.iter().zip(b).all(|(a, b)| a >= b) && a.iter().zip(b).any(|(a, b)| a > b)
+}
+
+/// Sorts the given objective values into fronts, by the fast non-dominated sort of NSGA-II. The
+/// first front holds the indices of the values that nothing dominates, the second front those that
+/// only the first front dominates, and so on.
+///
+/// # Examples
+///
+/// ```
+/// use bf_bot_evolve::multi_objective::non_dominated_fronts;
+/// let values = vec![vec![1.0, 1.0], vec![2.0, 0.0], vec![0.0, 0.0]];
+/// assert_eq!(non_dominated_fronts(&values), vec![vec![0, 1], vec![2]]);
+/// ```
+pub fn non_dominated_fronts(values: &[Vec<f64>]) -> Vec<Vec<usize>> {
+    let mut dominated_by = vec![vec![]; values.len()];
+    let mut nr_dominating = vec![0; values.len()];
+    for a in 0..values.len() {
+        for b in 0..values.len() {
+            if dominates(&values[a], &values[b]) {
+                dominated_by[a].push(b);
+            } else if dominates(&values[b], &values[a]) {
+                nr_dominating[a] += 1;
+            }
+        }
+    }
+    let mut fronts = vec![];
+    let mut front = (0..values.len())
+        .filter(|&index| nr_dominating[index] == 0)
+        .collect::<Vec<_>>();
+    while !front.is_empty() {
+        let mut next_front = vec![];
+        for &index in &front {
+            for &dominated in &dominated_by[index] {
+                nr_dominating[dominated] -= 1;
+                if nr_dominating[dominated] == 0 {
+                    next_front.push(dominated);
+                }
+            }
+        }
+        next_front.sort_unstable();
+        fronts.push(front);
+        front = next_front;
+    }
+    fronts
+}
+
+/// Returns the crowding distance of every member of the given front, in the order of the front:
+/// how far apart its neighbours on the front are, summed over all objectives after scaling each to
+/// the range of the front. The extremes of every objective get an infinite distance, so that they
+/// are always kept.
+pub fn crowding_distances(values: &[Vec<f64>], front: &[usize]) -> Vec<f64> {
+    let mut distances = vec![0.0; front.len()];
+    let nr_objectives = front.first().map_or(0, |&index| values[index].len());
+    let columns = (0..nr_objectives)
+        .map(|objective| front.iter().map(|&index| values[index][objective]).collect::<Vec<_>>());
+    for column in columns {
+        let mut order = (0..front.len()).collect::<Vec<_>>();
+        order.sort_by(|&a, &b| column[a].total_cmp(&column[b]));
+        let value = |position: usize| column[order[position]];
+        let last = order.len() - 1;
+        let range = value(last) - value(0);
+        distances[order[0]] = f64::INFINITY;
+        distances[order[last]] = f64::INFINITY;
+        if range == 0.0 {
+            continue;
+        }
+        for position in 1..last {
+            distances[order[position]] += (value(position + 1) - value(position - 1)) / range;
+        }
+    }
+    distances
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn nonDominatedFronts_chain_oneFrontEach() {
+        let values = vec![vec![0.0], vec![2.0], vec![1.0]];
+        assert_eq!(non_dominated_fronts(&values), vec![vec![1], vec![2], vec![0]]);
+    }
+
+    #[test]
+    fn crowdingDistances_line_extremesInfinite() {
+        let values = vec![vec![0.0, 4.0], vec![1.0, 3.0], vec![3.0, 1.0], vec![4.0, 0.0]];
+        let distances = crowding_distances(&values, &[0, 1, 2, 3]);
+        assert_eq!(distances[0], f64::INFINITY);
+        assert_eq!(distances[3], f64::INFINITY);
+        // Both neighbours of the second point lie 3 apart on both objectives, out of a range of 4.
+        assert_eq!(distances[1], 1.5);
+        assert_eq!(distances[2], 1.5);
+    }
+}
@@ -0,0 +1,170 @@
+use bf_bot_core::bf::Bot;
+use bf_bot_core::match_runner::Match;
+use bf_bot_core::simul_round::LossReason;
+
+/// A characterization of how a bot behaves, as a point in a space in which bots that behave alike
+/// lie close together.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Behavior {
+    pub features: Vec<f64>,
+}
+
+impl Behavior {
+    pub fn new(features: Vec<f64>) -> Behavior {
+        Behavior { features }
+    }
+
+    /// Returns the Euclidean distance between two behaviors. Features that only one of the
+    /// behaviors has are ignored.
+    pub fn distance(&self, other: &Behavior) -> f64 {
+        self.features
+            .iter()
+            .zip(&other.features)
+            .map(|(a, b)| (a - b) * (a - b))
+            .sum::<f64>()
+            .sqrt()
+    }
+}
+
+/// Determines the behavior of a bot.
+///
+/// Descriptors must be `Send` and `Sync`, for the same reasons that fitness functions must be.
+pub trait BehaviorDescriptor: Send + Sync {
+    fn describe(&self, bot: &Bot) -> Behavior;
+}
+
+/// Describes a bot by how it fares in every round of a complete match against each of a number of
+/// reference opponents. Every round contributes five features, each between 0 and 1 or -1 and 1:
+///
+/// * the outcome: 1 for a win, 0 for a draw and -1 for a loss;
+/// * the speed: how early the round was decided, as 1 minus the fraction of the maximum number
+///   of steps that was used;
+/// * whether the bot lost because its flag was zeroed, and whether it lost by running off the
+///   tape;
+/// * whether the bot won by zeroing the flag of its opponent, rather than by its opponent running
+///   off the tape.
+#[derive(Debug, Clone)]
+pub struct MatchBehavior {
+    opponents: Vec<Bot>,
+}
+
+impl MatchBehavior {
+    pub fn new(opponents: Vec<Bot>) -> MatchBehavior {
+        MatchBehavior { opponents }
+    }
+}
+
+impl BehaviorDescriptor for MatchBehavior {
+    fn describe(&self, bot: &Bot) -> Behavior {
+        let mut features = Vec::new();
+        for opponent in &self.opponents {
+            let result = Match::new(bot, opponent).run().expect("The standard rounds are valid.");
+            for round in result.rounds {
+                let result = round.result;
+                let outcome = if !result.has_winner() {
+                    0.0
+                } else if result.bot_b_lost() {
+                    1.0
+                } else {
+                    -1.0
+                };
+                let speed = 1.0 - f64::from(result.nr_steps) / f64::from(round.params.max_steps);
+                let lost_by = |reason| if result.has_winner() && result.bot_a_loss == Some(reason) {
+                    1.0
+                } else {
+                    0.0
+                };
+                let won_by_flag = result.has_winner() &&
+                                  result.bot_b_loss == Some(LossReason::FlagZeroed);
+                features.extend_from_slice(&[outcome,
+                                             speed,
+                                             lost_by(LossReason::FlagZeroed),
+                                             lost_by(LossReason::OffTape),
+                                             if won_by_flag { 1.0 } else { 0.0 }]);
+            }
+        }
+        Behavior::new(features)
+    }
+}
+
+/// Describes a bot by two features: the length of its program, in instructions, and its
+/// aggression: the fraction of the rounds against the given opponents that it wins by zeroing the
+/// flag of its opponent. With only an idle opponent, aggression measures how reliably the bot
+/// reaches and clears the flag at the far end of the tape.
+#[derive(Debug, Clone)]
+pub struct LengthAndAggression {
+    opponents: Vec<Bot>,
+}
+
+impl LengthAndAggression {
+    pub fn new(opponents: Vec<Bot>) -> LengthAndAggression {
+        LengthAndAggression { opponents }
+    }
+}
+
+impl BehaviorDescriptor for LengthAndAggression {
+    fn describe(&self, bot: &Bot) -> Behavior {
+        let mut nr_rounds = 0;
+        let mut nr_flags_zeroed = 0;
+        for opponent in &self.opponents {
+            let result = Match::new(bot, opponent).run().expect("The standard rounds are valid.");
+            for round in result.rounds {
+                nr_rounds += 1;
+                if round.result.has_winner() &&
+                   round.result.bot_b_loss == Some(LossReason::FlagZeroed) {
+                    nr_flags_zeroed += 1;
+                }
+            }
+        }
+        let aggression = if nr_rounds == 0 {
+            0.0
+        } else {
+            f64::from(nr_flags_zeroed) / f64::from(nr_rounds)
+        };
+        Behavior::new(vec![bot.get_program().len() as f64, aggression])
+    }
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod tests {
+    use super::*;
+    use bf_bot_core::bf::Instruction;
+    use genome::Genome;
+
+    #[test]
+    fn distance_isEuclidean() {
+        let a = Behavior::new(vec![0.0, 0.0]);
+        let b = Behavior::new(vec![3.0, 4.0]);
+        assert_eq!(a.distance(&b), 5.0);
+    }
+
+    #[test]
+    fn describe_suicidalBot_losesEveryRoundOffTape() {
+        let descriptor = MatchBehavior::new(vec![Bot::new(vec![])]);
+        let behavior = descriptor.describe(&Bot::new(vec![Instruction::MoveBack]));
+        assert_eq!(behavior.features.len(), 42 * 5);
+        for round in behavior.features.chunks(5) {
+            assert_eq!(round[0], -1.0);
+            assert_eq!(&round[2..], &[0.0, 1.0, 0.0]);
+        }
+    }
+
+    #[test]
+    fn describe_sameBehavior_zeroDistance() {
+        let descriptor = MatchBehavior::new(vec![Bot::new(vec![Instruction::MoveBack])]);
+        let a = descriptor.describe(&Bot::new(vec![]));
+        let b = descriptor.describe(&Bot::new(vec![Instruction::Increment]));
+        assert_eq!(a.distance(&b), 0.0);
+    }
+
+    #[test]
+    fn describe_lengthAndAggression_flagClearerAgainstIdleBot() {
+        let descriptor = LengthAndAggression::new(vec![Bot::new(vec![])]);
+        let careless_clear = Genome::from_source("(>)*9((-)*128.>)*21").unwrap().compile();
+        let behavior = descriptor.describe(&careless_clear);
+        assert_eq!(behavior.features[0], careless_clear.get_program().len() as f64);
+        assert_eq!(behavior.features[1], 1.0);
+        assert_eq!(descriptor.describe(&Bot::new(vec![])).features, vec![0.0, 0.0]);
+    }
+}
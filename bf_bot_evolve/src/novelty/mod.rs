@@ -0,0 +1,11 @@
+//! Novelty search: rewarding bots for behaving differently from the bots seen before, rather than
+//! only for winning. Evolution that is driven by fitness alone tends to stagnate once the whole
+//! population has converged on a single strategy; novelty keeps it exploring.
+//!
+//! The behavior of a bot is summarized by a `BehaviorDescriptor` as a `Behavior`: a vector of
+//! numbers, such that bots that behave alike have behaviors that lie close together.
+pub use self::behavior::{Behavior, BehaviorDescriptor, LengthAndAggression, MatchBehavior};
+mod behavior;
+
+pub use self::novelty_search::NoveltySearch;
+mod novelty_search;
@@ -0,0 +1,170 @@
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+
+use novelty::{Behavior, BehaviorDescriptor};
+use population::Population;
+
+/// Rewards individuals for behaving differently from the rest of the population and from the
+/// behaviors in its archive. The archive remembers every behavior that was novel enough when it
+/// was first seen, so that evolution doesn't go round in circles.
+///
+/// The novelty of a behavior is its average distance to its nearest neighbours among the other
+/// behaviors. It is blended with the objective fitness, as computed by the fitness function of the
+/// run, according to the novelty weight: with a weight of 1, only novelty counts.
+pub struct NoveltySearch {
+    descriptor: Box<dyn BehaviorDescriptor>,
+    archive: Vec<Behavior>,
+    nr_neighbours: usize,
+    archive_threshold: f64,
+    novelty_weight: f64,
+}
+
+impl NoveltySearch {
+    /// Constructs a pure novelty search that compares each behavior to its 15 nearest
+    /// neighbours, and archives behaviors with a novelty of more than 1.
+    pub fn new(descriptor: Box<dyn BehaviorDescriptor>) -> NoveltySearch {
+        NoveltySearch {
+            descriptor,
+            archive: vec![],
+            nr_neighbours: 15,
+            archive_threshold: 1.0,
+            novelty_weight: 1.0,
+        }
+    }
+
+    /// Selects the number of nearest neighbours that the novelty of a behavior is measured
+    /// against.
+    pub fn with_nr_neighbours(self, nr_neighbours: usize) -> NoveltySearch {
+        NoveltySearch { nr_neighbours, ..self }
+    }
+
+    /// Selects the novelty above which a behavior is added to the archive.
+    pub fn with_archive_threshold(self, archive_threshold: f64) -> NoveltySearch {
+        NoveltySearch { archive_threshold, ..self }
+    }
+
+    /// Selects how much novelty counts, from 0 for not at all to 1 for replacing the objective
+    /// fitness entirely.
+    pub fn with_novelty_weight(self, novelty_weight: f64) -> NoveltySearch {
+        NoveltySearch { novelty_weight, ..self }
+    }
+
+    pub fn get_archive(&self) -> &[Behavior] {
+        &self.archive
+    }
+
+    /// Returns the average distance from the given behavior to its nearest neighbours among the
+    /// given other behaviors and the archive. A behavior without any neighbours has novelty 0.
+    pub fn novelty(&self, behavior: &Behavior, others: &[&Behavior]) -> f64 {
+        let mut distances = others.iter()
+            .cloned()
+            .chain(&self.archive)
+            .map(|other| behavior.distance(other))
+            .collect::<Vec<_>>();
+        distances.sort_by(|a, b| a.total_cmp(b));
+        distances.truncate(self.nr_neighbours);
+        if distances.is_empty() {
+            0.0
+        } else {
+            distances.iter().sum::<f64>() / distances.len() as f64
+        }
+    }
+
+    /// Blends the novelty of every individual of an evaluated population into its fitness, and
+    /// archives the behaviors that are novel enough.
+    pub fn apply(&mut self, population: &mut Population) {
+        let behaviors = self.describe_all(population);
+        let novelties = (0..behaviors.len())
+            .map(|index| {
+                let others = behaviors.iter()
+                    .enumerate()
+                    .filter(|&(other, _)| other != index)
+                    .map(|(_, behavior)| behavior)
+                    .collect::<Vec<_>>();
+                self.novelty(&behaviors[index], &others)
+            })
+            .collect::<Vec<_>>();
+        for (individual, &novelty) in population.individuals.iter_mut().zip(&novelties) {
+            let objective = individual.fitness.unwrap_or(0.0);
+            individual.fitness =
+                Some((1.0 - self.novelty_weight) * objective + self.novelty_weight * novelty);
+        }
+        for (behavior, novelty) in behaviors.into_iter().zip(novelties) {
+            if novelty > self.archive_threshold {
+                self.archive.push(behavior);
+            }
+        }
+    }
+
+    #[cfg(not(feature = "rayon"))]
+    fn describe_all(&self, population: &Population) -> Vec<Behavior> {
+        population.individuals.iter().map(|ind| self.descriptor.describe(&ind.bot)).collect()
+    }
+
+    #[cfg(feature = "rayon")]
+    fn describe_all(&self, population: &Population) -> Vec<Behavior> {
+        population.individuals.par_iter().map(|ind| self.descriptor.describe(&ind.bot)).collect()
+    }
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod tests {
+    use super::*;
+    use bf_bot_core::bf::{Bot, Instruction};
+
+    /// Describes a bot by the length of its program.
+    struct ProgramLength;
+
+    impl BehaviorDescriptor for ProgramLength {
+        fn describe(&self, bot: &Bot) -> Behavior {
+            Behavior::new(vec![bot.get_program().len() as f64])
+        }
+    }
+
+    fn make_population(lengths: &[usize]) -> Population {
+        let bots = lengths.iter().map(|&length| Bot::new(vec![Instruction::Increment; length]));
+        let mut population = Population::from_bots(&bots.collect::<Vec<_>>());
+        population.evaluate(&|_: &Bot| 10.0);
+        population
+    }
+
+    #[test]
+    fn novelty_averagesNearestNeighbours() {
+        let search = NoveltySearch::new(Box::new(ProgramLength)).with_nr_neighbours(2);
+        let behavior = Behavior::new(vec![0.0]);
+        let others = [1.0, 3.0, 9.0].iter().map(|&x| Behavior::new(vec![x])).collect::<Vec<_>>();
+        assert_eq!(search.novelty(&behavior, &others.iter().collect::<Vec<_>>()), 2.0);
+    }
+
+    #[test]
+    fn apply_pureNovelty_outlierIsFittest() {
+        let mut search = NoveltySearch::new(Box::new(ProgramLength)).with_nr_neighbours(1);
+        let mut population = make_population(&[1, 2, 8]);
+        search.apply(&mut population);
+        assert_eq!(population.fitnesses(), vec![1.0, 1.0, 6.0]);
+    }
+
+    #[test]
+    fn apply_halfWeight_blendsWithObjective() {
+        let mut search = NoveltySearch::new(Box::new(ProgramLength))
+            .with_nr_neighbours(1)
+            .with_novelty_weight(0.5);
+        let mut population = make_population(&[1, 3]);
+        search.apply(&mut population);
+        assert_eq!(population.fitnesses(), vec![6.0, 6.0]);
+    }
+
+    #[test]
+    fn apply_archivesNovelBehaviors() {
+        let mut search = NoveltySearch::new(Box::new(ProgramLength))
+            .with_nr_neighbours(1)
+            .with_archive_threshold(2.0);
+        search.apply(&mut make_population(&[1, 2, 8]));
+        assert_eq!(search.get_archive(), &[Behavior::new(vec![8.0])]);
+        // Now that it's archived, the same behavior is no longer novel.
+        let mut population = make_population(&[8]);
+        search.apply(&mut population);
+        assert_eq!(population.fitnesses(), vec![0.0]);
+    }
+}
@@ -0,0 +1,134 @@
+use std::mem;
+
+use rand::{Rng, RngCore};
+
+use genome::Genome;
+
+/// Combines two parents into a single offspring.
+pub trait CrossoverOperator: Send {
+    fn crossover(&self, parent_a: &Genome, parent_b: &Genome, rng: &mut dyn RngCore) -> Genome;
+}
+
+/// Cuts both parents in two at a random position between their top-level genes and glues the
+/// start of the first parent to the end of the second parent. Since genes are never cut, the
+/// offspring is always valid.
+#[derive(Debug, Default)]
+pub struct SinglePointCrossover;
+
+impl CrossoverOperator for SinglePointCrossover {
+    fn crossover(&self, parent_a: &Genome, parent_b: &Genome, rng: &mut dyn RngCore) -> Genome {
+        let cut_a = rng.gen_range(0..=parent_a.genes.len());
+        let cut_b = rng.gen_range(0..=parent_b.genes.len());
+        let mut genes = parent_a.genes[..cut_a].to_vec();
+        genes.extend_from_slice(&parent_b.genes[cut_b..]);
+        Genome::new(genes)
+    }
+}
+
+/// Cuts both parents in three at two random positions between their top-level genes and replaces
+/// the middle part of the first parent by the middle part of the second parent.
+#[derive(Debug, Default)]
+pub struct TwoPointCrossover;
+
+impl CrossoverOperator for TwoPointCrossover {
+    fn crossover(&self, parent_a: &Genome, parent_b: &Genome, rng: &mut dyn RngCore) -> Genome {
+        let (start_a, end_a) = random_cuts(parent_a.genes.len(), rng);
+        let (start_b, end_b) = random_cuts(parent_b.genes.len(), rng);
+        let mut genes = parent_a.genes[..start_a].to_vec();
+        genes.extend_from_slice(&parent_b.genes[start_b..end_b]);
+        genes.extend_from_slice(&parent_a.genes[end_a..]);
+        Genome::new(genes)
+    }
+}
+
+/// Returns two ordered cut positions in a sequence of the given length.
+fn random_cuts(len: usize, rng: &mut dyn RngCore) -> (usize, usize) {
+    let first = rng.gen_range(0..=len);
+    let second = rng.gen_range(0..=len);
+    (first.min(second), first.max(second))
+}
+
+/// Replaces a random sequence of genes in the first parent, such as the body of a loop, by a
+/// random sequence from the second parent. Whole subtrees are swapped, so loops and repetitions
+/// always stay balanced. Either sequence may also be a parent's complete list of genes.
+#[derive(Debug, Default)]
+pub struct SubtreeCrossover;
+
+impl CrossoverOperator for SubtreeCrossover {
+    fn crossover(&self, parent_a: &Genome, parent_b: &Genome, rng: &mut dyn RngCore) -> Genome {
+        let mut donor = parent_b.clone();
+        let index_b = rng.gen_range(0..donor.nr_sequences());
+        let subtree = mem::take(donor.sequence_mut(index_b).expect("The index is in range."));
+        let mut child = parent_a.clone();
+        let index_a = rng.gen_range(0..child.nr_sequences());
+        *child.sequence_mut(index_a).expect("The index is in range.") = subtree;
+        child
+    }
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod tests {
+    use super::*;
+    use genome::{Gene, Op};
+    use evolution::seeded_rng;
+
+    #[test]
+    fn singlePointCrossover_childStartsLikeParentAAndEndsLikeParentB() {
+        let mut rng = seeded_rng(0);
+        let parent_a = Genome::new(vec![Gene::Op(Op::Increment); 8]);
+        let parent_b = Genome::new(vec![Gene::Op(Op::Decrement); 8]);
+        for _ in 0..20 {
+            let child = SinglePointCrossover.crossover(&parent_a, &parent_b, &mut rng);
+            let nr_increments = child.genes
+                .iter()
+                .take_while(|&gene| *gene == Gene::Op(Op::Increment))
+                .count();
+            assert!(child.genes[nr_increments..].iter().all(|g| *g == Gene::Op(Op::Decrement)));
+        }
+    }
+
+    #[test]
+    fn twoPointCrossover_childIsParentAWithMiddleOfParentB() {
+        let mut rng = seeded_rng(1);
+        let parent_a = Genome::new(vec![Gene::Op(Op::Increment); 8]);
+        let parent_b = Genome::new(vec![Gene::Op(Op::Decrement); 8]);
+        for _ in 0..20 {
+            let child = TwoPointCrossover.crossover(&parent_a, &parent_b, &mut rng);
+            let source = child.to_source();
+            let middle = source.trim_matches('+');
+            assert!(middle.chars().all(|c| c == '-'), "{}", source);
+        }
+    }
+
+    #[test]
+    fn subtreeCrossover_swapsLoopBodies() {
+        let mut rng = seeded_rng(2);
+        let parent_a = Genome::new(vec![Gene::Loop(vec![Gene::Op(Op::Increment)])]);
+        let parent_b = Genome::new(vec![Gene::Loop(vec![Gene::Op(Op::Decrement)])]);
+        let mut sources = Vec::new();
+        for _ in 0..50 {
+            let child = SubtreeCrossover.crossover(&parent_a, &parent_b, &mut rng);
+            sources.push(child.to_source());
+        }
+        // Every possible pairing of a sequence in parent A with one in parent B.
+        let possible = ["-", "[-]", "[[-]]"];
+        assert!(sources.iter().all(|source| possible.contains(&source.as_str())),
+                "{:?}",
+                sources);
+        assert!(sources.contains(&"[[-]]".to_string()));
+    }
+
+    #[test]
+    fn singlePointCrossover_loopsStayIntact() {
+        let mut rng = seeded_rng(3);
+        let parent = Genome::new(vec![Gene::Op(Op::Increment),
+                                      Gene::Loop(vec![Gene::Op(Op::Decrement)])]);
+        for _ in 0..20 {
+            let child = SinglePointCrossover.crossover(&parent, &parent, &mut rng);
+            assert!(child.genes
+                .iter()
+                .all(|gene| *gene == parent.genes[0] || *gene == parent.genes[1]));
+        }
+    }
+}
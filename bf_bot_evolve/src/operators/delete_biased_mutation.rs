@@ -0,0 +1,92 @@
+use rand::{Rng, RngCore};
+
+use genome::Genome;
+use operators::{DeletionMutation, MutationOperator};
+
+/// Counters bloat by favouring deletions in genomes that have grown beyond a target length. A
+/// genome of at most the target length is mutated by the wrapped operator. A longer genome is
+/// mutated by deleting a gene instead, with a probability that grows with its excess length: the
+/// fraction of its `Op` genes that lie beyond the target.
+pub struct DeleteBiasedMutation {
+    operator: Box<dyn MutationOperator>,
+    target_length: usize,
+}
+
+impl DeleteBiasedMutation {
+    pub fn new(operator: Box<dyn MutationOperator>, target_length: usize) -> DeleteBiasedMutation {
+        DeleteBiasedMutation {
+            operator,
+            target_length,
+        }
+    }
+
+    /// Returns the probability that a genome with the given number of `Op` genes is mutated by a
+    /// deletion.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// extern crate bf_bot_evolve;
+    /// use bf_bot_evolve::operators::{DeleteBiasedMutation, PointMutation};
+    /// let mutation = DeleteBiasedMutation::new(Box::new(PointMutation), 30);
+    /// assert_eq!(mutation.deletion_probability(20), 0.0);
+    /// assert_eq!(mutation.deletion_probability(40), 0.25);
+    /// ```
+    pub fn deletion_probability(&self, nr_ops: usize) -> f64 {
+        if nr_ops <= self.target_length {
+            0.0
+        } else {
+            (nr_ops - self.target_length) as f64 / nr_ops as f64
+        }
+    }
+}
+
+impl MutationOperator for DeleteBiasedMutation {
+    fn mutate(&self, genome: &Genome, rng: &mut dyn RngCore) -> Genome {
+        self.mutate_named(genome, rng).0
+    }
+
+    fn name(&self) -> &str {
+        "delete-biased"
+    }
+
+    fn mutate_named(&self, genome: &Genome, rng: &mut dyn RngCore) -> (Genome, &str) {
+        if rng.gen_bool(self.deletion_probability(genome.nr_ops())) {
+            let deletion: &'static DeletionMutation = &DeletionMutation;
+            deletion.mutate_named(genome, rng)
+        } else {
+            self.operator.mutate_named(genome, rng)
+        }
+    }
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod tests {
+    use super::*;
+    use genome::{Gene, Op};
+    use evolution::seeded_rng;
+
+    /// Appends an increment to every genome.
+    struct GrowthMutation;
+
+    impl MutationOperator for GrowthMutation {
+        fn mutate(&self, genome: &Genome, _: &mut dyn RngCore) -> Genome {
+            let mut genes = genome.genes.clone();
+            genes.push(Gene::Op(Op::Increment));
+            Genome::new(genes)
+        }
+    }
+
+    #[test]
+    fn mutate_repeatedly_lengthStaysNearTarget() {
+        let mut rng = seeded_rng(0);
+        // Growth and deletion balance out at twice the target length.
+        let mutation = DeleteBiasedMutation::new(Box::new(GrowthMutation), 10);
+        let mut genome = Genome::new(vec![Gene::Op(Op::Increment); 10]);
+        for _ in 0..500 {
+            genome = mutation.mutate(&genome, &mut rng);
+        }
+        assert!(genome.nr_ops() < 40, "{}", genome.nr_ops());
+    }
+}
@@ -0,0 +1,78 @@
+use rand::seq::SliceRandom;
+use rand::{Rng, RngCore};
+
+use operators::SelectionStrategy;
+
+/// Selects individuals by their outcomes on separate test cases, rather than by their fitness,
+/// which is ignored. The test cases are put in a random order, and every case in turn removes the
+/// candidates that did worse on it than the best remaining candidate, until a single candidate is
+/// left or the cases run out. The remaining candidates are equally likely to be selected.
+///
+/// Specialists that beat opponents or tape lengths that the rest of the population fails on thus
+/// get selected, even if their total score is mediocre, which keeps the population diverse.
+/// `TestCases` provides the outcomes of BF Joust bots.
+#[derive(Debug, Clone)]
+pub struct LexicaseSelection {
+    /// For every individual, its outcome on every case, where higher is better.
+    outcomes: Vec<Vec<f64>>,
+}
+
+impl LexicaseSelection {
+    /// Creates a selection for a population with the given outcomes, which must hold as many
+    /// outcomes for every individual, in the same order of the cases.
+    pub fn new(outcomes: Vec<Vec<f64>>) -> LexicaseSelection {
+        LexicaseSelection { outcomes }
+    }
+}
+
+impl SelectionStrategy for LexicaseSelection {
+    fn select(&self, fitnesses: &[f64], rng: &mut dyn RngCore) -> usize {
+        if self.outcomes.len() != fitnesses.len() {
+            // The outcomes are of another population, so there is nothing to go by.
+            return rng.gen_range(0..fitnesses.len());
+        }
+        let mut cases = (0..self.outcomes.first().map_or(0, Vec::len)).collect::<Vec<_>>();
+        cases.shuffle(rng);
+        let mut candidates = (0..fitnesses.len()).collect::<Vec<_>>();
+        for case in cases {
+            if candidates.len() == 1 {
+                break;
+            }
+            let best = candidates.iter()
+                .map(|&candidate| self.outcomes[candidate][case])
+                .fold(f64::NEG_INFINITY, f64::max);
+            candidates.retain(|&candidate| self.outcomes[candidate][case] == best);
+        }
+        *candidates.choose(rng).expect("The best candidate is always kept.")
+    }
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod tests {
+    use super::*;
+    use evolution::seeded_rng;
+
+    #[test]
+    fn select_specialists_eachSelectedSometimes() {
+        // The generalist has the best total, but every specialist beats it on one case.
+        let selection = LexicaseSelection::new(vec![vec![0.5, 0.5, 0.5],
+                                                    vec![1.0, 0.0, 0.0],
+                                                    vec![0.0, 1.0, 0.0],
+                                                    vec![0.0, 0.0, 1.0]]);
+        let mut rng = seeded_rng(1);
+        let mut nr_selected = [0; 4];
+        for _ in 0..300 {
+            nr_selected[selection.select(&[1.5, 1.0, 1.0, 1.0], &mut rng)] += 1;
+        }
+        assert_eq!(nr_selected[0], 0);
+        assert!(nr_selected[1..].iter().all(|&nr| nr > 50));
+    }
+
+    #[test]
+    fn select_dominatedIndividual_neverSelected() {
+        let selection = LexicaseSelection::new(vec![vec![1.0, 0.0], vec![1.0, -1.0]]);
+        let mut rng = seeded_rng(2);
+        assert!((0..50).all(|_| selection.select(&[0.0, 0.0], &mut rng) == 0));
+    }
+}
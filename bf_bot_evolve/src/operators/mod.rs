@@ -0,0 +1,26 @@
+//! Genetic operators: the ways in which the evolution engine picks parents and turns them into
+//! offspring.
+
+pub use self::mutation::{MutationOperator, PointMutation};
+mod mutation;
+
+pub use self::structural_mutation::{DeletionMutation, DuplicationMutation, InsertionMutation,
+                                    LoopWrapMutation, RepeatCountMutation, RepeatWrapMutation};
+mod structural_mutation;
+
+pub use self::weighted_mutation::WeightedMutation;
+mod weighted_mutation;
+
+pub use self::delete_biased_mutation::DeleteBiasedMutation;
+mod delete_biased_mutation;
+
+pub use self::crossover::{CrossoverOperator, SinglePointCrossover, SubtreeCrossover,
+                          TwoPointCrossover};
+mod crossover;
+
+pub use self::selection::{ranking, RankSelection, RouletteSelection, SelectionMethod,
+                          SelectionStrategy, TournamentSelection};
+mod selection;
+
+pub use self::lexicase_selection::LexicaseSelection;
+mod lexicase_selection;
@@ -0,0 +1,73 @@
+use rand::{Rng, RngCore};
+
+use genome::{Genome, Op};
+
+/// Produces a slightly altered copy of a genome.
+pub trait MutationOperator: Send {
+    fn mutate(&self, genome: &Genome, rng: &mut dyn RngCore) -> Genome;
+
+    /// A short name, under which the impact of the operator's mutations is reported.
+    fn name(&self) -> &str {
+        "mutation"
+    }
+
+    /// Mutates the genome like `mutate`, and also returns the name of the operator that made the
+    /// change. Operators that delegate to other operators return the name of the one they picked.
+    fn mutate_named(&self, genome: &Genome, rng: &mut dyn RngCore) -> (Genome, &str) {
+        (self.mutate(genome, rng), self.name())
+    }
+}
+
+/// Replaces a single `Op` gene, wherever it is in the genome, by a random other one. The structure
+/// of the genome is left untouched.
+#[derive(Debug, Default)]
+pub struct PointMutation;
+
+impl MutationOperator for PointMutation {
+    fn mutate(&self, genome: &Genome, rng: &mut dyn RngCore) -> Genome {
+        let mut mutant = genome.clone();
+        let nr_ops = mutant.nr_ops();
+        if nr_ops > 0 {
+            let index = rng.gen_range(0..nr_ops);
+            *mutant.op_mut(index).expect("The index is in range.") = Op::random(rng);
+        }
+        mutant
+    }
+
+    fn name(&self) -> &str {
+        "point"
+    }
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod tests {
+    use super::*;
+    use genome::{Gene, Op};
+    use evolution::seeded_rng;
+
+    #[test]
+    fn pointMutation_changesAtMostOneOp() {
+        let mut rng = seeded_rng(0);
+        let genome = Genome::new(vec![Gene::Op(Op::Increment); 10]);
+        let mutant = PointMutation.mutate(&genome, &mut rng);
+        let nr_changed = mutant.genes
+            .iter()
+            .filter(|&gene| *gene != Gene::Op(Op::Increment))
+            .count();
+        assert!(nr_changed <= 1);
+        assert_eq!(mutant.genes.len(), 10);
+    }
+
+    #[test]
+    fn pointMutation_keepsStructure() {
+        let mut rng = seeded_rng(1);
+        let genome = Genome::new(vec![Gene::Repeat {
+                                          body: vec![Gene::Loop(vec![Gene::Op(Op::Decrement)])],
+                                          count: 4,
+                                      }]);
+        let mutant = PointMutation.mutate(&genome, &mut rng);
+        assert_eq!(mutant.nr_ops(), 1);
+        assert!(mutant.to_source().starts_with("([") && mutant.to_source().ends_with("])*4"));
+    }
+}
@@ -0,0 +1,277 @@
+use std::fmt;
+use std::str::FromStr;
+
+use rand::{Rng, RngCore};
+
+/// Picks the parents of the next generation.
+pub trait SelectionStrategy: Send {
+    /// Returns the index of the selected individual, given the fitness of every individual in the
+    /// population. The population must not be empty.
+    fn select(&self, fitnesses: &[f64], rng: &mut dyn RngCore) -> usize;
+}
+
+/// Picks a number of individuals at random and selects the fittest among them. The more
+/// individuals compete in a tournament, the higher the selection pressure. A tournament size of
+/// zero is treated as one.
+#[derive(Debug)]
+pub struct TournamentSelection {
+    pub tournament_size: usize,
+}
+
+impl TournamentSelection {
+    pub fn new(tournament_size: usize) -> TournamentSelection {
+        TournamentSelection { tournament_size }
+    }
+}
+
+impl SelectionStrategy for TournamentSelection {
+    fn select(&self, fitnesses: &[f64], rng: &mut dyn RngCore) -> usize {
+        let mut best = rng.gen_range(0..fitnesses.len());
+        for _ in 1..self.tournament_size {
+            let candidate = rng.gen_range(0..fitnesses.len());
+            if fitnesses[candidate] > fitnesses[best] {
+                best = candidate;
+            }
+        }
+        best
+    }
+}
+
+/// Selects individuals with a probability proportional to their fitness. Since fitness may be
+/// negative, the fitness of the weakest individual is first subtracted from every fitness, so the
+/// weakest individual is never selected unless all individuals are equally fit. Individuals whose
+/// fitness is not a finite number, such as those that haven't been evaluated, are never selected
+/// either, unless no individual can be.
+#[derive(Debug, Default)]
+pub struct RouletteSelection;
+
+impl SelectionStrategy for RouletteSelection {
+    fn select(&self, fitnesses: &[f64], rng: &mut dyn RngCore) -> usize {
+        let min = fitnesses.iter()
+            .cloned()
+            .filter(|fitness| fitness.is_finite())
+            .fold(f64::INFINITY, f64::min);
+        let weights = fitnesses.iter().map(|fitness| fitness - min).collect::<Vec<_>>();
+        select_weighted(&weights, rng)
+    }
+}
+
+/// Selects individuals with a probability proportional to their rank: the weakest individual has
+/// weight 1, the next one weight 2, up to the fittest individual. Unlike roulette selection, the
+/// selection pressure does not depend on how far apart the fitnesses are.
+#[derive(Debug, Default)]
+pub struct RankSelection;
+
+impl SelectionStrategy for RankSelection {
+    fn select(&self, fitnesses: &[f64], rng: &mut dyn RngCore) -> usize {
+        let mut weights = vec![0.0; fitnesses.len()];
+        for (rank, index) in ranking(fitnesses).into_iter().rev().enumerate() {
+            weights[index] = (rank + 1) as f64;
+        }
+        select_weighted(&weights, rng)
+    }
+}
+
+/// Returns the indices of all individuals, from the fittest to the weakest. Individuals with equal
+/// fitness keep their order, and a fitness that is not a number ranks as the weakest.
+pub fn ranking(fitnesses: &[f64]) -> Vec<usize> {
+    let rank_key = |index: usize| {
+        let fitness = fitnesses[index];
+        if fitness.is_nan() {
+            f64::NEG_INFINITY
+        } else {
+            fitness
+        }
+    };
+    let mut indices = (0..fitnesses.len()).collect::<Vec<_>>();
+    indices.sort_by(|&a, &b| rank_key(b).total_cmp(&rank_key(a)));
+    indices
+}
+
+/// Picks an index with a probability proportional to its weight, or uniformly if no weight is
+/// positive or the weights don't add up to a finite number. Weights that are negative or not
+/// finite count as zero.
+fn select_weighted(weights: &[f64], rng: &mut dyn RngCore) -> usize {
+    let usable = |weight: f64| if weight.is_finite() && weight > 0.0 { weight } else { 0.0 };
+    let total = weights.iter().map(|&weight| usable(weight)).sum::<f64>();
+    if !(total > 0.0 && total.is_finite()) {
+        return rng.gen_range(0..weights.len());
+    }
+    let mut pick = rng.gen_range(0.0..total);
+    for (index, &weight) in weights.iter().enumerate() {
+        let weight = usable(weight);
+        if pick < weight {
+            return index;
+        }
+        pick -= weight;
+    }
+    // Rounding errors can leave a tiny remainder; it belongs to the last index with a weight.
+    weights.iter().rposition(|&weight| usable(weight) > 0.0).unwrap()
+}
+
+/// The built-in selection strategies, so that the strategy of a run can be part of its
+/// parameters.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SelectionMethod {
+    /// See `TournamentSelection`; holds the tournament size.
+    Tournament(usize),
+    /// See `RouletteSelection`.
+    Roulette,
+    /// See `RankSelection`.
+    Rank,
+}
+
+impl Default for SelectionMethod {
+    fn default() -> SelectionMethod {
+        SelectionMethod::Tournament(3)
+    }
+}
+
+/// Writes the method as `tournament <size>`, `roulette` or `rank`.
+impl fmt::Display for SelectionMethod {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            SelectionMethod::Tournament(size) => write!(f, "tournament {}", size),
+            SelectionMethod::Roulette => write!(f, "roulette"),
+            SelectionMethod::Rank => write!(f, "rank"),
+        }
+    }
+}
+
+/// Reads the method in the format that `Display` writes it in.
+impl FromStr for SelectionMethod {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<SelectionMethod, String> {
+        let words = s.split_whitespace().collect::<Vec<_>>();
+        match words.as_slice() {
+            ["tournament", size] => {
+                size.parse()
+                    .map(SelectionMethod::Tournament)
+                    .map_err(|_| format!("Invalid tournament size: {}", size))
+            }
+            ["roulette"] => Ok(SelectionMethod::Roulette),
+            ["rank"] => Ok(SelectionMethod::Rank),
+            _ => Err(format!("Unknown selection method: {}", s)),
+        }
+    }
+}
+
+impl SelectionStrategy for SelectionMethod {
+    fn select(&self, fitnesses: &[f64], rng: &mut dyn RngCore) -> usize {
+        match *self {
+            SelectionMethod::Tournament(size) => {
+                TournamentSelection::new(size).select(fitnesses, rng)
+            }
+            SelectionMethod::Roulette => RouletteSelection.select(fitnesses, rng),
+            SelectionMethod::Rank => RankSelection.select(fitnesses, rng),
+        }
+    }
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod tests {
+    use super::*;
+    use evolution::seeded_rng;
+
+    #[test]
+    fn tournamentSelection_largeTournament_picksBest() {
+        let mut rng = seeded_rng(0);
+        let fitnesses = [1.0, 5.0, 3.0];
+        let selection = TournamentSelection::new(50);
+        assert_eq!(selection.select(&fitnesses, &mut rng), 1);
+    }
+
+    #[test]
+    fn tournamentSelection_singleIndividual_picksIt() {
+        let mut rng = seeded_rng(1);
+        let selection = TournamentSelection::new(3);
+        assert_eq!(selection.select(&[0.0], &mut rng), 0);
+    }
+
+    #[test]
+    fn rouletteSelection_neverPicksWeakest() {
+        let mut rng = seeded_rng(2);
+        let fitnesses = [-3.0, 1.0, 2.0];
+        for _ in 0..50 {
+            assert!(RouletteSelection.select(&fitnesses, &mut rng) != 0);
+        }
+    }
+
+    #[test]
+    fn rouletteSelection_equalFitness_picksAnyone() {
+        let mut rng = seeded_rng(3);
+        let fitnesses = [2.0; 3];
+        let picks = (0..100)
+            .map(|_| RouletteSelection.select(&fitnesses, &mut rng))
+            .collect::<Vec<_>>();
+        assert!((0..3).all(|index| picks.contains(&index)));
+    }
+
+    #[test]
+    fn rankSelection_favoursFitter() {
+        let mut rng = seeded_rng(4);
+        let fitnesses = [1000.0, 0.0];
+        let nr_best = (0..300)
+            .filter(|_| RankSelection.select(&fitnesses, &mut rng) == 0)
+            .count();
+        // The fittest has weight 2 out of 3, regardless of how much fitter it is.
+        assert!(nr_best > 150 && nr_best < 250, "{}", nr_best);
+    }
+
+    #[test]
+    fn selectionMethod_displayAndParse_roundTrip() {
+        for &method in &[SelectionMethod::Tournament(7),
+                         SelectionMethod::Roulette,
+                         SelectionMethod::Rank] {
+            assert_eq!(method.to_string().parse(), Ok(method));
+        }
+        assert!("tournament".parse::<SelectionMethod>().is_err());
+    }
+
+    #[test]
+    fn ranking_ordersFromFittest() {
+        assert_eq!(ranking(&[1.0, 3.0, 2.0, 3.0]), vec![1, 3, 2, 0]);
+    }
+
+    #[test]
+    fn ranking_nanFitness_ranksWeakest() {
+        assert_eq!(ranking(&[1.0, f64::NAN, 2.0]), vec![2, 0, 1]);
+    }
+
+    #[test]
+    fn selectWeighted_nanWeight_neverPicksIt() {
+        let mut rng = seeded_rng(5);
+        for _ in 0..50 {
+            assert!(select_weighted(&[1.0, f64::NAN, 2.0], &mut rng) != 1);
+        }
+    }
+
+    #[test]
+    fn selectWeighted_infiniteWeight_neverPicksIt() {
+        let mut rng = seeded_rng(6);
+        for _ in 0..50 {
+            assert_eq!(select_weighted(&[1.0, f64::INFINITY], &mut rng), 0);
+        }
+    }
+
+    #[test]
+    fn selectWeighted_totalOverflows_picksAnyone() {
+        let mut rng = seeded_rng(7);
+        let weights = [f64::MAX; 3];
+        let picks = (0..100)
+            .map(|_| select_weighted(&weights, &mut rng))
+            .collect::<Vec<_>>();
+        assert!((0..3).all(|index| picks.contains(&index)));
+    }
+
+    #[test]
+    fn rouletteSelection_unevaluatedIndividual_neverPicksIt() {
+        let mut rng = seeded_rng(8);
+        let fitnesses = [1.0, f64::NEG_INFINITY, 3.0];
+        for _ in 0..50 {
+            assert_eq!(RouletteSelection.select(&fitnesses, &mut rng), 2);
+        }
+    }
+}
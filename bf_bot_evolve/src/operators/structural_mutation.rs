@@ -0,0 +1,253 @@
+use std::ops::Range;
+
+use rand::{Rng, RngCore};
+
+use genome::{Gene, Genome, Op};
+use operators::MutationOperator;
+
+/// Picks a random sequence of genes anywhere in the genome.
+fn random_sequence<'a>(genome: &'a mut Genome, rng: &mut dyn RngCore) -> &'a mut Vec<Gene> {
+    let index = rng.gen_range(0..genome.nr_sequences());
+    genome.sequence_mut(index).expect("The index is in range.")
+}
+
+/// Picks a random sequence of genes anywhere in the genome, together with a random non-empty
+/// segment of it. Returns `None` if the picked sequence is empty.
+fn random_segment<'a>(genome: &'a mut Genome,
+                      rng: &mut dyn RngCore)
+                      -> Option<(&'a mut Vec<Gene>, Range<usize>)> {
+    let sequence = random_sequence(genome, rng);
+    if sequence.is_empty() {
+        return None;
+    }
+    let start = rng.gen_range(0..sequence.len());
+    let end = rng.gen_range(start + 1..=sequence.len());
+    Some((sequence, start..end))
+}
+
+/// Inserts a random `Op` gene at a random position in the genome.
+#[derive(Debug, Default)]
+pub struct InsertionMutation;
+
+impl MutationOperator for InsertionMutation {
+    fn mutate(&self, genome: &Genome, rng: &mut dyn RngCore) -> Genome {
+        let mut mutant = genome.clone();
+        let op = Op::random(rng);
+        let sequence = random_sequence(&mut mutant, rng);
+        let position = rng.gen_range(0..=sequence.len());
+        sequence.insert(position, Gene::Op(op));
+        mutant
+    }
+
+    fn name(&self) -> &str {
+        "insertion"
+    }
+}
+
+/// Removes a random gene from the genome. If that gene contains other genes, such as a loop, they
+/// are removed along with it.
+#[derive(Debug, Default)]
+pub struct DeletionMutation;
+
+impl MutationOperator for DeletionMutation {
+    fn mutate(&self, genome: &Genome, rng: &mut dyn RngCore) -> Genome {
+        let mut mutant = genome.clone();
+        if let Some((sequence, segment)) = random_segment(&mut mutant, rng) {
+            sequence.remove(segment.start);
+        }
+        mutant
+    }
+
+    fn name(&self) -> &str {
+        "deletion"
+    }
+}
+
+/// Copies a random segment of the genome, inserting the copy right after the original.
+#[derive(Debug, Default)]
+pub struct DuplicationMutation;
+
+impl MutationOperator for DuplicationMutation {
+    fn mutate(&self, genome: &Genome, rng: &mut dyn RngCore) -> Genome {
+        let mut mutant = genome.clone();
+        if let Some((sequence, segment)) = random_segment(&mut mutant, rng) {
+            let copy = sequence[segment.clone()].to_vec();
+            let tail = sequence.split_off(segment.end);
+            sequence.extend(copy);
+            sequence.extend(tail);
+        }
+        mutant
+    }
+
+    fn name(&self) -> &str {
+        "duplication"
+    }
+}
+
+/// Wraps a random segment of the genome in a loop.
+#[derive(Debug, Default)]
+pub struct LoopWrapMutation;
+
+impl MutationOperator for LoopWrapMutation {
+    fn mutate(&self, genome: &Genome, rng: &mut dyn RngCore) -> Genome {
+        let mut mutant = genome.clone();
+        if let Some((sequence, segment)) = random_segment(&mut mutant, rng) {
+            let start = segment.start;
+            let body = sequence.drain(segment).collect();
+            sequence.insert(start, Gene::Loop(body));
+        }
+        mutant
+    }
+
+    fn name(&self) -> &str {
+        "loop wrap"
+    }
+}
+
+/// Wraps a random segment of the genome in a repetition, with a count between 2 and `max_count`.
+#[derive(Debug)]
+pub struct RepeatWrapMutation {
+    pub max_count: u32,
+}
+
+impl Default for RepeatWrapMutation {
+    fn default() -> RepeatWrapMutation {
+        RepeatWrapMutation { max_count: 10 }
+    }
+}
+
+impl MutationOperator for RepeatWrapMutation {
+    fn mutate(&self, genome: &Genome, rng: &mut dyn RngCore) -> Genome {
+        let mut mutant = genome.clone();
+        let count = rng.gen_range(2..=self.max_count.max(2));
+        if let Some((sequence, segment)) = random_segment(&mut mutant, rng) {
+            let start = segment.start;
+            let body = sequence.drain(segment).collect();
+            sequence.insert(start, Gene::Repeat { body, count });
+        }
+        mutant
+    }
+
+    fn name(&self) -> &str {
+        "repeat wrap"
+    }
+}
+
+/// Raises or lowers the count of a random repetition in the genome by at most `max_change`. The
+/// count never drops below 1.
+#[derive(Debug)]
+pub struct RepeatCountMutation {
+    pub max_change: u32,
+}
+
+impl Default for RepeatCountMutation {
+    fn default() -> RepeatCountMutation {
+        RepeatCountMutation { max_change: 4 }
+    }
+}
+
+impl MutationOperator for RepeatCountMutation {
+    fn mutate(&self, genome: &Genome, rng: &mut dyn RngCore) -> Genome {
+        let mut mutant = genome.clone();
+        let nr_counts = mutant.nr_counts();
+        if nr_counts > 0 {
+            let change = rng.gen_range(1..=self.max_change.max(1));
+            let raise = rng.gen::<bool>();
+            let count = mutant.count_mut(rng.gen_range(0..nr_counts))
+                .expect("The index is in range.");
+            *count = if raise {
+                count.saturating_add(change)
+            } else {
+                count.saturating_sub(change).max(1)
+            };
+        }
+        mutant
+    }
+
+    fn name(&self) -> &str {
+        "repeat count"
+    }
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod tests {
+    use super::*;
+    use evolution::seeded_rng;
+
+    fn make_genome() -> Genome {
+        Genome::new(vec![Gene::Op(Op::MoveForward),
+                         Gene::Repeat {
+                             body: vec![Gene::Op(Op::Increment), Gene::Op(Op::Increment)],
+                             count: 5,
+                         },
+                         Gene::Op(Op::Decrement)])
+    }
+
+    #[test]
+    fn insertionMutation_addsOneOp() {
+        let mut rng = seeded_rng(0);
+        let mutant = InsertionMutation.mutate(&make_genome(), &mut rng);
+        assert_eq!(mutant.nr_ops(), 5);
+    }
+
+    #[test]
+    fn deletionMutation_emptyGenome_staysEmpty() {
+        let mut rng = seeded_rng(1);
+        let mutant = DeletionMutation.mutate(&Genome::default(), &mut rng);
+        assert_eq!(mutant, Genome::default());
+    }
+
+    #[test]
+    fn deletionMutation_removesOneGene() {
+        let mut rng = seeded_rng(2);
+        let genome = Genome::new(vec![Gene::Op(Op::Increment); 10]);
+        let mutant = DeletionMutation.mutate(&genome, &mut rng);
+        assert_eq!(mutant.genes.len(), 9);
+    }
+
+    #[test]
+    fn duplicationMutation_copiesASegmentInPlace() {
+        let mut rng = seeded_rng(3);
+        let genome = Genome::new(vec![Gene::Op(Op::Increment), Gene::Op(Op::Decrement)]);
+        let source = DuplicationMutation.mutate(&genome, &mut rng).to_source();
+        assert!(["++-", "+--", "+-+-"].contains(&source.as_str()), "{}", source);
+    }
+
+    #[test]
+    fn loopWrapMutation_keepsOpsInOrder() {
+        let mut rng = seeded_rng(4);
+        let genome = make_genome();
+        let mutant = LoopWrapMutation.mutate(&genome, &mut rng);
+        let strip = |source: String| source.replace(['[', ']'], "");
+        assert_eq!(strip(mutant.to_source()), genome.to_source());
+        assert_eq!(mutant.nr_sequences(), genome.nr_sequences() + 1);
+    }
+
+    #[test]
+    fn repeatWrapMutation_addsACount() {
+        let mut rng = seeded_rng(5);
+        let mutation = RepeatWrapMutation { max_count: 3 };
+        let mutant = mutation.mutate(&make_genome(), &mut rng);
+        assert_eq!(mutant.nr_counts(), 2);
+        assert_eq!(mutant.nr_ops(), 4);
+    }
+
+    #[test]
+    fn repeatCountMutation_changesCountWithinBounds() {
+        let mut rng = seeded_rng(6);
+        let mutation = RepeatCountMutation { max_change: 10 };
+        for _ in 0..20 {
+            let mut mutant = mutation.mutate(&make_genome(), &mut rng);
+            let count = *mutant.count_mut(0).unwrap();
+            assert!((1..=15).contains(&count) && count != 5, "{}", count);
+        }
+    }
+
+    #[test]
+    fn repeatCountMutation_withoutRepetitions_leavesGenomeAlone() {
+        let mut rng = seeded_rng(7);
+        let genome = Genome::new(vec![Gene::Op(Op::Increment)]);
+        assert_eq!(RepeatCountMutation::default().mutate(&genome, &mut rng), genome);
+    }
+}
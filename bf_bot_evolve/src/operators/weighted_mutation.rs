@@ -0,0 +1,111 @@
+use rand::{Rng, RngCore};
+
+use genome::Genome;
+use operators::{DeletionMutation, DuplicationMutation, InsertionMutation, LoopWrapMutation,
+                MutationOperator, PointMutation, RepeatCountMutation, RepeatWrapMutation};
+
+/// Applies one of several mutation operators, picked at random in proportion to its weight.
+pub struct WeightedMutation {
+    operators: Vec<(f64, Box<dyn MutationOperator>)>,
+}
+
+impl WeightedMutation {
+    /// Constructs a mutation without any operators, which leaves every genome unchanged.
+    pub fn new() -> WeightedMutation {
+        WeightedMutation { operators: vec![] }
+    }
+
+    /// Adds an operator that is picked with a probability proportional to the given weight.
+    /// Operators with a weight of zero or less are never picked.
+    pub fn with_operator<M>(mut self, weight: f64, operator: M) -> WeightedMutation
+        where M: MutationOperator + 'static
+    {
+        self.operators.push((weight.max(0.0), Box::new(operator)));
+        self
+    }
+
+    fn total_weight(&self) -> f64 {
+        self.operators.iter().map(|(weight, _)| weight).sum()
+    }
+}
+
+impl Default for WeightedMutation {
+    /// Every operator in the suite, with point mutations being the most common.
+    fn default() -> WeightedMutation {
+        WeightedMutation::new()
+            .with_operator(4.0, PointMutation)
+            .with_operator(2.0, InsertionMutation)
+            .with_operator(2.0, DeletionMutation)
+            .with_operator(1.0, DuplicationMutation)
+            .with_operator(1.0, LoopWrapMutation)
+            .with_operator(1.0, RepeatWrapMutation::default())
+            .with_operator(1.0, RepeatCountMutation::default())
+    }
+}
+
+impl MutationOperator for WeightedMutation {
+    fn mutate(&self, genome: &Genome, rng: &mut dyn RngCore) -> Genome {
+        self.mutate_named(genome, rng).0
+    }
+
+    fn name(&self) -> &str {
+        "weighted"
+    }
+
+    fn mutate_named(&self, genome: &Genome, rng: &mut dyn RngCore) -> (Genome, &str) {
+        let total_weight = self.total_weight();
+        if total_weight <= 0.0 {
+            return (genome.clone(), self.name());
+        }
+        let mut pick = rng.gen_range(0.0..total_weight);
+        for (weight, operator) in &self.operators {
+            if pick < *weight {
+                return operator.mutate_named(genome, rng);
+            }
+            pick -= weight;
+        }
+        // Rounding errors can leave a tiny remainder; it belongs to the last operator that can
+        // be picked.
+        let (_, operator) = self.operators
+            .iter()
+            .rev()
+            .find(|(weight, _)| *weight > 0.0)
+            .expect("The total weight is positive.");
+        operator.mutate_named(genome, rng)
+    }
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod tests {
+    use super::*;
+    use genome::{Gene, Op};
+    use evolution::seeded_rng;
+
+    #[test]
+    fn mutate_withoutOperators_returnsCopy() {
+        let mut rng = seeded_rng(0);
+        let genome = Genome::new(vec![Gene::Op(Op::Increment)]);
+        assert_eq!(WeightedMutation::new().mutate(&genome, &mut rng), genome);
+    }
+
+    #[test]
+    fn mutate_onlyPicksOperatorsWithWeight() {
+        let mut rng = seeded_rng(1);
+        let mutation = WeightedMutation::new()
+            .with_operator(0.0, DeletionMutation)
+            .with_operator(1.0, InsertionMutation);
+        let genome = Genome::new(vec![Gene::Op(Op::Increment)]);
+        for _ in 0..20 {
+            assert_eq!(mutation.mutate(&genome, &mut rng).nr_ops(), 2);
+        }
+    }
+
+    #[test]
+    fn mutateNamed_namesPickedOperator() {
+        let mut rng = seeded_rng(2);
+        let mutation = WeightedMutation::new().with_operator(1.0, LoopWrapMutation);
+        let genome = Genome::new(vec![Gene::Op(Op::Increment)]);
+        assert_eq!(mutation.mutate_named(&genome, &mut rng).1, "loop wrap");
+    }
+}
@@ -0,0 +1,321 @@
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use rand::RngCore;
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+
+use bf_bot_core::bf::Bot;
+use fitness::FitnessFunction;
+use genome::{self, Gene, Genome, Op, TemplateConfig};
+use operators::ranking;
+
+/// A member of the population: a genome and the bot it compiles into, together with its fitness
+/// once it has been evaluated.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Individual {
+    pub genome: Genome,
+    pub bot: Bot,
+    pub fitness: Option<f64>,
+}
+
+impl Individual {
+    pub fn new(genome: Genome) -> Individual {
+        Individual {
+            bot: genome.compile(),
+            genome,
+            fitness: None,
+        }
+    }
+}
+
+/// A collection of bots that evolves over time.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Population {
+    pub individuals: Vec<Individual>,
+}
+
+impl Population {
+    pub fn new(genomes: Vec<Genome>) -> Population {
+        Population { individuals: genomes.into_iter().map(Individual::new).collect() }
+    }
+
+    /// Creates a population from existing bots, such as hand-written ones.
+    pub fn from_bots(bots: &[Bot]) -> Population {
+        Population::new(bots.iter().map(Genome::from_bot).collect())
+    }
+
+    /// Creates a population from the warriors in the given directory: every file with the `.bf`
+    /// extension, in the order of their file names. The sources are parsed with
+    /// `Genome::from_source`, so their loops and repetitions become genes of their own, and
+    /// sources that expand into too many instructions are refused with `InvalidData`.
+    pub fn from_sources(dir: &Path) -> io::Result<Population> {
+        let mut paths = vec![];
+        for entry in fs::read_dir(dir)? {
+            let path = entry?.path();
+            if path.extension().is_some_and(|extension| extension == "bf") {
+                paths.push(path);
+            }
+        }
+        paths.sort();
+        let genomes = paths.iter()
+            .map(|path| {
+                Genome::from_source(&fs::read_to_string(path)?).map_err(|error| {
+                    io::Error::new(io::ErrorKind::InvalidData,
+                                   format!("Could not parse {}: {}", path.display(), error))
+                })
+            })
+            .collect::<io::Result<_>>()?;
+        Ok(Population::new(genomes))
+    }
+
+    /// Returns a population of the given size, in which the fraction `random_ratio` consists of
+    /// random genomes of the given number of `Op` genes, and the rest of copies of the individuals
+    /// of this population, taken in turn. If this population is empty, all of it is random.
+    pub fn mixed_with_random(&self,
+                             size: usize,
+                             random_ratio: f64,
+                             genome_length: usize,
+                             rng: &mut dyn RngCore)
+                             -> Population {
+        let nr_copies = if self.is_empty() {
+            0
+        } else {
+            size - (size as f64 * random_ratio.clamp(0.0, 1.0)).round() as usize
+        };
+        let mut individuals =
+            self.individuals.iter().cycle().take(nr_copies).cloned().collect::<Vec<_>>();
+        individuals.extend(Population::random(size - nr_copies, genome_length, rng).individuals);
+        Population { individuals }
+    }
+
+    /// Creates a population of the given size, consisting of random genomes of the given number
+    /// of `Op` genes. These genomes contain no loops or repetitions.
+    pub fn random(size: usize, genome_length: usize, rng: &mut dyn RngCore) -> Population {
+        let genomes = (0..size)
+            .map(|_| {
+                Genome::new((0..genome_length)
+                    .map(|_| Gene::Op(Op::random(rng)))
+                    .collect())
+            })
+            .collect();
+        Population::new(genomes)
+    }
+
+    /// Creates a population of the given size, consisting of genomes generated by
+    /// `genome::random` from the given templates.
+    pub fn from_templates(size: usize,
+                          config: &TemplateConfig,
+                          rng: &mut dyn RngCore)
+                          -> Population {
+        Population::new((0..size).map(|_| genome::random(rng, config)).collect())
+    }
+
+    pub fn len(&self) -> usize {
+        self.individuals.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.individuals.is_empty()
+    }
+
+    /// Evaluates the fitness of all individuals whose fitness isn't known yet.
+    #[cfg(not(feature = "rayon"))]
+    pub fn evaluate(&mut self, fitness_function: &dyn FitnessFunction) {
+        for individual in self.individuals.iter_mut().filter(|ind| ind.fitness.is_none()) {
+            individual.fitness = Some(fitness_function.evaluate(&individual.bot));
+        }
+    }
+
+    /// Evaluates the fitness of all individuals whose fitness isn't known yet, in parallel.
+    #[cfg(feature = "rayon")]
+    pub fn evaluate(&mut self, fitness_function: &dyn FitnessFunction) {
+        self.individuals
+            .par_iter_mut()
+            .filter(|ind| ind.fitness.is_none())
+            .for_each(|individual| {
+                individual.fitness = Some(fitness_function.evaluate(&individual.bot));
+            });
+    }
+
+    /// Returns the fitness of every individual, in order. Individuals that haven't been evaluated
+    /// are treated as having the lowest possible fitness.
+    pub fn fitnesses(&self) -> Vec<f64> {
+        self.individuals
+            .iter()
+            .map(|individual| individual.fitness.unwrap_or(f64::NEG_INFINITY))
+            .collect()
+    }
+
+    /// Returns copies of the given number of fittest individuals, from the fittest down.
+    pub fn fittest(&self, nr_individuals: usize) -> Vec<Individual> {
+        ranking(&self.fitnesses())
+            .into_iter()
+            .take(nr_individuals)
+            .map(|index| self.individuals[index].clone())
+            .collect()
+    }
+
+    /// Replaces the weakest individuals by the given newcomers, one for one. If there are more
+    /// newcomers than individuals, the surplus newcomers are left out.
+    pub fn replace_weakest(&mut self, newcomers: Vec<Individual>) {
+        let weakest = ranking(&self.fitnesses()).into_iter().rev();
+        for (index, newcomer) in weakest.zip(newcomers) {
+            self.individuals[index] = newcomer;
+        }
+    }
+
+    /// Returns the evaluated individual with the highest fitness, if there is one.
+    pub fn best(&self) -> Option<&Individual> {
+        self.individuals
+            .iter()
+            .filter(|individual| individual.fitness.is_some())
+            .fold(None, |best: Option<&Individual>, individual| match best {
+                Some(best) if best.fitness >= individual.fitness => Some(best),
+                _ => Some(individual),
+            })
+    }
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod tests {
+    use super::*;
+    use bf_bot_core::bf::Instruction;
+    use evolution::seeded_rng;
+
+    #[test]
+    fn random_createsRequestedNumberOfBotsOfRequestedLength() {
+        let mut rng = seeded_rng(0);
+        let population = Population::random(7, 12, &mut rng);
+        assert_eq!(population.len(), 7);
+        assert!(population.individuals.iter().all(|ind| ind.bot.get_program().len() == 12));
+    }
+
+    #[test]
+    fn evaluate_setsFitnessOfEveryIndividual() {
+        let mut rng = seeded_rng(1);
+        let mut population = Population::random(3, 4, &mut rng);
+        population.evaluate(&|bot: &Bot| bot.get_program().len() as f64);
+        assert_eq!(population.fitnesses(), vec![4.0, 4.0, 4.0]);
+    }
+
+    #[test]
+    fn evaluate_sameResultAsEvaluatingOneByOne() {
+        let mut rng = seeded_rng(2);
+        let fitness = |bot: &Bot| {
+            bot.get_program().iter().filter(|&i| *i == Instruction::Increment).count() as f64
+        };
+        let mut population = Population::random(50, 20, &mut rng);
+        let expected: Vec<f64> =
+            population.individuals.iter().map(|individual| fitness(&individual.bot)).collect();
+        population.evaluate(&fitness);
+        assert_eq!(population.fitnesses(), expected);
+    }
+
+    #[test]
+    fn best_returnsIndividualWithHighestFitness() {
+        let mut population = Population::new(vec![Genome::new(vec![Gene::Op(Op::Increment)]),
+                                                  Genome::new(vec![Gene::Op(Op::Increment); 3]),
+                                                  Genome::default()]);
+        population.evaluate(&|bot: &Bot| bot.get_program().len() as f64);
+        assert_eq!(population.best().unwrap().fitness, Some(3.0));
+    }
+
+    fn make_evaluated_population() -> Population {
+        let mut population = Population::new((0..4)
+            .map(|length| Genome::new(vec![Gene::Op(Op::Increment); length]))
+            .collect());
+        population.evaluate(&|bot: &Bot| bot.get_program().len() as f64);
+        population
+    }
+
+    #[test]
+    fn fittest_returnsFittestFirst() {
+        let fittest = make_evaluated_population().fittest(2);
+        let fitnesses = fittest.iter().map(|ind| ind.fitness.unwrap()).collect::<Vec<_>>();
+        assert_eq!(fitnesses, vec![3.0, 2.0]);
+    }
+
+    #[test]
+    fn replaceWeakest_keepsFittest() {
+        let mut population = make_evaluated_population();
+        let newcomer = population.individuals[3].clone();
+        population.replace_weakest(vec![newcomer.clone(), newcomer]);
+        assert_eq!(population.fitnesses(), vec![3.0, 3.0, 2.0, 3.0]);
+    }
+
+    #[test]
+    fn best_notEvaluated_returnsNone() {
+        let population = Population::new(vec![Genome::default()]);
+        assert_eq!(population.best(), None);
+    }
+
+    #[test]
+    fn fromSources_readsBfFilesInOrder() {
+        let dir = std::env::temp_dir().join(format!("bf_seeds_{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("b.bf"), "(>)*9[-]").unwrap();
+        fs::write(dir.join("a.bf"), "a rush: >>+").unwrap();
+        fs::write(dir.join("notes.txt"), "[").unwrap();
+        let population = Population::from_sources(&dir);
+        fs::remove_dir_all(&dir).unwrap();
+        let sources = population.unwrap()
+            .individuals
+            .iter()
+            .map(|individual| individual.genome.to_source())
+            .collect::<Vec<_>>();
+        assert_eq!(sources, vec![">>+", "(>)*9[-]"]);
+    }
+
+    #[test]
+    fn fromSources_unbalancedLoop_fails() {
+        let dir = std::env::temp_dir().join(format!("bf_bad_seeds_{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("bad.bf"), "[+").unwrap();
+        let error = Population::from_sources(&dir).unwrap_err();
+        fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn fromSources_expandsTooFar_fails() {
+        let dir = std::env::temp_dir().join(format!("bf_long_seeds_{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("long.bf"), "(+)*4000000000").unwrap();
+        let error = Population::from_sources(&dir).unwrap_err();
+        fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn mixedWithRandom_copiesSeedsInTurnThenAddsRandom() {
+        let mut rng = seeded_rng(3);
+        let seeds = Population::new(vec![Genome::from_source("+").unwrap(),
+                                         Genome::from_source("-").unwrap()]);
+        let population = seeds.mixed_with_random(10, 0.3, 5, &mut rng);
+        let sources = population.individuals
+            .iter()
+            .map(|individual| individual.genome.to_source())
+            .collect::<Vec<_>>();
+        assert_eq!(&sources[..7], &["+", "-", "+", "-", "+", "-", "+"]);
+        assert!(population.individuals[7..].iter().all(|ind| ind.genome.nr_ops() == 5));
+    }
+
+    #[test]
+    fn mixedWithRandom_noSeeds_allRandom() {
+        let mut rng = seeded_rng(4);
+        let population = Population::default().mixed_with_random(4, 0.0, 3, &mut rng);
+        assert_eq!(population.len(), 4);
+        assert!(population.individuals.iter().all(|ind| ind.genome.nr_ops() == 3));
+    }
+
+    #[test]
+    fn fromBots_compilesBackIntoTheSameBots() {
+        let bots = vec![Bot::new(vec![Instruction::JumpIfZero(2),
+                                      Instruction::Decrement,
+                                      Instruction::JumpIfNonZero(0)])];
+        assert_eq!(Population::from_bots(&bots).individuals[0].bot, bots[0]);
+    }
+}
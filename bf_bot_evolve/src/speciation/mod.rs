@@ -0,0 +1,140 @@
+//! Speciation, as in NEAT: the population is divided into species of individuals with similar
+//! genomes, and every individual shares its fitness with the other members of its species. A
+//! large species of strong individuals thus can't take over the whole population, and a new
+//! lineage that is still weak gets the time to improve, since it only competes within its own,
+//! small species.
+use genome::{self, Genome};
+use population::Population;
+
+pub use self::species::Species;
+mod species;
+
+/// Divides every generation into species and shares fitness within each species.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Speciation {
+    threshold: f64,
+    species: Vec<Species>,
+}
+
+impl Speciation {
+    /// Constructs a speciation in which a genome joins the first species whose representative lies
+    /// within the given distance, as measured by `genome::distance`, between 0 and 1. A genome that
+    /// is too far from every representative founds a new species.
+    pub fn new(threshold: f64) -> Speciation {
+        Speciation {
+            threshold,
+            species: vec![],
+        }
+    }
+
+    /// Replaces the species by the given ones, as they were saved from another speciation.
+    pub fn with_species(self, species: Vec<Species>) -> Speciation {
+        Speciation { species, ..self }
+    }
+
+    pub fn get_threshold(&self) -> f64 {
+        self.threshold
+    }
+
+    /// Returns the species of the last population that was divided.
+    pub fn get_species(&self) -> &[Species] {
+        &self.species
+    }
+
+    /// Divides an evaluated population into species, keeping the representatives of the species
+    /// of the previous generation, and shares the fitness of every individual with the other
+    /// members of its species.
+    ///
+    /// Sharing only works for fitnesses that aren't negative, so the fitnesses are first shifted
+    /// such that the lowest one becomes zero. Then each is divided by the size of its species.
+    pub fn apply(&mut self, population: &mut Population) {
+        for species in &mut self.species {
+            species.members.clear();
+        }
+        for (index, individual) in population.individuals.iter().enumerate() {
+            match self.find_species(&individual.genome) {
+                Some(species) => species.members.push(index),
+                None => {
+                    self.species.push(Species {
+                        representative: individual.genome.clone(),
+                        members: vec![index],
+                    })
+                }
+            }
+        }
+        self.species.retain(|species| !species.members.is_empty());
+        for species in &mut self.species {
+            species.representative = population.individuals[species.members[0]].genome.clone();
+        }
+        self.share_fitness(population);
+    }
+
+    fn find_species(&mut self, genome: &Genome) -> Option<&mut Species> {
+        let threshold = self.threshold;
+        self.species
+            .iter_mut()
+            .find(|species| genome::distance(&species.representative, genome) <= threshold)
+    }
+
+    fn share_fitness(&self, population: &mut Population) {
+        let lowest = population.individuals
+            .iter()
+            .filter_map(|individual| individual.fitness)
+            .fold(f64::INFINITY, f64::min);
+        for species in &self.species {
+            let size = species.members.len() as f64;
+            for &index in &species.members {
+                let individual = &mut population.individuals[index];
+                individual.fitness = individual.fitness.map(|fitness| (fitness - lowest) / size);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod tests {
+    use super::*;
+
+    fn make_population(sources: &[&str], fitnesses: &[f64]) -> Population {
+        let mut population = Population::new(sources.iter()
+            .map(|source| Genome::from_source(source).unwrap())
+            .collect());
+        for (individual, &fitness) in population.individuals.iter_mut().zip(fitnesses) {
+            individual.fitness = Some(fitness);
+        }
+        population
+    }
+
+    #[test]
+    fn apply_similarGenomes_sameSpecies() {
+        let mut population = make_population(&["(>)*9([-]>)*21", "(>)*8([-]>)*21", "+++<<<"],
+                                             &[1.0, 1.0, 0.0]);
+        let mut speciation = Speciation::new(0.2);
+        speciation.apply(&mut population);
+        let members = speciation.get_species()
+            .iter()
+            .map(|species| species.members.clone())
+            .collect::<Vec<_>>();
+        assert_eq!(members, vec![vec![0, 1], vec![2]]);
+    }
+
+    #[test]
+    fn apply_sharedFitness_largeSpeciesDividedBySize() {
+        let mut population = make_population(&["+", "+", "+", "<<<<<"], &[2.0, 2.0, 2.0, 1.0]);
+        Speciation::new(0.5).apply(&mut population);
+        // Shifted so that the lowest fitness is 0: the three identical bots share 1 between them,
+        // while the loner keeps 0.
+        assert_eq!(population.fitnesses(), vec![1.0 / 3.0, 1.0 / 3.0, 1.0 / 3.0, 0.0]);
+    }
+
+    #[test]
+    fn apply_nextGeneration_keepsRepresentatives() {
+        let mut speciation = Speciation::new(0.3);
+        speciation.apply(&mut make_population(&["(>)*9([-]>)*21"], &[0.0]));
+        speciation.apply(&mut make_population(&["+++", "(>)*8([-]>)*21"], &[0.0, 0.0]));
+        let species = speciation.get_species();
+        assert_eq!(species[0].members, vec![1]);
+        assert_eq!(species[1].members, vec![0]);
+    }
+}
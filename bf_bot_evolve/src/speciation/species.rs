@@ -0,0 +1,12 @@
+use genome::Genome;
+
+/// A group of individuals with similar genomes, which compete for the share of fitness of the
+/// group rather than with the whole population.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Species {
+    /// The genome that new individuals are compared with to decide whether they belong to this
+    /// species. It is the genome of the first member of the previous generation.
+    pub representative: Genome,
+    /// The indices in the population of the members of the current generation.
+    pub members: Vec<usize>,
+}
@@ -0,0 +1,5 @@
+//! Records evolution runs in a SQLite database, so that long experiments can be queried and
+//! compared afterwards with any SQLite client. Only available with the `sqlite` feature.
+
+pub use self::results_database::{ResultsDatabase, RunId, StorageError, STORAGE_SCHEMA_VERSION};
+mod results_database;
@@ -0,0 +1,302 @@
+use std::error::Error;
+use std::fmt;
+use std::path::Path;
+
+use rusqlite::{params, Connection};
+
+use bf_bot_core::bf::Bot;
+use bf_bot_core::match_runner::{MatchResult, ScoringScheme};
+use evolution::{GenerationStats, RunMetadata};
+use population::Individual;
+
+/// The version of the layout of the database. It is stored in the database itself, as its
+/// `user_version`, and increased whenever a table or column is removed or changes its meaning.
+pub const STORAGE_SCHEMA_VERSION: i32 = 1;
+
+/// The tables of the database. Every run gets a row in `runs`, and every row in the other tables
+/// refers to its run by `run_id`. Matches that were not played as part of a run have no `run_id`.
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS runs (
+    id INTEGER PRIMARY KEY,
+    seed INTEGER NOT NULL,
+    population_size INTEGER NOT NULL,
+    description TEXT NOT NULL
+);
+CREATE TABLE IF NOT EXISTS generations (
+    run_id INTEGER NOT NULL REFERENCES runs(id),
+    generation INTEGER NOT NULL,
+    best_fitness REAL NOT NULL,
+    mean_fitness REAL NOT NULL,
+    median_fitness REAL NOT NULL,
+    fitness_std_dev REAL NOT NULL,
+    min_length INTEGER NOT NULL,
+    mean_length REAL NOT NULL,
+    max_length INTEGER NOT NULL,
+    diversity REAL NOT NULL,
+    PRIMARY KEY (run_id, generation)
+);
+CREATE TABLE IF NOT EXISTS champions (
+    run_id INTEGER NOT NULL REFERENCES runs(id),
+    generation INTEGER NOT NULL,
+    source TEXT NOT NULL,
+    fitness REAL,
+    PRIMARY KEY (run_id, generation)
+);
+CREATE TABLE IF NOT EXISTS matches (
+    id INTEGER PRIMARY KEY,
+    run_id INTEGER REFERENCES runs(id),
+    bot_a_name TEXT,
+    bot_b_name TEXT,
+    bot_a_source TEXT NOT NULL,
+    bot_b_source TEXT NOT NULL,
+    scoring_scheme TEXT NOT NULL,
+    bot_a_wins INTEGER NOT NULL,
+    bot_b_wins INTEGER NOT NULL,
+    draws INTEGER NOT NULL,
+    score INTEGER NOT NULL,
+    points REAL NOT NULL
+);";
+
+/// Identifies a run in the database.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct RunId(pub i64);
+
+/// Why the database could not be read or written.
+#[derive(Debug)]
+pub enum StorageError {
+    Sqlite(rusqlite::Error),
+    /// The database was written by a newer version, with the given schema version.
+    UnsupportedSchema(i32),
+}
+
+impl fmt::Display for StorageError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            StorageError::Sqlite(ref error) => write!(f, "{}", error),
+            StorageError::UnsupportedSchema(version) => {
+                write!(f,
+                       "The database has schema version {}, but only version {} is supported.",
+                       version,
+                       STORAGE_SCHEMA_VERSION)
+            }
+        }
+    }
+}
+
+impl Error for StorageError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match *self {
+            StorageError::Sqlite(ref error) => Some(error),
+            StorageError::UnsupportedSchema(_) => None,
+        }
+    }
+}
+
+impl From<rusqlite::Error> for StorageError {
+    fn from(error: rusqlite::Error) -> StorageError {
+        StorageError::Sqlite(error)
+    }
+}
+
+/// A SQLite database with the runs, generations, champions and matches of evolution experiments.
+/// Bots are stored as their source code. Any number of runs can share a database.
+///
+/// # Examples
+///
+/// ```
+/// extern crate bf_bot_evolve;
+/// use bf_bot_evolve::evolution::RunMetadata;
+/// use bf_bot_evolve::storage::ResultsDatabase;
+/// let database = ResultsDatabase::in_memory().unwrap();
+/// let metadata = RunMetadata { seed: 7, generation: 0, population_size: 100 };
+/// let run = database.record_run(&metadata, "a first try").unwrap();
+/// let seed: i64 = database.connection()
+///     .query_row("SELECT seed FROM runs WHERE id = ?1", [run.0], |row| row.get(0))
+///     .unwrap();
+/// assert_eq!(seed, 7);
+/// ```
+#[derive(Debug)]
+pub struct ResultsDatabase {
+    connection: Connection,
+}
+
+impl ResultsDatabase {
+    /// Opens the database in the given file, creating the file and its tables if needed.
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<ResultsDatabase, StorageError> {
+        ResultsDatabase::initialize(Connection::open(path)?)
+    }
+
+    /// Creates a database that only lives in memory, for example for tests.
+    pub fn in_memory() -> Result<ResultsDatabase, StorageError> {
+        ResultsDatabase::initialize(Connection::open_in_memory()?)
+    }
+
+    fn initialize(connection: Connection) -> Result<ResultsDatabase, StorageError> {
+        let version: i32 = connection.query_row("PRAGMA user_version", [], |row| row.get(0))?;
+        if version > STORAGE_SCHEMA_VERSION {
+            return Err(StorageError::UnsupportedSchema(version));
+        }
+        connection.execute_batch(SCHEMA)?;
+        connection.execute_batch(&format!("PRAGMA user_version = {}", STORAGE_SCHEMA_VERSION))?;
+        Ok(ResultsDatabase { connection })
+    }
+
+    /// Returns the connection to the database, for queries.
+    pub fn connection(&self) -> &Connection {
+        &self.connection
+    }
+
+    /// Adds a run, described by its metadata and a free text, and returns its identifier.
+    pub fn record_run(&self,
+                      metadata: &RunMetadata,
+                      description: &str)
+                      -> Result<RunId, StorageError> {
+        self.connection
+            .execute("INSERT INTO runs (seed, population_size, description) VALUES (?1, ?2, ?3)",
+                     params![metadata.seed as i64, metadata.population_size as i64, description])?;
+        Ok(RunId(self.connection.last_insert_rowid()))
+    }
+
+    /// Adds the statistics of a generation of the given run.
+    pub fn record_generation(&self,
+                             run: RunId,
+                             stats: &GenerationStats)
+                             -> Result<(), StorageError> {
+        self.connection
+            .execute("INSERT OR REPLACE INTO generations VALUES \
+                      (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+                     params![run.0,
+                             stats.generation,
+                             stats.best_fitness,
+                             stats.mean_fitness,
+                             stats.median_fitness,
+                             stats.fitness_std_dev,
+                             stats.min_length as i64,
+                             stats.mean_length,
+                             stats.max_length as i64,
+                             stats.diversity])?;
+        Ok(())
+    }
+
+    /// Adds the champion of a generation of the given run.
+    pub fn record_champion(&self,
+                           run: RunId,
+                           generation: u32,
+                           champion: &Individual)
+                           -> Result<(), StorageError> {
+        self.connection
+            .execute("INSERT OR REPLACE INTO champions VALUES (?1, ?2, ?3, ?4)",
+                     params![run.0, generation, champion.genome.to_source(), champion.fitness])?;
+        Ok(())
+    }
+
+    /// Adds a match between the given bots, optionally as part of a run.
+    pub fn record_match(&self,
+                        run: Option<RunId>,
+                        bot_a: &Bot,
+                        bot_b: &Bot,
+                        result: &MatchResult)
+                        -> Result<(), StorageError> {
+        self.connection
+            .execute("INSERT INTO matches (run_id, bot_a_name, bot_b_name, bot_a_source, \
+                      bot_b_source, scoring_scheme, bot_a_wins, bot_b_wins, draws, score, \
+                      points) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
+                     params![run.map(|run| run.0),
+                             result.bot_a_name,
+                             result.bot_b_name,
+                             bot_a.to_source(),
+                             bot_b.to_source(),
+                             scoring_scheme_name(result.scoring_scheme),
+                             result.nr_bot_a_wins() as i64,
+                             result.nr_bot_b_wins() as i64,
+                             result.nr_draws() as i64,
+                             result.score(),
+                             result.points()])?;
+        Ok(())
+    }
+}
+
+fn scoring_scheme_name(scoring_scheme: ScoringScheme) -> &'static str {
+    match scoring_scheme {
+        ScoringScheme::Egojoust => "egojoust",
+        ScoringScheme::Chainbf => "chainbf",
+    }
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod tests {
+    use super::*;
+    use bf_bot_core::bf::Instruction;
+    use bf_bot_core::match_runner::Match;
+    use genome::Genome;
+    use population::Population;
+    use std::{env, fs};
+
+    fn make_metadata() -> RunMetadata {
+        RunMetadata {
+            seed: 1,
+            generation: 0,
+            population_size: 2,
+        }
+    }
+
+    fn count(database: &ResultsDatabase, table: &str) -> i64 {
+        database.connection()
+            .query_row(&format!("SELECT COUNT(*) FROM {}", table), [], |row| row.get(0))
+            .unwrap()
+    }
+
+    #[test]
+    fn record_generationAndChampion_stored() {
+        let database = ResultsDatabase::in_memory().unwrap();
+        let run = database.record_run(&make_metadata(), "").unwrap();
+        let mut population = Population::new(vec![Genome::from_source("+>[-]").unwrap()]);
+        population.evaluate(&|bot: &Bot| bot.get_program().len() as f64);
+        database.record_generation(run, &GenerationStats::of(0, &population)).unwrap();
+        database.record_champion(run, 0, population.best().unwrap()).unwrap();
+        let (source, fitness): (String, f64) = database.connection()
+            .query_row("SELECT source, fitness FROM champions WHERE run_id = ?1",
+                       [run.0],
+                       |row| Ok((row.get(0)?, row.get(1)?)))
+            .unwrap();
+        assert_eq!((source.as_str(), fitness), ("+>[-]", 5.0));
+        assert_eq!(count(&database, "generations"), 1);
+    }
+
+    #[test]
+    fn recordMatch_storesTotals() {
+        let database = ResultsDatabase::in_memory().unwrap();
+        let suicidal = Bot::new(vec![Instruction::MoveBack]);
+        let idle = Bot::new(vec![]);
+        let result = Match::new(&suicidal, &idle).run().unwrap();
+        database.record_match(None, &suicidal, &idle, &result).unwrap();
+        let (source, score): (String, i64) = database.connection()
+            .query_row("SELECT bot_a_source, score FROM matches", [], |row| {
+                Ok((row.get(0)?, row.get(1)?))
+            })
+            .unwrap();
+        assert_eq!((source.as_str(), score), ("<", -42));
+    }
+
+    #[test]
+    fn open_existingFile_keepsRuns() {
+        let path = env::temp_dir().join(format!("bf_results_{}.sqlite", std::process::id()));
+        ResultsDatabase::open(&path).unwrap().record_run(&make_metadata(), "first").unwrap();
+        let database = ResultsDatabase::open(&path).unwrap();
+        let nr_runs = count(&database, "runs");
+        drop(database);
+        fs::remove_file(&path).unwrap();
+        assert_eq!(nr_runs, 1);
+    }
+
+    #[test]
+    fn open_newerSchema_fails() {
+        let path = env::temp_dir().join(format!("bf_results_new_{}.sqlite", std::process::id()));
+        Connection::open(&path).unwrap().execute_batch("PRAGMA user_version = 99").unwrap();
+        let error = ResultsDatabase::open(&path).unwrap_err();
+        fs::remove_file(&path).unwrap();
+        assert_eq!(error.to_string(),
+                   "The database has schema version 99, but only version 1 is supported.");
+    }
+}
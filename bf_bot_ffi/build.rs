@@ -0,0 +1,27 @@
+//! Generates the C header of the library from the `extern "C"` functions in its source, into
+//! `OUT_DIR` so that building never writes to the source directory. A test checks that the
+//! committed copy, `include/bf_bot_ffi.h`, matches the generated header.
+extern crate cbindgen;
+
+use std::env;
+use std::path::PathBuf;
+
+fn main() {
+    let crate_dir = PathBuf::from(env::var("CARGO_MANIFEST_DIR").unwrap());
+    let out_dir = PathBuf::from(env::var("OUT_DIR").unwrap());
+    let config = cbindgen::Config {
+        language: cbindgen::Language::C,
+        include_guard: Some("BF_BOT_FFI_H".to_string()),
+        autogen_warning: Some("/* Generated by cbindgen from the bf_bot_ffi source. Do not edit. */"
+            .to_string()),
+        documentation: true,
+        ..cbindgen::Config::default()
+    };
+    cbindgen::Builder::new()
+        .with_crate(&crate_dir)
+        .with_config(config)
+        .generate()
+        .expect("Could not generate the C header.")
+        .write_to_file(out_dir.join("bf_bot_ffi.h"));
+    println!("cargo:rerun-if-changed=src");
+}
@@ -0,0 +1,101 @@
+#ifndef BF_BOT_FFI_H
+#define BF_BOT_FFI_H
+
+/* Generated by cbindgen from the bf_bot_ffi source. Do not edit. */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+/**
+ * A bot, compiled from its source code.
+ */
+typedef struct BfBot BfBot;
+
+/**
+ * The result of a complete match between two bots.
+ */
+typedef struct BfMatchResult BfMatchResult;
+
+/**
+ * The totals of a match, from the point of view of its first bot.
+ */
+typedef struct BfMatchSummary {
+  uint32_t bot_a_wins;
+  uint32_t bot_b_wins;
+  uint32_t draws;
+  /**
+   * The number of rounds that the first bot won minus the number it lost.
+   */
+  int32_t score;
+} BfMatchSummary;
+
+/**
+ * Parses the `BrainFuck` source code of a bot. Returns the bot, or `NULL` if the source code is
+ * not valid. In that case, if `error` is not `NULL`, it is set to a message that describes the
+ * problem, which must be freed with `bf_string_free`.
+ *
+ * # Safety
+ *
+ * `source` must point to a NUL-terminated string, and `error` must be `NULL` or point to a
+ * writable pointer.
+ */
+struct BfBot *bf_bot_from_source(const char *source, char **error);
+
+/**
+ * Frees a bot. Does nothing if `bot` is `NULL`.
+ *
+ * # Safety
+ *
+ * `bot` must be `NULL` or a bot returned by `bf_bot_from_source` that was not freed yet.
+ */
+void bf_bot_free(struct BfBot *bot);
+
+/**
+ * Plays a complete match of 42 rounds between two bots.
+ *
+ * # Safety
+ *
+ * Both bots must have been returned by `bf_bot_from_source` and not freed yet.
+ */
+struct BfMatchResult *bf_match_run(const struct BfBot *bot_a, const struct BfBot *bot_b);
+
+/**
+ * Returns the totals of a match.
+ *
+ * # Safety
+ *
+ * `result` must have been returned by `bf_match_run` and not freed yet.
+ */
+struct BfMatchSummary bf_match_result_summary(const struct BfMatchResult *result);
+
+/**
+ * Returns a JSON report of a match, with the outcome of every round. The string must be freed with
+ * `bf_string_free`.
+ *
+ * # Safety
+ *
+ * `result` must have been returned by `bf_match_run` and not freed yet.
+ */
+char *bf_match_result_json(const struct BfMatchResult *result);
+
+/**
+ * Frees the result of a match. Does nothing if `result` is `NULL`.
+ *
+ * # Safety
+ *
+ * `result` must be `NULL` or a result returned by `bf_match_run` that was not freed yet.
+ */
+void bf_match_result_free(struct BfMatchResult *result);
+
+/**
+ * Frees a string that was returned by this library. Does nothing if `string` is `NULL`.
+ *
+ * # Safety
+ *
+ * `string` must be `NULL` or a string returned by this library that was not freed yet.
+ */
+void bf_string_free(char *string);
+
+#endif  /* BF_BOT_FFI_H */
@@ -0,0 +1,224 @@
+//! A C interface to the engine, so that hills and tools written in C, or in any language that can
+//! call C such as Python through `ctypes`, can play matches with it.
+//!
+//! The functions are declared in `include/bf_bot_ffi.h`, which is generated from this source by
+//! cbindgen; a test fails when the committed header is out of date. Bots and match results are
+//! opaque objects that are created by this library and must be freed by it, with `bf_bot_free`
+//! and `bf_match_result_free`. Strings returned by the library are freed with `bf_string_free`.
+//!
+//! ```c
+//! char *error = NULL;
+//! BfBot *rusher = bf_bot_from_source(">>>>>>>>>[-]", &error);
+//! BfBot *idle = bf_bot_from_source("", &error);
+//! BfMatchResult *result = bf_match_run(rusher, idle);
+//! printf("%d\n", bf_match_result_summary(result).score);
+//! bf_match_result_free(result);
+//! bf_bot_free(idle);
+//! bf_bot_free(rusher);
+//! ```
+extern crate bf_bot_compiler;
+extern crate bf_bot_core;
+
+use std::ffi::{CStr, CString};
+use std::os::raw::c_char;
+use std::ptr;
+
+use bf_bot_compiler::parser;
+use bf_bot_core::bf::Bot;
+use bf_bot_core::match_runner::{Match, MatchResult};
+
+/// A bot, compiled from its source code.
+pub struct BfBot {
+    bot: Bot,
+}
+
+/// The result of a complete match between two bots.
+pub struct BfMatchResult {
+    result: MatchResult,
+}
+
+/// The totals of a match, from the point of view of its first bot.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BfMatchSummary {
+    pub bot_a_wins: u32,
+    pub bot_b_wins: u32,
+    pub draws: u32,
+    /// The number of rounds that the first bot won minus the number it lost.
+    pub score: i32,
+}
+
+/// Parses the `BrainFuck` source code of a bot. Returns the bot, or `NULL` if the source code is
+/// not valid. In that case, if `error` is not `NULL`, it is set to a message that describes the
+/// problem, which must be freed with `bf_string_free`.
+///
+/// # Safety
+///
+/// `source` must point to a NUL-terminated string, and `error` must be `NULL` or point to a
+/// writable pointer.
+#[no_mangle]
+pub unsafe extern "C" fn bf_bot_from_source(source: *const c_char,
+                                            error: *mut *mut c_char)
+                                            -> *mut BfBot {
+    let parsed = match CStr::from_ptr(source).to_str() {
+        Ok(source) => parser::parse(source).map_err(|parse_error| parse_error.to_string()),
+        Err(_) => Err("The source code is not valid UTF-8.".to_string()),
+    };
+    match parsed {
+        Ok(bot) => Box::into_raw(Box::new(BfBot { bot })),
+        Err(message) => {
+            if !error.is_null() {
+                *error = to_c_string(message);
+            }
+            ptr::null_mut()
+        }
+    }
+}
+
+/// Frees a bot. Does nothing if `bot` is `NULL`.
+///
+/// # Safety
+///
+/// `bot` must be `NULL` or a bot returned by `bf_bot_from_source` that was not freed yet.
+#[no_mangle]
+pub unsafe extern "C" fn bf_bot_free(bot: *mut BfBot) {
+    if !bot.is_null() {
+        drop(Box::from_raw(bot));
+    }
+}
+
+/// Plays a complete match of 42 rounds between two bots.
+///
+/// # Safety
+///
+/// Both bots must have been returned by `bf_bot_from_source` and not freed yet.
+#[no_mangle]
+pub unsafe extern "C" fn bf_match_run(bot_a: *const BfBot,
+                                      bot_b: *const BfBot)
+                                      -> *mut BfMatchResult {
+    let result =
+        Match::new(&(*bot_a).bot, &(*bot_b).bot).run().expect("The standard rounds are valid.");
+    Box::into_raw(Box::new(BfMatchResult { result }))
+}
+
+/// Returns the totals of a match.
+///
+/// # Safety
+///
+/// `result` must have been returned by `bf_match_run` and not freed yet.
+#[no_mangle]
+pub unsafe extern "C" fn bf_match_result_summary(result: *const BfMatchResult) -> BfMatchSummary {
+    let result = &(*result).result;
+    BfMatchSummary {
+        bot_a_wins: result.nr_bot_a_wins() as u32,
+        bot_b_wins: result.nr_bot_b_wins() as u32,
+        draws: result.nr_draws() as u32,
+        score: result.score(),
+    }
+}
+
+/// Returns a JSON report of a match, with the outcome of every round. The string must be freed with
+/// `bf_string_free`.
+///
+/// # Safety
+///
+/// `result` must have been returned by `bf_match_run` and not freed yet.
+#[no_mangle]
+pub unsafe extern "C" fn bf_match_result_json(result: *const BfMatchResult) -> *mut c_char {
+    to_c_string((*result).result.to_json_report())
+}
+
+/// Frees the result of a match. Does nothing if `result` is `NULL`.
+///
+/// # Safety
+///
+/// `result` must be `NULL` or a result returned by `bf_match_run` that was not freed yet.
+#[no_mangle]
+pub unsafe extern "C" fn bf_match_result_free(result: *mut BfMatchResult) {
+    if !result.is_null() {
+        drop(Box::from_raw(result));
+    }
+}
+
+/// Frees a string that was returned by this library. Does nothing if `string` is `NULL`.
+///
+/// # Safety
+///
+/// `string` must be `NULL` or a string returned by this library that was not freed yet.
+#[no_mangle]
+pub unsafe extern "C" fn bf_string_free(string: *mut c_char) {
+    if !string.is_null() {
+        drop(CString::from_raw(string));
+    }
+}
+
+fn to_c_string(text: String) -> *mut c_char {
+    // None of the strings of this library contain a NUL character, but if one ever does, the
+    // string is cut short there rather than lost.
+    let text = match text.find('\0') {
+        Some(end) => text[..end].to_string(),
+        None => text,
+    };
+    CString::new(text).expect("The NUL characters were removed.").into_raw()
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod tests {
+    use super::*;
+
+    fn from_source(source: &str) -> (*mut BfBot, Option<String>) {
+        let source = CString::new(source).unwrap();
+        let mut error = ptr::null_mut();
+        unsafe {
+            let bot = bf_bot_from_source(source.as_ptr(), &mut error);
+            let message = if error.is_null() {
+                None
+            } else {
+                let message = CStr::from_ptr(error).to_string_lossy().into_owned();
+                bf_string_free(error);
+                Some(message)
+            };
+            (bot, message)
+        }
+    }
+
+    #[test]
+    fn header_committedCopy_matchesGeneratedHeader() {
+        let generated = concat!(env!("OUT_DIR"), "/bf_bot_ffi.h");
+        assert!(include_str!(concat!(env!("OUT_DIR"), "/bf_bot_ffi.h")) ==
+                include_str!("../include/bf_bot_ffi.h"),
+                "include/bf_bot_ffi.h is out of date; copy {} over it.",
+                generated);
+    }
+
+    #[test]
+    fn bfBotFromSource_invalidSource_nullAndError() {
+        let (bot, error) = from_source("[");
+        assert!(bot.is_null());
+        assert!(error.is_some());
+    }
+
+    #[test]
+    fn bfMatchRun_suicidalAgainstIdle_summaryAndJson() {
+        let (suicidal, _) = from_source("<");
+        let (idle, error) = from_source("");
+        assert_eq!(error, None);
+        unsafe {
+            let result = bf_match_run(suicidal, idle);
+            assert_eq!(bf_match_result_summary(result),
+                       BfMatchSummary {
+                           bot_a_wins: 0,
+                           bot_b_wins: 42,
+                           draws: 0,
+                           score: -42,
+                       });
+            let json = bf_match_result_json(result);
+            assert!(CStr::from_ptr(json).to_str().unwrap().contains("\"score\":-42"));
+            bf_string_free(json);
+            bf_match_result_free(result);
+            bf_bot_free(idle);
+            bf_bot_free(suicidal);
+        }
+    }
+}
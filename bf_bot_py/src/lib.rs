@@ -0,0 +1,304 @@
+//! Python bindings, so that fitness functions can be prototyped in Python while the rounds are
+//! still simulated in Rust.
+//!
+//! Build and install the module into the current Python environment with `maturin develop`. Then:
+//!
+//! ```python
+//! import bf_bot_py
+//! hill = [bf_bot_py.Bot(">(+)*10>(-)*10(>)*7([-]>)*20"), bf_bot_py.Bot("")]
+//!
+//! def fitness(bot):
+//!     return sum(bf_bot_py.play_match(bot, opponent).points for opponent in hill)
+//!
+//! run = bf_bot_py.EvolutionRun(fitness, population_size=50, seed=7)
+//! run.run_generations(20)
+//! champion, best_fitness = run.best()
+//! print(champion.source, best_fitness)
+//! ```
+// The code that the macros of PyO3 generate for methods that return a `PyResult` converts errors
+// into the type they already are.
+#![allow(clippy::useless_conversion)]
+extern crate bf_bot_compiler;
+extern crate bf_bot_core;
+extern crate bf_bot_evolve;
+// The code that the macros of PyO3 generate refers to `::core`, which needs this in Rust 2015.
+extern crate core;
+extern crate pyo3;
+
+use std::sync::{Arc, Mutex};
+
+use bf_bot_compiler::parser;
+use bf_bot_core::bf::Bot;
+use bf_bot_core::match_runner::{Match, MatchResult};
+use bf_bot_evolve::evolution::{seeded_rng, EvolutionParams, EvolutionRun};
+use bf_bot_evolve::fitness::FitnessFunction;
+use bf_bot_evolve::operators::{SinglePointCrossover, WeightedMutation};
+use bf_bot_evolve::population::Population;
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+
+/// A bot, compiled from its `BrainFuck` source code.
+#[pyclass(name = "Bot", frozen)]
+#[derive(Debug, Clone)]
+struct PyBot {
+    bot: Bot,
+}
+
+#[pymethods]
+impl PyBot {
+    /// Parses the source code. Raises a `ValueError` if it is not valid.
+    #[new]
+    fn new(source: &str) -> PyResult<PyBot> {
+        parser::parse(source)
+            .map(|bot| PyBot { bot })
+            .map_err(|error| PyValueError::new_err(error.to_string()))
+    }
+
+    /// The program as plain `BrainFuck`, with every repetition expanded.
+    #[getter]
+    fn source(&self) -> String {
+        self.bot.to_source()
+    }
+
+    fn __len__(&self) -> usize {
+        self.bot.get_program().len()
+    }
+
+    fn __repr__(&self) -> String {
+        format!("Bot('{}')", self.bot.to_source())
+    }
+}
+
+/// The result of a complete match, from the point of view of its first bot.
+#[pyclass(name = "MatchResult", frozen)]
+struct PyMatchResult {
+    result: MatchResult,
+}
+
+#[pymethods]
+impl PyMatchResult {
+    #[getter]
+    fn bot_a_wins(&self) -> usize {
+        self.result.nr_bot_a_wins()
+    }
+
+    #[getter]
+    fn bot_b_wins(&self) -> usize {
+        self.result.nr_bot_b_wins()
+    }
+
+    #[getter]
+    fn draws(&self) -> usize {
+        self.result.nr_draws()
+    }
+
+    /// The number of rounds won minus the number of rounds lost.
+    #[getter]
+    fn score(&self) -> i32 {
+        self.result.score()
+    }
+
+    /// The score, as the default scoring scheme counts it.
+    #[getter]
+    fn points(&self) -> f64 {
+        self.result.points()
+    }
+
+    /// Returns a JSON report with the outcome of every round.
+    fn to_json(&self) -> String {
+        self.result.to_json_report()
+    }
+}
+
+/// Plays a complete match of 42 rounds between two bots.
+#[pyfunction]
+fn play_match(bot_a: &PyBot, bot_b: &PyBot) -> PyMatchResult {
+    let result = Match::new(&bot_a.bot, &bot_b.bot).run().expect("The standard rounds are valid.");
+    PyMatchResult { result }
+}
+
+/// A fitness function that calls a Python function with the bot. If the function raises an
+/// exception, the bot gets the lowest possible fitness, and the first exception is kept, so that
+/// it can be raised once the generation has been evaluated.
+struct PythonFitness {
+    function: PyObject,
+    error: Arc<Mutex<Option<PyErr>>>,
+}
+
+impl FitnessFunction for PythonFitness {
+    fn evaluate(&self, bot: &Bot) -> f64 {
+        Python::with_gil(|py| {
+            let fitness = self.function
+                .call1(py, (PyBot { bot: bot.clone() },))
+                .and_then(|fitness| fitness.extract::<f64>(py));
+            fitness.unwrap_or_else(|error| {
+                self.error.lock().unwrap().get_or_insert(error);
+                f64::NEG_INFINITY
+            })
+        })
+    }
+}
+
+/// Evolves bots with a fitness function written in Python: any callable that takes a `Bot` and
+/// returns a number. The initial population is random, and offspring are bred with weighted
+/// mutation and single point crossover.
+#[pyclass(name = "EvolutionRun")]
+struct PyEvolutionRun {
+    run: EvolutionRun,
+    error: Arc<Mutex<Option<PyErr>>>,
+}
+
+#[pymethods]
+impl PyEvolutionRun {
+    /// Creates the initial population and evaluates it.
+    #[new]
+    #[pyo3(signature = (fitness, population_size=100, genome_length=20, elitism=0, seed=0))]
+    fn new(fitness: PyObject,
+           population_size: usize,
+           genome_length: usize,
+           elitism: usize,
+           seed: u64)
+           -> PyResult<PyEvolutionRun> {
+        let params = EvolutionParams {
+            population_size,
+            elitism,
+            seed,
+            ..EvolutionParams::default()
+        };
+        // The population is drawn from its own generator, as the command line tool does.
+        let population = Population::random(population_size, genome_length, &mut seeded_rng(seed));
+        let error = Arc::new(Mutex::new(None));
+        let fitness = PythonFitness {
+            function: fitness,
+            error: error.clone(),
+        };
+        let run = PyEvolutionRun {
+            run: EvolutionRun::new(params,
+                                   population,
+                                   Box::new(WeightedMutation::default()),
+                                   Box::new(SinglePointCrossover),
+                                   Box::new(fitness))
+                .map_err(|error| PyValueError::new_err(error.to_string()))?,
+            error,
+        };
+        run.raise_error()?;
+        Ok(run)
+    }
+
+    /// Breeds and evaluates the given number of generations. Raises the first exception of the
+    /// fitness function, if it raised any, after the generation in which it did.
+    fn run_generations(&mut self, nr_generations: u32) -> PyResult<()> {
+        for _ in 0..nr_generations {
+            self.run.run_generation();
+            self.raise_error()?;
+        }
+        Ok(())
+    }
+
+    /// The number of generations that have been run.
+    #[getter]
+    fn generation(&self) -> u32 {
+        self.run.get_generation()
+    }
+
+    /// Returns the fittest bot of the current generation and its fitness, or `None` if the
+    /// population is empty.
+    fn best(&self) -> Option<(PyBot, f64)> {
+        self.run
+            .best()
+            .map(|best| (PyBot { bot: best.bot.clone() }, best.fitness.unwrap_or(f64::NAN)))
+    }
+
+    /// Returns the statistics of the current generation as a dict.
+    fn stats<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        let stats = self.run.stats();
+        let dict = PyDict::new_bound(py);
+        dict.set_item("generation", stats.generation)?;
+        dict.set_item("best_fitness", stats.best_fitness)?;
+        dict.set_item("mean_fitness", stats.mean_fitness)?;
+        dict.set_item("median_fitness", stats.median_fitness)?;
+        dict.set_item("fitness_std_dev", stats.fitness_std_dev)?;
+        dict.set_item("min_length", stats.min_length)?;
+        dict.set_item("mean_length", stats.mean_length)?;
+        dict.set_item("max_length", stats.max_length)?;
+        dict.set_item("diversity", stats.diversity)?;
+        Ok(dict)
+    }
+}
+
+impl PyEvolutionRun {
+    fn raise_error(&self) -> PyResult<()> {
+        match self.error.lock().unwrap().take() {
+            Some(error) => Err(error),
+            None => Ok(()),
+        }
+    }
+}
+
+#[pymodule]
+fn bf_bot_py(module: &Bound<'_, PyModule>) -> PyResult<()> {
+    module.add_class::<PyBot>()?;
+    module.add_class::<PyMatchResult>()?;
+    module.add_class::<PyEvolutionRun>()?;
+    module.add_function(wrap_pyfunction!(play_match, module)?)?;
+    Ok(())
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod tests {
+    use super::*;
+
+    fn run_python<F: FnOnce(Python, &Bound<PyModule>)>(test: F) {
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            let module = PyModule::new_bound(py, "bf_bot_py").unwrap();
+            bf_bot_py(&module).unwrap();
+            test(py, &module);
+        });
+    }
+
+    #[test]
+    fn bot_invalidSource_valueError() {
+        run_python(|py, module| {
+            let error = module.getattr("Bot").unwrap().call1(("[",)).unwrap_err();
+            assert!(error.is_instance_of::<PyValueError>(py));
+        });
+    }
+
+    #[test]
+    fn playMatch_suicidalAgainstIdle_lostEveryRound() {
+        run_python(|_, module| {
+            let bot = |source: &str| module.getattr("Bot").unwrap().call1((source,)).unwrap();
+            let result = module.getattr("play_match").unwrap().call1((bot("<"), bot(""))).unwrap();
+            assert_eq!(result.getattr("score").unwrap().extract::<i32>().unwrap(), -42);
+        });
+    }
+
+    #[test]
+    fn evolutionRun_pythonFitness_favoursItsChoice() {
+        run_python(|py, module| {
+            let fitness = py.eval_bound("lambda bot: float(bot.source.count('+'))", None, None)
+                .unwrap();
+            let run = module.getattr("EvolutionRun")
+                .unwrap()
+                .call1((fitness, 10, 10, 1, 3))
+                .unwrap();
+            run.call_method1("run_generations", (3,)).unwrap();
+            assert_eq!(run.getattr("generation").unwrap().extract::<u32>().unwrap(), 3);
+            let (_, best_fitness) = run.call_method0("best").unwrap().extract::<(PyObject, f64)>()
+                .unwrap();
+            assert!(best_fitness > 0.0);
+        });
+    }
+
+    #[test]
+    fn evolutionRun_fitnessRaises_raisedToCaller() {
+        run_python(|py, module| {
+            let fitness = py.eval_bound("lambda bot: 1 / 0", None, None).unwrap();
+            let error = module.getattr("EvolutionRun").unwrap().call1((fitness, 4)).unwrap_err();
+            assert!(error.is_instance_of::<pyo3::exceptions::PyZeroDivisionError>(py));
+        });
+    }
+}
@@ -0,0 +1,107 @@
+//! JavaScript bindings for the engine, so that it can run in a browser, for example to power a
+//! visualizer of BF Joust rounds.
+//!
+//! Bots are passed in as their `BrainFuck` source code, and results are returned as JSON text,
+//! which `JSON.parse` turns into plain objects. Errors, such as source code that doesn't parse, are
+//! thrown as JavaScript `Error`s.
+//!
+//! Build the WebAssembly module with `wasm-pack build --target web`, or with
+//! `cargo build --target wasm32-unknown-unknown` followed by the `wasm-bindgen` command line tool.
+extern crate bf_bot_compiler;
+extern crate bf_bot_core;
+extern crate serde_json;
+extern crate wasm_bindgen;
+
+use bf_bot_compiler::parser;
+use bf_bot_core::bf::Bot;
+use bf_bot_core::engine::Arena;
+use bf_bot_core::match_runner::Match;
+use bf_bot_core::simul_round::RoundParams;
+use wasm_bindgen::prelude::*;
+
+/// Parses the source code of a bot and returns its program as plain `BrainFuck`, with every
+/// repetition expanded and every comment removed.
+#[wasm_bindgen]
+pub fn parse(source: &str) -> Result<String, JsError> {
+    to_js(parse_bot(source).map(|bot| bot.to_source()))
+}
+
+/// Plays a single round between two bots and returns its replay as JSON: the initial tape, the
+/// positions of both bots and the cells that changed after every step, and the result.
+#[wasm_bindgen(js_name = playRound)]
+pub fn play_round(left: &str,
+                  right: &str,
+                  tape_length: u32,
+                  invert_polarity: bool,
+                  max_steps: u32)
+                  -> Result<String, JsError> {
+    to_js(replay_json(left, right, &RoundParams::new(tape_length, invert_polarity, max_steps)))
+}
+
+/// Plays a complete match between two bots, all 42 rounds of it, and returns its report as JSON.
+/// See `MatchResult::to_json_report` for the fields of the report.
+#[wasm_bindgen(js_name = playMatch)]
+pub fn play_match(left: &str, right: &str) -> Result<String, JsError> {
+    to_js(match_json(left, right))
+}
+
+fn parse_bot(source: &str) -> Result<Bot, String> {
+    parser::parse(source).map_err(|error| error.to_string())
+}
+
+fn replay_json(left: &str, right: &str, round_params: &RoundParams) -> Result<String, String> {
+    if !(10..=30).contains(&round_params.tape_length) {
+        return Err(format!("The tape length must lie between 10 and 30, not {}.",
+                           round_params.tape_length));
+    }
+    let (left, right) = (parse_bot(left)?, parse_bot(right)?);
+    let mut arena = Arena::new(&left, &right, round_params).map_err(|error| error.to_string())?;
+    arena.attach_recorder();
+    arena.finish();
+    let replay = arena.take_replay().expect("A recorder was attached.");
+    serde_json::to_string(&replay).map_err(|error| error.to_string())
+}
+
+fn match_json(left: &str, right: &str) -> Result<String, String> {
+    let (left, right) = (parse_bot(left)?, parse_bot(right)?);
+    let result = Match::new(&left, &right).run().map_err(|error| error.to_string())?;
+    Ok(result.to_json_report())
+}
+
+/// Turns an error message into a JavaScript `Error`. This can only be done in WebAssembly, which is
+/// why the functions above are tested through the functions they wrap.
+fn to_js(result: Result<String, String>) -> Result<String, JsError> {
+    result.map_err(|message| JsError::new(&message))
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parseBot_repetition_expanded() {
+        assert_eq!(parse_bot("(+)*3 a comment [-]").unwrap().to_source(), "+++[-]");
+        assert!(parse_bot("[").is_err());
+    }
+
+    #[test]
+    fn replayJson_suicidalBot_stepsAndResult() {
+        let json = replay_json("<", "", &RoundParams::new(10, false, 100)).unwrap();
+        let replay = serde_json::from_str::<serde_json::Value>(&json).unwrap();
+        assert_eq!(replay["initial_tape"].as_array().unwrap().len(), 10);
+        assert_eq!(replay["steps"].as_array().unwrap().len(), 1);
+        assert!(!replay["result"].is_null());
+    }
+
+    #[test]
+    fn replayJson_tapeTooLong_error() {
+        assert!(replay_json("", "", &RoundParams::new(31, false, 100)).is_err());
+    }
+
+    #[test]
+    fn matchJson_idleBots_allDraws() {
+        let json = match_json("", "").unwrap();
+        assert!(json.contains("\"draws\":42"), "{}", json);
+    }
+}
@@ -1,11 +1,11 @@
 #![allow(dead_code, unused_variables)]
 
-extern crate bf_bot_core as core;
+extern crate bf_bot_core as bf_core;
 extern crate bf_bot_compiler as compiler;
 
 use compiler::parser;
-use core::bf::Bot;
-use core::simul_game;
+use bf_core::bf::Bot;
+use bf_core::simul_game;
 
 fn main() {
     print!("Bot1 input string: ");
@@ -19,5 +19,5 @@ fn main() {
 
 fn make_test_bot(code: String) -> Bot {
     println!("{}", code);
-    Bot::new(parser::parse_bot(code))
+    parser::parse(&code).expect("Test bot should be valid.")
 }